@@ -1,4 +1,4 @@
-use crate::FsEvent;
+use crate::{FsEvent, WatcherFlags};
 use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
 use dispatch2::{DispatchQueue, DispatchQueueAttr, DispatchRetained};
 use libc::dev_t;
@@ -7,8 +7,7 @@ use objc2_core_services::{
     ConstFSEventStreamRef, FSEventStreamContext, FSEventStreamCreate, FSEventStreamEventFlags,
     FSEventStreamEventId, FSEventStreamGetDeviceBeingWatched, FSEventStreamInvalidate,
     FSEventStreamRef, FSEventStreamRelease, FSEventStreamSetDispatchQueue, FSEventStreamStart,
-    FSEventStreamStop, kFSEventStreamCreateFlagFileEvents, kFSEventStreamCreateFlagNoDefer,
-    kFSEventStreamCreateFlagWatchRoot,
+    FSEventStreamStop,
 };
 use std::{
     ffi::c_void,
@@ -39,6 +38,22 @@ impl EventStream {
         since_event_id: FSEventStreamEventId,
         latency: CFTimeInterval,
         callback: EventsCallback,
+    ) -> Self {
+        Self::with_flags(
+            paths,
+            since_event_id,
+            latency,
+            WatcherFlags::default(),
+            callback,
+        )
+    }
+
+    pub fn with_flags(
+        paths: &[&str],
+        since_event_id: FSEventStreamEventId,
+        latency: CFTimeInterval,
+        flags: WatcherFlags,
+        callback: EventsCallback,
     ) -> Self {
         unsafe extern "C-unwind" fn drop_callback(info: *const c_void) {
             let _cb: Box<EventsCallback> = unsafe { Box::from_raw(info as _) };
@@ -86,9 +101,7 @@ impl EventStream {
                 paths.as_opaque(),
                 since_event_id,
                 latency,
-                kFSEventStreamCreateFlagNoDefer
-                    | kFSEventStreamCreateFlagFileEvents
-                    | kFSEventStreamCreateFlagWatchRoot,
+                flags.bits(),
             )
         };
         Self { stream }
@@ -163,13 +176,40 @@ impl EventWatcher {
         path: String,
         since_event_id: FSEventStreamEventId,
         latency: f64,
+    ) -> (dev_t, EventWatcher) {
+        Self::spawn_with(path, since_event_id, latency, WatcherFlags::default())
+    }
+
+    /// Like [`EventWatcher::spawn`], with explicit stream creation flags.
+    ///
+    /// `latency` is how long FSEvents waits before delivering a batch; raising
+    /// it coalesces bursts of changes into fewer batches at the cost of
+    /// responsiveness.
+    pub fn spawn_with(
+        path: String,
+        since_event_id: FSEventStreamEventId,
+        latency: f64,
+        flags: WatcherFlags,
+    ) -> (dev_t, EventWatcher) {
+        Self::spawn_paths(vec![path], since_event_id, latency, flags)
+    }
+
+    /// Like [`EventWatcher::spawn_with`], watching several paths through one
+    /// stream, e.g. every root of a multi-root cache.
+    pub fn spawn_paths(
+        paths: Vec<String>,
+        since_event_id: FSEventStreamEventId,
+        latency: f64,
+        flags: WatcherFlags,
     ) -> (dev_t, EventWatcher) {
         let (_cancellation_token, cancellation_token_rx) = bounded::<()>(1);
         let (sender, receiver) = unbounded();
-        let stream = EventStream::new(
-            &[&path],
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let stream = EventStream::with_flags(
+            &paths,
             since_event_id,
             latency,
+            flags,
             Box::new(move |events| {
                 let _ = sender.send(events);
             }),
@@ -234,6 +274,157 @@ mod tests {
                 "event watcher on non-existent path should not deliver events"
             );
         }
+
+        #[test]
+        fn higher_latency_coalesces_rapid_changes() {
+            let temp_dir = tempdir().expect("failed to create tempdir");
+            let watched_root = temp_dir.path().canonicalize().expect("failed to canonicalize");
+            let watch_path = watched_root
+                .to_str()
+                .expect("tempdir path should be utf8")
+                .to_string();
+
+            // Without NoDefer the first change also waits for the latency window.
+            let (_, watcher) = EventWatcher::spawn_with(
+                watch_path,
+                current_event_id(),
+                1.0,
+                WatcherFlags::FILE_EVENTS | WatcherFlags::WATCH_ROOT,
+            );
+            std::thread::sleep(Duration::from_millis(500));
+
+            const FILES: usize = 20;
+            for i in 0..FILES {
+                std::fs::write(watched_root.join(format!("burst_{i}.txt")), "cardinal")
+                    .expect("failed to write test file");
+            }
+
+            let mut batches = 0;
+            let mut seen = std::collections::HashSet::new();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while seen.len() < FILES && Instant::now() < deadline {
+                match watcher.recv_timeout(Duration::from_millis(200)) {
+                    Ok(batch) => {
+                        let before = seen.len();
+                        seen.extend(batch.iter().filter_map(|event| {
+                            let name = event.path.file_name()?.to_str()?;
+                            name.starts_with("burst_").then(|| name.to_string())
+                        }));
+                        if seen.len() > before {
+                            batches += 1;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            drop(watcher);
+            assert_eq!(seen.len(), FILES, "every burst file should be reported");
+            assert!(
+                batches <= 2,
+                "expected the burst to arrive in at most 2 batches, got {batches}"
+            );
+        }
+
+        #[test]
+        fn ignore_self_drops_own_writes_but_keeps_external_ones() {
+            let temp_dir = tempdir().expect("failed to create tempdir");
+            let watched_root = temp_dir.path().canonicalize().expect("failed to canonicalize");
+            let watch_path = watched_root
+                .to_str()
+                .expect("tempdir path should be utf8")
+                .to_string();
+
+            let (_, watcher) = EventWatcher::spawn_with(
+                watch_path,
+                current_event_id(),
+                0.05,
+                WatcherFlags::default() | WatcherFlags::IGNORE_SELF,
+            );
+            std::thread::sleep(Duration::from_millis(500));
+
+            let own_file = watched_root.join("own_write.txt");
+            std::fs::write(&own_file, "cardinal").expect("failed to write test file");
+            let external_file = watched_root.join("external_write.txt");
+            let status = std::process::Command::new("/usr/bin/touch")
+                .arg(&external_file)
+                .status()
+                .expect("failed to run touch");
+            assert!(status.success());
+
+            let mut saw_own = false;
+            let mut saw_external = false;
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while Instant::now() < deadline {
+                match watcher.recv_timeout(Duration::from_millis(200)) {
+                    Ok(batch) => {
+                        saw_own |= batch.iter().any(|event| event.path == own_file);
+                        saw_external |= batch.iter().any(|event| event.path == external_file);
+                        // Keep draining briefly so a late own-write event would be caught.
+                        if saw_external {
+                            std::thread::sleep(Duration::from_millis(300));
+                            while let Ok(batch) = watcher.try_recv() {
+                                saw_own |= batch.iter().any(|event| event.path == own_file);
+                            }
+                            break;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            drop(watcher);
+            assert!(saw_external, "external write should be forwarded");
+            assert!(!saw_own, "write from this process should be ignored");
+        }
+
+        #[test]
+        fn watcher_only_reports_changes_inside_its_subtree() {
+            let temp_dir = tempdir().expect("failed to create tempdir");
+            let temp_root = temp_dir.path().canonicalize().expect("failed to canonicalize");
+            let watched_root = temp_root.join("watched");
+            let outside_root = temp_root.join("outside");
+            std::fs::create_dir(&watched_root).expect("failed to create watched dir");
+            std::fs::create_dir(&outside_root).expect("failed to create outside dir");
+            let watch_path = watched_root
+                .to_str()
+                .expect("tempdir path should be utf8")
+                .to_string();
+
+            let (_, watcher) = EventWatcher::spawn(watch_path, current_event_id(), 0.05);
+            std::thread::sleep(Duration::from_millis(500));
+
+            let outside_file = outside_root.join("outside.txt");
+            std::fs::write(&outside_file, "cardinal").expect("failed to write test file");
+            let inside_file = watched_root.join("inside.txt");
+            std::fs::write(&inside_file, "cardinal").expect("failed to write test file");
+
+            let mut saw_inside = false;
+            let mut stray = Vec::new();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !saw_inside && Instant::now() < deadline {
+                match watcher.recv_timeout(Duration::from_millis(200)) {
+                    Ok(batch) => {
+                        saw_inside |= batch.iter().any(|event| event.path == inside_file);
+                        stray.extend(
+                            batch
+                                .into_iter()
+                                .filter(|event| event.path.starts_with(&temp_root))
+                                .filter(|event| !event.path.starts_with(&watched_root))
+                                .map(|event| event.path),
+                        );
+                    }
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            drop(watcher);
+            assert!(saw_inside, "change inside the watched subtree should be reported");
+            assert!(stray.is_empty(), "changes outside the subtree were reported: {stray:?}");
+        }
     }
 
     // ========================================================================
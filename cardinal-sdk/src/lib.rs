@@ -9,6 +9,7 @@ mod utils;
 
 #[cfg(target_os = "linux")]
 mod linux;
+mod watcher_flags;
 #[cfg(target_os = "linux")]
 use linux as event;
 #[cfg(target_os = "linux")]
@@ -26,3 +27,4 @@ pub use objc2_core_services::FSEventStreamEventId;
 pub type FSEventStreamEventId = u64; // Use u64 as equivalent type for Linux
 pub use event_stream::{EventStream, EventWatcher};
 pub use utils::{current_event_id, event_id_to_timestamp};
+pub use watcher_flags::WatcherFlags;
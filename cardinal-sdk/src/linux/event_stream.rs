@@ -1,4 +1,4 @@
-use crate::{FsEvent, EventFlag};
+use crate::{FsEvent, EventFlag, WatcherFlags};
 use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
 use libc::dev_t;
 use nix::sys::inotify::{Inotify, InitFlags};
@@ -84,9 +84,8 @@ impl EventStream {
                             for event in events {
                                 event_id_counter += 1;
                                 if let Some(ref path) = event.name {
-                                    let path_str = path.to_string_lossy();
                                     let fs_event = FsEvent {
-                                        path: PathBuf::from(path_str.as_ref()),
+                                        path: PathBuf::from(path),
                                         flag: EventFlag::from_inotify_mask(event),
                                         id: event_id_counter,
                                     };
@@ -164,12 +163,36 @@ impl EventWatcher {
         path: String,
         since_event_id: u64,
         latency: f64,
+    ) -> (dev_t, EventWatcher) {
+        Self::spawn_with(path, since_event_id, latency, WatcherFlags::default())
+    }
+
+    /// 与 `spawn` 相同，但显式指定 `WatcherFlags`
+    ///
+    /// 注意：inotify 没有对应的创建标志，`flags` 在 Linux 下被忽略；
+    /// `latency` 仍然控制两次读取之间的等待时间，用于合并突发事件。
+    pub fn spawn_with(
+        path: String,
+        since_event_id: u64,
+        latency: f64,
+        flags: WatcherFlags,
+    ) -> (dev_t, EventWatcher) {
+        Self::spawn_paths(vec![path], since_event_id, latency, flags)
+    }
+
+    /// 与 `spawn_with` 相同，但同时监听多个路径，例如多根缓存的每个根
+    pub fn spawn_paths(
+        paths: Vec<String>,
+        since_event_id: u64,
+        latency: f64,
+        _flags: WatcherFlags, // 在 Linux 下忽略此参数
     ) -> (dev_t, EventWatcher) {
         let (cancellation_tx, cancellation_rx) = bounded::<()>(1);
         let (sender, receiver) = unbounded();
-        
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
         let stream = EventStream::new(
-            &[&path],
+            &paths,
             since_event_id,
             latency,
             Box::new(move |events| {
@@ -289,4 +312,135 @@ mod tests {
             "respawned watcher failed to deliver file change event"
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn higher_latency_coalesces_rapid_changes() {
+        let temp_dir = tempdir().expect("failed to create tempdir");
+        let watched_root = temp_dir.path().canonicalize().expect("failed to canonicalize");
+        let watch_path = watched_root
+            .to_str()
+            .expect("tempdir path should be utf8")
+            .to_string();
+
+        let (_, watcher) = EventWatcher::spawn_with(watch_path, 0, 0.5, WatcherFlags::default());
+        std::thread::sleep(Duration::from_millis(200));
+
+        const FILES: usize = 20;
+        for i in 0..FILES {
+            std::fs::write(watched_root.join(format!("burst_{i}.txt")), "cardinal")
+                .expect("failed to write test file");
+        }
+
+        let mut batches = 0;
+        let mut seen = std::collections::HashSet::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while seen.len() < FILES && Instant::now() < deadline {
+            match watcher.recv_timeout(Duration::from_millis(200)) {
+                Ok(batch) => {
+                    let before = seen.len();
+                    seen.extend(batch.iter().filter_map(|event| {
+                        let name = event.path.file_name()?.to_str()?;
+                        name.starts_with("burst_").then(|| name.to_string())
+                    }));
+                    if seen.len() > before {
+                        batches += 1;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        drop(watcher);
+        assert_eq!(seen.len(), FILES, "every burst file should be reported");
+        // 第一次 read 可能只拿到突发的开头，其余事件在 latency 之后一次性送达
+        assert!(
+            batches <= 3,
+            "expected the burst to be coalesced, got {batches} batches"
+        );
+    }
+
+    #[test]
+    fn watcher_only_reports_changes_inside_its_subtree() {
+        let temp_dir = tempdir().expect("failed to create tempdir");
+        let temp_root = temp_dir.path().canonicalize().expect("failed to canonicalize");
+        let watched_root = temp_root.join("watched");
+        let outside_root = temp_root.join("outside");
+        std::fs::create_dir(&watched_root).expect("failed to create watched dir");
+        std::fs::create_dir(&outside_root).expect("failed to create outside dir");
+        let watch_path = watched_root
+            .to_str()
+            .expect("tempdir path should be utf8")
+            .to_string();
+
+        let (_, watcher) = EventWatcher::spawn(watch_path, 0, 0.05);
+        std::thread::sleep(Duration::from_millis(200));
+
+        std::fs::write(outside_root.join("outside.txt"), "cardinal")
+            .expect("failed to write test file");
+        std::fs::write(watched_root.join("inside.txt"), "cardinal")
+            .expect("failed to write test file");
+
+        // inotify 事件只带文件名，按名字区分内外
+        let mut saw_inside = false;
+        let mut saw_outside = false;
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !saw_inside && Instant::now() < deadline {
+            match watcher.recv_timeout(Duration::from_millis(200)) {
+                Ok(batch) => {
+                    for event in &batch {
+                        saw_inside |= event.path.ends_with("inside.txt");
+                        saw_outside |= event.path.ends_with("outside.txt");
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        drop(watcher);
+        assert!(saw_inside, "change inside the watched subtree should be reported");
+        assert!(!saw_outside, "change outside the subtree should not be reported");
+    }
+
+    #[test]
+    fn watcher_reports_changes_under_every_path() {
+        let temp_dir = tempdir().expect("failed to create tempdir");
+        let temp_root = temp_dir.path().canonicalize().expect("failed to canonicalize");
+        let first_root = temp_root.join("first");
+        let second_root = temp_root.join("second");
+        std::fs::create_dir(&first_root).expect("failed to create first dir");
+        std::fs::create_dir(&second_root).expect("failed to create second dir");
+        let paths = [&first_root, &second_root]
+            .map(|root| root.to_str().expect("tempdir path should be utf8").to_string());
+
+        let (_, watcher) =
+            EventWatcher::spawn_paths(paths.to_vec(), 0, 0.05, WatcherFlags::default());
+        std::thread::sleep(Duration::from_millis(200));
+
+        std::fs::write(first_root.join("first.txt"), "cardinal")
+            .expect("failed to write test file");
+        std::fs::write(second_root.join("second.txt"), "cardinal")
+            .expect("failed to write test file");
+
+        let mut saw_first = false;
+        let mut saw_second = false;
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !(saw_first && saw_second) && Instant::now() < deadline {
+            match watcher.recv_timeout(Duration::from_millis(200)) {
+                Ok(batch) => {
+                    for event in &batch {
+                        saw_first |= event.path.ends_with("first.txt");
+                        saw_second |= event.path.ends_with("second.txt");
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        drop(watcher);
+        assert!(saw_first, "change under the first path should be reported");
+        assert!(saw_second, "change under the second path should be reported");
+    }
+}
@@ -15,6 +15,9 @@
 //!
 //! 4. **设备 ID 不可用**：`dev()` 返回 0 作为占位符。
 //!
+//! 5. **`WatcherFlags` 被忽略**：inotify 无法区分事件来源进程，`IGNORE_SELF`
+//!    不生效，本进程的写入同样会被转发。
+//!
 //! # 可用功能
 //!
 //! - ✅ 运行期间的实时文件事件监控
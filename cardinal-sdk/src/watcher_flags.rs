@@ -0,0 +1,57 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// Stream creation flags passed to [`EventWatcher::spawn_with`](crate::EventWatcher::spawn_with).
+    ///
+    /// The bits mirror `kFSEventStreamCreateFlag*` so they can be handed to
+    /// `FSEventStreamCreate` unchanged. The inotify backend always reports
+    /// per-file events for the watched root and ignores these flags.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct WatcherFlags: u32 {
+        /// Deliver the first event of a quiet period immediately instead of
+        /// waiting for `latency` to elapse.
+        const NO_DEFER = 0x0000_0002;
+        /// Report changes to the watched root itself (moves, deletion).
+        const WATCH_ROOT = 0x0000_0004;
+        /// Drop events caused by the current process.
+        const IGNORE_SELF = 0x0000_0008;
+        /// Report individual files instead of only their parent directories.
+        const FILE_EVENTS = 0x0000_0010;
+    }
+}
+
+impl Default for WatcherFlags {
+    /// The combination [`EventWatcher::spawn`](crate::EventWatcher::spawn) uses.
+    fn default() -> Self {
+        Self::NO_DEFER | Self::FILE_EVENTS | Self::WATCH_ROOT
+    }
+}
+
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+    use super::*;
+    use objc2_core_services::{
+        kFSEventStreamCreateFlagFileEvents, kFSEventStreamCreateFlagIgnoreSelf,
+        kFSEventStreamCreateFlagNoDefer, kFSEventStreamCreateFlagWatchRoot,
+    };
+
+    #[test]
+    fn bits_match_fsevents_constants() {
+        assert_eq!(
+            WatcherFlags::NO_DEFER.bits(),
+            kFSEventStreamCreateFlagNoDefer
+        );
+        assert_eq!(
+            WatcherFlags::WATCH_ROOT.bits(),
+            kFSEventStreamCreateFlagWatchRoot
+        );
+        assert_eq!(
+            WatcherFlags::IGNORE_SELF.bits(),
+            kFSEventStreamCreateFlagIgnoreSelf
+        );
+        assert_eq!(
+            WatcherFlags::FILE_EVENTS.bits(),
+            kFSEventStreamCreateFlagFileEvents
+        );
+    }
+}
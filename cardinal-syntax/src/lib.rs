@@ -125,10 +125,10 @@ fn optimize_or(parts: Vec<Expr>) -> Expr {
 /// Reorders expression parts by priority to optimize query evaluation.
 ///
 /// Priority levels (lower executes first):
-/// - 0: Scope filters (`infolder:`, `parent:`) - narrow search space first
+/// - 0: Scope filters (`infolder:`, `parent:`, `siblings:`) - narrow search space first
 /// - 1: Non-filter terms (words, phrases, boolean ops) - cheap string matching
 /// - 2: Generic filters (`ext:`, `type:`, `size:`, etc.) - moderate cost
-/// - 3: Tag filters (`tag:`) - expensive metadata access, runs last
+/// - 3: Tag, `uti:`, `broken:` and `flag:` filters - expensive per-node lookups, run last
 fn reorder_by_priority(parts: &mut Vec<Expr>) {
     if parts.len() <= 1 {
         return;
@@ -137,8 +137,8 @@ fn reorder_by_priority(parts: &mut Vec<Expr>) {
     let priority = |expr: &Expr| -> u8 {
         match expr {
             Expr::Term(Term::Filter(filter)) => match filter.kind {
-                FilterKind::InFolder | FilterKind::Parent => 0,
-                FilterKind::Tag => 3,
+                FilterKind::InFolder | FilterKind::Parent | FilterKind::Siblings => 0,
+                FilterKind::Tag | FilterKind::Uti | FilterKind::Broken | FilterKind::Flag => 3,
                 _ => 2,
             },
             _ => 1,
@@ -264,6 +264,21 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Folder));
     /// ```
     Folder,
+    /// Whole-filename match (`name:`). A quoted argument is taken literally,
+    /// wildcards included.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("name:\"report*.txt\"").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Name));
+    /// ```
+    Name,
+    /// Names starting with the given text, ignoring case (`startswith:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("startswith:a").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::StartsWith));
+    /// ```
+    StartsWith,
     /// Extension filter (`ext:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -271,6 +286,20 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Ext));
     /// ```
     Ext,
+    /// Files sharing the extension of a referenced file (`sameext:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("sameext:/Users/demo/a.jpg").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::SameExtension));
+    /// ```
+    SameExtension,
+    /// Files whose name has no extension (`noext:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("noext:").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NoExtension));
+    /// ```
+    NoExtension,
     /// File type categories (`type:` such as `type:picture`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -306,6 +335,20 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Exe));
     /// ```
     Exe,
+    /// Symlinks whose target doesn't exist (`broken:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("broken:").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Broken));
+    /// ```
+    Broken,
+    /// BSD file flags such as locked or hidden (`flag:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("flag:locked").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Flag));
+    /// ```
+    Flag,
     /// Size comparisons or ranges (`size:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -313,6 +356,27 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Size));
     /// ```
     Size,
+    /// Total size of the files anywhere below a folder (`dirsize:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("dirsize:>10gb").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::DirSize));
+    /// ```
+    DirSize,
+    /// File name length in characters (`namelen:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("namelen:>100").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NameLength));
+    /// ```
+    NameLength,
+    /// Number of entries anywhere below a folder (`recursivecount:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("recursivecount:>1000").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::RecursiveCount));
+    /// ```
+    RecursiveCount,
     /// Date modified (`dm:` / `datemodified:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -341,13 +405,41 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::DateRun));
     /// ```
     DateRun,
-    /// Restrict to direct children of a folder (`parent:`).
+    /// Modified after a reference file was (`newerthan:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("newerthan:/tmp/stamp").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NewerThan));
+    /// ```
+    NewerThan,
+    /// Modified before a reference file was (`olderthan:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("olderthan:/tmp/stamp").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::OlderThan));
+    /// ```
+    OlderThan,
+    /// Files neither modified nor opened within a span of time (`stale:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("stale:1y").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Stale));
+    /// ```
+    Stale,
+    /// Restrict to direct children of a folder (`parent:` / `indir:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
     /// let Expr::Term(Term::Filter(filter)) = parse_query("parent:/Users").unwrap().expr else { panic!() };
     /// assert!(matches!(filter.kind, FilterKind::Parent));
     /// ```
     Parent,
+    /// Other entries in the folder holding a reference path (`siblings:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("siblings:/Users/demo/report.pdf").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Siblings));
+    /// ```
+    Siblings,
     /// Restrict to descendants of a folder (`infolder:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -362,6 +454,20 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::NoSubfolders));
     /// ```
     NoSubfolders,
+    /// Match nodes whose full path starts with a string (`pathprefix:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("pathprefix:/Users/demo/Doc").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::PathPrefix));
+    /// ```
+    PathPrefix,
+    /// Match a glob against the full path (`path:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("path:*node_modules*").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Path));
+    /// ```
+    Path,
     /// Require a folder containing matching children (`child:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -509,6 +615,13 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::Tag));
     /// ```
     Tag,
+    /// Spotlight content type filter (`uti:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("uti:public.image").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Uti));
+    /// ```
+    Uti,
     /// Content search (`content:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -538,20 +651,35 @@ impl FilterKind {
         match lower.as_str() {
             "file" => FilterKind::File,
             "folder" => FilterKind::Folder,
+            "name" => FilterKind::Name,
+            "startswith" => FilterKind::StartsWith,
             "ext" => FilterKind::Ext,
+            "sameext" => FilterKind::SameExtension,
+            "noext" => FilterKind::NoExtension,
             "type" => FilterKind::Type,
             "audio" => FilterKind::Audio,
             "video" => FilterKind::Video,
             "doc" => FilterKind::Doc,
             "exe" => FilterKind::Exe,
+            "broken" => FilterKind::Broken,
+            "flag" => FilterKind::Flag,
             "size" => FilterKind::Size,
+            "dirsize" => FilterKind::DirSize,
+            "namelen" => FilterKind::NameLength,
+            "recursivecount" => FilterKind::RecursiveCount,
             "dm" | "datemodified" => FilterKind::DateModified,
             "dc" | "datecreated" => FilterKind::DateCreated,
             "da" | "dateaccessed" => FilterKind::DateAccessed,
             "dr" | "daterun" => FilterKind::DateRun,
-            "parent" => FilterKind::Parent,
+            "newerthan" => FilterKind::NewerThan,
+            "olderthan" => FilterKind::OlderThan,
+            "stale" => FilterKind::Stale,
+            "parent" | "indir" => FilterKind::Parent,
+            "siblings" => FilterKind::Siblings,
             "infolder" | "in" => FilterKind::InFolder,
             "nosubfolders" => FilterKind::NoSubfolders,
+            "pathprefix" => FilterKind::PathPrefix,
+            "path" => FilterKind::Path,
             "child" => FilterKind::Child,
             "attrib" => FilterKind::Attribute,
             "attribdupe" => FilterKind::AttributeDuplicate,
@@ -573,6 +701,7 @@ impl FilterKind {
             "bitdepth" => FilterKind::BitDepth,
             "case" => FilterKind::CaseSensitive,
             "tag" | "t" => FilterKind::Tag,
+            "uti" => FilterKind::Uti,
             "content" => FilterKind::Content,
             "nowholefilename" => FilterKind::NoWholeFilename,
             _ => FilterKind::Custom(name.to_string()),
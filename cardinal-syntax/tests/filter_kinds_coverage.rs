@@ -17,13 +17,22 @@ fn maps_known_filter_names() {
     let cases: &[(&str, FilterKind)] = &[
         ("file", FilterKind::File),
         ("folder", FilterKind::Folder),
+        ("name", FilterKind::Name),
+        ("startswith", FilterKind::StartsWith),
         ("ext", FilterKind::Ext),
+        ("sameext", FilterKind::SameExtension),
+        ("noext", FilterKind::NoExtension),
         ("type", FilterKind::Type),
         ("audio", FilterKind::Audio),
         ("video", FilterKind::Video),
         ("doc", FilterKind::Doc),
         ("exe", FilterKind::Exe),
+        ("broken", FilterKind::Broken),
+        ("flag", FilterKind::Flag),
         ("size", FilterKind::Size),
+        ("dirsize", FilterKind::DirSize),
+        ("namelen", FilterKind::NameLength),
+        ("recursivecount", FilterKind::RecursiveCount),
         ("dm", FilterKind::DateModified),
         ("datemodified", FilterKind::DateModified),
         ("dc", FilterKind::DateCreated),
@@ -32,9 +41,14 @@ fn maps_known_filter_names() {
         ("dateaccessed", FilterKind::DateAccessed),
         ("dr", FilterKind::DateRun),
         ("daterun", FilterKind::DateRun),
+        ("stale", FilterKind::Stale),
         ("parent", FilterKind::Parent),
+        ("indir", FilterKind::Parent),
+        ("siblings", FilterKind::Siblings),
         ("infolder", FilterKind::InFolder),
         ("nosubfolders", FilterKind::NoSubfolders),
+        ("pathprefix", FilterKind::PathPrefix),
+        ("path", FilterKind::Path),
         ("child", FilterKind::Child),
         ("attrib", FilterKind::Attribute),
         ("attribdupe", FilterKind::AttributeDuplicate),
@@ -55,6 +69,7 @@ fn maps_known_filter_names() {
         ("orientation", FilterKind::Orientation),
         ("bitdepth", FilterKind::BitDepth),
         ("case", FilterKind::CaseSensitive),
+        ("uti", FilterKind::Uti),
         ("content", FilterKind::Content),
         ("nowholefilename", FilterKind::NoWholeFilename),
     ];
@@ -1,6 +1,10 @@
 use crate::{
-    commands::{NodeInfoRequest, SearchJob, WatchConfigUpdate},
-    lifecycle::{AppLifecycleState, load_app_state, update_app_state},
+    FSE_WATCHER_FLAGS,
+    commands::{ExtensionHistogramRequest, NodeInfoRequest, SearchJob, WatchConfigUpdate},
+    lifecycle::{
+        AppLifecycleState, BACKGROUND_LOOP_RUNNING, INDEXING_PAUSED, load_app_state,
+        update_app_state,
+    },
     search_activity,
     window_controls::is_main_window_foreground,
 };
@@ -12,9 +16,10 @@ use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use rayon::spawn;
 use search_cache::{
-    HandleFSEError, SearchCache, SearchOptions, SearchOutcome, SearchResultNode, SlabIndex,
-    WalkData,
+    HandleFSEError, IndexStats, PartialWalk, SearchCache, SearchOptions, SearchOutcome,
+    SearchResultNode, SlabIndex, WalkData,
 };
+use search_cancel::CancellationToken;
 use serde::Serialize;
 use std::{
     path::{Path, PathBuf},
@@ -22,7 +27,7 @@ use std::{
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tauri::{AppHandle, Emitter};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -32,6 +37,41 @@ pub struct StatusBarUpdate {
     pub rescan_errors: usize,
 }
 
+/// Indexing phases, emitted as `background_event` next to the plain counts
+/// of `status_bar_update` so the frontend can tell a walk from a cache load or
+/// an event replay.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all_fields = "camelCase")]
+pub enum BackgroundEvent {
+    WalkStarted {
+        root: String,
+    },
+    WalkProgress {
+        scanned_files: usize,
+    },
+    CacheLoaded {
+        total_files: usize,
+    },
+    /// Events the watcher replays from the last indexed event id.
+    EventReplayProgress {
+        processed_events: usize,
+    },
+    /// The replay caught up; events from here on are live.
+    Ready,
+}
+
+pub trait BackgroundEventSink {
+    fn send_background_event(&self, event: BackgroundEvent);
+}
+
+impl BackgroundEventSink for AppHandle {
+    fn send_background_event(&self, event: BackgroundEvent) {
+        if let Err(err) = self.emit("background_event", event) {
+            error!("Failed to emit background_event: {err:?}");
+        }
+    }
+}
+
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct IconPayload {
@@ -39,14 +79,42 @@ pub struct IconPayload {
     pub icon: String,
 }
 
+/// Most icons one `icon_update` event carries unless `CARDINAL_MAX_ICONS_PER_EMIT`
+/// says otherwise. A fast scroll can queue thousands, and landing them all in
+/// one event stalls the webview.
+const DEFAULT_MAX_ICONS_PER_EMIT: usize = 256;
+
+pub fn max_icons_per_emit() -> usize {
+    std::env::var("CARDINAL_MAX_ICONS_PER_EMIT")
+        .ok()
+        .and_then(|limit| limit.parse().ok())
+        .filter(|&limit| limit > 0)
+        .unwrap_or(DEFAULT_MAX_ICONS_PER_EMIT)
+}
+
+/// Hands `icons` to `emit` in order, at most `max_per_emit` at a time.
+pub fn emit_icon_batches(
+    icons: Vec<IconPayload>,
+    max_per_emit: usize,
+    mut emit: impl FnMut(Vec<IconPayload>),
+) {
+    let mut icons = icons.into_iter().peekable();
+    while icons.peek().is_some() {
+        emit(icons.by_ref().take(max_per_emit.max(1)).collect());
+    }
+}
+
 pub struct BackgroundLoopChannels {
     pub finish_rx: Receiver<Sender<Option<SearchCache>>>,
     pub update_window_state_rx: Receiver<()>,
     pub search_rx: Receiver<SearchJob>,
     pub result_tx: Sender<Result<SearchOutcome>>,
     pub node_info_rx: Receiver<NodeInfoRequest>,
+    pub stats_rx: Receiver<Sender<IndexStats>>,
+    pub extension_histogram_rx: Receiver<ExtensionHistogramRequest>,
     pub icon_viewport_rx: Receiver<(u64, Vec<SlabIndex>)>,
     pub rescan_rx: Receiver<()>,
+    pub resume_indexing_rx: Receiver<()>,
     pub watch_config_rx: Receiver<WatchConfigUpdate>,
     pub icon_update_tx: Sender<IconPayload>,
 }
@@ -92,6 +160,23 @@ pub fn emit_status_bar_update(
     }
 }
 
+/// `<db>.wal`, the log of the changes made since `db_path` was last flushed.
+pub(crate) fn wal_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("wal")
+}
+
+/// Puts `next` in place of `cache` and starts its WAL afresh. The old log
+/// was recorded against the old roots; replaying it on the next launch would
+/// apply those changes, a logged rescan included, to the new ones.
+fn replace_cache(cache: &mut SearchCache, next: SearchCache, db_path: &Path) {
+    *cache = next;
+    let wal_path = wal_path(db_path);
+    let _ = std::fs::remove_file(&wal_path);
+    if let Err(err) = cache.enable_wal(&wal_path) {
+        warn!("Running without a WAL: {err:?}");
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn handle_watch_config_update(
     app_handle: &AppHandle,
@@ -100,6 +185,7 @@ fn handle_watch_config_update(
     event_watcher: &mut EventWatcher,
     watch_root: &mut String,
     fse_latency_secs: f64,
+    db_path: &Path,
     history_ready: &mut bool,
     processed_events: &mut usize,
 ) {
@@ -127,19 +213,33 @@ fn handle_watch_config_update(
     *history_ready = false;
     *processed_events = 0;
 
-    let Some(next_cache) = build_search_cache(app_handle, next_watch_root, &next_ignore_paths)
-    else {
-        info!("Watch config change cancelled, keeping existing state");
-        return;
+    let next_cache = match build_search_cache(app_handle, next_watch_root, &next_ignore_paths) {
+        Ok(PartialWalk {
+            cache,
+            complete: true,
+        }) => cache,
+        Ok(PartialWalk {
+            complete: false, ..
+        }) => {
+            info!("Watch config change cancelled, app quitting");
+            return;
+        }
+        Err(_) => {
+            // The old watcher is gone, so the kept index would silently go
+            // stale behind a "still indexing" status.
+            update_app_state(app_handle, AppLifecycleState::Error);
+            return;
+        }
     };
 
     emit_status_bar_update(app_handle, next_cache.get_total_files(), 0, 0);
-    *cache = next_cache;
+    replace_cache(cache, next_cache, db_path);
     *watch_root = next_watch_root.to_string();
-    *event_watcher = EventWatcher::spawn(
-        watch_root.to_string(),
-        cache.last_event_id(),
+    *event_watcher = EventWatcher::spawn_paths(
+        cache.watch_paths(),
+        cache.resume_event_id(),
         fse_latency_secs,
+        FSE_WATCHER_FLAGS,
     )
     .1;
     update_app_state(app_handle, AppLifecycleState::Updating);
@@ -189,7 +289,8 @@ fn handle_event_watcher_events(
     history_ready: &mut bool,
     processed_events: &mut usize,
 ) {
-    *processed_events += events.len();
+    let replaying = !*history_ready;
+    let snapshots = track_event_batch(app_handle, &events, history_ready, processed_events);
 
     emit_status_bar_update(
         app_handle,
@@ -197,20 +298,8 @@ fn handle_event_watcher_events(
         *processed_events,
         cache.rescan_count() as usize,
     );
-
-    let mut snapshots = Vec::with_capacity(events.len());
-    for event in events.iter() {
-        if event.flag == EventFlag::HistoryDone {
-            *history_ready = true;
-            update_app_state(app_handle, AppLifecycleState::Ready);
-        } else if *history_ready {
-            snapshots.push(EventSnapshot {
-                path: event.path.clone(),
-                event_id: event.id,
-                flag: event.flag,
-                timestamp: unix_timestamp_now(),
-            });
-        }
+    if replaying && *history_ready {
+        update_app_state(app_handle, AppLifecycleState::Ready);
     }
 
     let handle_result = cache.handle_fs_events(events);
@@ -229,6 +318,106 @@ fn handle_event_watcher_events(
     }
 }
 
+/// Drops the events for Cardinal's own cache files: the database, the `.tmp`
+/// file it is staged in and the WAL beside it. Flushing or logging would
+/// otherwise feed itself back into the index. Everything else the app writes,
+/// such as exported results, is indexed like any other file.
+fn without_cache_file_events(mut events: Vec<FsEvent>, db_path: &Path) -> Vec<FsEvent> {
+    let mut tmp_path = db_path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let wal_path = wal_path(db_path);
+    events.retain(|event| {
+        event.path != db_path && event.path.as_os_str() != tmp_path && event.path != wal_path
+    });
+    events
+}
+
+/// Most watcher events held while indexing is paused. Past this, replaying
+/// them costs more than a rescan, which is what resuming does instead.
+const MAX_PAUSED_EVENTS: usize = 100_000;
+/// How long the loop has to sit idle before summing folder sizes. Every
+/// slice of the rollup waits for it again, which spaces out its disk reads.
+const ROLLUP_IDLE_DELAY: Duration = Duration::from_millis(500);
+/// Longest the rollup keeps the loop busy at a time; a metadata batch that
+/// has started is finished first.
+const ROLLUP_SLICE: Duration = Duration::from_millis(50);
+
+/// Watcher events that arrived while indexing was paused.
+#[derive(Default)]
+struct PausedEvents {
+    events: Vec<FsEvent>,
+    overflowed: bool,
+}
+
+/// What resuming indexing has to catch up on.
+#[derive(Debug)]
+enum Resumed {
+    /// The held events, in arrival order.
+    Events(Vec<FsEvent>),
+    /// More than [`MAX_PAUSED_EVENTS`] arrived, and they were dropped.
+    Rescan,
+}
+
+impl PausedEvents {
+    fn hold(&mut self, events: Vec<FsEvent>) {
+        self.hold_up_to(events, MAX_PAUSED_EVENTS);
+    }
+
+    fn hold_up_to(&mut self, events: Vec<FsEvent>, max: usize) {
+        if self.overflowed {
+            return;
+        }
+        if self.events.len() + events.len() > max {
+            self.events = Vec::new();
+            self.overflowed = true;
+        } else {
+            self.events.extend(events);
+        }
+    }
+
+    fn resume(self) -> Resumed {
+        if self.overflowed {
+            Resumed::Rescan
+        } else {
+            Resumed::Events(self.events)
+        }
+    }
+}
+
+/// Counts a batch of watcher events and reports the replay phase: progress
+/// while the watcher replays history, `Ready` once its `HistoryDone` arrives.
+/// Returns the events past the replay, which are the live ones.
+fn track_event_batch(
+    sink: &impl BackgroundEventSink,
+    events: &[FsEvent],
+    history_ready: &mut bool,
+    processed_events: &mut usize,
+) -> Vec<EventSnapshot> {
+    *processed_events += events.len();
+    let replaying = !*history_ready;
+    let mut snapshots = Vec::with_capacity(events.len());
+    for event in events {
+        if event.flag == EventFlag::HistoryDone {
+            *history_ready = true;
+        } else if *history_ready {
+            snapshots.push(EventSnapshot {
+                path: event.path.clone(),
+                event_id: event.id,
+                flag: event.flag,
+                timestamp: unix_timestamp_now(),
+            });
+        }
+    }
+    if !*history_ready {
+        sink.send_background_event(BackgroundEvent::EventReplayProgress {
+            processed_events: *processed_events,
+        });
+    } else if replaying {
+        sink.send_background_event(BackgroundEvent::Ready);
+    }
+    snapshots
+}
+
 fn handle_icon_viewport_update(
     cache: &mut SearchCache,
     update: (u64, Vec<SlabIndex>),
@@ -292,23 +481,34 @@ pub fn run_background_event_loop(
         search_rx,
         result_tx,
         node_info_rx,
+        stats_rx,
+        extension_histogram_rx,
         icon_viewport_rx,
         rescan_rx,
+        resume_indexing_rx,
         watch_config_rx,
         icon_update_tx,
     } = channels;
     let mut processed_events = 0usize;
     let mut history_ready = load_app_state() == AppLifecycleState::Ready;
+    let mut paused_events = PausedEvents::default();
+
+    BACKGROUND_LOOP_RUNNING.store(true, Ordering::Relaxed);
 
     let mut window_is_foreground = true;
     let mut hide_flush_remaining_ticks: u8 = 0;
     // Hide flush is polled on a 10s ticker; idle flush shares the same tick.
     let flush_ticker = crossbeam_channel::tick(Duration::from_secs(10));
+    // Idle time after a search goes to folder rollups, run in short slices
+    // and stopped by that search's token as soon as a newer search arrives.
+    let mut rollup_token: Option<CancellationToken> = None;
+    let mut rollup_pending = true;
 
     loop {
         crossbeam_channel::select! {
             recv(finish_rx) -> tx => {
                 let tx = tx.expect("Finish channel closed");
+                BACKGROUND_LOOP_RUNNING.store(false, Ordering::Relaxed);
                 tx.send(Some(cache)).expect("Failed to send cache");
                 return;
             }
@@ -339,6 +539,7 @@ pub fn run_background_event_loop(
                 let opts = SearchOptions::from(options);
                 let payload = cache.search_with_options(&query, opts, cancellation_token);
                 result_tx.send(payload).expect("Failed to send result");
+                rollup_token = Some(cancellation_token.without_deadline());
             }
             recv(node_info_rx) -> request => {
                 let request = request.expect("Node info channel closed");
@@ -349,6 +550,21 @@ pub fn run_background_event_loop(
                 let node_info_results = cache.expand_file_nodes(&slab_indices);
                 let _ = response_tx.send(node_info_results);
             }
+            recv(stats_rx) -> response_tx => {
+                let response_tx = response_tx.expect("Stats channel closed");
+                let _ = response_tx.send(cache.stats());
+            }
+            recv(extension_histogram_rx) -> request => {
+                let ExtensionHistogramRequest { root, response_tx } =
+                    request.expect("Extension histogram channel closed");
+                let histogram = match root {
+                    Some(root) => cache
+                        .index_of_path(Path::new(&root))
+                        .map(|index| cache.extension_histogram(Some(index))),
+                    None => Some(cache.extension_histogram(None)),
+                };
+                let _ = response_tx.send(histogram);
+            }
             recv(icon_viewport_rx) -> update => {
                 let update = update.expect("Icon viewport channel closed");
                 handle_icon_viewport_update(&mut cache, update, &icon_update_tx);
@@ -356,6 +572,8 @@ pub fn run_background_event_loop(
             recv(rescan_rx) -> request => {
                 request.expect("Rescan channel closed");
                 info!("Manual rescan requested");
+                paused_events = PausedEvents::default();
+                rollup_pending = true;
                 perform_rescan(
                     app_handle,
                     &mut cache,
@@ -368,6 +586,8 @@ pub fn run_background_event_loop(
             }
             recv(watch_config_rx) -> update => {
                 let next_update = update.expect("Watch config channel closed");
+                paused_events = PausedEvents::default();
+                rollup_pending = true;
                 handle_watch_config_update(
                     app_handle,
                     next_update,
@@ -375,19 +595,98 @@ pub fn run_background_event_loop(
                     &mut event_watcher,
                     &mut watch_root,
                     fse_latency_secs,
+                    &db_path,
                     &mut history_ready,
                     &mut processed_events,
                 );
             }
+            recv(resume_indexing_rx) -> request => {
+                request.expect("Resume indexing channel closed");
+                rollup_pending = true;
+                match std::mem::take(&mut paused_events).resume() {
+                    Resumed::Events(events) if events.is_empty() => {}
+                    Resumed::Events(events) => {
+                        info!("Applying {} events held while paused", events.len());
+                        handle_event_watcher_events(
+                            app_handle,
+                            &mut cache,
+                            events,
+                            &mut history_ready,
+                            &mut processed_events,
+                        );
+                    }
+                    Resumed::Rescan => {
+                        info!("Too many events while paused, rescanning");
+                        perform_rescan(
+                            app_handle,
+                            &mut cache,
+                            &mut event_watcher,
+                            &watch_root,
+                            fse_latency_secs,
+                            &mut history_ready,
+                            &mut processed_events,
+                        );
+                    }
+                }
+            }
             recv(event_watcher) -> events => {
-                let events = events.expect("Event stream closed");
-                handle_event_watcher_events(
-                    app_handle,
-                    &mut cache,
-                    events,
-                    &mut history_ready,
-                    &mut processed_events,
-                );
+                let events =
+                    without_cache_file_events(events.expect("Event stream closed"), &db_path);
+                if events.is_empty() {
+                    continue;
+                }
+                if INDEXING_PAUSED.load(Ordering::Relaxed) {
+                    paused_events.hold(events);
+                } else {
+                    handle_event_watcher_events(
+                        app_handle,
+                        &mut cache,
+                        events,
+                        &mut history_ready,
+                        &mut processed_events,
+                    );
+                    // The folders above the changes lost their rollups.
+                    rollup_pending = true;
+                }
+            }
+            default(ROLLUP_IDLE_DELAY) => {
+                if rollup_pending
+                    && let Some(token) = rollup_token
+                    && !INDEXING_PAUSED.load(Ordering::Relaxed)
+                {
+                    // One slice, given up as soon as anything else wants the
+                    // loop; the next idle tick resumes where it stopped.
+                    let slice_end = Instant::now() + ROLLUP_SLICE;
+                    let loop_busy = || {
+                        !finish_rx.is_empty()
+                            || !update_window_state_rx.is_empty()
+                            || !flush_ticker.is_empty()
+                            || !search_rx.is_empty()
+                            || !node_info_rx.is_empty()
+                            || !stats_rx.is_empty()
+                            || !extension_histogram_rx.is_empty()
+                            || !icon_viewport_rx.is_empty()
+                            || !rescan_rx.is_empty()
+                            || !resume_indexing_rx.is_empty()
+                            || !watch_config_rx.is_empty()
+                            || !event_watcher.is_empty()
+                    };
+                    let finished = cache
+                        .spawn_size_rollup_while(|| {
+                            token.is_cancelled()?;
+                            (Instant::now() < slice_end
+                                && !INDEXING_PAUSED.load(Ordering::Relaxed)
+                                && !loop_busy())
+                            .then_some(())
+                        })
+                        .is_some();
+                    if finished {
+                        rollup_pending = false;
+                    } else if token.is_cancelled().is_none() {
+                        // A newer search is queued; it brings a fresh token.
+                        rollup_token = None;
+                    }
+                }
             }
         }
     }
@@ -397,15 +696,19 @@ pub(crate) fn build_search_cache(
     app_handle: &AppHandle,
     watch_root: &str,
     ignore_paths: &[PathBuf],
-) -> Option<SearchCache> {
+) -> Result<PartialWalk> {
     let path = PathBuf::from(watch_root);
     let walk_data = WalkData::new(
         &path,
         ignore_paths,
         false,
         Some(&crate::lifecycle::APP_QUIT),
-    );
+    )
+    .keep_partial(true);
     let walking_done = AtomicBool::new(false);
+    app_handle.send_background_event(BackgroundEvent::WalkStarted {
+        root: watch_root.to_string(),
+    });
 
     std::thread::scope(|s| {
         s.spawn(|| {
@@ -414,13 +717,15 @@ pub(crate) fn build_search_cache(
                 let files = walk_data.num_files.load(Ordering::Relaxed);
                 let total = dirs + files;
                 emit_status_bar_update(app_handle, total, 0, 0);
+                app_handle.send_background_event(BackgroundEvent::WalkProgress {
+                    scanned_files: total,
+                });
                 std::thread::sleep(Duration::from_millis(100));
             }
         });
-        let cache =
-            SearchCache::walk_fs_with_walk_data(&walk_data, Some(&crate::lifecycle::APP_QUIT));
+        let walk = SearchCache::walk_fs_cancellable(&walk_data, Some(&crate::lifecycle::APP_QUIT));
         walking_done.store(true, Ordering::Relaxed);
-        cache
+        walk.inspect_err(|err| error!("Failed to walk {watch_root}: {err:?}"))
     })
 }
 
@@ -444,7 +749,7 @@ fn perform_rescan(
     let mut phantom2 = Vec::new();
     let walk_data = cache.walk_data(&mut phantom1, &mut phantom2);
     let walking_done = AtomicBool::new(false);
-    let stopped = std::thread::scope(|s| {
+    let rescan = std::thread::scope(|s| {
         s.spawn(|| {
             while !walking_done.load(Ordering::Relaxed) {
                 let dirs = walk_data.num_dirs.load(Ordering::Relaxed);
@@ -455,18 +760,28 @@ fn perform_rescan(
             }
         });
         // If rescan is cancelled, we have nothing to do
-        let stopped = cache.rescan_with_walk_data(&walk_data).is_none();
+        let rescan = cache.rescan_with_walk_data(&walk_data);
         walking_done.store(true, Ordering::Relaxed);
-        stopped
+        rescan
     });
 
+    let stopped = match rescan {
+        Ok(rescanned) => rescanned.is_none(),
+        Err(err) => {
+            // The previous index is kept, but it can't be brought up to date.
+            error!("Rescan of {watch_root} failed: {err:?}");
+            update_app_state(app_handle, AppLifecycleState::Error);
+            return;
+        }
+    };
     *event_watcher = if stopped {
         EventWatcher::noop()
     } else {
-        EventWatcher::spawn(
-            watch_root.to_string(),
-            cache.last_event_id(),
+        EventWatcher::spawn_paths(
+            cache.watch_paths(),
+            cache.resume_event_id(),
             fse_latency_secs,
+            FSE_WATCHER_FLAGS,
         )
         .1
     };
@@ -599,6 +914,201 @@ mod tests {
         }
     }
 
+    #[test]
+    fn icon_floods_are_split_into_capped_emits() {
+        let icons = (0..1000)
+            .map(|i| IconPayload {
+                slab_index: SlabIndex::new(i),
+                icon: String::new(),
+            })
+            .collect();
+        let mut emits = Vec::new();
+        emit_icon_batches(icons, 256, |batch| emits.push(batch));
+
+        let sizes: Vec<_> = emits.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [256, 256, 256, 232]);
+        let order: Vec<_> = emits.iter().flatten().map(|icon| icon.slab_index).collect();
+        assert_eq!(order, (0..1000).map(SlabIndex::new).collect::<Vec<_>>());
+
+        let mut emits = 0;
+        emit_icon_batches(Vec::new(), 256, |_| emits += 1);
+        assert_eq!(emits, 0);
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: std::cell::RefCell<Vec<BackgroundEvent>>,
+    }
+
+    impl BackgroundEventSink for RecordingSink {
+        fn send_background_event(&self, event: BackgroundEvent) {
+            self.events.borrow_mut().push(event);
+        }
+    }
+
+    fn fs_event(path: &str, flag: EventFlag, id: u64) -> FsEvent {
+        FsEvent {
+            path: PathBuf::from(path),
+            flag,
+            id,
+        }
+    }
+
+    #[test]
+    fn event_batches_report_replay_then_ready_once() {
+        let sink = RecordingSink::default();
+        let mut history_ready = false;
+        let mut processed_events = 0;
+        let mut batch = |events: Vec<FsEvent>| {
+            track_event_batch(&sink, &events, &mut history_ready, &mut processed_events)
+                .into_iter()
+                .map(|snapshot| snapshot.event_id)
+                .collect::<Vec<_>>()
+        };
+
+        let replayed = vec![
+            fs_event("/a", EventFlag::ItemCreated, 1),
+            fs_event("/b", EventFlag::ItemModified, 2),
+        ];
+        assert!(batch(replayed).is_empty());
+        assert!(batch(vec![fs_event("/c", EventFlag::ItemRemoved, 3)]).is_empty());
+        // Only what follows `HistoryDone` in the batch is live.
+        let done = vec![
+            fs_event("/d", EventFlag::ItemCreated, 4),
+            fs_event("/", EventFlag::HistoryDone, 5),
+            fs_event("/e", EventFlag::ItemCreated, 6),
+        ];
+        assert_eq!(batch(done), vec![6]);
+        assert_eq!(
+            batch(vec![fs_event("/f", EventFlag::ItemCreated, 7)]),
+            vec![7]
+        );
+
+        assert_eq!(
+            sink.events.into_inner(),
+            vec![
+                BackgroundEvent::EventReplayProgress {
+                    processed_events: 2
+                },
+                BackgroundEvent::EventReplayProgress {
+                    processed_events: 3
+                },
+                BackgroundEvent::Ready,
+            ]
+        );
+        assert!(history_ready);
+        assert_eq!(processed_events, 7);
+    }
+
+    #[test]
+    fn events_held_while_paused_apply_on_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = SearchCache::walk_fs(dir.path());
+        let created = dir.path().join("while-paused.txt");
+        std::fs::write(&created, b"").unwrap();
+
+        let mut paused = PausedEvents::default();
+        paused.hold(vec![FsEvent {
+            path: created.clone(),
+            flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+            id: 1,
+        }]);
+        assert!(cache.index_of_path(&created).is_none());
+
+        let Resumed::Events(events) = paused.resume() else {
+            panic!("one event can't overflow");
+        };
+        assert_eq!(events.len(), 1);
+        cache.handle_fs_events(events).unwrap();
+        assert!(cache.index_of_path(&created).is_some());
+    }
+
+    #[test]
+    fn switching_roots_starts_a_fresh_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let old_root = dir.path().join("old");
+        let new_root = dir.path().join("new");
+        std::fs::create_dir(&old_root).unwrap();
+        std::fs::create_dir(&new_root).unwrap();
+        let db_path = dir.path().join("cardinal.db");
+        let created = |root: &Path, name: &str, id| {
+            let path = root.join(name);
+            std::fs::write(&path, b"").unwrap();
+            FsEvent {
+                path,
+                flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+                id,
+            }
+        };
+
+        let mut cache = SearchCache::walk_fs(&old_root);
+        cache.enable_wal(&wal_path(&db_path)).unwrap();
+        cache
+            .handle_fs_events(vec![created(&old_root, "old.txt", 1)])
+            .unwrap();
+
+        replace_cache(&mut cache, SearchCache::walk_fs(&new_root), &db_path);
+        cache.flush_snapshot_to_file(&db_path).unwrap();
+        let after_flush = created(&new_root, "after-flush.txt", 2);
+        let after_flush_path = after_flush.path.clone();
+        cache.handle_fs_events(vec![after_flush]).unwrap();
+        drop(cache);
+
+        let mut reloaded =
+            SearchCache::try_read_persistent_cache(&new_root, &db_path, &Vec::new(), None).unwrap();
+        assert!(reloaded.index_of_path(&after_flush_path).is_none());
+        assert!(reloaded.replay_wal(&wal_path(&db_path)).unwrap() > 0);
+        assert!(reloaded.index_of_path(&after_flush_path).is_some());
+        assert!(reloaded.index_of_path(&old_root.join("old.txt")).is_none());
+    }
+
+    #[test]
+    fn only_cache_file_events_are_dropped() {
+        let db_path = Path::new("/config/cardinal.db");
+        let events = vec![
+            fs_event("/config/cardinal.db", EventFlag::ItemModified, 1),
+            fs_event("/config/cardinal.db.tmp", EventFlag::ItemRenamed, 2),
+            fs_event("/config/cardinal.wal", EventFlag::ItemModified, 3),
+            fs_event("/Users/me/results.csv", EventFlag::ItemCreated, 4),
+            fs_event("/config/cardinal.db.bak", EventFlag::ItemCreated, 5),
+        ];
+        let kept: Vec<_> = without_cache_file_events(events, db_path)
+            .into_iter()
+            .map(|event| event.id)
+            .collect();
+        assert_eq!(kept, [4, 5]);
+    }
+
+    #[test]
+    fn paused_events_past_the_cap_call_for_a_rescan() {
+        let mut paused = PausedEvents::default();
+        paused.hold_up_to(vec![fs_event("/a", EventFlag::ItemCreated, 1)], 2);
+        paused.hold_up_to(
+            vec![
+                fs_event("/b", EventFlag::ItemCreated, 2),
+                fs_event("/c", EventFlag::ItemCreated, 3),
+            ],
+            2,
+        );
+        assert!(paused.events.is_empty());
+        // Later batches don't refill it.
+        paused.hold_up_to(vec![fs_event("/d", EventFlag::ItemCreated, 4)], 2);
+        assert!(matches!(paused.resume(), Resumed::Rescan));
+    }
+
+    #[test]
+    fn background_events_serialize_with_a_kind_tag() {
+        let json = |event| serde_json::to_value(event).unwrap();
+        assert_eq!(
+            json(BackgroundEvent::WalkProgress { scanned_files: 12 }),
+            serde_json::json!({ "kind": "WalkProgress", "scannedFiles": 12 })
+        );
+        assert_eq!(
+            json(BackgroundEvent::Ready),
+            serde_json::json!({ "kind": "Ready" })
+        );
+    }
+
     #[test]
     fn hide_flush_resets_idle_window() {
         let mut cache = FakeCache::default();
@@ -0,0 +1,61 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Why a command failed, sent to the frontend as `{ kind, message }` so it can
+/// react to the kind and show the message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum CommandError {
+    /// The background loop holding the index isn't answering, e.g. while the
+    /// app starts up or shuts down.
+    BackendNotReady(String),
+    /// The query couldn't be parsed or evaluated.
+    InvalidQuery(String),
+    /// A path passed to the command doesn't exist.
+    PathNotFound(String),
+    /// No application matches the one asked for.
+    ApplicationNotFound(String),
+    /// Reading or writing a file, or launching a process, failed.
+    Io(String),
+}
+
+impl CommandError {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::BackendNotReady(message)
+            | Self::InvalidQuery(message)
+            | Self::PathNotFound(message)
+            | Self::ApplicationNotFound(message)
+            | Self::Io(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_kind_and_message() {
+        let error = CommandError::PathNotFound("\"/missing\" does not exist".to_string());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "pathNotFound",
+                "message": "\"/missing\" does not exist",
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(CommandError::BackendNotReady(String::new())).unwrap()["kind"],
+            "backendNotReady"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
     DEFAULT_SYSTEM_IGNORE_PATH, LOGIC_START, LogicStartConfig,
-    lifecycle::load_app_state,
+    command_error::CommandError,
+    export::{ExportFormat, write_export},
+    lifecycle::{BACKGROUND_LOOP_RUNNING, INDEXING_PAUSED, load_app_state},
+    preferences::{PreferencesStore, SearchPreferences},
     search_activity,
     sort::{SortEntry, SortStatePayload, sort_entries},
     window_controls::{activate_main_window_impl, hide_main_window_impl, toggle_main_window_impl},
@@ -20,10 +23,20 @@ use base64::{Engine as _, engine::general_purpose};
 use camino::{Utf8Path as Path, Utf8PathBuf as PathBuf};
 use crossbeam_channel::{Receiver, Sender, bounded};
 use parking_lot::Mutex;
-use search_cache::{SearchOptions, SearchOutcome, SearchResultNode, SlabIndex, SlabNodeMetadata};
+use rayon::prelude::*;
+use search_cache::{
+    EmptyBehavior, IndexStats, SearchOptions, SearchOutcome, SearchResultNode, SlabIndex,
+    SlabNodeMetadata, ThreadBudget,
+};
 use search_cancel::CancellationToken;
 use serde::{Deserialize, Serialize};
-use std::{cell::LazyCell, process::Command};
+use std::{
+    cell::LazyCell,
+    collections::BTreeMap,
+    process::Command,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
 use tauri::{AppHandle, State};
 use tracing::{error, info, warn};
 
@@ -33,7 +46,9 @@ use objc2::{
     runtime::ProtocolObject,
 };
 #[cfg(target_os = "macos")]
-use objc2_app_kit::{NSPasteboard, NSPasteboardItem, NSPasteboardTypeString, NSPasteboardWriting};
+use objc2_app_kit::{
+    NSPasteboard, NSPasteboardItem, NSPasteboardTypeString, NSPasteboardWriting, NSWorkspace,
+};
 #[cfg(target_os = "macos")]
 use objc2_foundation::{NSArray, NSString, NSURL};
 #[cfg(target_os = "macos")]
@@ -45,16 +60,88 @@ pub struct WatchConfigUpdate {
     pub ignore_paths: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchOptionsPayload {
     #[serde(default)]
     pub case_insensitive: bool,
+    #[serde(default)]
+    pub unicode_case_fold: bool,
+    #[serde(default)]
+    pub accent_insensitive: bool,
+    #[serde(default)]
+    pub match_full_path: bool,
+    #[serde(default)]
+    pub space_is_literal: bool,
+    #[serde(default)]
+    pub sniff_content: bool,
+    #[serde(default)]
+    pub min_query_len: Option<usize>,
 }
 
 impl From<SearchOptionsPayload> for SearchOptions {
-    fn from(SearchOptionsPayload { case_insensitive }: SearchOptionsPayload) -> Self {
-        SearchOptions { case_insensitive }
+    fn from(
+        SearchOptionsPayload {
+            case_insensitive,
+            unicode_case_fold,
+            accent_insensitive,
+            match_full_path,
+            space_is_literal,
+            sniff_content,
+            min_query_len,
+        }: SearchOptionsPayload,
+    ) -> Self {
+        let defaults = SearchOptions::default();
+        SearchOptions {
+            case_insensitive,
+            unicode_case_fold,
+            accent_insensitive,
+            match_full_path,
+            space_is_literal,
+            sniff_content,
+            min_query_len: min_query_len.unwrap_or(defaults.min_query_len),
+            // A cleared search box lists the whole index.
+            empty_query: EmptyBehavior::MatchAll,
+            ..defaults
+        }
+    }
+}
+
+/// The options a `search` call passes: each field left out keeps the saved
+/// preference, so the frontend can send just the toggles it shows.
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchOptionsOverrides {
+    pub case_insensitive: Option<bool>,
+    pub unicode_case_fold: Option<bool>,
+    pub accent_insensitive: Option<bool>,
+    pub match_full_path: Option<bool>,
+    pub space_is_literal: Option<bool>,
+    pub sniff_content: Option<bool>,
+    pub min_query_len: Option<usize>,
+}
+
+impl SearchOptionsOverrides {
+    /// `defaults` with every field this sets replaced.
+    pub fn apply_to(self, defaults: SearchOptionsPayload) -> SearchOptionsPayload {
+        let Self {
+            case_insensitive,
+            unicode_case_fold,
+            accent_insensitive,
+            match_full_path,
+            space_is_literal,
+            sniff_content,
+            min_query_len,
+        } = self;
+        SearchOptionsPayload {
+            case_insensitive: case_insensitive.unwrap_or(defaults.case_insensitive),
+            unicode_case_fold: unicode_case_fold.unwrap_or(defaults.unicode_case_fold),
+            accent_insensitive: accent_insensitive.unwrap_or(defaults.accent_insensitive),
+            match_full_path: match_full_path.unwrap_or(defaults.match_full_path),
+            space_is_literal: space_is_literal.unwrap_or(defaults.space_is_literal),
+            sniff_content: sniff_content.unwrap_or(defaults.sniff_content),
+            min_query_len: min_query_len.or(defaults.min_query_len),
+        }
     }
 }
 
@@ -71,6 +158,13 @@ pub struct NodeInfoRequest {
     pub response_tx: Sender<Vec<SearchResultNode>>,
 }
 
+#[derive(Debug, Clone)]
+pub struct ExtensionHistogramRequest {
+    /// Folder to scope the counts to; the whole index when `None`.
+    pub root: Option<String>,
+    pub response_tx: Sender<Option<BTreeMap<String, usize>>>,
+}
+
 #[derive(Default)]
 struct SortedViewCache {
     slab_indices: Vec<SlabIndex>,
@@ -82,21 +176,28 @@ pub struct SearchState {
     result_rx: Receiver<Result<SearchOutcome>>,
 
     node_info_tx: Sender<NodeInfoRequest>,
+    stats_tx: Sender<Sender<IndexStats>>,
+    extension_histogram_tx: Sender<ExtensionHistogramRequest>,
 
     icon_viewport_tx: Sender<(u64, Vec<SlabIndex>)>,
     rescan_tx: Sender<()>,
+    resume_indexing_tx: Sender<()>,
     watch_config_tx: Sender<WatchConfigUpdate>,
     sorted_view_cache: Mutex<Option<SortedViewCache>>,
     pub(crate) update_window_state_tx: Sender<()>,
 }
 
 impl SearchState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         search_tx: Sender<SearchJob>,
         result_rx: Receiver<Result<SearchOutcome>>,
         node_info_tx: Sender<NodeInfoRequest>,
+        stats_tx: Sender<Sender<IndexStats>>,
+        extension_histogram_tx: Sender<ExtensionHistogramRequest>,
         icon_viewport_tx: Sender<(u64, Vec<SlabIndex>)>,
         rescan_tx: Sender<()>,
+        resume_indexing_tx: Sender<()>,
         watch_config_tx: Sender<WatchConfigUpdate>,
         update_window_state_tx: Sender<()>,
     ) -> Self {
@@ -104,17 +205,23 @@ impl SearchState {
             search_tx,
             result_rx,
             node_info_tx,
+            stats_tx,
+            extension_histogram_tx,
             icon_viewport_tx,
             rescan_tx,
+            resume_indexing_tx,
             watch_config_tx,
             sorted_view_cache: Mutex::new(None),
             update_window_state_tx,
         }
     }
 
-    fn request_nodes(&self, slab_indices: Vec<SlabIndex>) -> Vec<SearchResultNode> {
+    fn request_nodes(
+        &self,
+        slab_indices: Vec<SlabIndex>,
+    ) -> Result<Vec<SearchResultNode>, CommandError> {
         if slab_indices.is_empty() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
 
         let (response_tx, response_rx) = bounded::<Vec<SearchResultNode>>(1);
@@ -123,12 +230,59 @@ impl SearchState {
             response_tx,
         }) {
             error!("Failed to send node info request: {e:?}");
-            return Vec::new();
+            return Err(backend_not_ready());
         }
 
-        response_rx.recv().unwrap_or_else(|e| {
+        response_rx.recv().map_err(|e| {
             error!("Failed to receive node info results: {e:?}");
+            backend_not_ready()
+        })
+    }
+
+    fn run_search(
+        &self,
+        query: String,
+        options: SearchOptionsPayload,
+        version: u64,
+        timeout: Option<Duration>,
+    ) -> Result<SearchResponse, CommandError> {
+        let mut cancellation_token = CancellationToken::new(version);
+        if let Some(timeout) = timeout {
+            cancellation_token = cancellation_token.with_deadline(Instant::now() + timeout);
+        }
+        if let Err(e) = self.search_tx.send(SearchJob {
+            query,
+            options,
+            cancellation_token,
+        }) {
+            error!("Failed to send search request: {e:?}");
+            return Err(backend_not_ready());
+        }
+
+        let outcome = self.result_rx.recv().map_err(|e| {
+            error!("Failed to receive search result: {e:?}");
+            backend_not_ready()
+        })?;
+        let SearchOutcome {
+            nodes,
+            highlights,
+            below_min_length,
+            timed_out,
+            ..
+        } = outcome.map_err(|e| CommandError::InvalidQuery(format!("{e:#}")))?;
+        let results = nodes.unwrap_or_else(|| {
+            if timed_out {
+                warn!("Search {version} timed out");
+            } else {
+                info!("Search {version} was cancelled");
+            }
             Vec::new()
+        });
+        Ok(SearchResponse {
+            results,
+            highlights,
+            below_min_length,
+            timed_out,
         })
     }
 
@@ -146,7 +300,9 @@ impl SearchState {
             return cached;
         }
 
-        let nodes = self.request_nodes(slab_indices.to_vec());
+        let nodes = self
+            .request_nodes(slab_indices.to_vec())
+            .unwrap_or_default();
         *cache_guard = Some(SortedViewCache {
             slab_indices: slab_indices.to_vec(),
             nodes: nodes.clone(),
@@ -155,6 +311,10 @@ impl SearchState {
     }
 }
 
+fn backend_not_ready() -> CommandError {
+    CommandError::BackendNotReady("The search index isn't available".to_string())
+}
+
 /// Normalizes user-provided path input into an absolute path string.
 ///
 /// Expands a leading `~` component using the current `HOME` directory and rejects
@@ -219,12 +379,41 @@ pub struct NodeInfo {
     pub path: String,
     pub metadata: Option<NodeInfoMetadata>,
     pub icon: Option<String>,
+    /// Finder tags, only read when `include_tags` is requested.
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct SearchResponse {
     pub results: Vec<SlabIndex>,
     pub highlights: Vec<String>,
+    /// The query was too short to run; see `SearchOptionsPayload::min_query_len`.
+    pub below_min_length: bool,
+    /// The search ran past its `timeout_ms` and gave up; `results` is empty.
+    pub timed_out: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStatsResponse {
+    pub files: usize,
+    pub directories: usize,
+    pub symlinks: usize,
+    pub unique_names: usize,
+    pub name_pool_bytes: usize,
+}
+
+impl From<IndexStats> for IndexStatsResponse {
+    fn from(stats: IndexStats) -> Self {
+        Self {
+            files: stats.files,
+            directories: stats.directories,
+            symlinks: stats.symlinks,
+            unique_names: stats.unique_names,
+            name_pool_bytes: stats.name_pool_bytes,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -303,63 +492,48 @@ pub async fn toggle_quicklook(app_handle: AppHandle, items: Vec<QuickLookItemInp
 #[tauri::command]
 pub async fn search(
     query: String,
-    options: Option<SearchOptionsPayload>,
+    options: Option<SearchOptionsOverrides>,
     version: u64,
+    timeout_ms: Option<u64>,
     state: State<'_, SearchState>,
-) -> Result<SearchResponse, String> {
+    preferences: State<'_, PreferencesStore>,
+) -> Result<SearchResponse, CommandError> {
     search_activity::note_search_activity();
 
-    let options = options.unwrap_or_default();
-    let cancellation_token = CancellationToken::new(version);
-    if let Err(e) = state.search_tx.send(SearchJob {
-        query,
-        options,
-        cancellation_token,
-    }) {
-        error!("Failed to send search request: {e:?}");
-        return Ok(SearchResponse::default());
-    }
-
-    match state.result_rx.recv() {
-        Ok(res) => res,
-        Err(e) => {
-            error!("Failed to receive search result: {e:?}");
-            return Ok(SearchResponse::default());
-        }
-    }
-    .map(|SearchOutcome { nodes, highlights }| {
-        let results = match nodes {
-            Some(list) => list,
-            None => {
-                info!("Search {version} was cancelled");
-                Vec::new()
-            }
-        };
-        SearchResponse {
-            results,
-            highlights,
-        }
-    })
-    .map_err(|e| format!("Failed to process search result: {e:?}"))
+    let options = preferences.options_with(options.unwrap_or_default());
+    let timeout = timeout_ms.map(Duration::from_millis);
+    state.run_search(query, options, version, timeout)
 }
 
 #[tauri::command(async)]
 pub fn get_nodes_info(
     results: Vec<SlabIndex>,
     include_icons: Option<bool>,
+    include_tags: Option<bool>,
     state: State<'_, SearchState>,
-) -> Vec<NodeInfo> {
+) -> Result<Vec<NodeInfo>, CommandError> {
     if results.is_empty() {
-        return Vec::new();
+        return Ok(Vec::new());
     }
 
     let include_icons = include_icons.unwrap_or(true);
-    let nodes = state.request_nodes(results);
+    let nodes: Vec<(String, _)> = state
+        .request_nodes(results)?
+        .into_iter()
+        .map(|SearchResultNode { path, metadata, .. }| {
+            (path.to_string_lossy().into_owned(), metadata)
+        })
+        .collect();
+    let tags = if include_tags.unwrap_or(false) {
+        read_tags_for_paths(nodes.iter().map(|(path, _)| path.as_str()))
+    } else {
+        Vec::new()
+    };
+    let mut tags = tags.into_iter();
 
-    nodes
+    Ok(nodes
         .into_iter()
-        .map(|SearchResultNode { path, metadata }| {
-            let path = path.to_string_lossy().into_owned();
+        .map(|(path, metadata)| {
             let icon = if include_icons {
                 #[cfg(target_os = "macos")]
                 {
@@ -386,9 +560,39 @@ pub fn get_nodes_info(
                 path,
                 icon,
                 metadata: metadata.as_ref().map(NodeInfoMetadata::from_metadata),
+                tags: tags.next().flatten(),
             }
         })
-        .collect()
+        .collect())
+}
+
+/// Threads reading tag xattrs for one page of `get_nodes_info`, so a large
+/// page doesn't take over the global rayon pool searches run on.
+const TAG_READ_THREADS: usize = 4;
+
+/// Tags of each path, in order. `None` where the attribute couldn't be read.
+fn read_tags_for_paths<'p>(paths: impl IntoIterator<Item = &'p str>) -> Vec<Option<Vec<String>>> {
+    let paths: Vec<&str> = paths.collect();
+    ThreadBudget::threads(TAG_READ_THREADS).install(|| {
+        paths
+            .par_iter()
+            .map(|path| file_tags::read_tags_from_path(std::path::Path::new(path), false))
+            .collect()
+    })
+}
+
+/// Saves the paths of `results` to `path`, with size and mtime columns in the
+/// JSON and CSV formats.
+#[tauri::command(async)]
+pub fn export_results(
+    results: Vec<SlabIndex>,
+    path: String,
+    format: ExportFormat,
+    state: State<'_, SearchState>,
+) -> Result<(), CommandError> {
+    let nodes = state.request_nodes(results)?;
+    write_export(&nodes, std::path::Path::new(&path), format)
+        .map_err(|e| CommandError::Io(format!("{e:#}")))
 }
 
 #[tauri::command(async)]
@@ -421,11 +625,57 @@ pub fn update_icon_viewport(id: u64, viewport: Vec<SlabIndex>, state: State<'_,
     }
 }
 
+/// Index totals for diagnostics, or `None` if the background loop is gone.
+#[tauri::command(async)]
+pub fn get_index_stats(state: State<'_, SearchState>) -> Option<IndexStatsResponse> {
+    let (response_tx, response_rx) = bounded::<IndexStats>(1);
+    if let Err(e) = state.stats_tx.send(response_tx) {
+        error!("Failed to request index stats: {e:?}");
+        return None;
+    }
+    match response_rx.recv() {
+        Ok(stats) => Some(stats.into()),
+        Err(e) => {
+            error!("Failed to receive index stats: {e:?}");
+            None
+        }
+    }
+}
+
+/// Files per extension, for drawing a breakdown of the index or of `root`.
+/// `None` if `root` isn't indexed or the background loop is gone.
+#[tauri::command(async)]
+pub fn get_extension_histogram(
+    root: Option<String>,
+    state: State<'_, SearchState>,
+) -> Option<BTreeMap<String, usize>> {
+    let (response_tx, response_rx) = bounded::<Option<BTreeMap<String, usize>>>(1);
+    if let Err(e) = state
+        .extension_histogram_tx
+        .send(ExtensionHistogramRequest { root, response_tx })
+    {
+        error!("Failed to request extension histogram: {e:?}");
+        return None;
+    }
+    response_rx.recv().unwrap_or_else(|e| {
+        error!("Failed to receive extension histogram: {e:?}");
+        None
+    })
+}
+
 #[tauri::command]
 pub async fn get_app_status() -> String {
     load_app_state().as_str().to_string()
 }
 
+/// Whether [`trigger_rescan`] reaches a background loop. A walk that failed
+/// once the loop was running leaves it there to retry; one that failed at
+/// startup doesn't.
+#[tauri::command]
+pub async fn get_rescan_available() -> bool {
+    BACKGROUND_LOOP_RUNNING.load(Ordering::Relaxed)
+}
+
 #[tauri::command(async)]
 pub fn trigger_rescan(state: State<'_, SearchState>) {
     if let Err(e) = state.rescan_tx.send(()) {
@@ -433,6 +683,21 @@ pub fn trigger_rescan(state: State<'_, SearchState>) {
     }
 }
 
+/// Holds file system events back until [`resume_indexing`], so indexing stays
+/// off the disk during heavy work.
+#[tauri::command(async)]
+pub fn pause_indexing() {
+    INDEXING_PAUSED.store(true, Ordering::Relaxed);
+}
+
+#[tauri::command(async)]
+pub fn resume_indexing(state: State<'_, SearchState>) {
+    INDEXING_PAUSED.store(false, Ordering::Relaxed);
+    if let Err(e) = state.resume_indexing_tx.send(()) {
+        error!("Failed to resume indexing: {e:?}");
+    }
+}
+
 #[tauri::command(async)]
 pub fn set_watch_config(
     watch_root: String,
@@ -453,6 +718,22 @@ pub fn set_watch_config(
     }
 }
 
+#[tauri::command(async)]
+pub fn get_search_preferences(preferences: State<'_, PreferencesStore>) -> SearchPreferences {
+    preferences.get()
+}
+
+/// Saves `preferences` as the defaults for searches that don't pass options.
+#[tauri::command(async)]
+pub fn set_search_preferences(
+    preferences: SearchPreferences,
+    store: State<'_, PreferencesStore>,
+) -> Result<(), CommandError> {
+    store
+        .set(preferences)
+        .map_err(|e| CommandError::Io(format!("{e:#}")))
+}
+
 #[tauri::command]
 pub async fn open_in_finder(path: String) {
     if let Err(e) = Command::new("open").arg("-R").arg(&path).spawn() {
@@ -461,12 +742,99 @@ pub async fn open_in_finder(path: String) {
 }
 
 #[tauri::command]
-pub async fn open_path(path: String) {
-    if let Err(e) = Command::new("open").arg(&path).spawn() {
+pub async fn open_path(path: String) -> Result<(), CommandError> {
+    require_existing_path(&path)?;
+    Command::new("open").arg(&path).spawn().map_err(|e| {
         error!("Failed to open path: {e}");
+        CommandError::Io(e.to_string())
+    })?;
+    Ok(())
+}
+
+fn require_existing_path(path: &str) -> Result<(), CommandError> {
+    if Path::new(path).exists() {
+        Ok(())
+    } else {
+        Err(CommandError::PathNotFound(format!(
+            "{path:?} does not exist"
+        )))
     }
 }
 
+/// Opens `path` in a chosen application, given either its bundle identifier
+/// (`com.apple.TextEdit`) or the path of its `.app` bundle.
+#[tauri::command]
+pub async fn open_path_with(path: String, app: String) -> Result<(), CommandError> {
+    let app = OpenWithApp::parse(&app);
+    validate_open_with(&path, app)?;
+    match Command::new("open")
+        .args(open_with_args(&path, app))
+        .spawn()
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("Failed to open path with {app:?}: {e}");
+            Err(CommandError::Io(e.to_string()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpenWithApp<'a> {
+    BundleId(&'a str),
+    Path(&'a str),
+}
+
+impl<'a> OpenWithApp<'a> {
+    /// Bundle identifiers never contain `/`, so anything that does is a path.
+    fn parse(app: &'a str) -> Self {
+        let app = app.trim();
+        if app.contains('/') {
+            Self::Path(app)
+        } else {
+            Self::BundleId(app)
+        }
+    }
+}
+
+fn validate_open_with(path: &str, app: OpenWithApp<'_>) -> Result<(), CommandError> {
+    require_existing_path(path)?;
+    match app {
+        OpenWithApp::Path(app) if !Path::new(app).exists() => Err(
+            CommandError::ApplicationNotFound(format!("Application {app:?} does not exist")),
+        ),
+        OpenWithApp::BundleId(id) if id.is_empty() || !application_installed(id) => {
+            Err(CommandError::ApplicationNotFound(format!(
+                "No application with bundle identifier {id:?}"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Arguments for `open` that launch `path` in `app`.
+fn open_with_args<'a>(path: &'a str, app: OpenWithApp<'a>) -> [&'a str; 3] {
+    match app {
+        OpenWithApp::BundleId(id) => ["-b", id, path],
+        OpenWithApp::Path(app) => ["-a", app, path],
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn application_installed(bundle_id: &str) -> bool {
+    autoreleasepool(|_| unsafe {
+        NSWorkspace::sharedWorkspace()
+            .URLForApplicationWithBundleIdentifier(&NSString::from_str(bundle_id))
+            .is_some()
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn application_installed(_bundle_id: &str) -> bool {
+    // Bundle identifiers only exist on macOS.
+    false
+}
+
 #[tauri::command]
 pub async fn start_logic(watch_root: String, ignore_paths: Vec<String>) {
     if let Some(sender) = LOGIC_START.get() {
@@ -611,6 +979,250 @@ mod tests {
         );
     }
 
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn read_tags_for_paths_returns_tags_in_request_order() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let tagged = dir.path().join("tagged.txt");
+        let plain = dir.path().join("plain.txt");
+        std::fs::write(&tagged, b"x").unwrap();
+        std::fs::write(&plain, b"x").unwrap();
+        let tags: Vec<plist::Value> = ["Red", "Project"]
+            .iter()
+            .map(|tag| plist::Value::String(format!("{tag}\n0")))
+            .collect();
+        let mut data = Vec::new();
+        plist::Value::Array(tags)
+            .to_writer_binary(&mut data)
+            .expect("encode tags");
+        xattr::set(&tagged, "com.apple.metadata:_kMDItemUserTags", &data).expect("write tag xattr");
+
+        let paths = [plain.to_str().unwrap(), tagged.to_str().unwrap()];
+        assert_eq!(
+            read_tags_for_paths(paths),
+            vec![
+                Some(Vec::new()),
+                Some(vec!["Red".to_string(), "Project".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn open_with_args_name_the_app_by_bundle_id_or_path() {
+        let file = "/Users/demo/notes.txt";
+        assert_eq!(
+            open_with_args(file, OpenWithApp::parse("com.apple.TextEdit")),
+            ["-b", "com.apple.TextEdit", file]
+        );
+        assert_eq!(
+            open_with_args(file, OpenWithApp::parse(" /Applications/TextEdit.app ")),
+            ["-a", "/Applications/TextEdit.app", file]
+        );
+    }
+
+    #[test]
+    fn validate_open_with_requires_the_file_and_the_app() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, b"x").unwrap();
+        let app = dir.path().join("Editor.app");
+        std::fs::create_dir(&app).unwrap();
+        let (file, app) = (file.to_str().unwrap(), app.to_str().unwrap());
+        let missing = dir.path().join("missing").to_str().unwrap().to_string();
+
+        assert!(validate_open_with(file, OpenWithApp::Path(app)).is_ok());
+        let err = validate_open_with(&missing, OpenWithApp::Path(app)).unwrap_err();
+        assert!(matches!(err, CommandError::PathNotFound(_)), "{err:?}");
+        assert!(err.to_string().contains("does not exist"), "{err}");
+        let err = validate_open_with(file, OpenWithApp::Path(&missing)).unwrap_err();
+        assert!(
+            matches!(err, CommandError::ApplicationNotFound(_)),
+            "{err:?}"
+        );
+        let err = validate_open_with(file, OpenWithApp::BundleId("")).unwrap_err();
+        assert!(
+            matches!(err, CommandError::ApplicationNotFound(_)),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains("bundle identifier"), "{err}");
+        assert!(matches!(
+            validate_open_with(file, OpenWithApp::BundleId("invalid.example.no-such-app")),
+            Err(CommandError::ApplicationNotFound(_))
+        ));
+    }
+
+    /// The ends of a [`SearchState`]'s channels the background loop would
+    /// hold. Dropping them makes the state look like a loop that went away.
+    struct Backend {
+        search_rx: Receiver<SearchJob>,
+        result_tx: Sender<Result<SearchOutcome>>,
+        node_info_rx: Receiver<NodeInfoRequest>,
+    }
+
+    fn search_state() -> (SearchState, Backend) {
+        let (search_tx, search_rx) = bounded(1);
+        let (result_tx, result_rx) = bounded(1);
+        let (node_info_tx, node_info_rx) = bounded(1);
+        let state = SearchState::new(
+            search_tx,
+            result_rx,
+            node_info_tx,
+            bounded(1).0,
+            bounded(1).0,
+            bounded(1).0,
+            bounded(1).0,
+            bounded(1).0,
+            bounded(1).0,
+            bounded(1).0,
+        );
+        let backend = Backend {
+            search_rx,
+            result_tx,
+            node_info_rx,
+        };
+        (state, backend)
+    }
+
+    #[test]
+    fn search_without_a_backend_is_not_ready() {
+        let (state, backend) = search_state();
+        drop(backend);
+        let err = state
+            .run_search("foo".to_string(), SearchOptionsPayload::default(), 1, None)
+            .err();
+        assert!(
+            matches!(err, Some(CommandError::BackendNotReady(_))),
+            "{err:?}"
+        );
+
+        // The job is taken but no answer ever comes back.
+        let (state, backend) = search_state();
+        let Backend {
+            search_rx,
+            result_tx,
+            ..
+        } = backend;
+        let loop_thread = std::thread::spawn(move || {
+            let _ = search_rx.recv();
+            drop(result_tx);
+        });
+        let err = state
+            .run_search("foo".to_string(), SearchOptionsPayload::default(), 1, None)
+            .err();
+        assert!(
+            matches!(err, Some(CommandError::BackendNotReady(_))),
+            "{err:?}"
+        );
+        loop_thread.join().unwrap();
+    }
+
+    #[test]
+    fn search_reports_query_errors_as_invalid_query() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        std::fs::write(dir.path().join("notes.txt"), b"x").unwrap();
+        let (state, backend) = search_state();
+        let loop_thread = std::thread::spawn(move || {
+            let mut cache = search_cache::SearchCache::walk_fs(dir.path());
+            for _ in 0..2 {
+                let SearchJob {
+                    query,
+                    options,
+                    cancellation_token,
+                } = backend.search_rx.recv().unwrap();
+                let outcome = cache.search_with_options(&query, options.into(), cancellation_token);
+                backend.result_tx.send(outcome).unwrap();
+            }
+        });
+
+        let response = state
+            .run_search(
+                "notes".to_string(),
+                SearchOptionsPayload::default(),
+                1,
+                None,
+            )
+            .unwrap();
+        assert_eq!(response.results.len(), 1);
+        let err = state
+            .run_search(
+                "size:".to_string(),
+                SearchOptionsPayload::default(),
+                2,
+                None,
+            )
+            .err();
+        assert!(
+            matches!(err, Some(CommandError::InvalidQuery(_))),
+            "{err:?}"
+        );
+        loop_thread.join().unwrap();
+    }
+
+    #[test]
+    fn search_past_its_deadline_times_out() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        // Enough bytes that `content:` can't scan them all within the deadline.
+        for i in 0..64 {
+            std::fs::write(
+                dir.path().join(format!("large{i}.bin")),
+                vec![b'x'; 1 << 20],
+            )
+            .unwrap();
+        }
+        let (state, backend) = search_state();
+        let loop_thread = std::thread::spawn(move || {
+            let mut cache = search_cache::SearchCache::walk_fs(dir.path());
+            let SearchJob {
+                query,
+                options,
+                cancellation_token,
+            } = backend.search_rx.recv().unwrap();
+            let outcome = cache.search_with_options(&query, options.into(), cancellation_token);
+            backend.result_tx.send(outcome).unwrap();
+        });
+
+        let response = state
+            .run_search(
+                "content:needle".to_string(),
+                SearchOptionsPayload::default(),
+                3,
+                Some(Duration::from_millis(1)),
+            )
+            .unwrap();
+        assert!(response.timed_out);
+        assert!(response.results.is_empty());
+        loop_thread.join().unwrap();
+    }
+
+    #[test]
+    fn node_info_without_a_backend_is_not_ready() {
+        let (state, backend) = search_state();
+        assert!(state.request_nodes(Vec::new()).unwrap().is_empty());
+        drop(backend);
+        let err = state.request_nodes(vec![SlabIndex::new(0)]).err();
+        assert!(
+            matches!(err, Some(CommandError::BackendNotReady(_))),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn open_path_requires_an_existing_path() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let missing = dir.path().join("missing").to_str().unwrap().to_string();
+        let err = tauri::async_runtime::block_on(open_path(missing.clone())).err();
+        assert!(
+            matches!(err, Some(CommandError::PathNotFound(_))),
+            "{err:?}"
+        );
+        let err =
+            tauri::async_runtime::block_on(open_path_with(missing, "/Applications".into())).err();
+        assert!(
+            matches!(err, Some(CommandError::PathNotFound(_))),
+            "{err:?}"
+        );
+    }
+
     #[test]
     fn normalize_rejects_relative_paths_and_tilde_users() {
         assert_eq!(normalize_path_input("relative/path"), None);
@@ -0,0 +1,212 @@
+use anyhow::{Context, Result};
+use search_cache::SearchResultNode;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+/// File layout for [`write_export`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// One path per line.
+    PlainText,
+    /// An array of `{ path, size, mtime }` objects.
+    Json,
+    /// `path,size,mtime` rows under a header line.
+    Csv,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportRow {
+    path: String,
+    /// `None` when the metadata couldn't be read.
+    size: Option<i64>,
+    /// Seconds since the Unix epoch.
+    mtime: Option<u32>,
+}
+
+impl ExportRow {
+    fn new(node: &SearchResultNode) -> Self {
+        let metadata = node.metadata.as_ref();
+        Self {
+            path: node.path.to_string_lossy().into_owned(),
+            size: metadata.as_ref().map(|metadata| metadata.size()),
+            mtime: metadata
+                .and_then(|metadata| metadata.mtime())
+                .map(|x| x.get()),
+        }
+    }
+}
+
+/// Writes `nodes` to `path` in `format`. The file is written next to `path`
+/// first and renamed over it, so a failed export leaves any old file intact.
+pub fn write_export(nodes: &[SearchResultNode], path: &Path, format: ExportFormat) -> Result<()> {
+    let rows: Vec<ExportRow> = nodes.iter().map(ExportRow::new).collect();
+    let contents = match format {
+        ExportFormat::PlainText => rows.iter().fold(String::new(), |mut out, row| {
+            out.push_str(&row.path);
+            out.push('\n');
+            out
+        }),
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+        ExportFormat::Csv => {
+            let mut out = String::from("path,size,mtime\n");
+            for row in &rows {
+                let field = |value: Option<String>| value.unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "{},{},{}",
+                    csv_field(&row.path),
+                    field(row.size.map(|size| size.to_string())),
+                    field(row.mtime.map(|mtime| mtime.to_string())),
+                );
+            }
+            out
+        }
+    };
+
+    let temp = temp_path(path)?;
+    std::fs::write(&temp, contents)
+        .with_context(|| format!("Failed to write export to {temp:?}"))?;
+    std::fs::rename(&temp, path).map_err(|err| {
+        let _ = std::fs::remove_file(&temp);
+        anyhow::Error::new(err).context(format!("Failed to move export into place at {path:?}"))
+    })
+}
+
+/// A hidden sibling of `path`, so the final rename stays on one file system.
+fn temp_path(path: &Path) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Export path {path:?} has no file name"))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    Ok(path.with_file_name(temp_name))
+}
+
+/// Quotes `value` if it holds a comma, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use search_cache::SlabNodeMetadataCompact;
+    use tempfile::tempdir;
+
+    fn node(path: PathBuf) -> SearchResultNode {
+        let metadata = std::fs::symlink_metadata(&path)
+            .map(|metadata| SlabNodeMetadataCompact::some(metadata.into()))
+            .unwrap_or_else(|_| SlabNodeMetadataCompact::unaccessible());
+        SearchResultNode {
+            path,
+            metadata,
+            index_seq: 1,
+        }
+    }
+
+    /// Splits one CSV line written by [`csv_field`] back into fields.
+    fn parse_csv_line(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    fields.last_mut().unwrap().push('"');
+                }
+                '"' => quoted = !quoted,
+                ',' if !quoted => fields.push(String::new()),
+                c => fields.last_mut().unwrap().push(c),
+            }
+        }
+        fields
+    }
+
+    #[test]
+    fn exports_round_trip_in_every_format() {
+        let dir = tempdir().unwrap();
+        let plain = dir.path().join("report.txt");
+        let awkward = dir.path().join("a, \"quoted\" name.md");
+        std::fs::write(&plain, b"12345").unwrap();
+        std::fs::write(&awkward, b"").unwrap();
+        let gone = dir.path().join("gone.txt");
+        let nodes = [
+            node(plain.clone()),
+            node(awkward.clone()),
+            node(gone.clone()),
+        ];
+        let mtime = |path: &Path| {
+            std::fs::metadata(path)
+                .unwrap()
+                .modified()
+                .unwrap()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                .to_string()
+        };
+        let out = dir.path().join("out");
+
+        write_export(&nodes, &out, ExportFormat::PlainText).unwrap();
+        let text = std::fs::read_to_string(&out).unwrap();
+        let lines: Vec<PathBuf> = text.lines().map(PathBuf::from).collect();
+        assert_eq!(lines, [plain.clone(), awkward.clone(), gone.clone()]);
+
+        write_export(&nodes, &out, ExportFormat::Json).unwrap();
+        let rows: Vec<ExportRow> = serde_json::from_slice(&std::fs::read(&out).unwrap()).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(PathBuf::from(&rows[0].path), plain);
+        assert_eq!(rows[0].size, Some(5));
+        assert_eq!(rows[0].mtime.map(|x| x.to_string()), Some(mtime(&plain)));
+        assert_eq!(PathBuf::from(&rows[1].path), awkward);
+        assert_eq!(rows[1].size, Some(0));
+        assert_eq!((rows[2].size, rows[2].mtime), (None, None));
+
+        write_export(&nodes, &out, ExportFormat::Csv).unwrap();
+        let csv = std::fs::read_to_string(&out).unwrap();
+        let rows: Vec<Vec<String>> = csv.lines().map(parse_csv_line).collect();
+        assert_eq!(rows[0], ["path", "size", "mtime"]);
+        let path_string = |path: &Path| path.to_string_lossy().into_owned();
+        assert_eq!(rows[1], [path_string(&plain), "5".into(), mtime(&plain)]);
+        assert_eq!(
+            rows[2],
+            [path_string(&awkward), "0".into(), mtime(&awkward)]
+        );
+        assert_eq!(rows[3], [path_string(&gone), String::new(), String::new()]);
+        assert_eq!(rows.len(), 4);
+
+        // Only the export itself is left behind, no temp file.
+        let mut entries: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        entries.sort();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.contains(&"out".into()));
+    }
+
+    #[test]
+    fn failed_export_leaves_no_temp_file() {
+        let dir = tempdir().unwrap();
+        assert!(write_export(&[], &dir.path().join("missing/out"), ExportFormat::Json).is_err());
+
+        // Renaming over a non-empty folder fails after the temp file is written.
+        let occupied = dir.path().join("occupied");
+        std::fs::create_dir(&occupied).unwrap();
+        std::fs::write(occupied.join("keep"), b"").unwrap();
+        assert!(write_export(&[], &occupied, ExportFormat::PlainText).is_err());
+        assert!(occupied.join("keep").exists());
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+}
@@ -1,6 +1,9 @@
 mod background;
+mod command_error;
 mod commands;
+mod export;
 mod lifecycle;
+mod preferences;
 #[cfg(target_os = "macos")]
 mod quicklook;
 #[cfg(target_os = "linux")]
@@ -11,23 +14,30 @@ mod window_controls;
 
 use anyhow::{Context, Result};
 use background::{
-    BackgroundLoopChannels, IconPayload, build_search_cache, emit_status_bar_update,
-    run_background_event_loop,
+    BackgroundEvent, BackgroundEventSink, BackgroundLoopChannels, IconPayload, build_search_cache,
+    emit_icon_batches, emit_status_bar_update, max_icons_per_emit, run_background_event_loop,
+    wal_path,
 };
-use cardinal_sdk::EventWatcher;
+use cardinal_sdk::{EventWatcher, WatcherFlags};
 use commands::{
-    NodeInfoRequest, SearchJob, SearchState, WatchConfigUpdate, activate_main_window,
-    close_quicklook, copy_files_to_clipboard, get_app_status, get_nodes_info, get_sorted_view,
-    hide_main_window, normalize_watch_config, open_in_finder, open_path, search,
-    set_tray_activation_policy, set_watch_config, start_logic, toggle_main_window,
-    toggle_quicklook, trigger_rescan, update_icon_viewport, update_quicklook,
+    ExtensionHistogramRequest, NodeInfoRequest, SearchJob, SearchState, WatchConfigUpdate,
+    activate_main_window, close_quicklook, copy_files_to_clipboard, export_results, get_app_status,
+    get_extension_histogram, get_index_stats, get_nodes_info, get_rescan_available,
+    get_search_preferences, get_sorted_view, hide_main_window, normalize_watch_config,
+    open_in_finder, open_path, open_path_with, pause_indexing, resume_indexing, search,
+    set_search_preferences, set_tray_activation_policy, set_watch_config, start_logic,
+    toggle_main_window, toggle_quicklook, trigger_rescan, update_icon_viewport, update_quicklook,
 };
 use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, bounded, unbounded};
 use lifecycle::{
-    APP_QUIT, AppLifecycleState, EXIT_REQUESTED, emit_app_state, load_app_state, update_app_state,
+    APP_QUIT, AppLifecycleState, CACHE_PENDING, EXIT_REQUESTED, emit_app_state, load_app_state,
+    update_app_state,
 };
 use once_cell::sync::OnceCell;
-use search_cache::{SearchCache, SearchOutcome, SlabIndex};
+use preferences::{PREFERENCES_FILE, PreferencesStore};
+use search_cache::{
+    CacheError, IndexStats, PartialWalk, SearchCache, SearchOutcome, SlabIndex, WalkError,
+};
 use std::{
     path::{Path, PathBuf},
     sync::{Once, atomic::Ordering},
@@ -42,6 +52,12 @@ static DB_PATH: OnceCell<PathBuf> = OnceCell::new();
 pub(crate) static LOGIC_START: OnceCell<Sender<LogicStartConfig>> = OnceCell::new();
 pub(crate) const DEFAULT_SYSTEM_IGNORE_PATH: &str = "/System/Volumes/Data";
 const FSE_LATENCY_SECS: f64 = 0.1;
+/// The default stream flags. `IGNORE_SELF` is left out so files the app
+/// writes, like exports, are indexed; the cache's own writes are dropped by
+/// path in the background loop instead.
+pub(crate) const FSE_WATCHER_FLAGS: WatcherFlags = WatcherFlags::NO_DEFER
+    .union(WatcherFlags::FILE_EVENTS)
+    .union(WatcherFlags::WATCH_ROOT);
 
 #[derive(Debug, Clone)]
 pub(crate) struct LogicStartConfig {
@@ -62,8 +78,11 @@ pub fn run() -> Result<()> {
     let (search_tx, search_rx) = unbounded::<SearchJob>();
     let (result_tx, result_rx) = unbounded::<Result<SearchOutcome>>();
     let (node_info_tx, node_info_rx) = unbounded::<NodeInfoRequest>();
+    let (stats_tx, stats_rx) = unbounded::<Sender<IndexStats>>();
+    let (extension_histogram_tx, extension_histogram_rx) = unbounded::<ExtensionHistogramRequest>();
     let (icon_viewport_tx, icon_viewport_rx) = unbounded::<(u64, Vec<SlabIndex>)>();
     let (rescan_tx, rescan_rx) = unbounded::<()>();
+    let (resume_indexing_tx, resume_indexing_rx) = unbounded::<()>();
     let (watch_config_tx, watch_config_rx) = unbounded::<WatchConfigUpdate>();
     let (icon_update_tx, icon_update_rx) = unbounded::<IconPayload>();
     let (update_window_state_tx, update_window_state_rx) = bounded::<()>(1);
@@ -124,21 +143,38 @@ pub fn run() -> Result<()> {
             search_tx,
             result_rx,
             node_info_tx,
+            stats_tx,
+            extension_histogram_tx,
             icon_viewport_tx.clone(),
             rescan_tx.clone(),
+            resume_indexing_tx,
             watch_config_tx.clone(),
             update_window_state_tx.clone(),
         ))
+        .setup(|app| {
+            let path = app.path().app_config_dir()?.join(PREFERENCES_FILE);
+            app.manage(PreferencesStore::load(path));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             search,
             get_nodes_info,
+            export_results,
+            get_index_stats,
+            get_extension_histogram,
             get_sorted_view,
             update_icon_viewport,
             get_app_status,
+            get_rescan_available,
             trigger_rescan,
+            pause_indexing,
+            resume_indexing,
             set_watch_config,
+            get_search_preferences,
+            set_search_preferences,
             open_in_finder,
             open_path,
+            open_path_with,
             toggle_quicklook,
             close_quicklook,
             update_quicklook,
@@ -162,8 +198,11 @@ pub fn run() -> Result<()> {
         search_rx,
         result_tx,
         node_info_rx,
+        stats_rx,
+        extension_histogram_rx,
         icon_viewport_rx,
         rescan_rx,
+        resume_indexing_rx,
         watch_config_rx,
         icon_update_tx,
         update_window_state_rx,
@@ -172,12 +211,15 @@ pub fn run() -> Result<()> {
     let icon_update_rx = &icon_update_rx;
     std::thread::scope(move |s| {
         s.spawn(|| {
+            let max_per_emit = max_icons_per_emit();
             while let Ok(icon) = icon_update_rx.recv() {
                 let mut icons = vec![icon];
                 std::thread::sleep(Duration::from_millis(100));
                 icons.extend(icon_update_rx.try_iter());
                 info!("emitting {} icons", icons.len());
-                app_handle.emit("icon_update", icons).unwrap();
+                emit_icon_batches(icons, max_per_emit, |batch| {
+                    app_handle.emit("icon_update", batch).unwrap();
+                });
             }
             info!("icon update thread exited");
         });
@@ -245,6 +287,7 @@ fn run_logic_thread(
     };
     let path = PathBuf::from(&watch_root);
     let ignore_paths: Vec<_> = ignore_paths.into_iter().map(PathBuf::from).collect();
+    let wal_path = wal_path(db_path);
 
     let mut cache = match SearchCache::try_read_persistent_cache(
         &path,
@@ -252,34 +295,76 @@ fn run_logic_thread(
         &ignore_paths,
         Some(&APP_QUIT),
     ) {
-        Ok(cached) => {
+        Ok(mut cached) => {
             info!("Loaded existing cache");
+            // Changes applied after the last flush, if the app didn't exit cleanly.
+            if let Err(err) = cached.replay_wal(&wal_path) {
+                warn!("Failed to replay WAL: {err:?}");
+            }
             emit_status_bar_update(app_handle, cached.get_total_files(), 0, 0);
+            app_handle.send_background_event(BackgroundEvent::CacheLoaded {
+                total_files: cached.get_total_files(),
+            });
+            if !cached.is_complete() {
+                info!("Loaded cache is partial, finishing the walk");
+                CACHE_PENDING.store(true, Ordering::Relaxed);
+                if cached.finish_walk().is_none() {
+                    info!("Finishing walk cancelled, app quitting");
+                    hand_over_cache(&channels, Some(cached));
+                    return;
+                }
+                emit_status_bar_update(app_handle, cached.get_total_files(), 0, 0);
+            }
             cached
         }
         Err(e) => {
-            info!("Walking filesystem: {:?}", e);
-            let Some(cache) = build_search_cache(app_handle, &watch_root, &ignore_paths) else {
+            match e.downcast_ref::<CacheError>() {
+                Some(reason) => info!("Cache format changed ({reason}), walking filesystem"),
+                None => info!("Walking filesystem: {:?}", e),
+            }
+            if APP_QUIT.load(Ordering::Relaxed) {
+                info!("App quitting before the walk started");
+                hand_over_cache(&channels, None);
+                return;
+            }
+            CACHE_PENDING.store(true, Ordering::Relaxed);
+            let PartialWalk { cache, complete } =
+                match build_search_cache(app_handle, &watch_root, &ignore_paths) {
+                    Ok(walk) => walk,
+                    Err(err) => {
+                        // Indexing the rest would leave a near-empty index
+                        // that looks healthy.
+                        if let Some(WalkError::AccessDenied(root)) = err.downcast_ref() {
+                            warn!("Access to {root:?} denied, is Full Disk Access granted?");
+                            update_app_state(app_handle, AppLifecycleState::Error);
+                        }
+                        hand_over_cache(&channels, None);
+                        return;
+                    }
+                };
+            if !complete {
+                // Flushed as is; the next launch finishes the walk.
                 info!("Walk filesystem cancelled, app quitting");
-                channels
-                    .finish_rx
-                    .recv()
-                    .expect("Failed to receive finish signal")
-                    .send(None)
-                    .expect("Failed to send None cache");
+                hand_over_cache(&channels, Some(cache));
                 return;
-            };
+            }
 
             emit_status_bar_update(app_handle, cache.get_total_files(), 0, 0);
+            // Logged against a cache that no longer exists.
+            let _ = std::fs::remove_file(&wal_path);
 
             cache
         }
     };
+    if let Err(err) = cache.enable_wal(&wal_path) {
+        warn!("Running without a WAL: {err:?}");
+    }
 
-    let event_watcher = EventWatcher::spawn(
-        watch_root.to_string(),
-        cache.last_event_id(),
+    let event_watcher = EventWatcher::spawn_paths(
+        cache.watch_paths(),
+        cache.resume_event_id(),
         FSE_LATENCY_SECS,
+        FSE_WATCHER_FLAGS,
     )
     .1;
     if load_app_state() != AppLifecycleState::Ready {
@@ -299,9 +384,20 @@ fn run_logic_thread(
     info!("Background thread exited");
 }
 
+/// Answers the flush request for a logic thread that quits before its event
+/// loop runs.
+fn hand_over_cache(channels: &BackgroundLoopChannels, cache: Option<SearchCache>) {
+    channels
+        .finish_rx
+        .recv()
+        .expect("Failed to receive finish signal")
+        .send(cache)
+        .expect("Failed to send cache");
+}
+
 fn flush_cache_to_file_once(finish_tx: &Sender<Sender<Option<SearchCache>>>, db_path: &PathBuf) {
     static FLUSH_ONCE: Once = Once::new();
-    if load_app_state() != AppLifecycleState::Ready {
+    if load_app_state() != AppLifecycleState::Ready && !CACHE_PENDING.load(Ordering::Relaxed) {
         info!("App not fully initialized, skipping cache flush");
         return;
     }
@@ -8,6 +8,9 @@ pub enum AppLifecycleState {
     Initializing = 0,
     Updating = 1,
     Ready = 2,
+    /// The watch root couldn't be listed. Nothing is indexed, or, once the
+    /// background loop runs, the index it holds can't be kept up to date.
+    Error = 3,
 }
 
 impl AppLifecycleState {
@@ -16,6 +19,7 @@ impl AppLifecycleState {
             0 => Self::Initializing,
             1 => Self::Updating,
             2 => Self::Ready,
+            3 => Self::Error,
             _ => Self::Initializing,
         }
     }
@@ -25,6 +29,7 @@ impl AppLifecycleState {
             Self::Initializing => "Initializing",
             Self::Updating => "Updating",
             Self::Ready => "Ready",
+            Self::Error => "Error",
         }
     }
 }
@@ -33,6 +38,15 @@ static APP_LIFECYCLE_STATE: AtomicU8 = AtomicU8::new(AppLifecycleState::Initiali
 
 pub static APP_QUIT: AtomicBool = AtomicBool::new(false);
 pub static EXIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+/// Set once the logic thread is bound to answer the flush request, so quitting
+/// before `Ready` still persists the cache, partial or not.
+pub static CACHE_PENDING: AtomicBool = AtomicBool::new(false);
+/// Set while the user has indexing paused: the background loop holds watcher
+/// events back until it's cleared.
+pub static INDEXING_PAUSED: AtomicBool = AtomicBool::new(false);
+/// Set while the background loop runs and can answer a rescan request, even
+/// after a failed walk left the state at `Error`.
+pub static BACKGROUND_LOOP_RUNNING: AtomicBool = AtomicBool::new(false);
 
 pub fn load_app_state() -> AppLifecycleState {
     AppLifecycleState::from_u8(APP_LIFECYCLE_STATE.load(Ordering::Acquire))
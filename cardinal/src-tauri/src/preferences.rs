@@ -0,0 +1,174 @@
+use crate::{
+    commands::{SearchOptionsOverrides, SearchOptionsPayload},
+    sort::SortStatePayload,
+};
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::warn;
+
+pub(crate) const PREFERENCES_FILE: &str = "search-preferences.json";
+
+/// Search defaults the user picked, kept across launches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchPreferences {
+    /// Used by every `search` call for the options it doesn't pass itself.
+    pub options: SearchOptionsPayload,
+    /// Sort the results view starts with; index order when `None`.
+    pub default_sort: Option<SortStatePayload>,
+}
+
+/// The current [`SearchPreferences`] and the file they are saved to.
+pub struct PreferencesStore {
+    path: PathBuf,
+    current: Mutex<SearchPreferences>,
+}
+
+impl PreferencesStore {
+    /// Reads the preferences saved at `path`. A missing file means defaults;
+    /// an unreadable one is logged and replaced on the next save.
+    pub fn load(path: PathBuf) -> Self {
+        let current = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+                warn!("Ignoring invalid search preferences at {path:?}: {err}");
+                SearchPreferences::default()
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => SearchPreferences::default(),
+            Err(err) => {
+                warn!("Failed to read search preferences at {path:?}: {err}");
+                SearchPreferences::default()
+            }
+        };
+        Self {
+            path,
+            current: Mutex::new(current),
+        }
+    }
+
+    pub fn get(&self) -> SearchPreferences {
+        *self.current.lock()
+    }
+
+    /// Saves `preferences` and makes them current. Nothing changes if the
+    /// file can't be written.
+    pub fn set(&self, preferences: SearchPreferences) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create config dir {dir:?}"))?;
+        }
+        let json = serde_json::to_vec_pretty(&preferences)?;
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write search preferences to {:?}", self.path))?;
+        *self.current.lock() = preferences;
+        Ok(())
+    }
+
+    /// The saved defaults with whatever `overrides` sets in their place.
+    pub fn options_with(&self, overrides: SearchOptionsOverrides) -> SearchOptionsPayload {
+        overrides.apply_to(self.current.lock().options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::{SortDirectionPayload, SortKeyPayload};
+    use search_cache::SearchOptions;
+    use tempfile::tempdir;
+
+    #[test]
+    fn saved_preferences_apply_to_searches_without_options() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config").join(PREFERENCES_FILE);
+        let preferences = SearchPreferences {
+            options: SearchOptionsPayload {
+                case_insensitive: true,
+                min_query_len: Some(3),
+                ..SearchOptionsPayload::default()
+            },
+            default_sort: Some(SortStatePayload {
+                key: SortKeyPayload::Size,
+                direction: SortDirectionPayload::Desc,
+            }),
+        };
+        PreferencesStore::load(path.clone())
+            .set(preferences)
+            .unwrap();
+
+        // A fresh store, as after a restart.
+        let store = PreferencesStore::load(path);
+        assert_eq!(store.get(), preferences);
+        let options = SearchOptions::from(store.options_with(SearchOptionsOverrides::default()));
+        assert!(options.case_insensitive);
+        assert_eq!(options.min_query_len, 3);
+
+        let explicit = SearchOptionsOverrides {
+            case_insensitive: Some(false),
+            ..SearchOptionsOverrides::default()
+        };
+        let options = SearchOptions::from(store.options_with(explicit));
+        assert!(!options.case_insensitive);
+        assert_eq!(options.min_query_len, 3);
+    }
+
+    #[test]
+    fn frontend_toggles_override_only_their_own_preference() {
+        let dir = tempdir().unwrap();
+        let store = PreferencesStore::load(dir.path().join(PREFERENCES_FILE));
+        store
+            .set(SearchPreferences {
+                options: SearchOptionsPayload {
+                    sniff_content: true,
+                    min_query_len: Some(2),
+                    ..SearchOptionsPayload::default()
+                },
+                default_sort: None,
+            })
+            .unwrap();
+
+        // The `options` argument exactly as useFileSearch sends it.
+        let args: serde_json::Value = serde_json::json!({
+            "query": "foo",
+            "options": { "caseInsensitive": true },
+            "version": 1,
+        });
+        let overrides: Option<SearchOptionsOverrides> =
+            serde_json::from_value(args["options"].clone()).unwrap();
+        let options = SearchOptions::from(store.options_with(overrides.unwrap_or_default()));
+        assert!(options.case_insensitive);
+        assert!(options.sniff_content);
+        assert_eq!(options.min_query_len, 2);
+
+        let omitted: Option<SearchOptionsOverrides> =
+            serde_json::from_value(serde_json::Value::Null).unwrap();
+        let options = SearchOptions::from(store.options_with(omitted.unwrap_or_default()));
+        assert!(!options.case_insensitive);
+        assert!(options.sniff_content);
+
+        // Before the saved preference reaches it, useFileSearch sends no toggles.
+        let empty: Option<SearchOptionsOverrides> =
+            serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(
+            store.options_with(empty.unwrap_or_default()),
+            store.get().options
+        );
+    }
+
+    #[test]
+    fn missing_or_invalid_file_means_defaults() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(PREFERENCES_FILE);
+        assert_eq!(
+            PreferencesStore::load(path.clone()).get(),
+            SearchPreferences::default()
+        );
+
+        std::fs::write(&path, b"{ not json").unwrap();
+        assert_eq!(
+            PreferencesStore::load(path).get(),
+            SearchPreferences::default()
+        );
+    }
+}
@@ -1,16 +1,16 @@
 use fswalk::NodeFileType;
 use search_cache::{SearchResultNode, SlabIndex, SlabNodeMetadataCompact};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering as StdOrdering, path::Path};
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SortStatePayload {
     pub key: SortKeyPayload,
     pub direction: SortDirectionPayload,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum SortKeyPayload {
     Filename,
@@ -18,9 +18,12 @@ pub enum SortKeyPayload {
     Size,
     Mtime,
     Ctime,
+    /// Order in which the index learned about each node, for checking that
+    /// the watcher delivers changes. Needs no metadata.
+    RecentlyIndexed,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SortDirectionPayload {
     Asc,
@@ -78,7 +81,7 @@ fn metadata_numeric(meta: &SlabNodeMetadataCompact, key: SortKeyPayload) -> i64
             .ctime()
             .map(|value| value.get() as i64)
             .unwrap_or(i64::MIN),
-        SortKeyPayload::FullPath | SortKeyPayload::Filename => 0,
+        SortKeyPayload::FullPath | SortKeyPayload::Filename | SortKeyPayload::RecentlyIndexed => 0,
     }
 }
 
@@ -109,6 +112,11 @@ fn compare_entries(a: &SortEntry, b: &SortEntry, sort: &SortStatePayload) -> Std
                 .then_with(|| type_order(&a.node).cmp(&type_order(&b.node)))
                 .then_with(|| a.path_key.cmp(&b.path_key))
         }
+        SortKeyPayload::RecentlyIndexed => a
+            .node
+            .index_seq
+            .cmp(&b.node.index_seq)
+            .then_with(|| a.path_key.cmp(&b.path_key)),
     };
 
     match sort.direction {
@@ -131,6 +139,7 @@ mod tests {
         let node = SearchResultNode {
             path: PathBuf::from(path),
             metadata,
+            index_seq: slab_index as u32,
         };
 
         SortEntry::new(SlabIndex::new(slab_index), node)
@@ -196,4 +205,23 @@ mod tests {
             "directories stay ahead when size and names match, while files fall back to path order"
         );
     }
+
+    #[test]
+    fn recently_indexed_sort_follows_index_sequence() {
+        let sort_state = SortStatePayload {
+            key: SortKeyPayload::RecentlyIndexed,
+            direction: SortDirectionPayload::Desc,
+        };
+        // Metadata plays no part: the helper sets `index_seq` to the slab index.
+        let mut entries = vec![
+            entry_with_metadata(3, "/tmp/a", SlabNodeMetadataCompact::none()),
+            entry_with_metadata(7, "/tmp/b", metadata_with_type(NodeFileType::Dir, 0)),
+            entry_with_metadata(5, "/tmp/c", metadata_with_type(NodeFileType::File, 9)),
+        ];
+
+        sort_entries(&mut entries, &sort_state);
+        let order: Vec<usize> = entries.iter().map(|entry| entry.slab_index.get()).collect();
+
+        assert_eq!(order, vec![7, 5, 3], "newest index entries come first");
+    }
 }
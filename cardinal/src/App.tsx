@@ -101,6 +101,7 @@ function App() {
     resultCount,
     searchError,
     lifecycleState,
+    rescanAvailable,
   } = state;
   const [activeTab, setActiveTab] = useState<ActiveTab>('files');
   // Drives the inactive selection styling for the files panel via data-window-focused.
@@ -791,6 +792,7 @@ function App() {
           scannedFiles={scannedFiles}
           processedEvents={processedEvents}
           lifecycleState={lifecycleState}
+          rescanAvailable={rescanAvailable}
           searchDurationMs={durationMs}
           resultCount={resultCount}
           activeTab={activeTab}
@@ -10,6 +10,8 @@ type StatusBarProps = {
   scannedFiles: number;
   processedEvents: number;
   lifecycleState: AppLifecycleStatus;
+  // Keeps Rescan enabled in `Error` while the backend can still retry.
+  rescanAvailable?: boolean;
   searchDurationMs?: number | null;
   resultCount?: number | null;
   activeTab?: StatusTabKey;
@@ -24,12 +26,14 @@ const LIFECYCLE_META: Record<AppLifecycleStatus, { icon: string; tone: string }>
   Initializing: { icon: '○', tone: 'initializing' },
   Updating: { icon: '◑', tone: 'updating' },
   Ready: { icon: '●', tone: 'ready' },
+  Error: { icon: '✕', tone: 'error' },
 };
 
 const StatusBar = ({
   scannedFiles,
   processedEvents,
   lifecycleState,
+  rescanAvailable = false,
   searchDurationMs,
   resultCount,
   activeTab = 'files',
@@ -91,7 +95,8 @@ const StatusBar = ({
   const lifecycleMeta = LIFECYCLE_META[lifecycleState] ?? LIFECYCLE_META.Initializing;
   const lifecycleLabel =
     t(`statusBar.lifecycle.${lifecycleState}`) ?? t('statusBar.lifecycle.Initializing');
-  const rescanDisabled = lifecycleState === 'Initializing';
+  const rescanDisabled =
+    lifecycleState === 'Initializing' || (lifecycleState === 'Error' && !rescanAvailable);
   const rescanTitle = rescanDisabled
     ? t('statusBar.rescan.disabledHint')
     : t('statusBar.rescan.enabledHint');
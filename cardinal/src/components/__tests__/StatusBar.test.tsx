@@ -0,0 +1,30 @@
+import React from 'react';
+import { render, screen } from '@testing-library/react';
+import { describe, expect, it, vi } from 'vitest';
+import StatusBar from '../StatusBar';
+
+vi.mock('react-i18next', () => ({
+  useTranslation: () => ({
+    t: (key: string) => key,
+  }),
+}));
+
+const renderStatusBar = (props: Partial<React.ComponentProps<typeof StatusBar>> = {}) =>
+  render(<StatusBar scannedFiles={0} processedEvents={0} lifecycleState="Ready" {...props} />);
+
+describe('StatusBar', () => {
+  it('disables rescan while initializing', () => {
+    renderStatusBar({ lifecycleState: 'Initializing', rescanAvailable: true });
+    expect(screen.getByLabelText('statusBar.aria.rescan')).toBeDisabled();
+  });
+
+  it('keeps rescan enabled after an error while the backend can retry', () => {
+    renderStatusBar({ lifecycleState: 'Error', rescanAvailable: true });
+    expect(screen.getByLabelText('statusBar.aria.rescan')).toBeEnabled();
+  });
+
+  it('disables rescan after an error with nothing to retry', () => {
+    renderStatusBar({ lifecycleState: 'Error' });
+    expect(screen.getByLabelText('statusBar.aria.rescan')).toBeDisabled();
+  });
+});
@@ -38,6 +38,8 @@ export const SCROLLBAR_THUMB_MIN = 24;
 // Cache and performance tuning
 export const CACHE_SIZE = 1000;
 export const SEARCH_DEBOUNCE_MS = 300;
+// Searches still running after this long give up, e.g. `content:` over huge files.
+export const SEARCH_TIMEOUT_MS = 30_000;
 export const STATUS_FADE_DELAY_MS = 2000;
 export const OVERSCAN_ROW_COUNT = 1;
 
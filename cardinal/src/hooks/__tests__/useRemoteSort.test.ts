@@ -0,0 +1,57 @@
+import { act, renderHook, waitFor } from '@testing-library/react';
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { toSlabIndexArray } from '../../types/slab';
+import { useRemoteSort } from '../useRemoteSort';
+
+const mocks = vi.hoisted(() => ({
+  invokeMock: vi.fn(),
+}));
+
+vi.mock('@tauri-apps/api/core', () => ({
+  invoke: mocks.invokeMock,
+}));
+
+const results = toSlabIndexArray([1, 2, 3]);
+
+describe('useRemoteSort', () => {
+  beforeEach(() => {
+    mocks.invokeMock.mockReset();
+    mocks.invokeMock.mockImplementation(async (command: string) => {
+      if (command === 'get_search_preferences') {
+        return {
+          options: { caseInsensitive: false },
+          defaultSort: { key: 'size', direction: 'desc' },
+        };
+      }
+      if (command === 'get_sorted_view') {
+        return [3, 2, 1];
+      }
+      return undefined;
+    });
+  });
+
+  it('starts with the saved default sort', async () => {
+    const { result } = renderHook(() => useRemoteSort(results, 1, 'en', () => null));
+
+    await waitFor(() =>
+      expect(result.current.sortState).toEqual({ key: 'size', direction: 'desc' }),
+    );
+    await waitFor(() => expect(result.current.displayedResults).toEqual([3, 2, 1]));
+    expect(mocks.invokeMock).toHaveBeenCalledWith('get_sorted_view', {
+      results,
+      sort: { key: 'size', direction: 'desc' },
+    });
+  });
+
+  it('keeps the sort the user picked over the default', async () => {
+    const { result } = renderHook(() => useRemoteSort(results, 1, 'en', () => null));
+
+    act(() => {
+      result.current.handleSortToggle('filename');
+    });
+    await waitFor(() => expect(mocks.invokeMock).toHaveBeenCalledWith('get_search_preferences'));
+    await act(async () => {});
+
+    expect(result.current.sortState).toEqual({ key: 'filename', direction: 'asc' });
+  });
+});
@@ -136,7 +136,14 @@ export function useDataLoader(results: SlabIndex[]) {
     if (needLoading.length === 0) return;
     const versionAtRequest = versionRef.current;
     const slice = needLoading.map((i) => list[i]);
-    const fetched = await invoke<NodeInfoResponse[]>('get_nodes_info', { results: slice });
+    let fetched: NodeInfoResponse[];
+    try {
+      fetched = await invoke<NodeInfoResponse[]>('get_nodes_info', { results: slice });
+    } catch (error) {
+      console.error('Failed to load node info:', error);
+      needLoading.forEach((i) => loadingRef.current.delete(i));
+      return;
+    }
     if (versionRef.current !== versionAtRequest) {
       needLoading.forEach((i) => loadingRef.current.delete(i));
       return;
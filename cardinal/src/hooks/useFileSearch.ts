@@ -1,8 +1,13 @@
 import { useReducer, useRef, useCallback, useEffect } from 'react';
 import type { MutableRefObject } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { SEARCH_DEBOUNCE_MS } from '../constants';
-import type { AppLifecycleStatus, SearchResponsePayload } from '../types/ipc';
+import { SEARCH_DEBOUNCE_MS, SEARCH_TIMEOUT_MS } from '../constants';
+import type {
+  AppLifecycleStatus,
+  SearchPreferencesPayload,
+  SearchResponsePayload,
+} from '../types/ipc';
+import { isCommandError } from '../types/ipc';
 import type { SlabIndex } from '../types/slab';
 import { toSlabIndexArray } from '../types/slab';
 
@@ -22,6 +27,8 @@ type SearchState = {
   resultCount: number;
   searchError: SearchError;
   lifecycleState: AppLifecycleStatus;
+  // Whether a background loop is there to answer a rescan, even in `Error`.
+  rescanAvailable: boolean;
 };
 
 type SearchParams = {
@@ -58,7 +65,8 @@ type SearchAction =
         duration: number;
       };
     }
-  | { type: 'SET_LIFECYCLE_STATE'; payload: { status: AppLifecycleStatus } };
+  | { type: 'SET_LIFECYCLE_STATE'; payload: { status: AppLifecycleStatus } }
+  | { type: 'SET_RESCAN_AVAILABLE'; payload: { available: boolean } };
 
 const initialSearchState: SearchState = {
   results: [],
@@ -74,6 +82,7 @@ const initialSearchState: SearchState = {
   resultCount: 0,
   searchError: null,
   lifecycleState: 'Initializing',
+  rescanAvailable: false,
 };
 
 const initialSearchParams: SearchParams = {
@@ -137,6 +146,11 @@ function reducer(state: SearchState, action: SearchAction): SearchState {
         ...state,
         lifecycleState: action.payload.status,
       };
+    case 'SET_RESCAN_AVAILABLE':
+      return {
+        ...state,
+        rescanAvailable: action.payload.available,
+      };
     default:
       return state;
   }
@@ -167,14 +181,43 @@ export function useFileSearch(): UseFileSearchResult {
   const hasInitialSearchRunRef = useRef(false);
   const debounceTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const loadingDelayTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  // Until the saved preference arrives or the user flips the toggle, searches
+  // leave case sensitivity to the backend's saved default.
+  const caseSensitivityKnownRef = useRef(false);
+  const caseSensitivityChosenRef = useRef(false);
 
   const [searchParams, patchSearchParams] = useReducer(searchParamsReducer, initialSearchParams);
 
   const updateSearchParams = useCallback((patch: Partial<SearchParams>) => {
+    if (patch.caseSensitive !== undefined) {
+      caseSensitivityKnownRef.current = true;
+      caseSensitivityChosenRef.current = true;
+    }
     latestSearchRef.current = { ...latestSearchRef.current, ...patch };
     patchSearchParams(patch);
   }, []);
 
+  useEffect(() => {
+    let cancelled = false;
+    (async () => {
+      try {
+        const preferences = await invoke<SearchPreferencesPayload>('get_search_preferences');
+        if (cancelled || caseSensitivityChosenRef.current) {
+          return;
+        }
+        const caseSensitive = !preferences.options.caseInsensitive;
+        caseSensitivityKnownRef.current = true;
+        latestSearchRef.current = { ...latestSearchRef.current, caseSensitive };
+        patchSearchParams({ caseSensitive });
+      } catch (error) {
+        console.error('Failed to fetch search preferences:', error);
+      }
+    })();
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
   const handleStatusUpdate = useCallback(
     (scannedFiles: number, processedEvents: number, rescanErrors: number) => {
       dispatch({
@@ -206,6 +249,26 @@ export function useFileSearch(): UseFileSearchResult {
     };
   }, [setLifecycleState]);
 
+  // A failed rescan keeps the loop and its index; only a failed startup has
+  // nothing left to rescan with.
+  useEffect(() => {
+    if (state.lifecycleState !== 'Error') return;
+    let cancelled = false;
+    (async () => {
+      try {
+        const available = await invoke<boolean>('get_rescan_available');
+        if (!cancelled) {
+          dispatch({ type: 'SET_RESCAN_AVAILABLE', payload: { available } });
+        }
+      } catch (error) {
+        console.error('Failed to fetch rescan availability:', error);
+      }
+    })();
+    return () => {
+      cancelled = true;
+    };
+  }, [state.lifecycleState]);
+
   const cancelPendingSearches = useCallback(() => {
     cancelTimer(debounceTimerRef);
     cancelTimer(loadingDelayTimerRef);
@@ -234,12 +297,15 @@ export function useFileSearch(): UseFileSearchResult {
     try {
       const rawResults = await invoke<SearchResponsePayload>('search', {
         query,
-        options: {
-          caseInsensitive: !caseSensitive,
-        },
+        options: caseSensitivityKnownRef.current ? { caseInsensitive: !caseSensitive } : {},
         version: requestVersion,
+        timeoutMs: SEARCH_TIMEOUT_MS,
       });
 
+      if (rawResults?.timedOut) {
+        throw new Error('Search timed out.');
+      }
+
       const slabResults = Array.isArray(rawResults?.results) ? rawResults.results : [];
       const searchResults = toSlabIndexArray(slabResults);
       const highlightTerms = Array.isArray(rawResults?.highlights)
@@ -278,7 +344,13 @@ export function useFileSearch(): UseFileSearchResult {
       const duration = endTs - startTs;
 
       const normalisedError =
-        error instanceof Error ? error : error ? String(error) : 'An unknown error occurred.';
+        error instanceof Error
+          ? error
+          : isCommandError(error)
+            ? error.message
+            : error
+              ? String(error)
+              : 'An unknown error occurred.';
 
       dispatch({
         type: 'SEARCH_FAILURE',
@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import type { SearchPreferencesPayload } from '../types/ipc';
 import type { SortKey, SortState } from '../types/sort';
 import type { SlabIndex } from '../types/slab';
 import { toSlabIndexArray } from '../types/slab';
@@ -63,7 +64,10 @@ export const useRemoteSort = (
   locale: string,
   formatDisabledTooltip: (limit: string) => string | null,
 ): RemoteSortControls => {
-  const [sortState, setSortState] = useState<SortState>(null);
+  const [sortState, setSortStateRaw] = useState<SortState>(null);
+  // The saved default sort, applied until the user picks one themselves.
+  const [defaultSort, setDefaultSort] = useState<SortState>(null);
+  const sortChosenRef = useRef(false);
   const [sortedResults, setSortedResults] = useState<SlabIndex[]>([]);
   const [sortThreshold, setSortThresholdState] = useState<number>(() => readStoredSortThreshold());
   const [isSorting, setIsSorting] = useState(false);
@@ -75,6 +79,34 @@ export const useRemoteSort = (
   const shouldUseSortedResults = Boolean(sortState && canSort);
   const displayedResults = shouldUseSortedResults ? sortedResults : results;
 
+  const setSortState = useCallback((next: SortState) => {
+    sortChosenRef.current = true;
+    setSortStateRaw(next);
+  }, []);
+
+  useEffect(() => {
+    let cancelled = false;
+    (async () => {
+      try {
+        const preferences = await invoke<SearchPreferencesPayload>('get_search_preferences');
+        if (!cancelled) {
+          setDefaultSort(preferences.defaultSort ?? null);
+        }
+      } catch (error) {
+        console.error('Failed to fetch search preferences:', error);
+      }
+    })();
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  useEffect(() => {
+    if (defaultSort && canSort && !sortState && !sortChosenRef.current) {
+      setSortStateRaw(defaultSort);
+    }
+  }, [defaultSort, canSort, sortState]);
+
   const setSortThreshold = useCallback((value: number) => {
     const normalized = clampSortThreshold(value);
     setSortThresholdState(normalized);
@@ -86,7 +118,8 @@ export const useRemoteSort = (
       if (!canSort) {
         return;
       }
-      setSortState((prev) => {
+      sortChosenRef.current = true;
+      setSortStateRaw((prev) => {
         if (!prev || prev.key !== nextKey) {
           return { key: nextKey, direction: 'asc' };
         }
@@ -101,7 +134,7 @@ export const useRemoteSort = (
 
   useEffect(() => {
     if (!canSort && sortState) {
-      setSortState(null);
+      setSortStateRaw(null);
     }
   }, [canSort, sortState]);
 
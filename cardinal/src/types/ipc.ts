@@ -1,4 +1,5 @@
 import type { SlabIndex } from './slab';
+import type { SortState } from './sort';
 
 export type StatusBarUpdatePayload = {
   scannedFiles: number;
@@ -6,6 +7,13 @@ export type StatusBarUpdatePayload = {
   rescanErrors: number;
 };
 
+export type BackgroundEventPayload =
+  | { kind: 'WalkStarted'; root: string }
+  | { kind: 'WalkProgress'; scannedFiles: number }
+  | { kind: 'CacheLoaded'; totalFiles: number }
+  | { kind: 'EventReplayProgress'; processedEvents: number }
+  | { kind: 'Ready' };
+
 export type IconUpdateWirePayload = {
   slabIndex: number;
   icon?: string;
@@ -23,9 +31,35 @@ export type RecentEventPayload = {
   timestamp: number;
 };
 
-export type AppLifecycleStatus = 'Initializing' | 'Updating' | 'Ready';
+export type AppLifecycleStatus = 'Initializing' | 'Updating' | 'Ready' | 'Error';
+
+// What `get_search_preferences` returns; only the fields the UI reads.
+export type SearchPreferencesPayload = {
+  options: { caseInsensitive: boolean };
+  defaultSort: SortState;
+};
 
 export type SearchResponsePayload = {
   results: number[];
   highlights?: string[];
+  timedOut?: boolean;
 };
+
+export type CommandErrorKind =
+  | 'backendNotReady'
+  | 'invalidQuery'
+  | 'pathNotFound'
+  | 'applicationNotFound'
+  | 'io';
+
+// Rejection value of commands returning `Result<_, CommandError>`.
+export type CommandErrorPayload = {
+  kind: CommandErrorKind;
+  message: string;
+};
+
+export const isCommandError = (value: unknown): value is CommandErrorPayload =>
+  typeof value === 'object' &&
+  value !== null &&
+  typeof (value as CommandErrorPayload).kind === 'string' &&
+  typeof (value as CommandErrorPayload).message === 'string';
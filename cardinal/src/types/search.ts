@@ -18,6 +18,7 @@ export type NodeInfoResponse = Readonly<{
   path: string;
   icon?: string | null;
   metadata?: SearchResultMetadata | null;
+  tags?: readonly string[] | null;
   size?: number | null;
   mtime?: number | null;
   ctime?: number | null;
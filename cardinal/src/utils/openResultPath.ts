@@ -5,5 +5,7 @@ export const openResultPath = (path: string | null | undefined): void => {
     return;
   }
 
-  void invoke('open_path', { path });
+  invoke('open_path', { path }).catch((error) => {
+    console.error('Failed to open path:', error);
+  });
 };
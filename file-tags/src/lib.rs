@@ -1,8 +1,10 @@
 use plist::Value;
 use std::{
-    io::{self, Cursor},
+    io::{self, Cursor, Read},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Output, Stdio},
+    thread,
+    time::{Duration, Instant},
 };
 use xattr::get;
 
@@ -10,10 +12,15 @@ const USER_TAG_XATTR: &str = "com.apple.metadata:_kMDItemUserTags";
 
 /// Searches for files with the specified tag using the `mdfind` command-line tool.
 ///
-/// Returns a vector of file paths that have the specified tag.
+/// Returns a vector of file paths that have the specified tag. The flags map
+/// to Spotlight's `c` and `d` comparison modifiers. With a `timeout`, `mdfind`
+/// is killed once it runs past it and an [`io::ErrorKind::TimedOut`] error is
+/// returned.
 pub fn search_tags_using_mdfind(
     tags: Vec<String>,
     case_insensitive: bool,
+    diacritic_insensitive: bool,
+    timeout: Option<Duration>,
 ) -> io::Result<Vec<PathBuf>> {
     if tags.is_empty() {
         return Ok(Vec::new());
@@ -27,13 +34,20 @@ pub fn search_tags_using_mdfind(
         }
     }
 
-    let modifier = if case_insensitive { "c" } else { "" };
+    let modifier = match (case_insensitive, diacritic_insensitive) {
+        (true, true) => "cd",
+        (true, false) => "c",
+        (false, true) => "d",
+        (false, false) => "",
+    };
     let query = tags
         .into_iter()
         .map(|tag| format!("kMDItemUserTags == '*{tag}*'{modifier}"))
         .collect::<Vec<_>>()
         .join(" || ");
-    let output = Command::new("mdfind").arg(query).output()?;
+    let mut command = Command::new("mdfind");
+    command.arg(query);
+    let output = output_with_timeout(command, timeout)?;
 
     if !output.status.success() {
         return Err(io::Error::other("mdfind command failed"));
@@ -45,6 +59,168 @@ pub fn search_tags_using_mdfind(
     Ok(paths)
 }
 
+/// Spotlight's content type tree: a file's UTI followed by every type it
+/// conforms to, so a PNG lists `public.png` and `public.image`.
+const CONTENT_TYPE_TREE: &str = "kMDItemContentTypeTree";
+
+/// Searches for files whose content type conforms to any of `types` using
+/// `mdfind`. UTIs are compared case-insensitively; `timeout` works as in
+/// [`search_tags_using_mdfind`].
+pub fn search_content_types_using_mdfind(
+    types: &[String],
+    timeout: Option<Duration>,
+) -> io::Result<Vec<PathBuf>> {
+    if types.is_empty() {
+        return Ok(Vec::new());
+    }
+    for uti in types {
+        check_uti(uti)?;
+    }
+    let query = types
+        .iter()
+        .map(|uti| format!("{CONTENT_TYPE_TREE} == '{uti}'c"))
+        .collect::<Vec<_>>()
+        .join(" || ");
+    let mut command = Command::new("mdfind");
+    command.arg(query);
+    let output = output_with_timeout(command, timeout)?;
+    if !output.status.success() {
+        return Err(io::Error::other("mdfind command failed"));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().map(PathBuf::from).collect())
+}
+
+/// Reads the content type tree of each of `paths` with one `mdls` run.
+/// Returns one list per path, in order; it is empty when Spotlight knows no
+/// type for the path, e.g. because it no longer exists.
+pub fn read_content_types(
+    paths: &[PathBuf],
+    timeout: Option<Duration>,
+) -> io::Result<Vec<Vec<String>>> {
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    if let Some(trees) = mdls_content_types(paths, timeout)? {
+        return Ok(trees);
+    }
+    // `mdls` gives up on the whole batch when one path is missing, so retry
+    // one path at a time.
+    paths
+        .iter()
+        .map(|path| {
+            Ok(mdls_content_types(std::slice::from_ref(path), timeout)?
+                .and_then(|mut trees| trees.pop())
+                .unwrap_or_default())
+        })
+        .collect()
+}
+
+/// One `mdls` run over `paths`, or `None` when it didn't yield a value for
+/// every path.
+fn mdls_content_types(
+    paths: &[PathBuf],
+    timeout: Option<Duration>,
+) -> io::Result<Option<Vec<Vec<String>>>> {
+    let mut command = Command::new("mdls");
+    command
+        .args(["-raw", "-name", CONTENT_TYPE_TREE])
+        .args(paths);
+    let output = output_with_timeout(command, timeout)?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    // With several paths `-raw` separates the values with NULs.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let values: Vec<&str> = stdout.trim_end_matches('\0').split('\0').collect();
+    Ok((values.len() == paths.len()).then(|| values.into_iter().map(parse_mdls_list).collect()))
+}
+
+/// Parses an array printed by `mdls -raw`, e.g. `("public.png", "public.image")`
+/// over several lines. `(null)` means no value.
+pub fn parse_mdls_list(raw: &str) -> Vec<String> {
+    let raw = raw.trim();
+    if raw == "(null)" {
+        return Vec::new();
+    }
+    let Some(items) = raw.strip_prefix('(').and_then(|raw| raw.strip_suffix(')')) else {
+        return Vec::new();
+    };
+    items
+        .split(',')
+        .map(|item| item.trim())
+        .map(|item| {
+            item.strip_prefix('"')
+                .and_then(|item| item.strip_suffix('"'))
+                .unwrap_or(item)
+        })
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// UTIs are reverse-DNS names; anything else could break out of the
+/// Spotlight query.
+pub fn check_uti(uti: &str) -> io::Result<()> {
+    match uti
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-')))
+    {
+        Some(forbidden_char) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("content type contains unsupported character '{forbidden_char}': {uti}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// How often a command running under a timeout is checked for exit.
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// [`Command::output`], but the child is killed after `timeout` and a
+/// [`io::ErrorKind::TimedOut`] error returned instead. Stderr is discarded.
+fn output_with_timeout(mut command: Command, timeout: Option<Duration>) -> io::Result<Output> {
+    let Some(timeout) = timeout else {
+        return command.output();
+    };
+    let deadline = Instant::now() + timeout;
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Drained on its own thread so a chatty child can't block on a full pipe.
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf).map(|_| buf)
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // The child may exit on its own between the check and the kill.
+            let _ = child.kill();
+            child.wait()?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("command timed out after {timeout:?}"),
+            ));
+        }
+        thread::sleep(TIMEOUT_POLL_INTERVAL);
+    };
+    let stdout = reader
+        .join()
+        .map_err(|_| io::Error::other("stdout reader panicked"))??;
+    Ok(Output {
+        status,
+        stdout,
+        stderr: Vec::new(),
+    })
+}
+
 fn tag_has_spotlight_forbidden_chars(tag: &str) -> Option<char> {
     tag.chars().find(|c| matches!(c, '\'' | '\\' | '*'))
 }
@@ -142,6 +318,24 @@ mod tests {
         assert_eq!(tags, vec!["Project".to_string()]);
     }
 
+    #[test]
+    fn parses_mdls_lists() {
+        let raw = "(\n    \"public.png\",\n    \"public.image\",\n    \"public.data\"\n)";
+        assert_eq!(
+            parse_mdls_list(raw),
+            ["public.png", "public.image", "public.data"]
+        );
+        assert!(parse_mdls_list("(null)").is_empty());
+        assert!(parse_mdls_list("(\n)").is_empty());
+    }
+
+    #[test]
+    fn search_content_types_using_mdfind_rejects_quotes() {
+        let result =
+            search_content_types_using_mdfind(&["public.image' || true".to_string()], None);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
     #[cfg(target_os = "macos")]
     fn write_xattr(path: &std::path::Path, tags: &[&str]) {
         use xattr::set;
@@ -234,14 +428,15 @@ mod tests {
     // Tests for search_tags_using_mdfind edge cases
     #[test]
     fn search_tags_using_mdfind_empty_list_returns_empty() {
-        let result = search_tags_using_mdfind(vec![], false);
+        let result = search_tags_using_mdfind(vec![], false, false, None);
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
 
     #[test]
     fn search_tags_using_mdfind_rejects_single_quote() {
-        let result = search_tags_using_mdfind(vec!["Project'Alpha".to_string()], false);
+        let result =
+            search_tags_using_mdfind(vec!["Project'Alpha".to_string()], false, false, None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
@@ -250,7 +445,8 @@ mod tests {
 
     #[test]
     fn search_tags_using_mdfind_rejects_backslash() {
-        let result = search_tags_using_mdfind(vec!["Project\\Alpha".to_string()], false);
+        let result =
+            search_tags_using_mdfind(vec!["Project\\Alpha".to_string()], false, false, None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
@@ -259,7 +455,7 @@ mod tests {
 
     #[test]
     fn search_tags_using_mdfind_rejects_asterisk() {
-        let result = search_tags_using_mdfind(vec!["Project*".to_string()], false);
+        let result = search_tags_using_mdfind(vec!["Project*".to_string()], false, false, None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
@@ -271,6 +467,8 @@ mod tests {
         let result = search_tags_using_mdfind(
             vec!["ValidTag".to_string(), "Invalid'Tag".to_string()],
             false,
+            false,
+            None,
         );
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -281,7 +479,8 @@ mod tests {
     #[test]
     fn search_tags_using_mdfind_allows_hyphen() {
         // Hyphen is not a forbidden character
-        let result = search_tags_using_mdfind(vec!["Project-Alpha".to_string()], false);
+        let result =
+            search_tags_using_mdfind(vec!["Project-Alpha".to_string()], false, false, None);
         // We can't verify success without actual files, but it should not reject the input
         // If mdfind is not available or returns no results, that's fine for this test
         match result {
@@ -293,7 +492,8 @@ mod tests {
 
     #[test]
     fn search_tags_using_mdfind_allows_underscore() {
-        let result = search_tags_using_mdfind(vec!["Project_Alpha".to_string()], false);
+        let result =
+            search_tags_using_mdfind(vec!["Project_Alpha".to_string()], false, false, None);
         match result {
             Ok(_) => {}
             Err(e) if e.to_string().contains("mdfind command failed") => {}
@@ -303,7 +503,7 @@ mod tests {
 
     #[test]
     fn search_tags_using_mdfind_allows_unicode() {
-        let result = search_tags_using_mdfind(vec!["项目".to_string()], false);
+        let result = search_tags_using_mdfind(vec!["项目".to_string()], false, false, None);
         match result {
             Ok(_) => {}
             Err(e) if e.to_string().contains("mdfind command failed") => {}
@@ -313,7 +513,7 @@ mod tests {
 
     #[test]
     fn search_tags_using_mdfind_allows_emoji() {
-        let result = search_tags_using_mdfind(vec!["🔴Important".to_string()], false);
+        let result = search_tags_using_mdfind(vec!["🔴Important".to_string()], false, false, None);
         match result {
             Ok(_) => {}
             Err(e) if e.to_string().contains("mdfind command failed") => {}
@@ -325,8 +525,12 @@ mod tests {
     fn search_tags_using_mdfind_multiple_tags_constructs_or_query() {
         // We can't easily verify the exact query without mocking, but we can verify
         // that multiple tags are accepted without error
-        let result =
-            search_tags_using_mdfind(vec!["Project".to_string(), "Important".to_string()], false);
+        let result = search_tags_using_mdfind(
+            vec!["Project".to_string(), "Important".to_string()],
+            false,
+            false,
+            None,
+        );
         match result {
             Ok(_) => {}
             Err(e) if e.to_string().contains("mdfind command failed") => {}
@@ -334,6 +538,39 @@ mod tests {
         }
     }
 
+    #[test]
+    #[ignore = "depends on how fast the local Spotlight index answers"]
+    #[cfg(target_os = "macos")]
+    fn search_tags_using_mdfind_times_out_on_broad_query() {
+        let result = search_tags_using_mdfind(
+            vec!["e".to_string()],
+            true,
+            true,
+            Some(Duration::from_millis(1)),
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn output_with_timeout_kills_slow_child() {
+        let mut command = Command::new("sleep");
+        command.arg("10");
+        let started = Instant::now();
+        let err = output_with_timeout(command, Some(Duration::from_millis(50))).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn output_with_timeout_returns_output_of_fast_child() {
+        let mut command = Command::new("echo");
+        command.arg("done");
+        let output = output_with_timeout(command, Some(Duration::from_secs(10))).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"done\n");
+    }
+
     #[test]
     fn tag_has_spotlight_forbidden_chars_returns_none_for_safe_string() {
         assert_eq!(tag_has_spotlight_forbidden_chars("Project-Alpha_123"), None);
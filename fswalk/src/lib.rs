@@ -1,13 +1,22 @@
-use rayon::{iter::ParallelBridge, prelude::ParallelIterator};
+mod name;
+
+pub use name::{name_from_os_str, name_to_os_str};
+use rayon::{ThreadPool, ThreadPoolBuilder, iter::ParallelBridge, prelude::ParallelIterator};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fmt,
     fs::{self, Metadata},
     io::{Error, ErrorKind},
-    num::NonZeroU64,
-    os::unix::fs::MetadataExt,
+    num::{NonZeroU64, NonZeroUsize},
+    os::unix::{ffi::OsStrExt, fs::MetadataExt},
     path::{Path, PathBuf},
-    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    sync::{
+        Arc, Mutex, OnceLock, PoisonError,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
     time::UNIX_EPOCH,
 };
 
@@ -80,6 +89,157 @@ impl From<fs::FileType> for NodeFileType {
     }
 }
 
+/// Order applied to each directory's `children` once they are collected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// Byte-wise by name, so alphabetical browsing needs no extra pass.
+    #[default]
+    Name,
+    /// Newest mtime first, ties broken by name. Nodes without an mtime (e.g.
+    /// files walked without metadata) go last.
+    ModifiedDesc,
+    /// Keep whatever order the parallel traversal produced.
+    Unsorted,
+}
+
+impl SortKey {
+    fn sort(self, children: &mut [Node]) {
+        match self {
+            SortKey::Name => children.sort_unstable_by(|a, b| a.name.cmp(&b.name)),
+            SortKey::ModifiedDesc => children.sort_unstable_by(|a, b| {
+                let mtime = |node: &Node| node.metadata.and_then(|meta| meta.mtime);
+                mtime(b).cmp(&mtime(a)).then_with(|| a.name.cmp(&b.name))
+            }),
+            SortKey::Unsorted => {}
+        }
+    }
+}
+
+/// How many threads a walk or search may spread its parallel work over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThreadBudget {
+    /// Rayon's global pool, sized to the available parallelism.
+    #[default]
+    Available,
+    /// A dedicated pool of exactly this many threads. `1` makes the work
+    /// serial.
+    Threads(NonZeroUsize),
+}
+
+impl ThreadBudget {
+    /// `Threads(threads)`, or [`ThreadBudget::Available`] for `0`.
+    pub fn threads(threads: usize) -> Self {
+        NonZeroUsize::new(threads).map_or(Self::Available, Self::Threads)
+    }
+
+    /// Runs `op` with rayon's parallel iterators confined to this budget. The
+    /// pool for a thread count is built on first use and kept for the rest of
+    /// the process, so repeated walks and searches share its threads.
+    pub fn install<R: Send>(self, op: impl FnOnce() -> R + Send) -> R {
+        let Self::Threads(threads) = self else {
+            return op();
+        };
+        match dedicated_pool(threads) {
+            Some(pool) => pool.install(op),
+            // Spawning the pool failed; the global pool beats not running at all.
+            None => op(),
+        }
+    }
+}
+
+fn dedicated_pool(threads: NonZeroUsize) -> Option<Arc<ThreadPool>> {
+    static POOLS: OnceLock<Mutex<HashMap<NonZeroUsize, Arc<ThreadPool>>>> = OnceLock::new();
+    let mut pools = POOLS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(pool) = pools.get(&threads) {
+        return Some(Arc::clone(pool));
+    }
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads.get())
+        .build()
+        .ok()?;
+    Some(Arc::clone(pools.entry(threads).or_insert(Arc::new(pool))))
+}
+
+/// Why the walk root couldn't be listed, leaving nothing indexed below it.
+/// Read it with [`WalkData::root_error`] once the walk returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalkError {
+    /// Listing the root was refused. On macOS this usually means Full Disk
+    /// Access hasn't been granted.
+    AccessDenied(PathBuf),
+    /// Listing the root failed for another reason.
+    Unreadable { path: PathBuf, kind: ErrorKind },
+}
+
+impl WalkError {
+    fn new(path: &Path, error: &Error) -> Self {
+        match error.kind() {
+            ErrorKind::PermissionDenied => WalkError::AccessDenied(path.to_path_buf()),
+            kind => WalkError::Unreadable {
+                path: path.to_path_buf(),
+                kind,
+            },
+        }
+    }
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalkError::AccessDenied(path) => write!(f, "permission denied listing {path:?}"),
+            WalkError::Unreadable { path, kind } => write!(f, "failed to list {path:?}: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for WalkError {}
+
+/// The filters and thread budget of a walk, owned so a cache built by one
+/// walk can walk the paths that change later the same way. See
+/// [`WalkData::options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// If set, only files with one of these extensions are recorded.
+    pub only_extensions: Option<Box<[Box<str>]>>,
+    /// If set, entries whose name starts with `.` are left out with their subtrees.
+    pub skip_hidden: bool,
+    /// Caps the threads the walk runs on.
+    pub thread_budget: ThreadBudget,
+}
+
+impl WalkOptions {
+    /// Whether a walk with these options records `relative`, a path below
+    /// its root, which is a directory if `is_dir`.
+    pub fn admits(&self, relative: &Path, is_dir: bool) -> bool {
+        !relative.iter().any(|component| self.skips_entry(component))
+            && (is_dir
+                || relative
+                    .file_name()
+                    .is_none_or(|name| self.records_file(name)))
+    }
+
+    fn skips_entry(&self, name: &OsStr) -> bool {
+        self.skip_hidden && name.as_bytes().starts_with(b".")
+    }
+
+    fn records_file(&self, name: &OsStr) -> bool {
+        let Some(extensions) = &self.only_extensions else {
+            return true;
+        };
+        Path::new(name)
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| {
+                extensions
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(ext))
+            })
+    }
+}
+
 #[derive(Debug)]
 pub struct WalkData<'w> {
     pub num_files: AtomicUsize,
@@ -90,6 +250,13 @@ pub struct WalkData<'w> {
     pub ignore_directories: &'w [PathBuf],
     /// If set, metadata will be collected for each file node(folder node will get free metadata).
     need_metadata: bool,
+    sort_key: SortKey,
+    options: WalkOptions,
+    /// If set, cancellation keeps what was walked so far instead of failing.
+    keep_partial: bool,
+    /// Directories whose listing was cut short by cancellation.
+    interrupted: Mutex<Vec<PathBuf>>,
+    root_error: Mutex<Option<WalkError>>,
 }
 
 impl<'w> WalkData<'w> {
@@ -101,6 +268,15 @@ impl<'w> WalkData<'w> {
             root_path,
             ignore_directories: &[],
             need_metadata,
+            sort_key: SortKey::Name,
+            options: WalkOptions {
+                only_extensions: None,
+                skip_hidden: false,
+                thread_budget: ThreadBudget::Available,
+            },
+            keep_partial: false,
+            interrupted: Mutex::new(Vec::new()),
+            root_error: Mutex::new(None),
         }
     }
 
@@ -117,20 +293,110 @@ impl<'w> WalkData<'w> {
             root_path,
             ignore_directories,
             need_metadata,
+            sort_key: SortKey::Name,
+            options: WalkOptions {
+                only_extensions: None,
+                skip_hidden: false,
+                thread_budget: ThreadBudget::Available,
+            },
+            keep_partial: false,
+            interrupted: Mutex::new(Vec::new()),
+            root_error: Mutex::new(None),
         }
     }
 
+    /// Sets the order of every directory's `children` in the walked tree.
+    /// Defaults to [`SortKey::Name`].
+    pub fn sort_children_by(mut self, sort_key: SortKey) -> Self {
+        self.sort_key = sort_key;
+        self
+    }
+
+    pub fn sort_key(&self) -> SortKey {
+        self.sort_key
+    }
+
+    /// Records only files whose extension (compared case-insensitively, without
+    /// the dot) is in `extensions`. Directories are always kept so the tree
+    /// keeps its shape, even when nothing under them matches.
+    pub fn only_extensions(mut self, extensions: &[&str]) -> Self {
+        self.options.only_extensions = Some(extensions.iter().map(|&ext| ext.into()).collect());
+        self
+    }
+
+    /// Leaves out dotfiles and hidden directories with everything below them.
+    /// The root itself is always walked, even when its own name is hidden.
+    /// Defaults to `false`.
+    pub fn skip_hidden(mut self, skip_hidden: bool) -> Self {
+        self.options.skip_hidden = skip_hidden;
+        self
+    }
+
+    /// Caps the threads the walk runs on. Defaults to
+    /// [`ThreadBudget::Available`].
+    pub fn thread_budget(mut self, budget: ThreadBudget) -> Self {
+        self.options.thread_budget = budget;
+        self
+    }
+
+    /// The filters set on this walk.
+    pub fn options(&self) -> &WalkOptions {
+        &self.options
+    }
+
+    /// Sets every filter at once, e.g. to the [`Self::options`] of an earlier
+    /// walk.
+    pub fn with_options(mut self, options: WalkOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Makes a cancelled walk return the tree gathered so far rather than
+    /// `None`. Every directory that may be missing entries is recorded, see
+    /// [`Self::take_interrupted`]. Defaults to `false`.
+    pub fn keep_partial(mut self, keep_partial: bool) -> Self {
+        self.keep_partial = keep_partial;
+        self
+    }
+
+    /// Drains the directories a cancelled [`Self::keep_partial`] walk didn't
+    /// finish listing. Ancestors of such a directory are listed as well,
+    /// since their remaining entries were skipped too.
+    pub fn take_interrupted(&self) -> Vec<PathBuf> {
+        std::mem::take(
+            &mut self
+                .interrupted
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Why the root directory couldn't be listed, if it couldn't. The walk
+    /// still returns a tree then, but it holds the root alone.
+    pub fn root_error(&self) -> Option<WalkError> {
+        self.root_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .map(|x| x.load(Ordering::Relaxed))
+            .unwrap_or_default()
+    }
+
     fn should_ignore(&self, path: &Path) -> bool {
         self.ignore_directories.iter().any(|ignore| ignore == path)
     }
 }
 
 pub fn walk_it_without_root_chain(walk_data: &WalkData) -> Option<Node> {
-    walk(walk_data.root_path, walk_data)
+    walk_with_budget(walk_data)
 }
 
 pub fn walk_it(walk_data: &WalkData) -> Option<Node> {
-    walk(walk_data.root_path, walk_data).map(|node_tree| {
+    walk_with_budget(walk_data).map(|node_tree| {
         if let Some(parent) = walk_data.root_path.parent() {
             let mut path = PathBuf::from(parent);
             let mut node = Node {
@@ -138,8 +404,8 @@ pub fn walk_it(walk_data: &WalkData) -> Option<Node> {
                 name: path
                     .iter()
                     .next_back()
+                    .map(name_from_os_str)
                     .expect("at least one parent segment in root path")
-                    .to_string_lossy()
                     .into_owned()
                     .into_boxed_str(),
                 metadata: metadata_of_path(&path).map(NodeMetadata::from),
@@ -150,8 +416,8 @@ pub fn walk_it(walk_data: &WalkData) -> Option<Node> {
                     name: path
                         .iter()
                         .next_back()
+                        .map(name_from_os_str)
                         .expect("at least one parent segment in root path")
-                        .to_string_lossy()
                         .into_owned()
                         .into_boxed_str(),
                     metadata: metadata_of_path(&path).map(NodeMetadata::from),
@@ -164,6 +430,80 @@ pub fn walk_it(walk_data: &WalkData) -> Option<Node> {
     })
 }
 
+/// Walks like [`walk_it_without_root_chain`], but hands every entry to `visit`
+/// as soon as it's listed instead of building a tree, so memory doesn't grow
+/// with the tree. A folder is visited before its contents; siblings arrive
+/// from the walk's threads in no particular order. Returns `false` if the
+/// walk was cancelled.
+pub fn walk_each(walk_data: &WalkData, visit: impl Fn(&Path, Option<NodeMetadata>) + Sync) -> bool {
+    walk_data.options.thread_budget.install(|| {
+        visit_tree(walk_data.root_path, walk_data, &visit);
+        !walk_data.is_cancelled()
+    })
+}
+
+fn visit_tree(
+    path: &Path,
+    walk_data: &WalkData,
+    visit: &(impl Fn(&Path, Option<NodeMetadata>) + Sync),
+) {
+    if walk_data.should_ignore(path) {
+        return;
+    }
+    let metadata = metadata_of_path(path);
+    let is_dir = metadata.as_ref().is_some_and(Metadata::is_dir);
+    visit(path, metadata.map(NodeMetadata::from));
+    if !is_dir {
+        walk_data.num_files.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+    walk_data.num_dirs.fetch_add(1, Ordering::Relaxed);
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(failed) => {
+            if handle_error_and_retry(&failed) {
+                return visit_tree(path, walk_data, visit);
+            }
+            if path == walk_data.root_path {
+                *walk_data
+                    .root_error
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some(WalkError::new(path, &failed));
+            }
+            return;
+        }
+    };
+    // Unlike `walk`, a failed entry isn't retried by listing the folder
+    // again, as its other entries were visited already.
+    entries.flatten().par_bridge().for_each(|entry| {
+        let name = entry.file_name();
+        if walk_data.is_cancelled() || walk_data.options.skips_entry(&name) {
+            return;
+        }
+        // doesn't traverse symlink
+        let Ok(file_type) = entry.file_type() else {
+            return;
+        };
+        if file_type.is_dir() {
+            visit_tree(&entry.path(), walk_data, visit);
+        } else if walk_data.options.records_file(&name) {
+            walk_data.num_files.fetch_add(1, Ordering::Relaxed);
+            let metadata = walk_data
+                .need_metadata
+                .then(|| entry.metadata().ok().map(NodeMetadata::from))
+                .flatten();
+            visit(&entry.path(), metadata);
+        }
+    });
+}
+
+fn walk_with_budget(walk_data: &WalkData) -> Option<Node> {
+    walk_data
+        .options
+        .thread_budget
+        .install(|| walk(walk_data.root_path, walk_data))
+}
+
 fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
     if walk_data.should_ignore(path) {
         return None;
@@ -179,14 +519,12 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                 .filter_map(|entry| {
                     match &entry {
                         Ok(entry) => {
-                            if walk_data
-                                .cancel
-                                .map(|x| x.load(Ordering::Relaxed))
-                                .unwrap_or_default()
-                            {
+                            if walk_data.is_cancelled() {
                                 return None;
                             }
-                            if walk_data.should_ignore(path) {
+                            if walk_data.should_ignore(path)
+                                || walk_data.options.skips_entry(&entry.file_name())
+                            {
                                 return None;
                             }
                             // doesn't traverse symlink
@@ -194,12 +532,13 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                                 if data.is_dir() {
                                     return walk(&entry.path(), walk_data);
                                 } else {
+                                    let name = entry.file_name();
+                                    if !walk_data.options.records_file(&name) {
+                                        return None;
+                                    }
                                     walk_data.num_files.fetch_add(1, Ordering::Relaxed);
-                                    let name = entry
-                                        .file_name()
-                                        .to_string_lossy()
-                                        .into_owned()
-                                        .into_boxed_str();
+                                    let name =
+                                        name_from_os_str(&name).into_owned().into_boxed_str();
                                     return Some(Node {
                                         children: vec![],
                                         name,
@@ -226,28 +565,37 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
             Err(failed) => {
                 if handle_error_and_retry(&failed) {
                     return walk(path, walk_data);
-                } else {
-                    vec![]
                 }
+                if path == walk_data.root_path {
+                    *walk_data
+                        .root_error
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner) =
+                        Some(WalkError::new(path, &failed));
+                }
+                vec![]
             }
         }
     } else {
         walk_data.num_files.fetch_add(1, Ordering::Relaxed);
         vec![]
     };
-    if walk_data
-        .cancel
-        .map(|x| x.load(Ordering::Relaxed))
-        .unwrap_or_default()
-    {
-        return None;
+    if walk_data.is_cancelled() {
+        if !walk_data.keep_partial {
+            return None;
+        }
+        walk_data
+            .interrupted
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(path.to_path_buf());
     }
     let name = path
         .file_name()
-        .map(|x| x.to_string_lossy().into_owned().into_boxed_str())
+        .map(|x| name_from_os_str(x).into_owned().into_boxed_str())
         .unwrap_or_default();
     let mut children = children;
-    children.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    walk_data.sort_key.sort(&mut children);
     Some(Node {
         children,
         name,
@@ -288,6 +636,16 @@ mod tests {
     };
     use tempdir::TempDir;
 
+    fn child_names(node: &Node) -> Vec<&str> {
+        node.children.iter().map(|child| &*child.name).collect()
+    }
+
+    fn set_mtime(path: &Path, secs: u64) {
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
     fn node_for_path<'a>(node: &'a Node, path: &Path) -> &'a Node {
         let mut current = node;
         for component in path.components() {
@@ -358,6 +716,77 @@ mod tests {
         assert_no_file_metadata(root_node);
     }
 
+    #[test]
+    fn test_unlistable_root_is_reported_as_access_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new("fswalk_denied").unwrap();
+        let locked = tmp.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::File::create(locked.join("hidden.txt")).unwrap();
+
+        let readable = WalkData::simple(&locked, false);
+        walk_it(&readable).unwrap();
+        assert_eq!(readable.root_error(), None);
+
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        let listable = fs::read_dir(&locked).is_ok();
+        let denied = WalkData::simple(&locked, false);
+        let node = walk_it_without_root_chain(&denied).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        if listable {
+            // Permissions don't bind root.
+            return;
+        }
+        assert!(node.children.is_empty());
+        assert_eq!(denied.root_error(), Some(WalkError::AccessDenied(locked)));
+    }
+
+    #[test]
+    fn test_sort_children_by_modified_desc() {
+        let tmp = TempDir::new("fswalk_sort_mtime").unwrap();
+        let root = tmp.path();
+        for (name, secs) in [
+            ("old.txt", 1_000_000),
+            ("newest.txt", 3_000_000),
+            ("middle_b.txt", 2_000_000),
+            ("middle_a.txt", 2_000_000),
+        ] {
+            fs::File::create(root.join(name)).unwrap();
+            set_mtime(&root.join(name), secs);
+        }
+
+        let walk_data = WalkData::simple(root, true).sort_children_by(SortKey::ModifiedDesc);
+        let node = walk_it(&walk_data).expect("walked tree");
+        assert_eq!(
+            child_names(node_for_path(&node, root)),
+            ["newest.txt", "middle_a.txt", "middle_b.txt", "old.txt"]
+        );
+    }
+
+    #[test]
+    fn test_sort_children_by_applies_to_nested_directories() {
+        let tmp = TempDir::new("fswalk_sort_nested").unwrap();
+        let root = tmp.path();
+        fs::create_dir(root.join("nested")).unwrap();
+        for name in ["zeta", "alpha", "mu"] {
+            fs::File::create(root.join("nested").join(name)).unwrap();
+        }
+
+        let walk_data = WalkData::simple(root, false).sort_children_by(SortKey::Name);
+        let node = walk_it(&walk_data).expect("walked tree");
+        assert_eq!(
+            child_names(node_for_path(&node, &root.join("nested"))),
+            ["alpha", "mu", "zeta"]
+        );
+
+        let walk_data = WalkData::simple(root, false).sort_children_by(SortKey::Unsorted);
+        let node = walk_it(&walk_data).expect("walked tree");
+        let mut names = child_names(node_for_path(&node, &root.join("nested")));
+        names.sort_unstable();
+        assert_eq!(names, ["alpha", "mu", "zeta"]);
+    }
+
     #[test]
     fn test_walk_sorts_children_and_preserves_global_order() {
         let tmp = TempDir::new("fswalk_sorted_children").unwrap();
@@ -408,6 +837,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_thread_budget_of_one_walks_serially_with_identical_tree() {
+        let serial = ThreadBudget::threads(1);
+        assert_eq!(serial.install(rayon::current_num_threads), 1);
+        assert_eq!(ThreadBudget::threads(0), ThreadBudget::Available);
+
+        let tmp = TempDir::new("fswalk_thread_budget").unwrap();
+        for dir in 0..8 {
+            for file in 0..16 {
+                let path = tmp.path().join(format!("dir_{dir}/sub_{}", file % 3));
+                fs::create_dir_all(&path).unwrap();
+                fs::File::create(path.join(format!("file_{file}.txt"))).unwrap();
+            }
+        }
+
+        fn flatten(node: &Node, prefix: &Path, acc: &mut Vec<PathBuf>) {
+            let current = prefix.join(&*node.name);
+            for child in &node.children {
+                flatten(child, &current, acc);
+            }
+            acc.push(current);
+        }
+        let walk = |budget| {
+            let walk_data = WalkData::simple(tmp.path(), false).thread_budget(budget);
+            let node = walk_it_without_root_chain(&walk_data).expect("walked tree");
+            let mut paths = Vec::new();
+            flatten(&node, Path::new(""), &mut paths);
+            let counts = (
+                walk_data.num_files.load(Ordering::Relaxed),
+                walk_data.num_dirs.load(Ordering::Relaxed),
+            );
+            (paths, counts)
+        };
+        let parallel = walk(ThreadBudget::Available);
+        assert_eq!(parallel.1, (128, 33));
+        assert_eq!(walk(serial), parallel);
+    }
+
+    #[test]
+    fn test_thread_budget_reuses_its_pool() {
+        let budget = ThreadBudget::threads(3);
+        let threads: std::collections::HashSet<_> = (0..64)
+            .map(|_| budget.install(|| std::thread::current().id()))
+            .collect();
+        // A fresh pool per call would run each call on new threads.
+        assert!(threads.len() <= 3, "{threads:?}");
+    }
+
     #[test]
     fn test_walk_with_metadata_enabled() {
         let tmp = TempDir::new("fswalk_meta").unwrap();
@@ -559,4 +1036,41 @@ mod tests {
             });
         });
     }
+
+    #[test]
+    fn walk_each_visits_what_the_walk_keeps() {
+        let tmp = TempDir::new("fswalk_each").unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("dir_a/nested")).unwrap();
+        fs::create_dir(root.join(".hidden")).unwrap();
+        fs::write(root.join("dir_a/nested/file.txt"), b"hello").unwrap();
+        fs::write(root.join("dir_a/skip.log"), b"").unwrap();
+        fs::write(root.join(".hidden/file.txt"), b"").unwrap();
+        let walk_data = WalkData::simple(root, true)
+            .only_extensions(&["txt"])
+            .skip_hidden(true);
+
+        let visited = Mutex::new(Vec::new());
+        assert!(walk_each(&walk_data, |path, metadata| {
+            visited
+                .lock()
+                .unwrap()
+                .push((path.strip_prefix(root).unwrap().to_path_buf(), metadata));
+        }));
+        let visited = visited.into_inner().unwrap();
+        let position = |path: &str| {
+            visited
+                .iter()
+                .position(|(visited, _)| visited == Path::new(path))
+                .unwrap_or_else(|| panic!("{path} not visited"))
+        };
+        assert_eq!(visited.len(), 4, "{visited:?}");
+        assert!(position("") < position("dir_a"));
+        assert!(position("dir_a") < position("dir_a/nested"));
+        assert!(position("dir_a/nested") < position("dir_a/nested/file.txt"));
+        let file = &visited[position("dir_a/nested/file.txt")].1;
+        assert_eq!(file.as_ref().map(|metadata| metadata.size), Some(5));
+        assert_eq!(walk_data.num_files.load(Ordering::Relaxed), 1);
+        assert_eq!(walk_data.num_dirs.load(Ordering::Relaxed), 3);
+    }
 }
@@ -0,0 +1,114 @@
+//! Lossless conversion between file names and the `str` names kept in trees.
+//!
+//! Unix file names are arbitrary bytes, but every index downstream works on
+//! `str`. Valid UTF-8 passes through untouched. Each byte of an invalid sequence
+//! becomes one char from the last 128 code points of plane 16 (private use),
+//! `U+10FF80 + (byte - 0x80)`. Real names holding one of those chars get it
+//! escaped byte by byte too, so [`name_to_os_str`] always restores the original
+//! bytes. Searches see the escaped form, so the valid parts of a name still
+//! match normally.
+
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    os::unix::ffi::{OsStrExt, OsStringExt},
+};
+
+const ESCAPE_BASE: u32 = 0x10FF00;
+
+/// First byte of every char in plane 16, escape chars included. Names without
+/// it can't hold an escape.
+const PLANE_16_LEAD: u8 = 0xF4;
+
+fn is_escape(c: char) -> bool {
+    (ESCAPE_BASE + 0x80..=ESCAPE_BASE + 0xFF).contains(&(c as u32))
+}
+
+fn escape(byte: u8) -> char {
+    debug_assert!(byte >= 0x80, "only non-ASCII bytes are escaped");
+    char::from_u32(ESCAPE_BASE + u32::from(byte)).expect("escape range is valid")
+}
+
+/// The name stored for `name`; borrowed unless it needed escaping.
+pub fn name_from_os_str(name: &OsStr) -> Cow<'_, str> {
+    let bytes = name.as_bytes();
+    if let Ok(name) = std::str::from_utf8(bytes)
+        && !bytes.contains(&PLANE_16_LEAD)
+    {
+        return Cow::Borrowed(name);
+    }
+    let mut escaped = String::with_capacity(bytes.len() * 2);
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if is_escape(c) {
+                escaped.extend(c.encode_utf8(&mut [0; 4]).bytes().map(escape));
+            } else {
+                escaped.push(c);
+            }
+        }
+        escaped.extend(chunk.invalid().iter().copied().map(escape));
+    }
+    Cow::Owned(escaped)
+}
+
+/// Inverse of [`name_from_os_str`].
+pub fn name_to_os_str(name: &str) -> Cow<'_, OsStr> {
+    if !name.as_bytes().contains(&PLANE_16_LEAD) {
+        return Cow::Borrowed(OsStr::new(name));
+    }
+    let mut bytes = Vec::with_capacity(name.len());
+    for c in name.chars() {
+        if is_escape(c) {
+            bytes.push((c as u32 - ESCAPE_BASE) as u8);
+        } else {
+            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+    }
+    Cow::Owned(OsString::from_vec(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(bytes: &[u8]) -> Cow<'static, str> {
+        let name = OsStr::from_bytes(bytes);
+        let stored = name_from_os_str(name).into_owned();
+        assert_eq!(name_to_os_str(&stored).as_bytes(), bytes);
+        Cow::Owned(stored)
+    }
+
+    #[test]
+    fn utf8_names_are_stored_as_is() {
+        assert!(matches!(
+            name_from_os_str(OsStr::new("café 🦀.txt")),
+            Cow::Borrowed("café 🦀.txt")
+        ));
+        assert!(matches!(
+            name_to_os_str("café 🦀.txt"),
+            Cow::Borrowed(name) if name == "café 🦀.txt"
+        ));
+        // Plane 16 chars outside the escape range need no escaping.
+        assert_eq!(round_trip("\u{10FF7F}".as_bytes()), "\u{10FF7F}");
+    }
+
+    #[test]
+    fn invalid_bytes_round_trip() {
+        let stored = round_trip(b"caf\xE9.txt");
+        assert!(stored.starts_with("caf") && stored.ends_with(".txt"));
+        assert!(!stored.contains('\u{FFFD}'));
+        round_trip(b"\xFF\xFE");
+        round_trip(b"ok\xC3");
+        round_trip(b"\xE2\x82 mid");
+    }
+
+    #[test]
+    fn real_escape_chars_are_escaped_too() {
+        // A valid name holding an escape char must not decode to a single byte.
+        let stored = round_trip("x\u{10FFE9}".as_bytes());
+        assert_eq!(stored.chars().count(), 5);
+        let mut mixed = "\u{10FFE9}".as_bytes().to_vec();
+        mixed.push(0xE9);
+        round_trip(&mixed);
+    }
+}
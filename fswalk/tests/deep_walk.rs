@@ -102,3 +102,84 @@ fn cancellation_stops_traversal_early() {
         "expected immediate cancellation to abort traversal"
     );
 }
+
+#[test]
+fn keep_partial_returns_the_tree_walked_before_cancellation() {
+    let tmp = TempDir::new("fswalk_keep_partial").unwrap();
+    build_deep_fixture(tmp.path());
+    let cancel = AtomicBool::new(true);
+    let walk_data = WalkData::new(tmp.path(), &[], false, Some(&cancel)).keep_partial(true);
+    let tree = walk_it(&walk_data).expect("partial root node");
+    let root = node_for_path(&tree, tmp.path());
+    assert!(root.children.is_empty());
+    assert_eq!(walk_data.take_interrupted(), [tmp.path().to_path_buf()]);
+    assert!(walk_data.take_interrupted().is_empty());
+
+    // A walk that isn't cancelled leaves nothing interrupted.
+    cancel.store(false, Ordering::Relaxed);
+    let walk_data = WalkData::new(tmp.path(), &[], false, Some(&cancel)).keep_partial(true);
+    let tree = walk_it(&walk_data).expect("root node");
+    assert_eq!(node_for_path(&tree, tmp.path()).children.len(), 4);
+    assert!(walk_data.take_interrupted().is_empty());
+}
+
+#[test]
+fn only_extensions_keeps_matching_files_and_all_directories() {
+    let tmp = TempDir::new("fswalk_only_ext").unwrap();
+    build_deep_fixture(tmp.path());
+    fs::write(tmp.path().join("main.rs"), b"m").unwrap();
+    fs::write(tmp.path().join("Cargo.TOML"), b"c").unwrap();
+    fs::write(tmp.path().join("keep_dir/nested/lib.rs"), b"l").unwrap();
+    fs::write(tmp.path().join("keep_dir/rs"), b"r").unwrap();
+    let walk_data = WalkData::new(tmp.path(), &[], false, None).only_extensions(&["rs", "toml"]);
+    let tree = walk_it(&walk_data).expect("root node");
+    let tree = node_for_path(&tree, tmp.path());
+    let names = |node: &fswalk::Node| -> Vec<String> {
+        node.children.iter().map(|c| c.name.to_string()).collect()
+    };
+
+    assert_eq!(
+        names(tree),
+        ["Cargo.TOML", "keep_dir", "main.rs", "skip_dir"]
+    );
+    // Directories stay even when nothing inside them matches.
+    let skip_dir = node_for_path(tree, Path::new("skip_dir"));
+    assert!(skip_dir.children.is_empty());
+    let keep_dir = node_for_path(tree, Path::new("keep_dir"));
+    assert_eq!(names(keep_dir), ["nested"]);
+    let nested = node_for_path(tree, Path::new("keep_dir/nested"));
+    assert_eq!(names(nested), ["lib.rs"]);
+    assert_eq!(walk_data.num_files.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn skip_hidden_leaves_out_dotfiles_and_hidden_subtrees() {
+    let tmp = TempDir::new("fswalk_skip_hidden").unwrap();
+    build_deep_fixture(tmp.path());
+    fs::create_dir_all(tmp.path().join(".git/objects")).unwrap();
+    fs::write(tmp.path().join(".git/HEAD"), b"ref").unwrap();
+    fs::write(tmp.path().join(".DS_Store"), b"d").unwrap();
+    fs::write(tmp.path().join("keep_dir/.env"), b"e").unwrap();
+    let names = |node: &fswalk::Node| -> Vec<String> {
+        node.children.iter().map(|c| c.name.to_string()).collect()
+    };
+
+    let walk_data = WalkData::new(tmp.path(), &[], false, None);
+    let tree = walk_it(&walk_data).expect("root node");
+    let tree = node_for_path(&tree, tmp.path());
+    assert!(names(tree).contains(&".git".to_string()));
+    assert!(names(tree).contains(&".DS_Store".to_string()));
+
+    let walk_data = WalkData::new(tmp.path(), &[], false, None).skip_hidden(true);
+    let tree = walk_it(&walk_data).expect("root node");
+    let tree = node_for_path(&tree, tmp.path());
+    assert_eq!(
+        names(tree),
+        ["keep_a.txt", "keep_b.log", "keep_dir", "skip_dir"]
+    );
+    let keep_dir = node_for_path(tree, Path::new("keep_dir"));
+    assert_eq!(names(keep_dir), ["nested"]);
+    // Nothing under `.git` was visited.
+    assert_eq!(walk_data.num_dirs.load(Ordering::Relaxed), 4);
+    assert_eq!(walk_data.num_files.load(Ordering::Relaxed), 4);
+}
@@ -6,7 +6,10 @@ use napi_ohos::{Error, Result};
 use ohos_fileuri_binding::get_path_from_uri;
 use ohos_hilog_binding::{hilog_debug, hilog_info};
 use once_cell::sync::{Lazy, OnceCell};
-use search_cache::{SearchCache, SearchOptions, SearchResultNode, SlabNodeMetadataCompact, WalkData};
+use search_cache::{
+    EmptyBehavior, IndexStats, SearchCache, SearchOptions, SearchResultNode,
+    SlabNodeMetadataCompact, WalkData,
+};
 use search_cancel::CancellationToken;
 use std::{
     path::PathBuf,
@@ -39,6 +42,28 @@ pub struct NodeInfoMetadata {
     pub mtime: u32,
 }
 
+// 索引统计 - 与 Tauri 版本保持一致
+#[napi(object)]
+pub struct IndexStatsInfo {
+    pub files: u32,
+    pub directories: u32,
+    pub symlinks: u32,
+    pub unique_names: u32,
+    pub name_pool_bytes: i64,
+}
+
+impl From<IndexStats> for IndexStatsInfo {
+    fn from(stats: IndexStats) -> Self {
+        Self {
+            files: stats.files as u32,
+            directories: stats.directories as u32,
+            symlinks: stats.symlinks as u32,
+            unique_names: stats.unique_names as u32,
+            name_pool_bytes: stats.name_pool_bytes as i64,
+        }
+    }
+}
+
 #[napi]
 #[repr(u8)]
 pub enum NodeFileType {
@@ -219,8 +244,11 @@ pub(crate) fn build_search_cache(
                 std::thread::sleep(Duration::from_millis(100));
             }
         });
-        let cache =
-            SearchCache::walk_fs_with_walk_data(&walk_data, Some(&APP_QUIT));
+        let cache = SearchCache::walk_fs_with_walk_data(&walk_data, Some(&APP_QUIT))
+            .unwrap_or_else(|e| {
+                hilog_info!("Failed to walk {}: {:?}", watch_root, e);
+                None
+            });
         walking_done.store(true, Ordering::Relaxed);
         cache
     })
@@ -315,6 +343,9 @@ pub async fn search(
     // 配置搜索选项
     let options = SearchOptions {
         case_insensitive: case_insensitive.unwrap_or(false),
+        // 空查询列出全部文件
+        empty_query: EmptyBehavior::MatchAll,
+        ..Default::default()
     };
 
     // 执行搜索
@@ -386,7 +417,7 @@ pub async fn get_nodes_info(
 
     let node_infos: Vec<NodeInfo> = nodes
         .into_iter()
-        .map(|SearchResultNode { path, metadata }| {
+        .map(|SearchResultNode { path, metadata, .. }| {
             let path_str = path.to_string_lossy().into_owned();
 
             // 计算图标（如果需要）
@@ -418,6 +449,19 @@ pub async fn get_nodes_info(
     Ok(node_infos)
 }
 
+// 获取索引统计
+#[napi]
+pub async fn get_index_stats() -> Result<IndexStatsInfo> {
+    let state = BACKEND_STATE.read().unwrap();
+    let search_cache_ref = match &state.search_cache {
+        Some(cache) => cache.clone(),
+        None => return Err(Error::from_reason("Search cache not initialized")),
+    };
+    let stats = search_cache_ref.read().unwrap().stats();
+    hilog_debug!("Backend: get_index_stats {:?}", stats);
+    Ok(stats.into())
+}
+
 // 触发重新扫描 - 桩实现
 #[napi]
 pub async fn trigger_rescan() -> Result<()> {
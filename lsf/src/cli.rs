@@ -1,5 +1,5 @@
-use clap::Parser;
-use std::path::PathBuf;
+use clap::{Parser, ValueEnum};
+use std::{path::PathBuf, time::Duration};
 
 #[derive(Parser)]
 pub struct Cli {
@@ -8,4 +8,39 @@ pub struct Cli {
     pub refresh: bool,
     #[clap(long, default_value = "/")]
     pub path: PathBuf,
+    #[clap(long, default_value = "false")]
+    /// Answer newline-delimited queries from stdin without prompts, for
+    /// editors and other frontends. Each result block ends with a blank line.
+    pub server: bool,
+    #[clap(long, default_value = "false", requires = "server")]
+    /// In server mode, end each path with `\0` instead of a newline and each
+    /// block with an extra `\0`, so paths may contain newlines.
+    pub null: bool,
+    #[clap(long)]
+    /// Print paths under this directory relative to it.
+    pub relative: Option<PathBuf>,
+    #[clap(long, default_value = "false")]
+    /// Print bare paths, each ending with `\0`, for `xargs -0`. Without
+    /// `--server` the prompt and per-result details are left out too.
+    pub print0: bool,
+    #[clap(long, value_enum)]
+    /// Walk `--path` and print every entry to stdout instead of searching.
+    /// The cache is neither read nor written.
+    pub emit: Option<Emit>,
+    #[clap(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..))]
+    /// Milliseconds between progress lines while walking the filesystem; at
+    /// least 1.
+    pub progress_interval_ms: u64,
+}
+
+impl Cli {
+    pub fn progress_interval(&self) -> Duration {
+        Duration::from_millis(self.progress_interval_ms)
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Emit {
+    /// One `{"path", "type", "size", "mtime"}` JSON object per line.
+    Jsonl,
 }
@@ -0,0 +1,69 @@
+//! `--emit jsonl` mode: the walked entries as JSON lines on stdout, for
+//! feeding other tools without going through the search index.
+
+use anyhow::{Context, Result, ensure};
+use fswalk::{NodeFileType, NodeMetadata, WalkData, walk_each};
+use serde::Serialize;
+use std::{io::Write, path::Path};
+
+/// One line of output. Unknown metadata is written as `null`.
+#[derive(Serialize)]
+struct Entry<'a> {
+    path: &'a str,
+    r#type: &'static str,
+    size: Option<u64>,
+    /// Seconds since the Unix epoch.
+    mtime: Option<u64>,
+}
+
+/// How many walked entries may wait for the writer before the walk blocks.
+const PENDING_ENTRIES: usize = 4096;
+
+/// Walks `walk_data` and writes every entry to `output` as a JSON object on
+/// its own line as soon as the walk lists it, each folder before its contents
+/// but siblings in no particular order. Non-UTF-8 paths are written lossily.
+/// Returns how many entries were written.
+pub fn emit_jsonl(walk_data: &WalkData, mut output: impl Write) -> Result<usize> {
+    let (sender, receiver) = crossbeam_channel::bounded(PENDING_ENTRIES);
+    let (complete, written) = std::thread::scope(|s| {
+        let walker = s.spawn(move || {
+            walk_each(walk_data, |path, metadata| {
+                // Only fails once the writer gave up; the walk runs out regardless.
+                let _ = sender.send((path.to_path_buf(), metadata));
+            })
+        });
+        let mut written = 0;
+        for (path, metadata) in receiver {
+            write_entry(&mut output, &path, metadata.as_ref())?;
+            written += 1;
+        }
+        let complete = walker.join().expect("walk thread panicked");
+        anyhow::Ok((complete, written))
+    })?;
+    ensure!(complete, "Failed to walk {:?}", walk_data.root_path);
+    output.flush().context("Failed to flush entries")?;
+    Ok(written)
+}
+
+fn write_entry(
+    output: &mut impl Write,
+    path: &Path,
+    metadata: Option<&NodeMetadata>,
+) -> Result<()> {
+    let entry = Entry {
+        path: &path.to_string_lossy(),
+        r#type: match metadata.map(|metadata| metadata.r#type) {
+            Some(NodeFileType::File) => "file",
+            Some(NodeFileType::Dir) => "dir",
+            Some(NodeFileType::Symlink) => "symlink",
+            Some(NodeFileType::Unknown) | None => "unknown",
+        },
+        size: metadata.map(|metadata| metadata.size),
+        mtime: metadata
+            .and_then(|metadata| metadata.mtime)
+            .map(|x| x.get()),
+    };
+    serde_json::to_writer(&mut *output, &entry)?;
+    output.write_all(b"\n")?;
+    Ok(())
+}
@@ -1,10 +1,17 @@
 mod cli;
+mod emit;
+mod output;
+mod progress;
+mod server;
 
 use anyhow::{Context, Result};
-use cardinal_sdk::EventWatcher;
+use cardinal_sdk::{EventWatcher, WatcherFlags};
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Emit};
 use crossbeam_channel::{Sender, bounded, unbounded};
+use fswalk::WalkData;
+use output::{PathFormat, Separator};
+use progress::StderrProgress;
 use search_cache::{HandleFSEError, SearchCache, SearchResultNode};
 use search_cancel::CancellationToken;
 use std::{
@@ -17,7 +24,8 @@ const CACHE_PATH: &str = "target/cache.zstd";
 const IGNORE_PATH: &str = "/System/Volumes/Data"; // macOS specific ignore path
 
 fn main() -> Result<()> {
-    let builder = tracing_subscriber::fmt();
+    // Logs share stderr with status messages; stdout only carries results.
+    let builder = tracing_subscriber::fmt().with_writer(std::io::stderr);
     if let Ok(filter) = EnvFilter::try_from_default_env() {
         builder.with_env_filter(filter).init();
     } else {
@@ -25,30 +33,47 @@ fn main() -> Result<()> {
     }
 
     let cli = Cli::parse();
+    let progress_interval = cli.progress_interval();
     let path = cli.path;
     let ignore_paths = vec![PathBuf::from(IGNORE_PATH)];
+    let walk = || {
+        let walk_data = WalkData::new(&path, &ignore_paths, false, None);
+        progress::walk_with_progress(&walk_data, progress_interval, &mut StderrProgress)
+            .map(|cache| cache.expect("walk without a cancel flag always finishes"))
+    };
+    if let Some(Emit::Jsonl) = cli.emit {
+        let walk_data = WalkData::new(&path, &ignore_paths, true, None);
+        let written = emit::emit_jsonl(&walk_data, std::io::stdout().lock())?;
+        eprintln!("Emitted {written} entries");
+        return Ok(());
+    }
     let mut cache = if cli.refresh {
-        println!("Walking filesystem...");
-        SearchCache::walk_fs_with_ignore(&path, &ignore_paths)
+        walk()?
     } else {
-        println!("Try reading cache...");
+        eprintln!("Try reading cache...");
         SearchCache::try_read_persistent_cache(&path, Path::new(CACHE_PATH), &ignore_paths, None)
-            .unwrap_or_else(|e| {
-                println!("Failed to read cache: {e:?}. Re-walking filesystem...");
-                SearchCache::walk_fs_with_ignore(&path, &ignore_paths)
-            })
+            .or_else(|e| {
+                eprintln!("Failed to read cache: {e:?}. Re-walking filesystem...");
+                walk()
+            })?
     };
 
-    println!("Cache is: {cache:?}");
+    eprintln!("Cache is: {cache:?}");
 
     let (finish_tx, finish_rx) = bounded::<Sender<SearchCache>>(1);
     let (search_tx, search_rx) = unbounded::<String>();
     let (search_result_tx, search_result_rx) = unbounded::<Result<Vec<SearchResultNode>>>();
 
     std::thread::spawn(move || {
-        let (dev, mut event_watcher) =
-            EventWatcher::spawn("/".to_string(), cache.last_event_id(), 0.1);
-        println!("Processing changes of dev:{dev} during preparation.");
+        // Watch the same subtrees that were walked, so changes elsewhere
+        // aren't even reported.
+        let (dev, mut event_watcher) = EventWatcher::spawn_paths(
+            cache.watch_paths(),
+            cache.resume_event_id(),
+            0.1,
+            WatcherFlags::default(),
+        );
+        eprintln!("Processing changes of dev:{dev} during preparation.");
         loop {
             crossbeam_channel::select! {
                 recv(finish_rx) -> tx => {
@@ -66,28 +91,83 @@ fn main() -> Result<()> {
                 recv(event_watcher) -> events => {
                     let events = events.expect("event_stream is closed");
                     if let Err(HandleFSEError::Rescan) = cache.handle_fs_events(events) {
-                        println!("!!!!!!!!!! Rescan triggered !!!!!!!!");
+                        eprintln!("!!!!!!!!!! Rescan triggered !!!!!!!!");
                         // Here we clear event_watcher first as rescan may take a lot of time
                         #[allow(unused_assignments)]
                         {
                             event_watcher = EventWatcher::noop();
                         }
-                        cache.rescan();
-                        event_watcher = EventWatcher::spawn("/".to_string(), cache.last_event_id(), 0.1).1;
+                        if let Err(err) = cache.rescan() {
+                            eprintln!("Rescan failed, keeping the old index: {err:?}");
+                        }
+                        event_watcher = EventWatcher::spawn_paths(cache.watch_paths(), cache.resume_event_id(), 0.1, WatcherFlags::default()).1;
                     }
                 }
             }
         }
-        println!("fsevent processing is done");
+        eprintln!("fsevent processing is done");
     });
 
+    // The outer error means the search thread is gone, the inner one that
+    // the query failed.
+    let search = |query: String| -> Result<Result<Vec<SearchResultNode>>> {
+        search_tx.send(query).context("search_tx is closed")?;
+        search_result_rx
+            .recv()
+            .context("search_result_rx is closed")
+    };
+    let format = PathFormat {
+        relative_to: cli
+            .relative
+            .map(std::path::absolute)
+            .transpose()
+            .context("Failed to resolve --relative")?,
+        separator: if cli.null || cli.print0 {
+            Separator::Null
+        } else {
+            Separator::Newline
+        },
+    };
+    if cli.server {
+        server::serve(
+            std::io::stdin().lock(),
+            std::io::stdout().lock(),
+            &format,
+            |query| Ok(search(query)??.into_iter().map(|node| node.path).collect()),
+        )?;
+    } else {
+        repl(search, &format, cli.print0)?;
+    }
+
+    let (cache_tx, cache_rx) = bounded::<SearchCache>(1);
+    finish_tx.send(cache_tx).context("cache_tx is closed")?;
+    let cache = cache_rx.recv().context("cache_tx is closed")?;
+    eprintln!("start writing cache: {cache:?}");
+    cache
+        .flush_to_file(Path::new(CACHE_PATH))
+        .context("Failed to write cache to file")?;
+
+    Ok(())
+}
+
+/// Interactive loop until `/bye`. With `bare`, only the formatted paths are
+/// printed, and input ends at EOF as well.
+fn repl(
+    search: impl Fn(String) -> Result<Result<Vec<SearchResultNode>>>,
+    format: &PathFormat,
+    bare: bool,
+) -> Result<()> {
     let stdin = std::io::stdin();
     let mut stdout = std::io::stdout();
     loop {
-        print!("> ");
-        stdout.flush().unwrap();
+        if !bare {
+            print!("> ");
+            stdout.flush().unwrap();
+        }
         let mut line = String::new();
-        stdin.read_line(&mut line).unwrap();
+        if stdin.read_line(&mut line).unwrap() == 0 && bare {
+            break;
+        }
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -95,16 +175,16 @@ fn main() -> Result<()> {
             break;
         }
 
-        search_tx
-            .send(line.to_string())
-            .context("search_tx is closed")?;
-        let search_result = search_result_rx
-            .recv()
-            .context("search_result_rx is closed")?;
-        match search_result {
+        match search(line.to_string())? {
+            Ok(path_set) if bare => {
+                for path in path_set {
+                    format.write(&mut stdout, &path.path)?;
+                }
+                stdout.flush()?;
+            }
             Ok(path_set) => {
                 for (i, path) in path_set.into_iter().enumerate() {
-                    println!("[{i}] {:?} {:?}", path.path, path.metadata);
+                    println!("[{i}] {:?} {:?}", format.render(&path.path), path.metadata);
                 }
             }
             Err(e) => {
@@ -112,14 +192,5 @@ fn main() -> Result<()> {
             }
         }
     }
-
-    let (cache_tx, cache_rx) = bounded::<SearchCache>(1);
-    finish_tx.send(cache_tx).context("cache_tx is closed")?;
-    let cache = cache_rx.recv().context("cache_tx is closed")?;
-    println!("start writing cache: {cache:?}");
-    cache
-        .flush_to_file(Path::new(CACHE_PATH))
-        .context("Failed to write cache to file")?;
-
     Ok(())
 }
@@ -0,0 +1,122 @@
+//! How result paths are written to stdout.
+
+use std::{
+    io::{self, Write},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+/// What ends each written path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    Newline,
+    Null,
+}
+
+impl Separator {
+    pub fn byte(self) -> u8 {
+        match self {
+            Separator::Newline => b'\n',
+            Separator::Null => b'\0',
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PathFormat {
+    /// Paths under this absolute root are written relative to it; others
+    /// keep their absolute form.
+    pub relative_to: Option<PathBuf>,
+    pub separator: Separator,
+}
+
+impl PathFormat {
+    /// `path` as it should be shown. The root itself becomes `.`, so no
+    /// result is ever written as an empty record.
+    pub fn render<'p>(&self, path: &'p Path) -> &'p Path {
+        match self
+            .relative_to
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+        {
+            Some(relative) if relative.as_os_str().is_empty() => Path::new("."),
+            Some(relative) => relative,
+            None => path,
+        }
+    }
+
+    /// Writes the rendered `path` as raw bytes followed by the separator.
+    pub fn write(&self, output: &mut impl Write, path: &Path) -> io::Result<()> {
+        output.write_all(self.render(path).as_os_str().as_bytes())?;
+        self.end_record(output)
+    }
+
+    /// Writes a lone separator, which ends a block in server mode.
+    pub fn end_record(&self, output: &mut impl Write) -> io::Result<()> {
+        output.write_all(&[self.separator.byte()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use search_cache::SearchCache;
+    use search_cancel::CancellationToken;
+    use std::fs;
+    use tempdir::TempDir;
+
+    fn format(relative_to: Option<&Path>, separator: Separator) -> PathFormat {
+        PathFormat {
+            relative_to: relative_to.map(Path::to_path_buf),
+            separator,
+        }
+    }
+
+    #[test]
+    fn relative_paths_strip_whole_components_only() {
+        let format = format(Some(Path::new("/data/photos")), Separator::Newline);
+        assert_eq!(
+            format.render(Path::new("/data/photos/2024/a.jpg")),
+            Path::new("2024/a.jpg")
+        );
+        assert_eq!(format.render(Path::new("/data/photos")), Path::new("."));
+        // A sibling sharing the root's text as a prefix is outside it.
+        assert_eq!(
+            format.render(Path::new("/data/photos-old/b.jpg")),
+            Path::new("/data/photos-old/b.jpg")
+        );
+        assert_eq!(
+            format.render(Path::new("/etc/hosts")),
+            Path::new("/etc/hosts")
+        );
+    }
+
+    #[test]
+    fn print0_terminates_every_cached_path_with_nul() {
+        let tmp = TempDir::new("lsf_output").unwrap();
+        let root = tmp.path();
+        fs::create_dir(root.join("sub")).unwrap();
+        for name in ["top-a.txt", "sub/top-b.txt", "sub/line\ntop-c.txt"] {
+            fs::write(root.join(name), b"x").unwrap();
+        }
+        let mut cache = SearchCache::walk_fs(root);
+        let nodes = cache
+            .query_files("top-".to_string(), CancellationToken::noop())
+            .unwrap()
+            .unwrap();
+
+        let format = format(Some(root), Separator::Null);
+        let mut output = Vec::new();
+        for node in &nodes {
+            format.write(&mut output, &node.path).unwrap();
+        }
+        assert_eq!(output.last(), Some(&b'\0'));
+        let mut records: Vec<&[u8]> = output.split(|&byte| byte == b'\0').collect();
+        assert_eq!(records.pop(), Some(&[][..]));
+        records.sort();
+        assert_eq!(
+            records,
+            [&b"sub/line\ntop-c.txt"[..], b"sub/top-b.txt", b"top-a.txt"]
+        );
+    }
+}
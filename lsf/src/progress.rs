@@ -0,0 +1,152 @@
+//! Progress of the initial walk, reported through a [`ProgressSink`] so other
+//! frontends can show it instead of the stderr lines.
+
+use anyhow::Result;
+use fswalk::WalkData;
+use search_cache::SearchCache;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+/// A snapshot of the walk so far.
+#[derive(Debug, Clone, Copy)]
+pub struct WalkProgress {
+    /// Files and folders visited.
+    pub entries: usize,
+    pub elapsed: Duration,
+    /// Set on the last report only, once the walk has returned.
+    pub done: bool,
+}
+
+impl WalkProgress {
+    /// Entries visited per second so far.
+    pub fn rate(&self) -> f64 {
+        self.entries as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+pub trait ProgressSink: Send {
+    /// Called every reporting interval while walking, and once more with
+    /// [`WalkProgress::done`] set when the walk ends.
+    fn report(&mut self, progress: WalkProgress);
+}
+
+/// Prints each report as a line on stderr.
+pub struct StderrProgress;
+
+impl ProgressSink for StderrProgress {
+    fn report(&mut self, progress: WalkProgress) {
+        if progress.done {
+            eprintln!(
+                "Walked {} entries in {:.2?}",
+                progress.entries, progress.elapsed
+            );
+        } else {
+            eprintln!(
+                "Walking filesystem... {} entries ({:.0}/s)",
+                progress.entries,
+                progress.rate()
+            );
+        }
+    }
+}
+
+/// [`SearchCache::walk_fs_with_walk_data`], reporting to `sink` every
+/// `interval` from a thread alongside the walk.
+pub fn walk_with_progress(
+    walk_data: &WalkData,
+    interval: Duration,
+    sink: &mut impl ProgressSink,
+) -> Result<Option<SearchCache>> {
+    let started = Instant::now();
+    let walking_done = AtomicBool::new(false);
+    let progress = |done| WalkProgress {
+        entries: walk_data.num_dirs.load(Ordering::Relaxed)
+            + walk_data.num_files.load(Ordering::Relaxed),
+        elapsed: started.elapsed(),
+        done,
+    };
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let mut next = started + interval;
+            while !walking_done.load(Ordering::Relaxed) {
+                if Instant::now() >= next {
+                    sink.report(progress(false));
+                    next += interval;
+                }
+                std::thread::sleep(interval.min(Duration::from_millis(10)));
+            }
+            sink.report(progress(true));
+        });
+        // Set even if the walk panics, or the scope would wait on the
+        // reporter forever instead of unwinding.
+        let _done = SetOnDrop(&walking_done);
+        SearchCache::walk_fs_with_walk_data(walk_data, None)
+    })
+}
+
+struct SetOnDrop<'a>(&'a AtomicBool);
+
+impl Drop for SetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use search_cancel::CancellationToken;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[derive(Default)]
+    struct Recorded(Vec<WalkProgress>);
+
+    impl ProgressSink for Recorded {
+        fn report(&mut self, progress: WalkProgress) {
+            self.0.push(progress);
+        }
+    }
+
+    #[test]
+    fn reports_end_with_the_whole_walk() {
+        let tmp = TempDir::new("lsf_progress").unwrap();
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+        for name in ["a.txt", "b.txt", "sub/c.txt"] {
+            fs::write(tmp.path().join(name), b"x").unwrap();
+        }
+        let walk_data = WalkData::new(tmp.path(), &[], false, None);
+        let mut sink = Recorded::default();
+        let mut cache = walk_with_progress(&walk_data, Duration::from_millis(1), &mut sink)
+            .unwrap()
+            .unwrap();
+
+        let (last, rest) = sink.0.split_last().unwrap();
+        assert!(last.done);
+        // The root, `sub` and the three files.
+        assert_eq!(last.entries, 5);
+        assert!(rest.iter().all(|progress| !progress.done));
+        assert!(
+            rest.windows(2)
+                .all(|pair| pair[0].entries <= pair[1].entries)
+        );
+        let txt = cache
+            .query_files("*.txt".to_string(), CancellationToken::noop())
+            .unwrap()
+            .unwrap();
+        assert_eq!(txt.len(), 3);
+    }
+
+    #[test]
+    fn a_panicking_walk_stops_the_reporter() {
+        let walking_done = AtomicBool::new(false);
+        let unwound = std::panic::catch_unwind(|| {
+            let _done = SetOnDrop(&walking_done);
+            panic!("walk failed");
+        });
+        assert!(unwound.is_err());
+        assert!(walking_done.load(Ordering::Relaxed));
+    }
+}
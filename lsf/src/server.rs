@@ -0,0 +1,140 @@
+//! `--server` mode: one query per input line, answered by a block of result
+//! paths and an empty record, flushed as soon as it's complete.
+
+use crate::output::PathFormat;
+use anyhow::{Context, Result};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+
+/// Answers every line of `input` with one block on `output` until `input`
+/// ends. Blank lines get an empty block without searching, and a failed
+/// search is reported on stderr and also answered with an empty block, so a
+/// client can always pair blocks with the queries it sent.
+pub fn serve(
+    input: impl BufRead,
+    mut output: impl Write,
+    format: &PathFormat,
+    mut search: impl FnMut(String) -> Result<Vec<PathBuf>>,
+) -> Result<()> {
+    for line in input.lines() {
+        let line = line.context("Failed to read query")?;
+        let query = line.trim();
+        let paths = if query.is_empty() {
+            Vec::new()
+        } else {
+            search(query.to_string()).unwrap_or_else(|e| {
+                eprintln!("Failed to search {query:?}: {e:?}");
+                Vec::new()
+            })
+        };
+        for path in paths {
+            format.write(&mut output, &path)?;
+        }
+        format.end_record(&mut output)?;
+        output.flush().context("Failed to flush results")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::Separator;
+    use anyhow::bail;
+    use search_cache::SearchCache;
+    use search_cancel::CancellationToken;
+    use std::{collections::BTreeSet, ffi::OsStr, fs, os::unix::ffi::OsStrExt};
+    use tempdir::TempDir;
+
+    fn build_cache() -> (TempDir, SearchCache) {
+        let tmp = TempDir::new("lsf_server").unwrap();
+        for name in [
+            "alpha-one.txt",
+            "alpha-two.md",
+            "beta-one.txt",
+            "line\nbreak-one.txt",
+        ] {
+            fs::write(tmp.path().join(name), b"x").unwrap();
+        }
+        let cache = SearchCache::walk_fs(tmp.path());
+        (tmp, cache)
+    }
+
+    fn run(cache: &mut SearchCache, input: &str, separator: Separator) -> Vec<u8> {
+        let mut output = Vec::new();
+        let format = PathFormat {
+            relative_to: None,
+            separator,
+        };
+        serve(input.as_bytes(), &mut output, &format, |query| {
+            if query == "fail" {
+                bail!("search failed");
+            }
+            Ok(cache
+                .query_files(query, CancellationToken::noop())?
+                .unwrap()
+                .into_iter()
+                .map(|node| node.path)
+                .collect())
+        })
+        .unwrap();
+        output
+    }
+
+    /// Splits `output` into blocks of file names, one per query.
+    fn blocks(output: &[u8], separator: u8) -> Vec<BTreeSet<String>> {
+        let mut records: Vec<&[u8]> = output.split(|&byte| byte == separator).collect();
+        assert_eq!(records.pop(), Some(&[][..]), "output ends with a separator");
+        let mut blocks = Vec::new();
+        let mut block = BTreeSet::new();
+        for record in records {
+            if record.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            } else {
+                let path = PathBuf::from(OsStr::from_bytes(record));
+                block.insert(path.file_name().unwrap().to_string_lossy().into_owned());
+            }
+        }
+        assert!(block.is_empty(), "last block is closed");
+        blocks
+    }
+
+    fn names(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn each_query_gets_one_newline_terminated_block() {
+        let (_tmp, mut cache) = build_cache();
+        let output = run(
+            &mut cache,
+            "alpha-\n\n  beta-  \nfail\nmissing-\n",
+            Separator::Newline,
+        );
+        assert_eq!(
+            blocks(&output, b'\n'),
+            [
+                names(&["alpha-one.txt", "alpha-two.md"]),
+                names(&[]),
+                names(&["beta-one.txt"]),
+                names(&[]),
+                names(&[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn null_separator_keeps_paths_with_newlines_intact() {
+        let (_tmp, mut cache) = build_cache();
+        let output = run(&mut cache, "break-\n-one.txt", Separator::Null);
+        assert_eq!(
+            blocks(&output, b'\0'),
+            [
+                names(&["line\nbreak-one.txt"]),
+                names(&["alpha-one.txt", "beta-one.txt", "line\nbreak-one.txt"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use std::process::Command;
+
+#[test]
+fn zero_progress_interval_is_rejected() {
+    let output = Command::new(env!("CARGO_BIN_EXE_lsf"))
+        .args(["--progress-interval-ms", "0"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--progress-interval-ms"), "{stderr}");
+}
@@ -0,0 +1,67 @@
+#![cfg(unix)]
+
+use serde_json::Value;
+use std::{fs, process::Command};
+use tempdir::TempDir;
+
+#[test]
+fn emit_jsonl_prints_every_walked_entry() {
+    let tmp = TempDir::new("lsf_emit").unwrap();
+    let root = tmp.path();
+    fs::create_dir(root.join("sub")).unwrap();
+    fs::write(root.join("a.txt"), b"hello").unwrap();
+    fs::write(root.join("sub/b.bin"), b"").unwrap();
+    std::os::unix::fs::symlink("a.txt", root.join("link")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_lsf"))
+        .args(["--emit", "jsonl", "--path"])
+        .arg(root)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    // Nothing but entries on stdout, and the cache is left alone.
+    let entries: Vec<Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let path = |name: &str| match name {
+        "" => root.to_string_lossy().into_owned(),
+        name => root.join(name).to_string_lossy().into_owned(),
+    };
+    let position = |name: &str| {
+        entries
+            .iter()
+            .position(|entry| entry["path"] == path(name))
+            .unwrap()
+    };
+    // Streamed as walked: folders first, siblings in any order.
+    assert!(position("") < position("sub"));
+    assert!(position("sub") < position("sub/b.bin"));
+
+    let mut summary: Vec<(String, &str)> = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry["path"].as_str().unwrap().to_string(),
+                entry["type"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    summary.sort_unstable();
+    assert_eq!(
+        summary,
+        [
+            (path(""), "dir"),
+            (path("a.txt"), "file"),
+            (path("link"), "symlink"),
+            (path("sub"), "dir"),
+            (path("sub/b.bin"), "file"),
+        ]
+    );
+    let a = &entries[position("a.txt")];
+    assert_eq!(a["size"], 5);
+    assert!(a["mtime"].as_u64().unwrap() > 0);
+    assert_eq!(entries[position("sub/b.bin")]["size"], 0);
+}
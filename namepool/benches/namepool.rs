@@ -0,0 +1,131 @@
+//! Throughput and latency of the pool's building blocks at 10k, 100k and 1M
+//! names; run with `cargo bench -p namepool`, adding `-- <filter>` to pick labels
+//! containing e.g. `substr` or `/1000000`.
+//!
+//! Names come from a fixed seed, so every run measures the same pool and
+//! numbers from before and after a change can be compared directly. Each
+//! figure is the median of several runs.
+
+use namepool::NamePool;
+use search_cancel::CancellationToken;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+const SEED: u64 = 0x5eed_cafe_f00d_d00d;
+const RUNS: usize = 9;
+
+const WORDS: [&str; 16] = [
+    "report",
+    "invoice",
+    "photo",
+    "draft",
+    "notes",
+    "backup",
+    "summary",
+    "config",
+    "index",
+    "readme",
+    "budget",
+    "archive",
+    "Screenshot",
+    "final",
+    "export",
+    "cache",
+];
+const EXTENSIONS: [&str; 8] = ["txt", "pdf", "jpg", "rs", "md", "json", "png", "zip"];
+
+/// splitmix64: tiny, seedable and good enough to spread names around.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn pick<'a>(&mut self, from: &[&'a str]) -> &'a str {
+        from[self.next() as usize % from.len()]
+    }
+}
+
+/// File-like names such as `draft_budget-3f9a1c.pdf`; the hex tail keeps
+/// nearly all of them distinct.
+fn names(count: usize) -> Vec<String> {
+    let mut rng = Rng(SEED);
+    (0..count)
+        .map(|_| {
+            format!(
+                "{}_{}-{:06x}.{}",
+                rng.pick(&WORDS),
+                rng.pick(&WORDS),
+                rng.next() & 0xff_ffff,
+                rng.pick(&EXTENSIONS)
+            )
+        })
+        .collect()
+}
+
+fn median(mut f: impl FnMut()) -> Duration {
+    let mut times: Vec<_> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    times.sort_unstable();
+    times[RUNS / 2]
+}
+
+fn report(filter: Option<&str>, label: &str, time: impl FnOnce() -> Duration) {
+    if filter.is_none_or(|filter| label.contains(filter)) {
+        println!("{label:<32} {:>12.3?}", time());
+    }
+}
+
+fn main() {
+    // `cargo bench` passes `--bench`; anything else is a filter.
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let filter = filter.as_deref();
+    for size in SIZES {
+        let names = names(size);
+        report(filter, &format!("push/{size}"), || {
+            median(|| {
+                let pool = NamePool::new();
+                for name in &names {
+                    black_box(pool.push(name));
+                }
+            })
+        });
+
+        let pool = NamePool::new();
+        for name in &names {
+            pool.push(name);
+        }
+        let existing = names[size / 2].as_str();
+        for (search, query) in [
+            ("substr", "get_note"),
+            ("substr-short", "ep"),
+            ("prefix", "draft_b"),
+            ("exact", existing),
+        ] {
+            report(filter, &format!("{search}/{size}"), || {
+                median(|| {
+                    let token = CancellationToken::noop();
+                    let hits = match search {
+                        "prefix" => pool.search_prefix(query, token),
+                        "exact" => pool.search_exact(query, token),
+                        _ => pool.search_substr(query, token),
+                    };
+                    black_box(hits.expect("noop token never cancels"));
+                })
+            });
+        }
+    }
+}
@@ -1,12 +1,62 @@
 #![feature(str_from_raw_parts)]
+mod trigram;
+
 use core::str;
 use parking_lot::Mutex;
 use regex::Regex;
 use search_cancel::CancellationToken;
-use std::collections::BTreeSet;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Bound,
+};
+use trigram::TrigramIndex;
+
+/// A boolean tree over name predicates, evaluated per name by
+/// [`NamePool::search_expr`] so a whole expression costs one pool scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameExpr {
+    /// Every child matches; an empty `And` matches everything.
+    And(Vec<NameExpr>),
+    /// Any child matches; an empty `Or` matches nothing.
+    Or(Vec<NameExpr>),
+    Not(Box<NameExpr>),
+    Term(NameTerm),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameTerm {
+    Substr(String),
+    Prefix(String),
+    Suffix(String),
+}
+
+impl NameExpr {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NameExpr::And(parts) => parts.iter().all(|part| part.matches(name)),
+            NameExpr::Or(parts) => parts.iter().any(|part| part.matches(name)),
+            NameExpr::Not(inner) => !inner.matches(name),
+            NameExpr::Term(term) => term.matches(name),
+        }
+    }
+}
+
+impl NameTerm {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NameTerm::Substr(needle) => name.contains(needle.as_str()),
+            NameTerm::Prefix(needle) => name.starts_with(needle.as_str()),
+            NameTerm::Suffix(needle) => name.ends_with(needle.as_str()),
+        }
+    }
+}
 
 pub struct NamePool {
-    inner: Mutex<BTreeSet<Box<str>>>,
+    /// Each interned name with the number of [`push`](NamePool::push) calls
+    /// not yet matched by a [`remove`](NamePool::remove).
+    inner: Mutex<BTreeMap<Box<str>, u32>>,
+    /// Only locked while `inner` is held, so both always agree.
+    trigrams: Option<Mutex<TrigramIndex>>,
 }
 
 impl std::fmt::Debug for NamePool {
@@ -26,7 +76,24 @@ impl Default for NamePool {
 impl NamePool {
     pub fn new() -> Self {
         Self {
-            inner: Mutex::new(BTreeSet::new()),
+            inner: Mutex::new(BTreeMap::new()),
+            trigrams: None,
+        }
+    }
+
+    /// A pool that also keeps a trigram index, so substring searches of three
+    /// or more bytes only check the names sharing the needle's rarest byte
+    /// trigram instead of scanning the whole pool. Shorter needles still scan.
+    ///
+    /// The index costs 4 bytes per distinct trigram of every name plus a
+    /// pointer per name, which for typical file names comes to several times
+    /// the size of the names themselves (see
+    /// [`trigram_index_bytes`](Self::trigram_index_bytes)). Pushing gets
+    /// slower too, which is why [`new`](Self::new) leaves it out.
+    pub fn with_trigram_index() -> Self {
+        Self {
+            inner: Mutex::new(BTreeMap::new()),
+            trigrams: Some(Mutex::new(TrigramIndex::default())),
         }
     }
 
@@ -38,6 +105,87 @@ impl NamePool {
         self.inner.lock().is_empty()
     }
 
+    /// Approximate heap bytes held by the interned names: each name's bytes
+    /// plus its boxed pointer and count, in B-tree nodes counted as full.
+    /// Growth can leave nodes as little as half full until
+    /// [`compact`](Self::compact) packs them again, which this doesn't see.
+    pub fn memory_bytes(&self) -> usize {
+        names_bytes(&self.inner.lock())
+    }
+
+    /// Approximate heap bytes held by the trigram index, `0` for a pool built
+    /// without one.
+    pub fn trigram_index_bytes(&self) -> usize {
+        let _inner = self.inner.lock();
+        self.trigrams
+            .as_ref()
+            .map_or(0, |trigrams| trigrams.lock().memory_bytes())
+    }
+
+    /// Counts the interned names by byte length. A snapshot for weighing the
+    /// trigram index against plain scans, which needles under three bytes
+    /// fall back to anyway.
+    pub fn length_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for name in self.inner.lock().keys() {
+            *histogram.entry(name.len()).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Rebuilds the pool's storage at its current size, releasing what growth
+    /// and removals left behind: the name set is rebuilt in one pass with
+    /// packed B-tree nodes, and the trigram index is rebuilt from the names
+    /// still interned, without the slots of removed ones or spare capacity.
+    /// Interned names stay where they are, so strings returned by
+    /// [`push`](Self::push) remain valid. Returns the drop in
+    /// [`trigram_index_bytes`](Self::trigram_index_bytes);
+    /// [`memory_bytes`](Self::memory_bytes) counts the name set as packed
+    /// already.
+    pub fn compact(&self) -> usize {
+        let mut inner = self.inner.lock();
+        // Moving the boxes doesn't move the names they point to.
+        let names = std::mem::take(&mut *inner);
+        *inner = names.into_iter().collect();
+        let Some(trigrams) = &self.trigrams else {
+            return 0;
+        };
+        let mut trigrams = trigrams.lock();
+        let before = trigrams.memory_bytes();
+        // Interned names aren't moved while the pool lives, and `remove`
+        // drops them from the trigram index before freeing.
+        trigrams.rebuild(
+            inner
+                .keys()
+                .map(|name| unsafe { str::from_raw_parts(name.as_ptr(), name.len()) }),
+        );
+        before.saturating_sub(trigrams.memory_bytes())
+    }
+
+    /// Snapshots every interned name in sorted order.
+    ///
+    /// This holds the pool lock while copying one pointer per name, so for a
+    /// pool with millions of names it costs a large allocation and blocks
+    /// concurrent `push`/search calls for the duration. Prefer
+    /// [`for_each`](Self::for_each) when the names don't need to be kept.
+    pub fn iter_all(&self) -> Vec<&str> {
+        self.inner
+            .lock()
+            .keys()
+            .map(|x| unsafe { str::from_raw_parts(x.as_ptr(), x.len()) })
+            .collect()
+    }
+
+    /// Visits every interned name in sorted order without allocating.
+    ///
+    /// The pool lock is held while `f` runs, so `f` must not call back into
+    /// this pool.
+    pub fn for_each(&self, mut f: impl FnMut(&str)) {
+        for x in self.inner.lock().keys() {
+            f(x);
+        }
+    }
+
     /// This function add a name into last cache line, if the last cache line is
     /// full, a new cache line will be added.
     ///
@@ -49,37 +197,146 @@ impl NamePool {
     ///
     /// One important feature of NamePool is that the returned offset is stable
     /// and won't be overwritten.
+    ///
+    /// Every call counts as one more reference to `name`, to be given back
+    /// with [`remove`](Self::remove) if the name should ever leave the pool.
     pub fn push<'c>(&'c self, name: &str) -> &'c str {
         let mut inner = self.inner.lock();
-        if !inner.contains(name) {
-            inner.insert(name.into());
+        if let Some(count) = inner.get_mut(name) {
+            // A count that can't go higher pins the name, see `remove`.
+            *count = count.saturating_add(1);
+        } else {
+            inner.insert(name.into(), 1);
+            if let Some(trigrams) = &self.trigrams {
+                let (interned, _) = inner.get_key_value(name).unwrap();
+                // Interned names aren't moved while the pool lives, and
+                // `remove` drops them from the trigram index before freeing.
+                trigrams
+                    .lock()
+                    .insert(unsafe { str::from_raw_parts(interned.as_ptr(), interned.len()) });
+            }
         }
-        let existing = inner.get(name).unwrap();
+        let (existing, _) = inner.get_key_value(name).unwrap();
         unsafe { str::from_raw_parts(existing.as_ptr(), existing.len()) }
     }
 
+    /// The interned copy of `name`, without interning it if it's missing.
+    pub fn get<'c>(&'c self, name: &str) -> Option<&'c str> {
+        let inner = self.inner.lock();
+        let (existing, _) = inner.get_key_value(name)?;
+        Some(unsafe { str::from_raw_parts(existing.as_ptr(), existing.len()) })
+    }
+
+    /// Gives back one reference taken by [`push`](Self::push). The name is
+    /// freed once every push of it has been matched, so nodes sharing a name
+    /// keep it until the last one goes; one pushed `u32::MAX` times is never
+    /// freed. Returns whether `name` was interned.
+    ///
+    /// # Safety
+    ///
+    /// Once this drops the last reference, no `&str` handed out for `name`
+    /// by this pool (from `push`, `get` or a search) may be used again.
+    pub unsafe fn remove(&self, name: &str) -> bool {
+        let mut inner = self.inner.lock();
+        let Some(count) = inner.get_mut(name) else {
+            return false;
+        };
+        // Pushes past the count's range weren't counted, so a name that
+        // reached it stays interned for good rather than being freed early.
+        if *count == u32::MAX {
+            return true;
+        }
+        *count -= 1;
+        if *count == 0 {
+            let (interned, _) = inner.remove_entry(name).unwrap();
+            if let Some(trigrams) = &self.trigrams {
+                trigrams.lock().remove(&interned);
+            }
+        }
+        true
+    }
+
     pub fn search_substr<'search, 'pool: 'search>(
         &'pool self,
         substr: &'search str,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
         let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
+        self.for_each_substr_candidate(substr, |i, x| {
             cancellation_token.is_cancelled_sparse(i)?;
             if x.contains(substr) {
                 result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
             }
-        }
+            Some(())
+        })?;
         Some(result)
     }
 
+    /// Feeds `f` every name that may contain `substr` with its position in
+    /// the walk: the trigram index's candidates when there are any, otherwise
+    /// the whole pool in sorted order. Stops early when `f` returns `None`.
+    fn for_each_substr_candidate(
+        &self,
+        substr: &str,
+        mut f: impl FnMut(usize, &str) -> Option<()>,
+    ) -> Option<()> {
+        let inner = self.inner.lock();
+        if let Some(trigrams) = &self.trigrams {
+            let trigrams = trigrams.lock();
+            if let Some(candidates) = trigrams.candidates(substr) {
+                return candidates.enumerate().try_for_each(|(i, name)| f(i, name));
+            }
+        }
+        inner
+            .keys()
+            .enumerate()
+            .try_for_each(|(i, name)| f(i, name))
+    }
+
+    pub fn search_expr(
+        &self,
+        expr: &NameExpr,
+        cancellation_token: CancellationToken,
+    ) -> Option<BTreeSet<&str>> {
+        self.search_by(|name| expr.matches(name), cancellation_token)
+    }
+
+    /// Allocation-free variant of [`search_substr`](Self::search_substr) for
+    /// hot loops: matches are written into `out` in sorted order.
+    ///
+    /// `out` is cleared first, so the same buffer can be reused across
+    /// keystrokes without results accumulating. On cancellation `out` is left
+    /// empty and `None` is returned.
+    pub fn search_substr_into<'pool>(
+        &'pool self,
+        substr: &str,
+        out: &mut Vec<&'pool str>,
+        cancellation_token: CancellationToken,
+    ) -> Option<()> {
+        out.clear();
+        let scanned = self.for_each_substr_candidate(substr, |i, x| {
+            cancellation_token.is_cancelled_sparse(i)?;
+            if x.contains(substr) {
+                out.push(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
+            }
+            Some(())
+        });
+        if scanned.is_none() {
+            out.clear();
+            return None;
+        }
+        // Trigram candidates come in id order, not sorted.
+        out.sort_unstable();
+        Some(())
+    }
+
     pub fn search_suffix<'search, 'pool: 'search>(
         &'pool self,
         suffix: &'search str,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
         let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
+        for (i, x) in self.inner.lock().keys().enumerate() {
             cancellation_token.is_cancelled_sparse(i)?;
             if x.ends_with(suffix) {
                 result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
@@ -94,23 +351,50 @@ impl NamePool {
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
         let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
+        // Names sharing `prefix` sort contiguously from `prefix` itself, so seek
+        // there and stop at the first name that no longer starts with it.
+        let inner = self.inner.lock();
+        let candidates = inner
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .map(|(name, _)| name);
+        for (i, x) in candidates.enumerate() {
             cancellation_token.is_cancelled_sparse(i)?;
-            if x.starts_with(prefix) {
-                result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
+            if !x.starts_with(prefix) {
+                break;
             }
+            result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
         }
 
         Some(result)
     }
 
+    /// Returns the alphabetically-first name starting with `prefix`, seeking
+    /// straight to it in the ordered pool instead of scanning every name.
+    ///
+    /// The inner `None` means nothing matches; the outer `None` means cancelled.
+    pub fn first_prefix(
+        &self,
+        prefix: &str,
+        cancellation_token: CancellationToken,
+    ) -> Option<Option<&str>> {
+        cancellation_token.is_cancelled()?;
+        let inner = self.inner.lock();
+        let first = inner
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .next()
+            .map(|(name, _)| name)
+            .filter(|x| x.starts_with(prefix))
+            .map(|x| unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
+        Some(first)
+    }
+
     pub fn search_regex<'search, 'pool: 'search>(
         &'pool self,
         pattern: &Regex,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
         let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
+        for (i, x) in self.inner.lock().keys().enumerate() {
             cancellation_token.is_cancelled_sparse(i)?;
             let existing = unsafe { str::from_raw_parts(x.as_ptr(), x.len()) };
             if pattern.is_match(existing) {
@@ -120,22 +404,51 @@ impl NamePool {
         Some(result)
     }
 
-    // `exact` should starts with a '\0', and ends with a '\0',
-    // e.g. b"\0hello\0"
-    pub fn search_exact<'search, 'pool: 'search>(
-        &'pool self,
-        exact: &'search str,
+    pub fn search_by(
+        &self,
+        predicate: impl Fn(&str) -> bool,
         cancellation_token: CancellationToken,
-    ) -> Option<BTreeSet<&'pool str>> {
+    ) -> Option<BTreeSet<&str>> {
         let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
+        for (i, x) in self.inner.lock().keys().enumerate() {
             cancellation_token.is_cancelled_sparse(i)?;
-            if &**x == exact {
+            if predicate(x) {
                 result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
             }
         }
         Some(result)
     }
+
+    /// Looks `exact` up directly in the ordered pool, so the returned set holds
+    /// at most one name.
+    ///
+    /// The lookup is O(log n) and effectively instant; `cancellation_token` is
+    /// kept for parity with the scanning searches and only checked up front.
+    pub fn search_exact<'search, 'pool: 'search>(
+        &'pool self,
+        exact: &'search str,
+        cancellation_token: CancellationToken,
+    ) -> Option<BTreeSet<&'pool str>> {
+        cancellation_token.is_cancelled()?;
+        let result = self
+            .inner
+            .lock()
+            .get_key_value(exact)
+            .map(|(x, _)| unsafe { str::from_raw_parts(x.as_ptr(), x.len()) })
+            .into_iter()
+            .collect();
+        Some(result)
+    }
+}
+
+/// Entries a node of `std`'s B-tree holds at most.
+const BTREE_NODE_CAPACITY: usize = 11;
+
+fn names_bytes(names: &BTreeMap<Box<str>, u32>) -> usize {
+    let nodes = names.len().div_ceil(BTREE_NODE_CAPACITY);
+    // Each node also keeps its parent pointer, position and length.
+    let node_bytes = BTREE_NODE_CAPACITY * size_of::<(Box<str>, u32)>() + 2 * size_of::<usize>();
+    names.keys().map(|name| name.len()).sum::<usize>() + nodes * node_bytes
 }
 
 #[cfg(test)]
@@ -166,6 +479,222 @@ mod tests {
         guard(pool.search_regex(pattern, CancellationToken::noop()))
     }
 
+    #[test]
+    fn memory_bytes_counts_each_name_once() {
+        let pool = NamePool::new();
+        assert_eq!(pool.memory_bytes(), 0);
+        pool.push("alpha");
+        pool.push("beta");
+        // Both fit in one B-tree node.
+        let node = BTREE_NODE_CAPACITY * size_of::<(Box<str>, u32)>() + 2 * size_of::<usize>();
+        assert_eq!(pool.memory_bytes(), 9 + node);
+        pool.push("alpha");
+        assert_eq!(pool.memory_bytes(), 9 + node);
+    }
+
+    #[test]
+    fn get_finds_interned_names_without_interning() {
+        let pool = NamePool::new();
+        assert_eq!(pool.get("alpha"), None);
+        assert!(pool.is_empty());
+        let interned = pool.push("alpha");
+        assert_eq!(pool.get("alpha").map(str::as_ptr), Some(interned.as_ptr()));
+        assert_eq!(pool.get("alp"), None);
+    }
+
+    #[test]
+    fn remove_evicts_only_after_the_last_push() {
+        for pool in [NamePool::new(), NamePool::with_trigram_index()] {
+            pool.push("foo.txt");
+            pool.push("foo.txt");
+            pool.push("bar.txt");
+            assert_eq!(pool.len(), 2);
+
+            assert!(unsafe { pool.remove("foo.txt") });
+            assert!(pool.get("foo.txt").is_some());
+            assert_eq!(substr(&pool, "foo"), BTreeSet::from(["foo.txt"]));
+            assert_eq!(substr(&pool, "o.t"), BTreeSet::from(["foo.txt"]));
+
+            assert!(unsafe { pool.remove("foo.txt") });
+            assert_eq!(pool.get("foo.txt"), None);
+            assert!(substr(&pool, "foo").is_empty());
+            assert!(substr(&pool, "o.t").is_empty());
+            assert_eq!(substr(&pool, ".txt"), BTreeSet::from(["bar.txt"]));
+            assert_eq!(pool.len(), 1);
+            assert!(!unsafe { pool.remove("foo.txt") });
+
+            // Interning it again starts a fresh count.
+            pool.push("foo.txt");
+            assert_eq!(substr(&pool, "o.t"), BTreeSet::from(["foo.txt"]));
+            assert!(unsafe { pool.remove("foo.txt") });
+            assert!(substr(&pool, "foo").is_empty());
+        }
+    }
+
+    #[test]
+    fn a_saturated_push_count_pins_the_name() {
+        let pool = NamePool::new();
+        let interned = pool.push("busy.txt");
+        *pool.inner.lock().get_mut("busy.txt").unwrap() = u32::MAX - 1;
+        pool.push("busy.txt");
+        pool.push("busy.txt");
+        assert_eq!(pool.inner.lock()["busy.txt"], u32::MAX);
+        for _ in 0..3 {
+            assert!(unsafe { pool.remove("busy.txt") });
+        }
+        assert!(std::ptr::eq(pool.get("busy.txt").unwrap(), interned));
+    }
+
+    #[test]
+    fn length_histogram_counts_distinct_names_by_byte_length() {
+        let pool = NamePool::new();
+        assert!(pool.length_histogram().is_empty());
+        for name in ["a", "bb", "cc", "cc", "dddd", "é", "名前"] {
+            pool.push(name);
+        }
+        // "cc" is interned once; "é" is two bytes and "名前" six.
+        assert_eq!(
+            pool.length_histogram(),
+            BTreeMap::from([(1, 1), (2, 3), (4, 1), (6, 1)])
+        );
+    }
+
+    #[test]
+    fn compact_reclaims_removed_names_and_keeps_the_rest_valid() {
+        let pool = NamePool::with_trigram_index();
+        let names: Vec<String> = (0..5000).map(|i| format!("file-{i}.txt")).collect();
+        let interned: Vec<&str> = names.iter().map(|name| pool.push(name)).collect();
+        let full = pool.memory_bytes();
+        let (kept, removed) = names.split_at(1000);
+        for name in removed {
+            assert!(unsafe { pool.remove(name) });
+        }
+        assert!(pool.memory_bytes() < full);
+        let trigram_bytes = pool.trigram_index_bytes();
+
+        let reclaimed = pool.compact();
+        assert!(reclaimed > 0);
+        assert_eq!(pool.trigram_index_bytes(), trigram_bytes - reclaimed);
+        assert_eq!(pool.compact(), 0, "nothing left to release");
+        // The dead slots are gone, not just unreachable.
+        let trigrams = pool.trigrams.as_ref().unwrap().lock();
+        assert_eq!(
+            trigrams.memory_bytes(),
+            compacted_pool(kept).trigram_index_bytes()
+        );
+        drop(trigrams);
+
+        let expected: BTreeSet<&str> = kept
+            .iter()
+            .filter(|name| name.contains("le-12"))
+            .map(String::as_str)
+            .collect();
+        assert_eq!(substr(&pool, "le-12"), expected);
+        assert!(substr(&pool, "le-1200").is_empty());
+        for (name, interned) in kept.iter().zip(interned) {
+            assert_eq!(interned, name);
+            assert!(std::ptr::eq(pool.push(name), interned));
+        }
+    }
+
+    /// A trigram pool holding just `names`, compacted.
+    fn compacted_pool(names: &[String]) -> NamePool {
+        let pool = NamePool::with_trigram_index();
+        for name in names {
+            pool.push(name);
+        }
+        pool.compact();
+        pool
+    }
+
+    #[test]
+    fn test_trigram_index_matches_linear_scan() {
+        let linear = NamePool::new();
+        let indexed = NamePool::with_trigram_index();
+        let mut names: Vec<String> = (0..2_000)
+            .flat_map(|idx| {
+                [
+                    format!("report_{idx}.pdf"),
+                    format!("名前_{idx}.txt"),
+                    format!("résumé {}", idx % 37),
+                ]
+            })
+            .collect();
+        names.extend(
+            [
+                "",
+                "a",
+                "ab",
+                "aaaa",
+                "abcabc",
+                "日本語メモ",
+                "🦀crab🦀",
+                "e\u{301}cole",
+            ]
+            .map(String::from),
+        );
+        for name in &names {
+            linear.push(name);
+            indexed.push(name);
+        }
+        assert_eq!(linear.len(), indexed.len());
+
+        let mut out = Vec::new();
+        for needle in [
+            "",
+            "a",
+            "ab",
+            "aaa",
+            "aaaa",
+            "abc",
+            "cab",
+            "report_1",
+            "_19",
+            ".pdf",
+            "99.txt",
+            "名前",
+            "名前_4",
+            "前_",
+            "日本語",
+            "語メ",
+            "résumé 3",
+            "umé",
+            "é",
+            "🦀",
+            "🦀c",
+            "rab🦀",
+            "\u{301}co",
+            "missing",
+            "report_2000",
+        ] {
+            let expected = substr(&linear, needle);
+            assert_eq!(substr(&indexed, needle), expected, "needle {needle:?}");
+            guard(indexed.search_substr_into(needle, &mut out, CancellationToken::noop()));
+            assert_eq!(
+                out,
+                expected.iter().copied().collect::<Vec<_>>(),
+                "needle {needle:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_trigram_index_is_opt_in() {
+        let linear = NamePool::new();
+        let indexed = NamePool::with_trigram_index();
+        for name in ["alpha", "beta", "alpha"] {
+            linear.push(name);
+            indexed.push(name);
+        }
+        assert_eq!(linear.trigram_index_bytes(), 0);
+        assert!(indexed.trigram_index_bytes() > 0);
+        assert_eq!(indexed.memory_bytes(), linear.memory_bytes());
+
+        let token = CancellationToken::new(32);
+        let _ = CancellationToken::new(33);
+        assert!(indexed.search_substr("alp", token).is_none());
+    }
+
     #[test]
     fn test_search_substr_cancelled_returns_none() {
         let pool = NamePool::new();
@@ -192,6 +721,269 @@ mod tests {
         assert!(pool.search_regex(&regex, token).is_none());
     }
 
+    #[test]
+    fn test_search_substr_into_reuses_buffer() {
+        let pool = NamePool::new();
+        for name in ["beta", "alpha", "gamma", "alphabet"] {
+            pool.push(name);
+        }
+
+        let mut out = Vec::new();
+        guard(pool.search_substr_into("alpha", &mut out, CancellationToken::noop()));
+        assert_eq!(out, ["alpha", "alphabet"]);
+        let capacity = out.capacity();
+
+        guard(pool.search_substr_into("mm", &mut out, CancellationToken::noop()));
+        assert_eq!(out, ["gamma"]);
+        assert_eq!(out.capacity(), capacity);
+
+        guard(pool.search_substr_into("zzz", &mut out, CancellationToken::noop()));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_search_substr_into_matches_search_substr() {
+        let pool = NamePool::new();
+        for idx in 0..50 {
+            pool.push(&format!("file_{idx:02}.txt"));
+        }
+        let mut out = Vec::new();
+        guard(pool.search_substr_into("_1", &mut out, CancellationToken::noop()));
+        assert_eq!(out, substr(&pool, "_1").into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_search_substr_into_cancelled_clears_buffer() {
+        let pool = NamePool::new();
+        pool.push("alpha");
+        let mut out = vec!["stale"];
+
+        let token = CancellationToken::new(20);
+        let _ = CancellationToken::new(21);
+
+        assert!(pool.search_substr_into("a", &mut out, token).is_none());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_iter_all_and_for_each_enumerate_sorted_pool() {
+        let pool = NamePool::new();
+        for name in ["delta", "alpha", "charlie", "bravo", "alpha"] {
+            pool.push(name);
+        }
+        let expected = ["alpha", "bravo", "charlie", "delta"];
+
+        assert_eq!(pool.iter_all(), expected);
+
+        let mut visited = Vec::new();
+        pool.for_each(|name| visited.push(name.to_string()));
+        assert_eq!(visited, expected);
+
+        assert!(NamePool::new().iter_all().is_empty());
+    }
+
+    fn term(term: NameTerm) -> NameExpr {
+        NameExpr::Term(term)
+    }
+
+    fn expr_pool() -> NamePool {
+        let pool = NamePool::new();
+        for name in [
+            "report_final.pdf",
+            "report_draft.pdf",
+            "report_final.txt",
+            "notes.txt",
+            "cache.tmp",
+            "draft.tmp",
+        ] {
+            pool.push(name);
+        }
+        pool
+    }
+
+    #[test]
+    fn test_search_expr_and_or_not_grouping() {
+        let pool = expr_pool();
+        // (report AND final) OR NOT .pdf/.txt suffix
+        let expr = NameExpr::Or(vec![
+            NameExpr::And(vec![
+                term(NameTerm::Prefix("report".into())),
+                term(NameTerm::Substr("final".into())),
+            ]),
+            NameExpr::Not(Box::new(NameExpr::Or(vec![
+                term(NameTerm::Suffix(".pdf".into())),
+                term(NameTerm::Suffix(".txt".into())),
+            ]))),
+        ]);
+        let result = guard(pool.search_expr(&expr, CancellationToken::noop()));
+        assert_eq!(
+            result.into_iter().collect::<Vec<_>>(),
+            [
+                "cache.tmp",
+                "draft.tmp",
+                "report_final.pdf",
+                "report_final.txt"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_expr_precedence_changes_with_grouping() {
+        let pool = expr_pool();
+        let draft = || term(NameTerm::Substr("draft".into()));
+        let pdf = || term(NameTerm::Suffix(".pdf".into()));
+        let tmp = || term(NameTerm::Suffix(".tmp".into()));
+
+        // draft AND (pdf OR tmp)
+        let grouped = NameExpr::And(vec![draft(), NameExpr::Or(vec![pdf(), tmp()])]);
+        // (draft AND pdf) OR tmp
+        let regrouped = NameExpr::Or(vec![NameExpr::And(vec![draft(), pdf()]), tmp()]);
+
+        let grouped = guard(pool.search_expr(&grouped, CancellationToken::noop()));
+        let regrouped = guard(pool.search_expr(&regrouped, CancellationToken::noop()));
+        assert_eq!(
+            grouped.into_iter().collect::<Vec<_>>(),
+            ["draft.tmp", "report_draft.pdf"]
+        );
+        assert_eq!(
+            regrouped.into_iter().collect::<Vec<_>>(),
+            ["cache.tmp", "draft.tmp", "report_draft.pdf"]
+        );
+    }
+
+    #[test]
+    fn test_search_expr_empty_groups() {
+        let pool = expr_pool();
+        let all = guard(pool.search_expr(&NameExpr::And(vec![]), CancellationToken::noop()));
+        let none = guard(pool.search_expr(&NameExpr::Or(vec![]), CancellationToken::noop()));
+        assert_eq!(all.len(), pool.len());
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_search_prefix_range_matches_full_scan() {
+        let pool = NamePool::new();
+        for name in [
+            "a",
+            "ab",
+            "abc",
+            "abd",
+            "ac",
+            "b",
+            "b\u{0}",
+            "résumé",
+            "résumé.pdf",
+            "rést",
+            "re",
+            "日本",
+            "日本語",
+            "日本語メモ",
+            "日",
+            "\u{10FFFF}x",
+            "zz",
+            "",
+        ] {
+            pool.push(name);
+        }
+        for prefix in [
+            "",
+            "a",
+            "ab",
+            "abc",
+            "abcd",
+            "b",
+            "r",
+            "ré",
+            "résumé",
+            "日",
+            "日本語",
+            "\u{10FFFF}",
+            "zzz",
+            "~",
+        ] {
+            let scanned =
+                guard(pool.search_by(|name| name.starts_with(prefix), CancellationToken::noop()));
+            assert_eq!(prefix_search(&pool, prefix), scanned, "prefix {prefix:?}");
+        }
+    }
+
+    #[test]
+    fn test_search_exact_lookup_matches_full_scan_on_large_pool() {
+        let pool = NamePool::new();
+        for idx in 0..20_000 {
+            pool.push(&format!("name_{idx}"));
+            pool.push(&format!("名前_{idx}.txt"));
+        }
+        for needle in [
+            "name_0",
+            "name_19999",
+            "name_20000",
+            "name_",
+            "名前_42.txt",
+            "名前_42",
+            "",
+        ] {
+            let scanned = guard(pool.search_by(|name| name == needle, CancellationToken::noop()));
+            assert_eq!(exact_search(&pool, needle), scanned, "needle {needle:?}");
+        }
+    }
+
+    #[test]
+    fn test_first_prefix_returns_alphabetically_first_match() {
+        let pool = NamePool::new();
+        for name in ["report_zeta", "reports", "report", "repo", "rez", "apple"] {
+            pool.push(name);
+        }
+        let first = |prefix| guard(pool.first_prefix(prefix, CancellationToken::noop()));
+
+        assert_eq!(first("rep"), Some("repo"));
+        assert_eq!(first("report"), Some("report"));
+        assert_eq!(first("report_"), Some("report_zeta"));
+        assert_eq!(first(""), Some("apple"));
+        assert_eq!(first("re"), Some("repo"));
+    }
+
+    #[test]
+    fn test_first_prefix_without_match() {
+        let pool = NamePool::new();
+        pool.push("alpha");
+        pool.push("gamma");
+
+        assert_eq!(
+            guard(pool.first_prefix("beta", CancellationToken::noop())),
+            None
+        );
+        assert_eq!(
+            guard(pool.first_prefix("zzz", CancellationToken::noop())),
+            None
+        );
+        assert_eq!(
+            guard(NamePool::new().first_prefix("a", CancellationToken::noop())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_first_prefix_cancelled() {
+        let pool = NamePool::new();
+        pool.push("alpha");
+        let token = CancellationToken::new(30);
+        let _ = CancellationToken::new(31);
+        assert!(pool.first_prefix("a", token).is_none());
+    }
+
+    #[test]
+    fn test_search_by_predicate() {
+        let pool = NamePool::new();
+        pool.push("Straße");
+        pool.push("strand");
+        let result = guard(pool.search_by(
+            |name| name.to_lowercase().starts_with("stra"),
+            CancellationToken::noop(),
+        ));
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), ["Straße", "strand"]);
+    }
+
     #[test]
     fn test_new() {
         let pool = NamePool::new();
@@ -0,0 +1,92 @@
+//! Byte-trigram postings that narrow a substring search down to the names
+//! sharing the needle's rarest trigram.
+
+use rustc_hash::FxHashMap;
+
+/// Names get an id in the order they were indexed; each trigram lists the
+/// ids of the names containing it.
+#[derive(Default)]
+pub(crate) struct TrigramIndex {
+    names: Vec<&'static str>,
+    postings: FxHashMap<[u8; 3], Vec<u32>>,
+}
+
+impl TrigramIndex {
+    /// Indexes a newly interned `name`, which must stay alive as long as the
+    /// index does.
+    pub(crate) fn insert(&mut self, name: &'static str) {
+        let id =
+            u32::try_from(self.names.len()).expect("trigram index holds at most u32::MAX names");
+        self.names.push(name);
+        let mut trigrams: Vec<_> = trigrams(name).collect();
+        trigrams.sort_unstable();
+        trigrams.dedup();
+        for trigram in trigrams {
+            self.postings.entry(trigram).or_default().push(id);
+        }
+    }
+
+    /// Unindexes `name`, which must be the copy given to [`Self::insert`].
+    /// Its slot in `names` is left behind, unreachable from any posting, until
+    /// [`Self::rebuild`] reclaims it.
+    pub(crate) fn remove(&mut self, name: &str) {
+        let mut trigrams: Vec<_> = trigrams(name).collect();
+        trigrams.sort_unstable();
+        trigrams.dedup();
+        for trigram in trigrams {
+            let Some(ids) = self.postings.get_mut(&trigram) else {
+                continue;
+            };
+            ids.retain(|&id| !std::ptr::eq(self.names[id as usize], name));
+            if ids.is_empty() {
+                self.postings.remove(&trigram);
+            }
+        }
+    }
+
+    /// Names that may contain `needle`, in id order; the caller still
+    /// has to check each one. `None` when `needle` is shorter than a trigram.
+    pub(crate) fn candidates(
+        &self,
+        needle: &str,
+    ) -> Option<impl Iterator<Item = &'static str> + '_> {
+        let rarest = trigrams(needle)
+            .map(|trigram| self.postings.get(&trigram).map_or(&[][..], Vec::as_slice))
+            .min_by_key(|ids| ids.len())?;
+        Some(rarest.iter().map(|&id| self.names[id as usize]))
+    }
+
+    /// Indexes `names` from scratch, each of which must stay alive as long as
+    /// the index does. Drops the slots removed names left behind and the
+    /// capacity the vectors grew past their contents.
+    pub(crate) fn rebuild(&mut self, names: impl Iterator<Item = &'static str>) {
+        let mut rebuilt = Self::default();
+        for name in names {
+            rebuilt.insert(name);
+        }
+        rebuilt.names.shrink_to_fit();
+        rebuilt.postings.shrink_to_fit();
+        for ids in rebuilt.postings.values_mut() {
+            ids.shrink_to_fit();
+        }
+        *self = rebuilt;
+    }
+
+    pub(crate) fn memory_bytes(&self) -> usize {
+        self.names.capacity() * size_of::<&str>()
+            + self.postings.capacity() * size_of::<([u8; 3], Vec<u32>)>()
+            + self
+                .postings
+                .values()
+                .map(|ids| ids.capacity() * size_of::<u32>())
+                .sum::<usize>()
+    }
+}
+
+/// Every window of three bytes. Working on bytes rather than chars keeps
+/// multi-byte UTF-8 names correct: a substring shares all of its byte windows.
+fn trigrams(text: &str) -> impl Iterator<Item = [u8; 3]> + '_ {
+    text.as_bytes()
+        .windows(3)
+        .map(|window| [window[0], window[1], window[2]])
+}
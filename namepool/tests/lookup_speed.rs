@@ -0,0 +1,67 @@
+//! Manual timing checks for the ordered-set lookups; run with
+//! `cargo test -p namepool --release --test lookup_speed -- --ignored --nocapture`.
+
+use namepool::NamePool;
+use search_cancel::CancellationToken;
+use std::time::Instant;
+
+fn build_pool(count: usize) -> NamePool {
+    let pool = NamePool::new();
+    for i in 0..count {
+        pool.push(&format!(
+            "{:x}_{i}.txt",
+            i.wrapping_mul(2_654_435_761) % 65_536
+        ));
+    }
+    pool
+}
+
+#[test]
+#[ignore = "timing benchmark, run manually"]
+fn prefix_range_vs_full_scan() {
+    let pool = build_pool(1_000_000);
+    let prefix = "ab";
+
+    let start = Instant::now();
+    let ranged = pool
+        .search_prefix(prefix, CancellationToken::noop())
+        .unwrap();
+    let ranged_time = start.elapsed();
+
+    let start = Instant::now();
+    let scanned = pool
+        .search_by(|name| name.starts_with(prefix), CancellationToken::noop())
+        .unwrap();
+    let scan_time = start.elapsed();
+
+    println!(
+        "prefix {prefix:?}: {} hits, range {ranged_time:?}, scan {scan_time:?}",
+        ranged.len()
+    );
+    assert_eq!(ranged, scanned);
+    assert!(ranged_time < scan_time);
+}
+
+#[test]
+#[ignore = "timing benchmark, run manually"]
+fn exact_lookup_vs_full_scan() {
+    let pool = build_pool(1_000_000);
+    let needle = "ab12_4242.txt";
+    pool.push(needle);
+
+    let start = Instant::now();
+    let looked_up = pool
+        .search_exact(needle, CancellationToken::noop())
+        .unwrap();
+    let lookup_time = start.elapsed();
+
+    let start = Instant::now();
+    let scanned = pool
+        .search_by(|name| name == needle, CancellationToken::noop())
+        .unwrap();
+    let scan_time = start.elapsed();
+
+    println!("exact {needle:?}: lookup {lookup_time:?}, scan {scan_time:?}");
+    assert_eq!(looked_up, scanned);
+    assert!(lookup_time < scan_time);
+}
@@ -0,0 +1,121 @@
+//! User-defined query aliases: `bigpdf:` or `@bigpdf` stand for a registered
+//! query fragment such as `type:pdf size:>10mb`.
+
+use anyhow::{Result, anyhow, bail};
+use cardinal_syntax::{Expr, Filter, FilterKind, Query, Term, parse_query};
+use std::collections::BTreeMap;
+
+/// Aliases keyed by lowercased name, with the parsed expansion.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct QueryAliases {
+    aliases: BTreeMap<String, Expr>,
+}
+
+impl QueryAliases {
+    /// Adds or replaces `name`. Fails on names that aren't a bare filter name,
+    /// names of built-in filters, unparsable expansions, and expansions that
+    /// would reach `name` again; the previous definition is kept on failure.
+    pub(crate) fn register(&mut self, name: &str, expansion: &str) -> Result<()> {
+        let key = alias_key(name)?;
+        let expansion = parse_query(expansion)
+            .map_err(|err| anyhow!("Failed to parse alias `{key}`: {err}"))?
+            .expr;
+        let previous = self.aliases.insert(key.clone(), expansion);
+        if let Err(err) = self.expand_alias(&key, &mut Vec::new()) {
+            match previous {
+                Some(previous) => self.aliases.insert(key, previous),
+                None => self.aliases.remove(&key),
+            };
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) -> bool {
+        self.aliases.remove(&name.to_ascii_lowercase()).is_some()
+    }
+
+    /// Replaces every alias reference in `query` with its expansion.
+    pub(crate) fn expand(&self, query: Query) -> Result<Query> {
+        if self.aliases.is_empty() {
+            return Ok(query);
+        }
+        Ok(Query {
+            expr: self.expand_expr(query.expr, &mut Vec::new())?,
+        })
+    }
+
+    fn expand_expr(&self, expr: Expr, stack: &mut Vec<String>) -> Result<Expr> {
+        Ok(match expr {
+            Expr::Term(term) => match self.reference(&term)? {
+                Some(key) => self.expand_alias(&key, stack)?,
+                None => Expr::Term(term),
+            },
+            Expr::Not(inner) => Expr::Not(Box::new(self.expand_expr(*inner, stack)?)),
+            Expr::And(parts) => Expr::And(
+                parts
+                    .into_iter()
+                    .map(|part| self.expand_expr(part, stack))
+                    .collect::<Result<_>>()?,
+            ),
+            Expr::Or(parts) => Expr::Or(
+                parts
+                    .into_iter()
+                    .map(|part| self.expand_expr(part, stack))
+                    .collect::<Result<_>>()?,
+            ),
+            Expr::Empty => Expr::Empty,
+        })
+    }
+
+    fn expand_alias(&self, key: &str, stack: &mut Vec<String>) -> Result<Expr> {
+        if stack.iter().any(|seen| seen == key) {
+            bail!("Alias cycle: {} -> {key}", stack.join(" -> "));
+        }
+        stack.push(key.to_string());
+        let expanded = self.expand_expr(self.aliases[key].clone(), stack)?;
+        stack.pop();
+        Ok(expanded)
+    }
+
+    /// The registered alias `term` refers to, if any. Unknown `@words` stay
+    /// plain words; unknown `name:` filters are left for the evaluator to reject.
+    fn reference(&self, term: &Term) -> Result<Option<String>> {
+        let (name, argument) = match term {
+            Term::Filter(Filter {
+                kind: FilterKind::Custom(name),
+                argument,
+            }) => (name.as_str(), argument.is_some()),
+            Term::Word(word) => match word.strip_prefix('@') {
+                Some(name) => (name, false),
+                None => return Ok(None),
+            },
+            Term::Filter(_) | Term::Regex(_) => return Ok(None),
+        };
+        let key = name.to_ascii_lowercase();
+        if !self.aliases.contains_key(&key) {
+            return Ok(None);
+        }
+        if argument {
+            bail!("Alias `{key}` takes no argument");
+        }
+        Ok(Some(key))
+    }
+}
+
+/// Lowercased `name`, provided the parser reads `name:` as a custom filter.
+fn alias_key(name: &str) -> Result<String> {
+    let parsed = parse_query(&format!("{name}:"))
+        .ok()
+        .map(|query| query.expr);
+    match parsed {
+        Some(Expr::Term(Term::Filter(Filter {
+            kind: FilterKind::Custom(parsed),
+            argument: None,
+        }))) if parsed == name && !name.starts_with('@') => Ok(name.to_ascii_lowercase()),
+        Some(Expr::Term(Term::Filter(_))) if !name.is_empty() => {
+            bail!("Alias `{name}` would shadow a built-in filter")
+        }
+        _ => bail!("Invalid alias name `{name}`"),
+    }
+}
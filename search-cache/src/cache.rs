@@ -1,22 +1,42 @@
 use crate::{
-    FileNodes, NameIndex, SearchOptions, SearchResultNode, SlabIndex, SlabNode,
-    SlabNodeMetadataCompact, State, ThinSlab,
+    FileNodes, NameIndex, QuickFilter, SearchDiagnostics, SearchOptions, SearchResultNode,
+    SlabIndex, SlabNode, SlabNodeMetadataCompact, State, ThinSlab,
+    aliases::QueryAliases,
+    child_names::{self, ChildNames},
+    diagnostics::DiagnosticsCounters,
+    dir_rollup::DirRollups,
+    hardlinks::FileIds,
     highlight::derive_highlight_terms,
-    persistent::{PersistentStorage, read_cache_from_file, write_cache_to_file},
-    query_preprocessor::{expand_query_home_dirs, strip_query_quotes},
+    match_spans::{name_match_spans, name_span_matchers},
+    persistent::{
+        CompressionOptions, PersistentStorage, read_cache_from_file, write_cache_to_file_with,
+    },
+    query::{AccessTimes, ContentTypes},
+    query_preprocessor::{expand_query_home_dirs, join_spaced_words, strip_query_quotes},
+    wal::{Wal, WalRecord, read_wal},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use cardinal_sdk::{EventFlag, FsEvent, ScanType, current_event_id, event_id_to_timestamp};
+use cardinal_syntax::{Query, optimize_query, parse_query};
+use fswalk::{
+    Node, NodeFileType, NodeMetadata, SortKey, WalkData, WalkOptions, name_from_os_str, walk_it,
+    walk_it_without_root_chain,
 };
-use anyhow::{Context, Result, anyhow};
-use cardinal_sdk::{EventFlag, FsEvent, ScanType, current_event_id};
-use cardinal_syntax::{optimize_query, parse_query};
-use fswalk::{Node, NodeMetadata, WalkData, walk_it, walk_it_without_root_chain};
 use hashbrown::HashSet;
 use namepool::NamePool;
 use search_cancel::CancellationToken;
 use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashMap},
     ffi::OsStr,
     io::ErrorKind,
+    ops::Range,
+    os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
-    sync::{LazyLock, atomic::AtomicBool},
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Instant,
 };
 use thin_vec::ThinVec;
@@ -25,28 +45,108 @@ use typed_num::Num;
 
 pub struct SearchCache {
     pub(crate) file_nodes: FileNodes,
-    last_event_id: u64,
+    /// Newest event applied to the cache, or the one stored with it when
+    /// loaded from disk. `None` for a fresh walk.
+    last_event_id: Option<u64>,
+    /// The id current before the cache was walked, or the stored one for a
+    /// cache loaded from disk; see [`Self::resume_event_id`].
+    walk_event_id: u64,
+    /// When a cache loaded from disk was last in sync, as Unix seconds derived
+    /// from its stored event id. `None` for a fresh walk.
+    pub(crate) index_timestamp: Option<i64>,
     rescan_count: u64,
     pub(crate) name_index: NameIndex,
     stop: Option<&'static AtomicBool>,
+    pub(crate) diagnostics: DiagnosticsCounters,
+    pub(crate) aliases: QueryAliases,
+    /// Directories a cancelled walk left unfinished, see [`Self::finish_walk`].
+    unfinished_dirs: Vec<PathBuf>,
+    /// Filters of the walk the cache was built by, applied again whenever a
+    /// changed path or the whole tree is walked anew.
+    walk_options: WalkOptions,
+    wal: Option<Wal>,
+    child_names: ChildNames,
+    pub(crate) dir_rollups: DirRollups,
+    pub(crate) file_ids: FileIds,
+    pub(crate) access_times: AccessTimes,
+    pub(crate) content_types: ContentTypes,
+}
+
+/// The result of [`SearchCache::walk_fs_cancellable`].
+#[derive(Debug)]
+pub struct PartialWalk {
+    pub cache: SearchCache,
+    /// `false` when cancellation stopped the walk before every directory was
+    /// listed.
+    pub complete: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct SearchOutcome {
+    /// The hits, sorted by [`SlabIndex`] with each node once; `None` if the
+    /// search was cancelled.
     pub nodes: Option<Vec<SlabIndex>>,
     pub highlights: Vec<String>,
+    /// Present only when [`SearchOptions::collect_diagnostics`] is set.
+    pub diagnostics: Option<SearchDiagnostics>,
+    /// The query was shorter than [`SearchOptions::min_query_len`] and never
+    /// ran; `nodes` is empty rather than a real result.
+    pub below_min_length: bool,
+    /// The token's deadline passed before the search finished, so `nodes` is
+    /// `None`; see [`CancellationToken::with_deadline`].
+    pub timed_out: bool,
+}
+
+/// A search hit paired with the byte ranges of its name matched by the query.
+pub type SpannedMatch = (SlabIndex, Vec<Range<usize>>);
+
+/// Metadata ordering used by [`SearchCache::search_top_k`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopKKey {
+    MtimeDesc,
+    MtimeAsc,
+    CtimeDesc,
+    CtimeAsc,
+    SizeDesc,
+    SizeAsc,
+}
+
+impl TopKKey {
+    /// Ranking value where larger is better, or `None` when the node lacks it.
+    fn score(self, metadata: SlabNodeMetadataCompact) -> Option<i64> {
+        let meta = metadata.as_ref()?;
+        let time = |value: Option<std::num::NonZeroU32>| value.map(|v| i64::from(v.get()));
+        Some(match self {
+            TopKKey::MtimeDesc => time(meta.mtime())?,
+            TopKKey::MtimeAsc => -time(meta.mtime())?,
+            TopKKey::CtimeDesc => time(meta.ctime())?,
+            TopKKey::CtimeAsc => -time(meta.ctime())?,
+            TopKKey::SizeDesc => meta.size(),
+            TopKKey::SizeAsc => -meta.size(),
+        })
+    }
 }
 
 impl SearchOutcome {
-    fn new(nodes: Option<Vec<SlabIndex>>, highlights: Vec<String>) -> Self {
-        Self { nodes, highlights }
+    fn new(
+        nodes: Option<Vec<SlabIndex>>,
+        highlights: Vec<String>,
+        diagnostics: Option<SearchDiagnostics>,
+    ) -> Self {
+        Self {
+            nodes,
+            highlights,
+            diagnostics,
+            below_min_length: false,
+            timed_out: false,
+        }
     }
 }
 
 impl std::fmt::Debug for SearchCache {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SearchCache")
-            .field("path", &self.file_nodes.path())
+            .field("roots", &self.file_nodes.roots())
             .field("last_event_id", &self.last_event_id)
             .field("rescan_count", &self.rescan_count)
             .field("slab_root", &self.file_nodes.root())
@@ -67,16 +167,32 @@ impl SearchCache {
         cache_path: &Path,
         current_ignore_paths: &Vec<PathBuf>,
         cancel: Option<&'static AtomicBool>,
+    ) -> Result<Self> {
+        Self::try_read_persistent_cache_with_roots(
+            &[path.to_path_buf()],
+            cache_path,
+            current_ignore_paths,
+            cancel,
+        )
+    }
+
+    /// [`Self::try_read_persistent_cache`] for a cache built by [`Self::walk_fs_roots`].
+    /// `roots` must match the walked roots in order.
+    pub fn try_read_persistent_cache_with_roots(
+        roots: &[PathBuf],
+        cache_path: &Path,
+        current_ignore_paths: &Vec<PathBuf>,
+        cancel: Option<&'static AtomicBool>,
     ) -> Result<Self> {
         read_cache_from_file(cache_path)
             .and_then(|x| {
-                (x.path == path)
+                (x.roots == roots)
                     .then_some(())
                     .ok_or_else(|| {
                         anyhow!(
-                            "Inconsistent root path: expected: {:?}, actual: {:?}",
-                            path,
-                            &x.path
+                            "Inconsistent root paths: expected: {:?}, actual: {:?}",
+                            roots,
+                            &x.roots
                         )
                     })
                     .map(|()| x)
@@ -96,18 +212,37 @@ impl SearchCache {
             .map(
                 |PersistentStorage {
                      version: _,
-                     path,
+                     roots,
                      ignore_paths,
                      slab_root,
                      slab,
                      name_index,
                      last_event_id,
                      rescan_count,
+                     unfinished_dirs,
+                     only_extensions,
+                     skip_hidden,
                  }| {
                     // name pool construction speed is fast enough that caching it doesn't worth it.
                     let name_index = NameIndex::construct_name_pool(name_index);
-                    let slab = FileNodes::new(path, ignore_paths, slab, slab_root);
-                    Self::new(slab, last_event_id, rescan_count, name_index, cancel)
+                    let slab = FileNodes::new(roots, ignore_paths, slab, slab_root);
+                    let index_timestamp = event_timestamp(slab.path(), last_event_id);
+                    let mut cache = Self::new(
+                        slab,
+                        Some(last_event_id),
+                        last_event_id,
+                        rescan_count,
+                        name_index,
+                        cancel,
+                    );
+                    cache.index_timestamp = index_timestamp;
+                    cache.unfinished_dirs = unfinished_dirs;
+                    cache.walk_options = WalkOptions {
+                        only_extensions,
+                        skip_hidden,
+                        ..WalkOptions::default()
+                    };
+                    cache
                 },
             )
     }
@@ -117,28 +252,92 @@ impl SearchCache {
         self.file_nodes.len()
     }
 
+    /// # Panics
+    ///
+    /// Panics if `path` can't be listed, see [`Self::walk_fs_with_walk_data`].
     pub fn walk_fs_with_ignore(path: &Path, ignore_paths: &[PathBuf]) -> Self {
-        Self::walk_fs_with_walk_data(&WalkData::new(path, ignore_paths, false, None), None).unwrap()
+        Self::walk_fs_with_walk_data(&WalkData::new(path, ignore_paths, false, None), None)
+            .unwrap()
+            .unwrap()
     }
 
+    /// # Panics
+    ///
+    /// Panics if `path` can't be listed, see [`Self::walk_fs_with_walk_data`].
     pub fn walk_fs(path: &Path) -> Self {
-        Self::walk_fs_with_walk_data(&WalkData::new(path, &[], false, None), None).unwrap()
+        Self::walk_fs_with_walk_data(&WalkData::new(path, &[], false, None), None)
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Walks several roots into one cache, e.g. `/` plus mounted volumes.
+    ///
+    /// The first root is the primary one (see [`Self::walk_data`]). Every root
+    /// must be absolute: their ancestor chains meet at the `/` node, so
+    /// [`Self::node_path`] yields the real absolute path whichever root a node
+    /// came from, and overlapping roots share their common nodes.
+    ///
+    /// Fails if `roots` is empty, if several roots are given and one of them
+    /// is relative, or if a root can't be listed.
+    pub fn walk_fs_roots(roots: &[&Path]) -> Result<Self> {
+        let walk_datas: Vec<_> = roots
+            .iter()
+            .map(|root| WalkData::new(root, &[], false, None))
+            .collect();
+        Ok(
+            Self::walk_fs_with_roots(&walk_datas.iter().collect::<Vec<_>>(), None)?
+                .expect("walk without a cancel flag always finishes"),
+        )
     }
 
     /// This function is expected to be called with WalkData which metadata is not fetched.
-    /// If cancelled during walking, None is returned.
+    /// If cancelled during walking, `Ok(None)` is returned. A root that can't
+    /// be listed (see [`WalkData::root_error`]) is an error rather than an
+    /// empty cache.
+    ///
+    /// A walk sorted by anything other than [`SortKey::Name`] is re-sorted by
+    /// name first, which the name index relies on.
     pub fn walk_fs_with_walk_data(
         walk_data: &WalkData,
         cancel: Option<&'static AtomicBool>,
-    ) -> Option<Self> {
-        // Return None if cancelled
-        fn walkfs_to_slab(
-            walk_data: &WalkData,
-        ) -> Option<(SlabIndex, ThinSlab<SlabNode>, NameIndex)> {
-            // Build the tree of file names in parallel first (we cannot construct the slab directly
-            // because slab nodes reference each other and we prefer to avoid locking).
+    ) -> Result<Option<Self>> {
+        Self::walk_roots(&[walk_data], cancel)
+    }
+
+    /// [`Self::walk_fs_with_walk_data`] over one `WalkData` per root, primary
+    /// first. The ignore paths of the primary root are the ones kept for rescans.
+    ///
+    /// Fails if `walk_datas` is empty, if several roots are given and one of
+    /// them is relative, or if a root can't be listed; `Ok(None)` means the
+    /// walk was cancelled.
+    pub fn walk_fs_with_roots(
+        walk_datas: &[&WalkData],
+        cancel: Option<&'static AtomicBool>,
+    ) -> Result<Option<Self>> {
+        if walk_datas.is_empty() {
+            bail!("SearchCache requires a root");
+        }
+        if walk_datas.len() > 1
+            && let Some(walk_data) = walk_datas
+                .iter()
+                .find(|walk_data| !walk_data.root_path.is_absolute())
+        {
+            bail!("multiple roots must be absolute: {:?}", walk_data.root_path);
+        }
+        Self::walk_roots(walk_datas, cancel)
+    }
+
+    /// Walks roots already checked by [`Self::walk_fs_with_roots`].
+    fn walk_roots(
+        walk_datas: &[&WalkData],
+        cancel: Option<&'static AtomicBool>,
+    ) -> Result<Option<Self>> {
+        // Taken before walking: whatever changes from here on may be missed
+        // by the walk, so a watcher resuming from it has to replay them.
+        let event_id = current_event_id();
+        fn walk_root(walk_data: &WalkData) -> Result<Node> {
             let visit_time = Instant::now();
-            let node = walk_it(walk_data).unwrap_or_else(|| {
+            let mut node = walk_it(walk_data).unwrap_or_else(|| {
                 warn!("failed to walk path: {:?}", walk_data.root_path);
                 Node {
                     children: Vec::new(),
@@ -150,17 +349,49 @@ impl SearchCache {
                     metadata: None,
                 }
             });
+            if let Some(err) = walk_data.root_error() {
+                return Err(err.into());
+            }
+            if walk_data.sort_key() != SortKey::Name {
+                sort_walked_tree_by_name(&mut node);
+            }
             info!(
                 "Walk data: {:?}, time: {:?}",
                 walk_data,
                 visit_time.elapsed()
             );
+            Ok(node)
+        }
+
+        // Return None if cancelled
+        fn walkfs_to_slab(
+            walk_datas: &[&WalkData],
+            cancel: Option<&AtomicBool>,
+        ) -> Result<Option<(SlabIndex, ThinSlab<SlabNode>, NameIndex)>> {
+            // Build the tree of file names in parallel first (we cannot construct the slab directly
+            // because slab nodes reference each other and we prefer to avoid locking).
+            let mut node = walk_root(walk_datas[0])?;
+            for walk_data in &walk_datas[1..] {
+                merge_walked_trees(&mut node, walk_root(walk_data)?);
+            }
 
             // Then create the slab.
             let slab_time = Instant::now();
             let mut slab = ThinSlab::new();
             let mut name_index = NameIndex::default();
-            let slab_root = construct_node_slab_name_index(None, &node, &mut slab, &mut name_index);
+            let mut interrupted = Vec::new();
+            let slab_root = construct_node_slab_name_index(
+                None,
+                &node,
+                &mut slab,
+                &mut name_index,
+                cancel,
+                &mut interrupted,
+            );
+            if !interrupted.is_empty() {
+                info!("Slab construction cancelled.");
+                return Ok(None);
+            }
             info!(
                 "Slab & NameIndex construction time: {:?}, slab root: {:?}, slab len: {:?}",
                 slab_time.elapsed(),
@@ -168,24 +399,209 @@ impl SearchCache {
                 slab.len()
             );
 
-            Some((slab_root, slab, name_index))
+            Ok(Some((slab_root, slab, name_index)))
         }
 
-        let last_event_id = current_event_id();
-        let (slab_root, slab, name_index) = walkfs_to_slab(walk_data)?;
+        let Some((slab_root, slab, name_index)) = walkfs_to_slab(walk_datas, cancel)? else {
+            return Ok(None);
+        };
         let slab = FileNodes::new(
-            walk_data.root_path.to_path_buf(),
-            walk_data.ignore_directories.to_vec(),
+            walk_datas
+                .iter()
+                .map(|walk_data| walk_data.root_path.to_path_buf())
+                .collect(),
+            walk_datas[0].ignore_directories.to_vec(),
             slab,
             slab_root,
         );
         // metadata cache inits later
-        Some(Self::new(slab, last_event_id, 0, name_index, cancel))
+        let mut cache = Self::new(slab, None, event_id, 0, name_index, cancel);
+        cache.walk_options = walk_datas[0].options().clone();
+        Ok(Some(cache))
+    }
+
+    /// Like [`Self::walk_fs_with_walk_data`], but a cancelled walk still
+    /// yields a searchable cache of the nodes walked so far, which can be
+    /// flushed and completed later by [`Self::finish_walk`]. `walk_data` has to
+    /// be built with [`WalkData::keep_partial`]; without it cancellation is an
+    /// error, as is a root that can't be walked. Other sort orders than
+    /// [`SortKey::Name`] are re-sorted by name.
+    pub fn walk_fs_cancellable(
+        walk_data: &WalkData,
+        cancel: Option<&'static AtomicBool>,
+    ) -> Result<PartialWalk> {
+        // See Self::walk_roots.
+        let event_id = current_event_id();
+        let mut node = walk_it(walk_data)
+            .ok_or_else(|| anyhow!("Failed to walk path: {:?}", walk_data.root_path))?;
+        if walk_data.sort_key() != SortKey::Name {
+            sort_walked_tree_by_name(&mut node);
+        }
+        if let Some(err) = walk_data.root_error() {
+            return Err(err.into());
+        }
+        let mut unfinished_dirs = walk_data.take_interrupted();
+        // A cancelled walk is kept as far as it got, so only a quit arriving
+        // after the walk cuts construction short too.
+        let stop = if unfinished_dirs.is_empty() {
+            cancel
+        } else {
+            None
+        };
+        let mut slab = ThinSlab::new();
+        let mut name_index = NameIndex::default();
+        let mut interrupted = Vec::new();
+        let slab_root = construct_node_slab_name_index(
+            None,
+            &node,
+            &mut slab,
+            &mut name_index,
+            stop,
+            &mut interrupted,
+        );
+        let slab = FileNodes::new(
+            vec![walk_data.root_path.to_path_buf()],
+            walk_data.ignore_directories.to_vec(),
+            slab,
+            slab_root,
+        );
+        // Directories above the root only hold the way down to it, so being
+        // cut short there just leaves the root to be listed.
+        unfinished_dirs.extend(interrupted.into_iter().filter_map(|index| {
+            let path = slab.node_path(index)?;
+            Some(if path.starts_with(walk_data.root_path) {
+                path
+            } else {
+                walk_data.root_path.to_path_buf()
+            })
+        }));
+        unfinished_dirs.dedup();
+        let mut cache = Self::new(slab, None, event_id, 0, name_index, cancel);
+        cache.unfinished_dirs = unfinished_dirs;
+        cache.walk_options = walk_data.options().clone();
+        let complete = cache.is_complete();
+        if !complete {
+            info!(
+                "Walk cancelled with {} nodes, {} directories unfinished",
+                cache.get_total_files(),
+                cache.unfinished_dirs.len()
+            );
+        }
+        Ok(PartialWalk { cache, complete })
+    }
+
+    /// `false` while directories left by a cancelled
+    /// [`Self::walk_fs_cancellable`] wait for [`Self::finish_walk`].
+    pub fn is_complete(&self) -> bool {
+        self.unfinished_dirs.is_empty()
+    }
+
+    /// Lists the directories a cancelled walk didn't finish and walks the
+    /// entries it missed; subtrees that were fully walked are left as they
+    /// are. Returns `None` if cancelled, keeping whatever is still unfinished
+    /// for the next call.
+    pub fn finish_walk(&mut self) -> Option<()> {
+        self.dir_rollups.clear();
+        let cancelled =
+            |stop: Option<&AtomicBool>| stop.is_some_and(|stop| stop.load(Ordering::Relaxed));
+        while let Some(dir) = self.unfinished_dirs.last().cloned() {
+            if cancelled(self.stop) {
+                info!("Finishing walk cancelled.");
+                return None;
+            }
+            // A directory that vanished since has nothing left to add.
+            if let Ok(entries) = std::fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    // Unfinished subdirectories are in the cache already and
+                    // take their own turn.
+                    if self.file_nodes.ignore_paths().contains(&path)
+                        || self.index_of_path(&path).is_some()
+                    {
+                        continue;
+                    }
+                    if self.scan_path_recursive(&path).is_none() && cancelled(self.stop) {
+                        info!("Finishing walk cancelled.");
+                        return None;
+                    }
+                }
+            }
+            self.unfinished_dirs.pop();
+        }
+        Some(())
+    }
+
+    /// A cache holding only `root` and its ancestors, which
+    /// [`IndexingCache`](crate::IndexingCache) fills entry by entry. Also
+    /// returns the slab index of `root`.
+    pub(crate) fn root_chain_only(
+        root: &Path,
+        ignore_paths: &[PathBuf],
+        cancel: Option<&'static AtomicBool>,
+    ) -> (Self, SlabIndex) {
+        assert!(
+            root.is_absolute(),
+            "indexing root must be absolute: {root:?}"
+        );
+        let mut chain: Option<Node> = None;
+        for path in root.ancestors() {
+            let name = path
+                .file_name()
+                .map_or_else(|| path.to_string_lossy(), name_from_os_str);
+            chain = Some(Node {
+                children: chain.into_iter().collect(),
+                name: name.into_owned().into_boxed_str(),
+                metadata: path.symlink_metadata().ok().map(NodeMetadata::from),
+            });
+        }
+        let chain = chain.expect("absolute path has at least one ancestor");
+        let mut slab = ThinSlab::new();
+        let mut name_index = NameIndex::default();
+        let slab_root = construct_node_slab_name_index(
+            None,
+            &chain,
+            &mut slab,
+            &mut name_index,
+            None,
+            &mut Vec::new(),
+        );
+        let mut root_index = slab_root;
+        while let Some(&child) = slab[root_index].children.first() {
+            root_index = child;
+        }
+        let file_nodes = FileNodes::new(
+            vec![root.to_path_buf()],
+            ignore_paths.to_vec(),
+            slab,
+            slab_root,
+        );
+        // Filled only afterwards, so the walk starts from here too.
+        (
+            Self::new(file_nodes, None, current_event_id(), 0, name_index, cancel),
+            root_index,
+        )
+    }
+
+    /// Links a subtree walked without metadata under `parent`.
+    ///
+    /// Names are indexed on the ordered fast path, so subtrees must arrive in
+    /// path order: each one sorts after every node already in the cache.
+    pub(crate) fn append_walked_subtree(&mut self, parent: SlabIndex, node: &Node) {
+        let index = construct_node_slab_name_index(
+            Some(parent),
+            node,
+            &mut self.file_nodes,
+            &mut self.name_index,
+            None,
+            &mut Vec::new(),
+        );
+        self.link_child(parent, index);
     }
 
     fn new(
         slab: FileNodes,
-        last_event_id: u64,
+        last_event_id: Option<u64>,
+        walk_event_id: u64,
         rescan_count: u64,
         name_index: NameIndex,
         cancel: Option<&'static AtomicBool>,
@@ -193,9 +609,101 @@ impl SearchCache {
         Self {
             file_nodes: slab,
             last_event_id,
+            walk_event_id,
+            index_timestamp: None,
             rescan_count,
             name_index,
             stop: cancel,
+            diagnostics: DiagnosticsCounters::default(),
+            aliases: QueryAliases::default(),
+            unfinished_dirs: Vec::new(),
+            walk_options: WalkOptions::default(),
+            wal: None,
+            child_names: ChildNames::default(),
+            dir_rollups: DirRollups::default(),
+            file_ids: FileIds::default(),
+            access_times: AccessTimes::default(),
+            content_types: ContentTypes::default(),
+        }
+    }
+
+    /// Lets queries use `name:` or `@name` in place of `expansion`, e.g.
+    /// `bigpdf` for `type:pdf size:>10mb`. Names are case-insensitive, may not
+    /// shadow a built-in filter, and may refer to other aliases as long as no
+    /// chain leads back to `name`. Aliases survive rescans but aren't persisted.
+    pub fn register_alias(&mut self, name: &str, expansion: &str) -> Result<()> {
+        self.aliases.register(name, expansion)
+    }
+
+    /// Forgets an alias registered with [`Self::register_alias`].
+    pub fn remove_alias(&mut self, name: &str) -> bool {
+        self.aliases.remove(name)
+    }
+
+    /// Appends every change applied from now on to the log at `path`, which
+    /// each successful flush empties again. After a crash, load the last
+    /// flushed cache and call [`Self::replay_wal`] with the same path.
+    pub fn enable_wal(&mut self, path: &Path) -> Result<()> {
+        self.wal = Some(Wal::open(path)?);
+        Ok(())
+    }
+
+    /// Applies the changes logged at `path` by [`Self::enable_wal`], bringing
+    /// a stale cache loaded from disk up to date. Logged paths are rescanned,
+    /// so they end up as they are on disk now; a logged rescan is redone in
+    /// full. Returns the number of records replayed; a missing log replays
+    /// nothing.
+    pub fn replay_wal(&mut self, path: &Path) -> Result<usize> {
+        let records = read_wal(path)?;
+        self.dir_rollups.clear();
+        // Replayed changes are in the log already.
+        let wal = self.wal.take();
+        // Nothing before the last rescan is worth applying.
+        let start = records
+            .iter()
+            .rposition(|record| *record == WalRecord::Rescan)
+            .unwrap_or(0);
+        for record in &records[start..] {
+            match record {
+                WalRecord::Rescan => {
+                    if let Err(err) = self.rescan() {
+                        self.wal = wal;
+                        return Err(err);
+                    }
+                }
+                WalRecord::Insert(path) if path.parent().is_some() => {
+                    self.scan_path_recursive(path);
+                }
+                WalRecord::Insert(_) => {}
+                WalRecord::Remove(path) => {
+                    self.remove_node_path(path);
+                }
+                WalRecord::EventId(event_id) => self.update_last_event_id(*event_id),
+            }
+        }
+        self.wal = wal;
+        info!("Replayed {} WAL records from {path:?}", records.len());
+        Ok(records.len())
+    }
+
+    /// Appends `record` to the WAL, if enabled. A failed write disables it;
+    /// what was logged before stays replayable.
+    fn log_to_wal(&mut self, record: WalRecord) {
+        if let Some(wal) = &mut self.wal
+            && let Err(err) = wal.append(&record)
+        {
+            warn!("Disabling WAL: {err:?}");
+            self.wal = None;
+        }
+    }
+
+    /// Empties the WAL once a flush has persisted everything it holds.
+    fn truncate_wal(&mut self) {
+        if let Some(wal) = &mut self.wal
+            && let Err(err) = wal.truncate()
+        {
+            warn!("Disabling WAL: {err:?}");
+            self.wal = None;
         }
     }
 
@@ -209,21 +717,120 @@ impl SearchCache {
             .map(|outcome| outcome.nodes.unwrap_or_default())
     }
 
+    /// Runs the query behind `filter`, see [`QuickFilter::query`].
+    pub fn quick_filter(
+        &mut self,
+        filter: QuickFilter,
+        options: SearchOptions,
+        cancellation_token: CancellationToken,
+    ) -> Result<SearchOutcome> {
+        self.search_with_options(filter.query(), options, cancellation_token)
+    }
+
     pub fn search_with_options(
         &mut self,
         line: &str,
         options: SearchOptions,
         cancellation_token: CancellationToken,
     ) -> Result<SearchOutcome> {
-        let parsed = parse_query(line).map_err(|err| anyhow!("Failed to parse query: {err}"))?;
-        let expanded = expand_query_home_dirs(parsed);
-        let unquoted = strip_query_quotes(expanded);
+        if options.gates(line) {
+            return Ok(SearchOutcome {
+                below_min_length: true,
+                ..SearchOutcome::new(Some(Vec::new()), Vec::new(), None)
+            });
+        }
+        let unquoted = preprocess_query(line, &self.aliases, options)?;
         let highlights = derive_highlight_terms(&unquoted.expr);
         let optimized = optimize_query(unquoted);
+        self.diagnostics.reset();
         let search_time = Instant::now();
-        let result = self.evaluate_expr(&optimized.expr, options, cancellation_token);
-        info!("Search time: {:?}", search_time.elapsed());
-        result.map(|nodes| SearchOutcome::new(nodes, highlights))
+        let result = self.evaluate_query(&optimized.expr, options, cancellation_token);
+        let elapsed = search_time.elapsed();
+        info!("Search time: {elapsed:?}");
+        let diagnostics = options
+            .collect_diagnostics
+            .then(|| self.diagnostics.snapshot(elapsed));
+        result.map(|nodes| SearchOutcome {
+            timed_out: nodes.is_none() && cancellation_token.timed_out(),
+            ..SearchOutcome::new(nodes, highlights, diagnostics)
+        })
+    }
+
+    /// Same as [`Self::search_with_options`], but pairs every hit with the byte
+    /// ranges of its name matched by the query's name terms, so the UI can bold
+    /// them. Filter-only queries yield empty ranges. `None` means cancelled.
+    pub fn search_with_spans(
+        &mut self,
+        line: &str,
+        options: SearchOptions,
+        cancellation_token: CancellationToken,
+    ) -> Result<Option<Vec<SpannedMatch>>> {
+        if options.gates(line) {
+            return Ok(Some(Vec::new()));
+        }
+        let unquoted = preprocess_query(line, &self.aliases, options)?;
+        let matchers = name_span_matchers(&unquoted.expr, options)?;
+        let optimized = optimize_query(unquoted);
+        let Some(nodes) = self.evaluate_query(&optimized.expr, options, cancellation_token)? else {
+            return Ok(None);
+        };
+        Ok(nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, index)| {
+                cancellation_token.is_cancelled_sparse(i)?;
+                let spans = name_match_spans(self.file_nodes[index].name(), &matchers);
+                Some((index, spans))
+            })
+            .collect())
+    }
+
+    /// Returns the `k` hits that rank highest by `key`, best first, without
+    /// sorting the whole result set.
+    ///
+    /// A bounded heap keeps only the current top `k` while scanning, and
+    /// metadata is fetched (and cached) only for hits that lack it. Hits whose
+    /// metadata can't be read are left out; ties keep the search's slab order,
+    /// which follows names only among the entries of one folder.
+    /// `None` means cancelled.
+    pub fn search_top_k(
+        &mut self,
+        line: &str,
+        k: usize,
+        key: TopKKey,
+        options: SearchOptions,
+        cancellation_token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        if options.gates(line) {
+            return Ok(Some(Vec::new()));
+        }
+        let optimized = optimize_query(preprocess_query(line, &self.aliases, options)?);
+        let Some(nodes) = self.evaluate_query(&optimized.expr, options, cancellation_token)? else {
+            return Ok(None);
+        };
+        if k == 0 {
+            return Ok(Some(Vec::new()));
+        }
+        // Min-heap on (score, later position) so the root is the entry to evict.
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (position, index) in nodes.into_iter().enumerate() {
+            if cancellation_token.is_cancelled_sparse(position).is_none() {
+                return Ok(None);
+            }
+            let Some(score) = key.score(self.ensure_metadata(index)) else {
+                continue;
+            };
+            heap.push(Reverse((score, Reverse(position), index)));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        Ok(Some(
+            heap.into_sorted_vec()
+                .into_iter()
+                .map(|Reverse((_, _, index))| index)
+                .collect(),
+        ))
     }
 
     /// Get the path of the node in the slab.
@@ -231,27 +838,126 @@ impl SearchCache {
         self.file_nodes.node_path(index)
     }
 
-    /// Locate the slab index for an absolute path when it belongs to the watch root.
-    pub fn node_index_for_path(&self, path: &Path) -> Option<SlabIndex> {
-        let Ok(path) = path.strip_prefix("/") else {
-            return None;
+    /// Paths for a page of results; see [`FileNodes::node_paths`].
+    pub fn node_paths(&self, indices: &[SlabIndex]) -> Vec<Option<PathBuf>> {
+        self.file_nodes.node_paths(indices)
+    }
+
+    /// Breadcrumbs for a result: its folders from `/` down to its parent, with
+    /// their slab indices, taken from parent links rather than by splitting
+    /// [`Self::node_path`].
+    pub fn ancestors(&self, index: SlabIndex) -> Vec<(SlabIndex, &str)> {
+        self.file_nodes.ancestors(index)
+    }
+
+    /// How many entries sit directly inside the directory at `index`, read from
+    /// the slab without walking or expanding anything. `None` for files and
+    /// indices not in the cache.
+    pub fn child_count(&self, index: SlabIndex) -> Option<usize> {
+        let node = self.file_nodes.get(index)?;
+        (node.file_type_hint() == NodeFileType::Dir).then(|| node.children.len())
+    }
+
+    /// [`Self::child_count`] for a batch of indices, in the same order.
+    pub fn child_counts(&self, indices: &[SlabIndex]) -> Vec<Option<usize>> {
+        indices
+            .iter()
+            .map(|&index| self.child_count(index))
+            .collect()
+    }
+
+    /// Results grouped by the folder holding them, so they can be shown as a
+    /// tree. Each group keeps the order `indices` came in; the root, which has
+    /// no parent, and indices not in the cache are left out.
+    pub fn group_by_parent(&self, indices: &[SlabIndex]) -> BTreeMap<SlabIndex, Vec<SlabIndex>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for &index in indices {
+            if let Some(parent) = self.file_nodes.get(index).and_then(SlabNode::parent) {
+                groups.entry(parent).or_default().push(index);
+            }
+        }
+        groups
+    }
+
+    /// Up to `limit` distinct file names starting with `prefix` (case
+    /// sensitive), for autocompletion. Names carried by more nodes come first;
+    /// ties are broken by name.
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .name_index
+            .names_with_prefix(prefix)
+            .map(|(name, indices)| (indices.len(), name))
+            .collect();
+        names.sort_unstable_by(|(a_count, a_name), (b_count, b_name)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+        names
+            .into_iter()
+            .take(limit)
+            .map(|(_, name)| name.to_string())
+            .collect()
+    }
+
+    /// Locate the slab index for an absolute path by descending from the root
+    /// one component at a time. `None` for relative paths and paths the cache
+    /// doesn't hold.
+    pub fn index_of_path(&self, path: &Path) -> Option<SlabIndex> {
+        let path = path.strip_prefix("/").ok()?;
+        path.iter()
+            .try_fold(self.file_nodes.root(), |current, name| {
+                self.child_named(current, name)
+            })
+    }
+
+    /// [`Self::index_of_path`] for a batch, in the same order. Each folder on
+    /// the way is looked up once, however many of the paths pass through it.
+    pub fn indices_of_paths(&self, paths: &[PathBuf]) -> Vec<Option<SlabIndex>> {
+        let mut resolved = HashMap::new();
+        paths
+            .iter()
+            .map(|path| self.resolve_path(path, &mut resolved))
+            .collect()
+    }
+
+    fn resolve_path<'p>(
+        &self,
+        path: &'p Path,
+        resolved: &mut HashMap<&'p Path, Option<SlabIndex>>,
+    ) -> Option<SlabIndex> {
+        if let Some(&index) = resolved.get(path) {
+            return index;
+        }
+        let index = match path.parent() {
+            Some(parent) => self
+                .resolve_path(parent, resolved)
+                .zip(path.file_name())
+                .and_then(|(parent, name)| self.child_named(parent, name)),
+            None => (path == Path::new("/")).then(|| self.file_nodes.root()),
         };
-        let mut current = self.file_nodes.root();
-        for segment in path {
-            let next = self.file_nodes[current]
-                .children
-                .iter()
-                .find_map(|&child| {
-                    let name = self.file_nodes[child].name();
-                    if OsStr::new(name) == segment {
-                        Some(child)
-                    } else {
-                        None
-                    }
-                })?;
-            current = next;
+        resolved.insert(path, index);
+        index
+    }
+
+    /// The child of `parent` called `name`, if the cache holds one. Wide
+    /// directories answer from a name map instead of a scan.
+    pub(crate) fn child_named(&self, parent: SlabIndex, name: &OsStr) -> Option<SlabIndex> {
+        let children = &self.file_nodes[parent].children;
+        if children.len() >= child_names::MIN_CHILDREN {
+            return self
+                .child_names
+                .get(&self.file_nodes, parent, &name_from_os_str(name));
         }
-        Some(current)
+        children
+            .iter()
+            .copied()
+            .find(|&child| *self.file_nodes[child].os_name() == *name)
+    }
+
+    /// Appends `child` to the children of `parent`.
+    fn link_child(&mut self, parent: SlabIndex, child: SlabIndex) {
+        self.file_nodes[parent].add_children(child);
+        let name = self.file_nodes[child].name();
+        self.child_names.insert(parent, name, child);
     }
 
     /// Get all subnode indices of a given node index
@@ -291,21 +997,7 @@ impl SearchCache {
 
     /// Removes a node by path and its children recursively.
     fn remove_node_path(&mut self, path: &Path) -> Option<SlabIndex> {
-        let Ok(path) = path.strip_prefix("/") else {
-            return None;
-        };
-        let mut current = self.file_nodes.root();
-        for name in path {
-            if let Some(&index) = self.file_nodes[current]
-                .children
-                .iter()
-                .find(|&&x| self.file_nodes[x].name() == name)
-            {
-                current = index;
-            } else {
-                return None;
-            }
-        }
+        let current = self.index_of_path(path)?;
         self.remove_node(current);
         Some(current)
     }
@@ -319,18 +1011,14 @@ impl SearchCache {
         let mut current_path = PathBuf::from("/");
         for name in path {
             current_path.push(name);
-            current = if let Some(&index) = self.file_nodes[current]
-                .children
-                .iter()
-                .find(|&&x| self.file_nodes[x].name() == name)
-            {
+            current = if let Some(index) = self.child_named(current, name) {
                 index
             } else {
                 // TODO(ldm0): optimize: slab node children is empty, we can create a node chain directly.
                 let metadata = std::fs::symlink_metadata(&current_path)
                     .map(NodeMetadata::from)
                     .ok();
-                let name = NAME_POOL.push(name.to_string_lossy().as_ref());
+                let name = NAME_POOL.push(&name_from_os_str(name));
                 let node = SlabNode::new(
                     Some(current),
                     name,
@@ -340,7 +1028,7 @@ impl SearchCache {
                     },
                 );
                 let index = self.push_node(node);
-                self.file_nodes[current].add_children(index);
+                self.link_child(current, index);
                 index
             };
         }
@@ -352,8 +1040,17 @@ impl SearchCache {
     // - Procedure contains metadata fetching, if metadata fetching failed, None is returned.
     fn scan_path_recursive(&mut self, path: &Path) -> Option<SlabIndex> {
         // Ensure path is under the watch root
-        if path.symlink_metadata().err().map(|e| e.kind()) == Some(ErrorKind::NotFound) {
+        let metadata = path.symlink_metadata();
+        // Paths the walk filtered out don't belong in the cache either.
+        if metadata
+            .as_ref()
+            .is_err_and(|err| err.kind() == ErrorKind::NotFound)
+            || metadata
+                .as_ref()
+                .is_ok_and(|metadata| !self.admits(path, metadata.is_dir()))
+        {
             self.remove_node_path(path);
+            self.log_to_wal(WalRecord::Remove(path.to_path_buf()));
             return None;
         };
         let parent = path.parent().expect(
@@ -362,21 +1059,140 @@ impl SearchCache {
         // Ensure node of the path parent is existed
         let parent = self.create_node_chain(parent);
         // Remove node(if exists) and do a full rescan
-        if let Some(&old_node) = self.file_nodes[parent]
-            .children
-            .iter()
-            .find(|&&x| path.file_name() == Some(OsStr::new(self.file_nodes[x].name())))
+        if let Some(old_node) = path
+            .file_name()
+            .and_then(|name| self.child_named(parent, name))
         {
             self.remove_node(old_node);
         }
         // For incremental data, we need metadata
-        let walk_data = WalkData::new(path, self.file_nodes.ignore_paths(), true, self.stop);
-        walk_it_without_root_chain(&walk_data).map(|node| {
+        let walk_data = WalkData::new(path, self.file_nodes.ignore_paths(), true, self.stop)
+            .with_options(self.walk_options.clone());
+        let node = walk_it_without_root_chain(&walk_data).map(|node| {
             let node = self.create_node_slab_update_name_index_and_name_pool(Some(parent), &node);
             // Push the newly created node to the parent's children
-            self.file_nodes[parent].add_children(node);
+            self.link_child(parent, node);
             node
-        })
+        });
+        // Logged even when the walk was cancelled: the old subtree is gone
+        // either way, and a replay walks it again.
+        self.log_to_wal(WalRecord::Insert(path.to_path_buf()));
+        node
+    }
+
+    /// Whether the walk options let `path` into the cache, judged below the
+    /// root it's under.
+    fn admits(&self, path: &Path, is_dir: bool) -> bool {
+        self.file_nodes
+            .roots()
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+            .is_none_or(|relative| self.walk_options.admits(relative, is_dir))
+    }
+
+    /// The filters of the walk the cache was built by, see
+    /// [`WalkData::options`].
+    pub fn walk_options(&self) -> &WalkOptions {
+        &self.walk_options
+    }
+
+    /// Sets the filters applied to changed paths and rescans. A cache loaded
+    /// from disk doesn't store them, so it applies none until this is called.
+    pub fn set_walk_options(&mut self, options: WalkOptions) {
+        self.walk_options = options;
+    }
+
+    /// A rename that only changes case, such as `Photo.JPG` to `Photo.jpg`,
+    /// comes in as events on both spellings, and a case-insensitive volume
+    /// resolves either one, so rescanning both would keep the old spelling
+    /// as a second node. Where the folder lists a single spelling of such a
+    /// group, the cached node takes it in place, keeping its index and
+    /// subtree, and the spellings it doesn't list aren't rescanned. Returns
+    /// the paths still to scan, in order.
+    fn settle_case_only_renames(&mut self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut spellings: HashMap<_, Vec<usize>> = HashMap::new();
+        for (i, path) in paths.iter().enumerate() {
+            if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+                let folded = name_from_os_str(name).to_lowercase();
+                spellings.entry((parent, folded)).or_default().push(i);
+            }
+        }
+        let mut settled = vec![false; paths.len()];
+        for ((parent_path, folded), group) in spellings {
+            if group.len() < 2 {
+                continue;
+            }
+            let Some(parent) = self.index_of_path(parent_path) else {
+                continue;
+            };
+            let Ok(entries) = std::fs::read_dir(parent_path) else {
+                continue;
+            };
+            let mut listed = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.file_name()))
+                .filter(|name| name_from_os_str(name).to_lowercase() == folded);
+            let (Some(current), None) = (listed.next(), listed.next()) else {
+                continue;
+            };
+            let mut kept = self.child_named(parent, &current).is_some();
+            let mut moved = false;
+            for &i in &group {
+                let path = &paths[i];
+                if path.file_name() == Some(&*current) {
+                    continue;
+                }
+                settled[i] = true;
+                let Some(stale) = path
+                    .file_name()
+                    .and_then(|name| self.child_named(parent, name))
+                else {
+                    continue;
+                };
+                if kept {
+                    self.remove_node(stale);
+                } else {
+                    self.rename_node(stale, &current);
+                    (kept, moved) = (true, true);
+                }
+                self.log_to_wal(WalRecord::Remove(path.clone()));
+            }
+            if moved {
+                for &i in &group {
+                    settled[i] = true;
+                }
+                self.log_to_wal(WalRecord::Insert(parent_path.join(&current)));
+            }
+        }
+        paths
+            .into_iter()
+            .zip(settled)
+            .filter_map(|(path, settled)| (!settled).then_some(path))
+            .collect()
+    }
+
+    /// Gives the node at `index` a new name, keeping its slab index, metadata
+    /// and subtree. Every node below moves to a new path, so they're all
+    /// filed again in the name index, which is ordered by path.
+    fn rename_node(&mut self, index: SlabIndex, name: &OsStr) {
+        let name = NAME_POOL.push(&name_from_os_str(name));
+        let mut moved = vec![index];
+        self.all_subnodes_recursive(index, &mut moved, &mut 0, CancellationToken::noop())
+            .expect("noop token never cancels");
+        for &node in &moved {
+            self.name_index
+                .remove_index(self.file_nodes[node].name(), node);
+        }
+        if let Some(parent) = self.file_nodes[index].parent() {
+            let old = self.file_nodes[index].name();
+            self.child_names
+                .remove(&self.file_nodes, parent, old, index);
+            self.child_names.insert(parent, name, index);
+        }
+        self.file_nodes[index].rename(name);
+        for &node in &moved {
+            self.name_index
+                .add_index(self.file_nodes[node].name(), node, &self.file_nodes);
+        }
     }
 
     // `Self::scan_path_nonrecursive`function returns index of the constructed node.
@@ -392,6 +1208,19 @@ impl SearchCache {
         Some(self.create_node_chain(path))
     }
 
+    /// Every root, primary first, as the paths to hand to
+    /// [`EventWatcher::spawn_paths`](cardinal_sdk::EventWatcher::spawn_paths):
+    /// watching only the primary one would miss changes under the others.
+    pub fn watch_paths(&self) -> Vec<String> {
+        self.file_nodes
+            .roots()
+            .iter()
+            .map(|root| root.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// Walk data for the primary root; [`Self::rescan_with_walk_data`] adds
+    /// the other roots itself.
     pub fn walk_data<'p>(
         &self,
         phantom1: &'p mut PathBuf,
@@ -399,33 +1228,63 @@ impl SearchCache {
     ) -> WalkData<'p> {
         *phantom1 = self.file_nodes.path().to_path_buf();
         *phantom2 = self.file_nodes.ignore_paths().clone();
-        WalkData::new(phantom1, phantom2, false, self.stop)
+        WalkData::new(phantom1, phantom2, false, self.stop).with_options(self.walk_options.clone())
     }
 
-    pub fn rescan_with_walk_data(&mut self, walk_data: &WalkData) -> Option<()> {
-        let Some(new_cache) = Self::walk_fs_with_walk_data(walk_data, self.stop) else {
+    /// Rebuilds the cache from a fresh walk of every root. Returns `Ok(None)`
+    /// if cancelled; on that or an error, e.g. a root that can no longer be
+    /// listed, the current cache is kept as it is.
+    pub fn rescan_with_walk_data(&mut self, walk_data: &WalkData) -> Result<Option<()>> {
+        let extra_roots = self.file_nodes.roots()[1..].to_vec();
+        let extra_walk_datas: Vec<_> = extra_roots
+            .iter()
+            .map(|root| {
+                WalkData::new(root, walk_data.ignore_directories, false, self.stop)
+                    .with_options(walk_data.options().clone())
+            })
+            .collect();
+        let walk_datas: Vec<_> = std::iter::once(walk_data)
+            .chain(&extra_walk_datas)
+            .collect();
+        let Some(new_cache) = Self::walk_roots(&walk_datas, self.stop)? else {
             info!("Rescan cancelled.");
-            return None;
+            return Ok(None);
         };
-        *self = new_cache;
-        Some(())
+        self.replace_with_rescan(new_cache);
+        Ok(Some(()))
     }
 
-    pub fn rescan(&mut self) {
+    /// [`Self::rescan_with_walk_data`] with the cache's own walk data. A
+    /// cancelled rescan keeps the current cache and isn't an error.
+    pub fn rescan(&mut self) -> Result<()> {
+        let roots = self.file_nodes.roots().to_vec();
+        let ignore_paths = self.file_nodes.ignore_paths().clone();
+        let walk_datas: Vec<_> = roots
+            .iter()
+            .map(|root| {
+                WalkData::new(root, &ignore_paths, false, self.stop)
+                    .with_options(self.walk_options.clone())
+            })
+            .collect();
+        let walk_datas: Vec<_> = walk_datas.iter().collect();
         // Remove all memory consuming cache early for memory consumption in Self::walk_fs_new.
-        let Some(new_cache) = Self::walk_fs_with_walk_data(
-            &WalkData::new(
-                self.file_nodes.path(),
-                self.file_nodes.ignore_paths(),
-                false,
-                self.stop,
-            ),
-            self.stop,
-        ) else {
+        let Some(new_cache) = Self::walk_roots(&walk_datas, self.stop)? else {
             info!("Rescan cancelled.");
-            return;
+            return Ok(());
         };
+        self.replace_with_rescan(new_cache);
+        Ok(())
+    }
+
+    /// Swaps in the cache a rescan walked, keeping the aliases and the WAL.
+    /// The log restarts with the rescan, so a replay redoes it rather than
+    /// applying older changes on top of a snapshot they may not fit.
+    fn replace_with_rescan(&mut self, mut new_cache: Self) {
+        new_cache.aliases = std::mem::take(&mut self.aliases);
+        new_cache.wal = self.wal.take();
         *self = new_cache;
+        self.truncate_wal();
+        self.log_to_wal(WalRecord::Rescan);
     }
 
     /// Removes a node and its children recursively by index.
@@ -434,83 +1293,164 @@ impl SearchCache {
             if let Some(node) = cache.file_nodes.try_remove(index) {
                 let removed = cache.name_index.remove_index(node.name(), index);
                 assert!(removed, "inconsistent name index and node");
+                // The index may be reused by an unrelated node.
+                cache.dir_rollups.remove(index);
+                cache.file_ids.remove(index);
+                cache.access_times.remove(index);
+                cache.content_types.remove(index);
             }
         }
 
         // Remove parent reference, make whole subtree unreachable.
         if let Some(parent) = self.file_nodes[index].parent() {
             self.file_nodes[parent].children.retain(|&x| x != index);
+            let name = self.file_nodes[index].name();
+            self.child_names
+                .remove(&self.file_nodes, parent, name, index);
         }
         let mut stack = vec![index];
         while let Some(current) = stack.pop() {
             stack.extend_from_slice(&self.file_nodes[current].children);
+            self.child_names.forget(current);
             remove_single_node(self, current);
         }
     }
 
     pub fn flush_snapshot_to_file(&mut self, cache_path: &Path) -> Result<()> {
+        self.flush_snapshot_to_file_with(cache_path, CompressionOptions::default())
+    }
+
+    /// [`Self::flush_snapshot_to_file`] with explicit compression settings.
+    pub fn flush_snapshot_to_file_with(
+        &mut self,
+        cache_path: &Path,
+        compression: CompressionOptions,
+    ) -> Result<()> {
         let name_index = self.name_index.as_persistent();
         let slab = self.file_nodes.take_slab();
 
         let storage = PersistentStorage {
             version: Num,
-            last_event_id: self.last_event_id,
+            last_event_id: self.persisted_event_id(),
             rescan_count: self.rescan_count,
-            path: self.file_nodes.path().to_path_buf(),
+            roots: self.file_nodes.roots().to_vec(),
             ignore_paths: self.file_nodes.ignore_paths().clone(),
             slab_root: self.file_nodes.root(),
             name_index,
             slab,
+            unfinished_dirs: self.unfinished_dirs.clone(),
+            only_extensions: self.walk_options.only_extensions.clone(),
+            skip_hidden: self.walk_options.skip_hidden,
         };
 
-        let flush_result =
-            write_cache_to_file(cache_path, &storage).context("Write cache to file failed.");
+        let flush_result = write_cache_to_file_with(cache_path, &storage, compression)
+            .context("Write cache to file failed.");
 
         let PersistentStorage { slab, .. } = storage;
         self.file_nodes.put_slab(slab);
+        if flush_result.is_ok() {
+            self.truncate_wal();
+        }
 
         flush_result
     }
 
     pub fn flush_to_file(self, cache_path: &Path) -> Result<()> {
+        self.flush_to_file_with(cache_path, CompressionOptions::default())
+    }
+
+    /// [`Self::flush_to_file`] with explicit compression settings, e.g. a
+    /// higher level for large indexes where disk matters more than CPU.
+    pub fn flush_to_file_with(
+        self,
+        cache_path: &Path,
+        compression: CompressionOptions,
+    ) -> Result<()> {
+        let last_event_id = self.persisted_event_id();
         let Self {
             file_nodes,
-            last_event_id,
+            last_event_id: _,
+            walk_event_id: _,
+            index_timestamp: _,
             rescan_count,
             name_index,
             stop: _,
+            diagnostics: _,
+            aliases: _,
+            unfinished_dirs,
+            walk_options,
+            wal,
+            child_names: _,
+            dir_rollups: _,
+            file_ids: _,
+            access_times: _,
+            content_types: _,
         } = self;
-        let (path, ignore_paths, slab_root, slab) = file_nodes.into_parts();
+        let (roots, ignore_paths, slab_root, slab) = file_nodes.into_parts();
         let name_index = name_index.into_persistent();
-        write_cache_to_file(
+        write_cache_to_file_with(
             cache_path,
             &PersistentStorage {
                 version: Num,
-                path,
+                roots,
                 ignore_paths,
                 slab_root,
                 slab,
                 name_index,
                 last_event_id,
                 rescan_count,
+                unfinished_dirs,
+                only_extensions: walk_options.only_extensions,
+                skip_hidden: walk_options.skip_hidden,
             },
+            compression,
         )
-        .context("Write cache to file failed.")
+        .context("Write cache to file failed.")?;
+        if let Some(mut wal) = wal {
+            wal.truncate()?;
+        }
+        Ok(())
+    }
+
+    /// The id a snapshot is consistent with: the newest applied event, or the
+    /// current id for a fresh walk no event has reached yet.
+    fn persisted_event_id(&self) -> u64 {
+        self.last_event_id.unwrap_or_else(current_event_id)
     }
 
     fn update_last_event_id(&mut self, event_id: u64) {
-        if event_id <= self.last_event_id {
-            debug!("last_event_id {} |< {event_id}", self.last_event_id);
-        } else {
-            debug!("last_event_id {} => {event_id}", self.last_event_id);
-            self.last_event_id = event_id;
+        match self.last_event_id {
+            Some(last) if event_id <= last => debug!("last_event_id {last} |< {event_id}"),
+            last => {
+                debug!("last_event_id {last:?} => {event_id}");
+                self.last_event_id = Some(event_id);
+            }
         }
     }
 
-    pub fn last_event_id(&mut self) -> u64 {
+    /// The newest event id the cache is in sync with.
+    ///
+    /// `None` for a cache that was just walked and hasn't applied any event;
+    /// [`Self::resume_event_id`] covers that case. A cache loaded from disk
+    /// returns the id stored by the flush.
+    pub fn last_event_id(&self) -> Option<u64> {
         self.last_event_id
     }
 
+    /// The event id to resume watching from: [`Self::last_event_id`], or for
+    /// a fresh walk the id current before it walked, so a stream started from
+    /// it replays what changed meanwhile.
+    pub fn resume_event_id(&self) -> u64 {
+        self.last_event_id.unwrap_or(self.walk_event_id)
+    }
+
+    /// When the cache loaded from disk was last in sync with the filesystem,
+    /// in Unix seconds; `dm:sincelastindex` matches what changed after it.
+    /// `None` for a cache that was walked rather than loaded.
+    pub fn index_timestamp(&self) -> Option<i64> {
+        self.index_timestamp
+    }
+
     pub fn rescan_count(&self) -> u64 {
         self.rescan_count
     }
@@ -548,16 +1488,17 @@ impl SearchCache {
         &mut self,
         nodes: &[SlabIndex],
     ) -> Vec<SearchResultNode> {
+        let paths = self.file_nodes.node_paths(nodes);
         nodes
             .iter()
             .copied()
-            .map(|node_index| {
-                let path = self.node_path(node_index);
-                let metadata = self
+            .zip(paths)
+            .map(|(node_index, path)| {
+                let (metadata, index_seq) = self
                     .file_nodes
                     .get_mut(node_index)
                     .map(|node| {
-                        match (node.state(), &path) {
+                        let metadata = match (node.state(), &path) {
                             (State::None, Some(path)) if FETCH_META => {
                                 // try fetching metadata if it's not cached and cache them
                                 let metadata = match std::fs::symlink_metadata(path) {
@@ -568,12 +1509,14 @@ impl SearchCache {
                                 metadata
                             }
                             _ => node.metadata,
-                        }
+                        };
+                        (metadata, node.index_seq())
                     })
-                    .unwrap_or_else(SlabNodeMetadataCompact::unaccessible);
+                    .unwrap_or_else(|| (SlabNodeMetadataCompact::unaccessible(), 0));
                 SearchResultNode {
                     path: path.unwrap_or_default(),
                     metadata,
+                    index_seq,
                 }
             })
             .collect()
@@ -586,7 +1529,12 @@ impl SearchCache {
             if event.flag.contains(EventFlag::HistoryDone) {
                 info!("History processing done: {:?}", event);
             }
-            if event.should_rescan(self.file_nodes.path()) {
+            if self
+                .file_nodes
+                .roots()
+                .iter()
+                .any(|root| event.should_rescan(root))
+            {
                 info!("Event rescan: {:?}", event);
                 true
             } else {
@@ -596,8 +1544,10 @@ impl SearchCache {
             self.rescan_count = self.rescan_count.saturating_add(1);
             return Err(HandleFSEError::Rescan);
         }
-        for scan_path in scan_paths(events) {
+        let scan_paths = scan_paths(events);
+        for scan_path in self.settle_case_only_renames(scan_paths) {
             info!("Scanning path: {scan_path:?}");
+            self.invalidate_rollups_above(&scan_path);
             let folder = self.scan_path_recursive(&scan_path);
             if folder.is_some() {
                 info!("Node changed: {folder:?}");
@@ -605,6 +1555,7 @@ impl SearchCache {
         }
         if let Some(max_event_id) = max_event_id {
             self.update_last_event_id(max_event_id);
+            self.log_to_wal(WalRecord::EventId(max_event_id));
         }
         Ok(())
     }
@@ -702,6 +1653,28 @@ fn has_selected_ancestor(path: &Path, selected: &HashSet<PathBuf>) -> bool {
     false
 }
 
+/// Parses `line` and applies the query rewrites shared by every search entry point.
+fn preprocess_query(line: &str, aliases: &QueryAliases, options: SearchOptions) -> Result<Query> {
+    let parsed = parse_query(line).map_err(|err| anyhow!("Failed to parse query: {err}"))?;
+    rewrite_query(parsed, aliases, options)
+}
+
+/// The rewrites [`preprocess_query`] applies once `line` is parsed, for
+/// callers that report parse errors their own way.
+pub(crate) fn rewrite_query(
+    parsed: Query,
+    aliases: &QueryAliases,
+    options: SearchOptions,
+) -> Result<Query> {
+    let expanded = expand_query_home_dirs(aliases.expand(parsed)?);
+    let unquoted = strip_query_quotes(expanded);
+    Ok(if options.space_is_literal {
+        join_spaced_words(unquoted)
+    } else {
+        unquoted
+    })
+}
+
 /// Error type for `SearchCache::handle_fs_event`.
 #[derive(Debug)]
 pub enum HandleFSEError {
@@ -709,12 +1682,53 @@ pub enum HandleFSEError {
     Rescan,
 }
 
+/// Merges the walked tree `other` into `into`, joining directories present in
+/// both. Children stay name-sorted as long as both inputs are.
+fn merge_walked_trees(into: &mut Node, other: Node) {
+    for child in other.children {
+        match into
+            .children
+            .binary_search_by(|probe| probe.name.cmp(&child.name))
+        {
+            Ok(position) => merge_walked_trees(&mut into.children[position], child),
+            Err(position) => into.children.insert(position, child),
+        }
+    }
+}
+
+/// Puts every folder's children of a tree walked in another [`SortKey`]
+/// order back in name order.
+fn sort_walked_tree_by_name(node: &mut Node) {
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        node.children.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        stack.extend(node.children.iter_mut());
+    }
+}
+
+/// Resolves a stored event id to the time it was issued, via the event
+/// history of the device holding `root`.
+fn event_timestamp(root: &Path, event_id: u64) -> Option<i64> {
+    let dev = root.metadata().ok()?.dev();
+    Some(event_id_to_timestamp(
+        dev as _,
+        event_id,
+        &mut HashMap::new(),
+    ))
+}
+
 /// Note: This function is expected to be called with WalkData which metadata is not fetched.
+///
+/// Once `cancel` is set the remaining children are skipped: every directory
+/// left short is pushed to `interrupted`, innermost first, and the subtree is
+/// kept linked as far as it got.
 fn construct_node_slab_name_index(
     parent: Option<SlabIndex>,
     node: &Node,
     slab: &mut ThinSlab<SlabNode>,
     name_index: &mut NameIndex,
+    cancel: Option<&AtomicBool>,
+    interrupted: &mut Vec<SlabIndex>,
 ) -> SlabIndex {
     let metadata = match node.metadata {
         Some(metadata) => SlabNodeMetadataCompact::some(metadata),
@@ -728,11 +1742,26 @@ fn construct_node_slab_name_index(
         // so this preorder traversal visits nodes in lexicographic path order.
         name_index.add_index_ordered(name, index);
     }
-    slab[index].children = node
-        .children
-        .iter()
-        .map(|node| construct_node_slab_name_index(Some(index), node, slab, name_index))
-        .collect();
+    let mut children = ThinVec::with_capacity(node.children.len());
+    for child in &node.children {
+        if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            interrupted.push(index);
+            break;
+        }
+        children.push(construct_node_slab_name_index(
+            Some(index),
+            child,
+            slab,
+            name_index,
+            cancel,
+            interrupted,
+        ));
+        if !interrupted.is_empty() {
+            interrupted.push(index);
+            break;
+        }
+    }
+    slab[index].children = children;
     index
 }
 
@@ -847,7 +1876,12 @@ mod tests {
         let root_target = push_child(&mut slab, root_idx, "target.txt");
         let alpha_target = push_child(&mut slab, alpha, "target.txt");
         let beta_target = push_child(&mut slab, beta, "target.txt");
-        let file_nodes = FileNodes::new(PathBuf::from("/virtual/root"), Vec::new(), slab, root_idx);
+        let file_nodes = FileNodes::new(
+            vec![PathBuf::from("/virtual/root")],
+            Vec::new(),
+            slab,
+            root_idx,
+        );
         (file_nodes, [root_target, alpha_target, beta_target])
     }
 
@@ -863,8 +1897,16 @@ mod tests {
         );
         let mut slab = ThinSlab::new();
         let mut name_index = NameIndex::default();
-        let root = construct_node_slab_name_index(None, &tree, &mut slab, &mut name_index);
-        let file_nodes = FileNodes::new(PathBuf::from("/virtual/root"), Vec::new(), slab, root);
+        let root = construct_node_slab_name_index(
+            None,
+            &tree,
+            &mut slab,
+            &mut name_index,
+            None,
+            &mut Vec::new(),
+        );
+        let file_nodes =
+            FileNodes::new(vec![PathBuf::from("/virtual/root")], Vec::new(), slab, root);
 
         let shared_entries = name_index.get("shared").expect("shared entries");
         assert_eq!(shared_entries.len(), 3);
@@ -880,6 +1922,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_construct_node_slab_name_index_stops_once_cancelled() {
+        const DIRS: usize = 20;
+        const FILES: usize = 10_000;
+        let tree = make_node(
+            "construct-cancel-root",
+            (0..DIRS)
+                .map(|dir| {
+                    let files = (0..FILES)
+                        .map(|file| make_leaf(&format!("construct-cancel-{dir}-{file}")))
+                        .collect();
+                    make_node(&format!("construct-cancel-dir-{dir}"), files)
+                })
+                .collect(),
+        );
+        let cancel = AtomicBool::new(false);
+        let before = NAME_POOL.len();
+        let mut slab = ThinSlab::new();
+        let mut name_index = NameIndex::default();
+        let mut interrupted = Vec::new();
+        let root = std::thread::scope(|s| {
+            s.spawn(|| {
+                // Cancel once construction has interned some names.
+                while NAME_POOL.len() < before + 1000 {
+                    std::hint::spin_loop();
+                }
+                cancel.store(true, Ordering::Relaxed);
+            });
+            construct_node_slab_name_index(
+                None,
+                &tree,
+                &mut slab,
+                &mut name_index,
+                Some(&cancel),
+                &mut interrupted,
+            )
+        });
+
+        assert!(slab.len() < 1 + DIRS * (1 + FILES), "built {}", slab.len());
+        // Innermost first, each one the parent of the one before.
+        assert_eq!(interrupted.last(), Some(&root));
+        for pair in interrupted.windows(2) {
+            assert_eq!(slab[pair[0]].parent(), Some(pair[1]));
+        }
+        for &index in &interrupted {
+            let wanted = if index == root { DIRS } else { FILES };
+            assert!(slab[index].children.len() <= wanted);
+        }
+        // Whatever was built is linked: every node hangs off the root.
+        let file_nodes =
+            FileNodes::new(vec![PathBuf::from("/virtual/root")], Vec::new(), slab, root);
+        let mut reached = 0;
+        let mut stack = vec![root];
+        while let Some(index) = stack.pop() {
+            reached += 1;
+            stack.extend(file_nodes[index].children.iter().copied());
+        }
+        assert_eq!(reached, file_nodes.len());
+    }
+
     #[test]
     fn test_name_index_add_index_sorts_paths() {
         let (file_nodes, targets) = manual_target_tree_file_nodes();
@@ -914,7 +2016,9 @@ mod tests {
         fs::File::create(root.join("beta/target.txt")).unwrap();
 
         let walk_data = WalkData::simple(root, false);
-        let cache = SearchCache::walk_fs_with_walk_data(&walk_data, None).expect("walk cache");
+        let cache = SearchCache::walk_fs_with_walk_data(&walk_data, None)
+            .unwrap()
+            .expect("walk cache");
 
         let entries = cache
             .name_index
@@ -933,6 +2037,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn walks_in_other_orders_are_indexed_by_name() {
+        let temp_dir = TempDir::new("walk_other_orders").expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        for dir in ["beta", "alpha", "gamma"] {
+            fs::create_dir(root.join(dir)).unwrap();
+            fs::File::create(root.join(dir).join("target.txt")).unwrap();
+        }
+
+        for sort_key in [SortKey::ModifiedDesc, SortKey::Unsorted] {
+            let walk_data = WalkData::simple(root, true).sort_children_by(sort_key);
+            let cache = SearchCache::walk_fs_with_walk_data(&walk_data, None)
+                .unwrap()
+                .unwrap();
+            assert_eq!(cache.verify(), Ok(()), "{sort_key:?}");
+            let entries: Vec<SlabIndex> = cache
+                .name_index
+                .get("target.txt")
+                .unwrap()
+                .iter()
+                .copied()
+                .collect();
+            let paths = cache.file_nodes.node_paths(&entries);
+            let mut sorted = paths.clone();
+            sorted.sort();
+            assert_eq!(paths, sorted, "{sort_key:?}");
+
+            let walk_data = WalkData::simple(root, true).sort_children_by(sort_key);
+            let partial = SearchCache::walk_fs_cancellable(&walk_data, None).unwrap();
+            assert_eq!(partial.cache.verify(), Ok(()), "{sort_key:?}");
+        }
+    }
+
+    #[test]
+    fn unlistable_roots_fail_walks_and_keep_the_cache_on_rescan() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new("unlistable_root").expect("Failed to create temp dir");
+        let root = temp_dir.path().join("locked");
+        fs::create_dir(&root).unwrap();
+        fs::File::create(root.join("kept.txt")).unwrap();
+        let mut cache = SearchCache::walk_fs(&root);
+        let total = cache.get_total_files();
+
+        fs::set_permissions(&root, fs::Permissions::from_mode(0o000)).unwrap();
+        let listable = fs::read_dir(&root).is_ok();
+        let walk = SearchCache::walk_fs_with_walk_data(&WalkData::simple(&root, false), None);
+        let roots = SearchCache::walk_fs_roots(&[&root, temp_dir.path()]).map(|_| ());
+        let rescan = cache.rescan();
+        fs::set_permissions(&root, fs::Permissions::from_mode(0o755)).unwrap();
+        if listable {
+            // Permissions don't bind root.
+            return;
+        }
+        assert!(walk.is_err());
+        assert!(roots.is_err());
+        assert!(rescan.is_err());
+        assert_eq!(cache.get_total_files(), total);
+    }
+
     #[test]
     fn test_search_cache_walk_and_verify() {
         let temp_dir = TempDir::new("test_cache").expect("Failed to create temp directory");
@@ -1053,9 +2217,9 @@ mod tests {
     // --- New comprehensive tests for recent changes ---
 
     #[test]
-    fn node_index_for_path_with_absolute_paths() {
+    fn index_of_path_with_absolute_paths() {
         let temp_dir =
-            TempDir::new("node_index_for_path_absolute").expect("Failed to create temp directory");
+            TempDir::new("index_of_path_absolute").expect("Failed to create temp directory");
         let root = temp_dir.path();
         fs::create_dir_all(root.join("alpha/beta/gamma")).expect("Failed to create directories");
         fs::File::create(root.join("alpha/beta/file.txt")).expect("Failed to create file");
@@ -1063,33 +2227,60 @@ mod tests {
         let cache = SearchCache::walk_fs(root);
 
         // Test retrieval with absolute paths
-        let alpha_index = cache.node_index_for_path(&root.join("alpha"));
+        let alpha_index = cache.index_of_path(&root.join("alpha"));
         assert!(alpha_index.is_some(), "should find alpha directory");
 
-        let beta_index = cache.node_index_for_path(&root.join("alpha/beta"));
+        let beta_index = cache.index_of_path(&root.join("alpha/beta"));
         assert!(beta_index.is_some(), "should find alpha/beta directory");
 
-        let file_index = cache.node_index_for_path(&root.join("alpha/beta/file.txt"));
+        let file_index = cache.index_of_path(&root.join("alpha/beta/file.txt"));
         assert!(file_index.is_some(), "should find file.txt");
 
-        let nonexistent = cache.node_index_for_path(&root.join("alpha/nonexistent"));
+        let nonexistent = cache.index_of_path(&root.join("alpha/nonexistent"));
         assert!(nonexistent.is_none(), "should not find nonexistent path");
     }
 
     #[test]
-    fn node_index_for_path_with_relative_path_fails() {
+    fn index_of_path_with_relative_path_fails() {
         let temp_dir =
-            TempDir::new("node_index_for_path_relative").expect("Failed to create temp directory");
+            TempDir::new("index_of_path_relative").expect("Failed to create temp directory");
         let root = temp_dir.path();
         fs::create_dir_all(root.join("dir")).expect("Failed to create directory");
 
         let cache = SearchCache::walk_fs(root);
 
         // Relative paths should not be found
-        let result = cache.node_index_for_path(Path::new("dir"));
+        let result = cache.index_of_path(Path::new("dir"));
         assert!(result.is_none(), "relative paths should not match");
     }
 
+    #[test]
+    fn index_of_path_round_trips_an_inserted_nested_file() {
+        let temp_dir =
+            TempDir::new("index_of_path_insert").expect("Failed to create temp directory");
+        let root = temp_dir.path();
+        let mut cache = SearchCache::walk_fs(root);
+
+        let file = root.join("outer/inner/leaf.txt");
+        fs::create_dir_all(file.parent().unwrap()).expect("Failed to create directories");
+        fs::File::create(&file).expect("Failed to create file");
+        cache
+            .handle_fs_events(vec![FsEvent {
+                path: root.join("outer"),
+                id: cache.last_event_id.unwrap_or_default() + 1,
+                flag: EventFlag::ItemCreated,
+            }])
+            .unwrap();
+
+        let index = cache
+            .index_of_path(&file)
+            .expect("inserted file should resolve");
+        assert_eq!(cache.node_path(index), Some(file.clone()));
+        let inner = cache.index_of_path(&root.join("outer/inner")).unwrap();
+        assert_eq!(cache.file_nodes[index].parent(), Some(inner));
+        assert!(cache.index_of_path(&file.join("missing")).is_none());
+    }
+
     #[test]
     fn node_path_returns_absolute_paths() {
         let temp_dir = TempDir::new("node_path_absolute").expect("Failed to create temp directory");
@@ -1100,7 +2291,7 @@ mod tests {
         let cache = SearchCache::walk_fs(root);
 
         let folder_index = cache
-            .node_index_for_path(&root.join("folder"))
+            .index_of_path(&root.join("folder"))
             .expect("folder should exist");
         let folder_path = cache
             .node_path(folder_index)
@@ -1112,7 +2303,7 @@ mod tests {
         assert_eq!(folder_path, root.join("folder"));
 
         let file_index = cache
-            .node_index_for_path(&root.join("folder/file.txt"))
+            .index_of_path(&root.join("folder/file.txt"))
             .expect("file should exist");
         let file_path = cache.node_path(file_index).expect("should get file path");
         assert!(file_path.is_absolute(), "returned path should be absolute");
@@ -1157,7 +2348,7 @@ mod tests {
 
         // Find the deepest node
         let e_index = cache
-            .node_index_for_path(&root.join("a/b/c/d/e"))
+            .index_of_path(&root.join("a/b/c/d/e"))
             .expect("deepest node should exist");
 
         // Verify we can navigate all the way back to root
@@ -1199,7 +2390,7 @@ mod tests {
         let target_path = root.join("to_remove");
 
         // Verify node exists before removal
-        assert!(cache.node_index_for_path(&target_path).is_some());
+        assert!(cache.index_of_path(&target_path).is_some());
 
         // Remove the node
         let removed = cache.remove_node_path(&target_path);
@@ -1212,12 +2403,70 @@ mod tests {
         );
 
         // Verify node no longer exists
-        assert!(cache.node_index_for_path(&target_path).is_none());
-        assert!(
+        assert!(cache.index_of_path(&target_path).is_none());
+        assert!(cache.index_of_path(&target_path.join("child")).is_none());
+    }
+
+    #[test]
+    fn wide_directory_lookup_moves_to_a_remaining_same_named_child() {
+        let temp_dir = TempDir::new("child_names_twins").unwrap();
+        let wide = temp_dir.path().join("wide");
+        fs::create_dir(&wide).unwrap();
+        for i in 0..child_names::MIN_CHILDREN {
+            fs::File::create(wide.join(format!("file-{i:03}.txt"))).unwrap();
+        }
+        fs::File::create(wide.join("dup.txt")).unwrap();
+        let mut cache = SearchCache::walk_fs(temp_dir.path());
+        let dup = wide.join("dup.txt");
+        let parent = cache.index_of_path(&wide).unwrap();
+        let original = cache.index_of_path(&dup).unwrap();
+
+        // Left out of the name index, which can't hold two nodes at one path.
+        let name = cache.file_nodes[original].name();
+        let twin = cache.file_nodes.insert(SlabNode::new(
+            Some(parent),
+            name,
+            SlabNodeMetadataCompact::none(),
+        ));
+        cache.link_child(parent, twin);
+        assert_eq!(cache.index_of_path(&dup), Some(original));
+
+        let unlink = |cache: &mut SearchCache, child| {
+            cache.file_nodes[parent].children.retain(|&x| x != child);
             cache
-                .node_index_for_path(&target_path.join("child"))
-                .is_none()
-        );
+                .child_names
+                .remove(&cache.file_nodes, parent, name, child);
+        };
+        unlink(&mut cache, original);
+        assert_eq!(cache.index_of_path(&dup), Some(twin));
+        unlink(&mut cache, twin);
+        assert_eq!(cache.index_of_path(&dup), None);
+    }
+
+    #[test]
+    fn ext_index_follows_node_insert_and_remove() {
+        let temp_dir = TempDir::new("ext_index_updates").unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("docs")).unwrap();
+        fs::write(root.join("docs/old.md"), b"x").unwrap();
+        let mut cache = SearchCache::walk_fs(root);
+        let md_names = |cache: &SearchCache| -> Vec<&str> {
+            cache
+                .name_index
+                .names_with_extension("md")
+                .map(|names| names.iter().copied().collect())
+                .unwrap_or_default()
+        };
+        assert_eq!(md_names(&cache), ["old.md"]);
+
+        fs::write(root.join("docs/new.md"), b"x").unwrap();
+        cache.create_node_chain(&root.join("docs/new.md"));
+        assert_eq!(md_names(&cache), ["new.md", "old.md"]);
+        assert_eq!(cache.search("ext:md").unwrap().len(), 2);
+
+        cache.remove_node_path(&root.join("docs"));
+        assert!(md_names(&cache).is_empty());
+        assert!(cache.search("ext:md").unwrap().is_empty());
     }
 
     #[test]
@@ -1246,7 +2495,7 @@ mod tests {
 
         // Verify all intermediate nodes are marked as unaccessible
         let missing1_index = cache
-            .node_index_for_path(&root.join("exists/missing1"))
+            .index_of_path(&root.join("exists/missing1"))
             .expect("missing1 should exist");
         assert_eq!(
             cache.file_nodes[missing1_index].state(),
@@ -1266,7 +2515,7 @@ mod tests {
 
         // Get the original metadata state
         let existing_index = cache
-            .node_index_for_path(&root.join("dir/existing.txt"))
+            .index_of_path(&root.join("dir/existing.txt"))
             .expect("file should exist");
         let original_state = cache.file_nodes[existing_index].state();
         let original_type = cache.file_nodes[existing_index].file_type_hint();
@@ -1306,15 +2555,11 @@ mod tests {
         );
 
         // Verify the new nodes are accessible
-        assert!(cache.node_index_for_path(&root.join("new_dir")).is_some());
-        assert!(
-            cache
-                .node_index_for_path(&root.join("new_dir/sub"))
-                .is_some()
-        );
+        assert!(cache.index_of_path(&root.join("new_dir")).is_some());
+        assert!(cache.index_of_path(&root.join("new_dir/sub")).is_some());
         assert!(
             cache
-                .node_index_for_path(&root.join("new_dir/file.txt"))
+                .index_of_path(&root.join("new_dir/file.txt"))
                 .is_some()
         );
     }
@@ -1343,10 +2588,10 @@ mod tests {
         let cache = SearchCache::walk_fs(root);
 
         // Verify Unicode paths work correctly
-        let folder_index = cache.node_index_for_path(&root.join("文件夹"));
+        let folder_index = cache.index_of_path(&root.join("文件夹"));
         assert!(folder_index.is_some(), "should find Unicode folder");
 
-        let file_index = cache.node_index_for_path(&root.join("文件夹/文件.txt"));
+        let file_index = cache.index_of_path(&root.join("文件夹/文件.txt"));
         assert!(file_index.is_some(), "should find Unicode file");
 
         // Verify path reconstruction
@@ -1367,10 +2612,10 @@ mod tests {
         let cache = SearchCache::walk_fs(root);
 
         // Verify paths with spaces and special chars work
-        let dir_index = cache.node_index_for_path(&root.join("dir with spaces"));
+        let dir_index = cache.index_of_path(&root.join("dir with spaces"));
         assert!(dir_index.is_some(), "should find directory with spaces");
 
-        let file_index = cache.node_index_for_path(&root.join("dir with spaces/file (1).txt"));
+        let file_index = cache.index_of_path(&root.join("dir with spaces/file (1).txt"));
         assert!(
             file_index.is_some(),
             "should find file with special characters"
@@ -1459,12 +2704,12 @@ mod tests {
         cache.create_node_chain(&target);
 
         // Verify parent relationships
-        let d_index = cache.node_index_for_path(&target).expect("d should exist");
+        let d_index = cache.index_of_path(&target).expect("d should exist");
         let c_index = cache
-            .node_index_for_path(&root.join("a/b/c"))
+            .index_of_path(&root.join("a/b/c"))
             .expect("c should exist");
         let b_index = cache
-            .node_index_for_path(&root.join("a/b"))
+            .index_of_path(&root.join("a/b"))
             .expect("b should exist");
 
         // Check parent pointers
@@ -1495,7 +2740,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::ItemCreated,
         }];
 
@@ -1519,7 +2764,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::ItemCreated,
         }];
 
@@ -1537,6 +2782,7 @@ mod tests {
         let temp_path = temp_dir.path();
 
         let mut cache = SearchCache::walk_fs(temp_dir.path());
+        cache.last_event_id = Some(100);
 
         assert_eq!(cache.file_nodes.len(), 1 + depth(temp_path));
         assert_eq!(cache.name_index.len(), 1 + depth(temp_path));
@@ -1545,7 +2791,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id.saturating_sub(1),
+            id: 99,
             flag: EventFlag::ItemCreated,
         }];
 
@@ -1554,6 +2800,7 @@ mod tests {
         assert_eq!(cache.file_nodes.len(), 2 + depth(temp_path));
         assert_eq!(cache.name_index.len(), 2 + depth(temp_path));
         assert_eq!(cache.search("new_file.txt").unwrap().len(), 1);
+        assert_eq!(cache.last_event_id(), Some(100));
     }
 
     #[test]
@@ -1592,6 +2839,7 @@ mod tests {
             "bar !foo",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             token,
         );
@@ -1609,6 +2857,7 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir);
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let indices =
             guard_indices(cache.search_with_options("alpha.txt", opts, CancellationToken::noop()));
@@ -1619,6 +2868,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let miss =
             guard_indices(cache.search_with_options("gamma.txt", opts, CancellationToken::noop()));
@@ -1638,6 +2888,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices =
             guard_indices(cache.search_with_options("alpha*.md", opts, CancellationToken::noop()));
@@ -1647,6 +2898,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let indices =
             guard_indices(cache.search_with_options("alpha*.md", opts, CancellationToken::noop()));
@@ -1667,6 +2919,7 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir);
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices = guard_indices(cache.search_with_options(
             "content:memchr",
@@ -1679,6 +2932,7 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let insensitive = guard_indices(cache.search_with_options(
             "content:MEMCHR",
@@ -1703,6 +2957,7 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir);
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices = guard_indices(cache.search_with_options(
             "content:XYZ",
@@ -1728,6 +2983,7 @@ mod tests {
             "content:a",
             SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1737,6 +2993,7 @@ mod tests {
             "content:a",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1747,6 +3004,7 @@ mod tests {
             "content:A",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1757,6 +3015,7 @@ mod tests {
             "content:z",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1781,6 +3040,7 @@ mod tests {
             "content:XYZ",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1811,6 +3071,7 @@ mod tests {
             &query,
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1848,6 +3109,7 @@ mod tests {
             "file_a",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             token,
         );
@@ -1882,7 +3144,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::ItemRemoved,
         }];
 
@@ -1900,7 +3162,7 @@ mod tests {
         let instant = std::time::Instant::now();
         let root = Path::new("/Library/Developer/CoreSimulator");
         let mut cache = SearchCache::walk_fs(root);
-        let mut event_id = cache.last_event_id + 1;
+        let mut event_id = cache.last_event_id.unwrap_or_default() + 1;
         println!(
             "Cache size: {}, process time: {:?}",
             cache.file_nodes.len(),
@@ -1918,7 +3180,7 @@ mod tests {
             cache.handle_fs_events(mock_events).unwrap();
             event_id += 1;
             println!(
-                "Event id: {}, process time: {:?}",
+                "Event id: {:?}, process time: {:?}",
                 cache.last_event_id,
                 instant.elapsed()
             );
@@ -1938,7 +3200,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("new_file.txt"),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::ItemRemoved,
         }];
 
@@ -1964,12 +3226,12 @@ mod tests {
         let mock_events = vec![
             FsEvent {
                 path: temp_path.join("new_file.txt"),
-                id: cache.last_event_id + 1,
+                id: cache.last_event_id.unwrap_or_default() + 1,
                 flag: EventFlag::ItemCreated,
             },
             FsEvent {
                 path: temp_path.join("new_file.txt"),
-                id: cache.last_event_id + 1,
+                id: cache.last_event_id.unwrap_or_default() + 1,
                 flag: EventFlag::ItemRemoved,
             },
         ];
@@ -1998,7 +3260,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.to_path_buf(),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::RootChanged,
         }];
 
@@ -2028,7 +3290,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.to_path_buf(),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::RootChanged,
         }];
 
@@ -2056,7 +3318,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.to_path_buf(),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::ItemModified,
         }];
 
@@ -2094,7 +3356,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("src"),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::ItemRemoved | EventFlag::ItemIsDir,
         }];
 
@@ -2137,7 +3399,7 @@ mod tests {
 
         let mock_events = vec![FsEvent {
             path: temp_path.join("src/foo"),
-            id: cache.last_event_id + 1,
+            id: cache.last_event_id.unwrap_or_default() + 1,
             flag: EventFlag::ItemRemoved | EventFlag::ItemIsDir,
         }];
 
@@ -2214,7 +3476,7 @@ mod tests {
         fs::File::create(root_path.join("subdir1/file2.txt")).expect("Failed to create file1.txt");
 
         let mut cache = SearchCache::walk_fs(root_path);
-        let mut last_event_id = cache.last_event_id();
+        let mut last_event_id = cache.last_event_id().unwrap_or_default();
 
         let new_file_path = root_path.join("event_file.txt");
         fs::write(&new_file_path, b"heck").expect("Failed to create event_file.txt");
@@ -2751,7 +4013,7 @@ mod tests {
         // Simulate an event for a new file
         let event_file_path = root_path.join("event_added_file.txt");
         fs::write(&event_file_path, "content123").unwrap(); // content of size 10
-        let last_event_id = cache.last_event_id();
+        let last_event_id = cache.last_event_id().unwrap_or_default();
         let event = FsEvent {
             path: event_file_path.clone(),
             id: last_event_id + 1,
@@ -2773,7 +4035,7 @@ mod tests {
         let file_in_event_dir_path = event_dir_path.join("inner_event.dat");
         fs::write(&file_in_event_dir_path, "data").unwrap(); // content of size 4
 
-        let last_event_id_2 = cache.last_event_id();
+        let last_event_id_2 = cache.last_event_id().unwrap();
         let event_dir = FsEvent {
             path: event_dir_path.clone(), // Event is for the directory
             id: last_event_id_2 + 1,
@@ -0,0 +1,89 @@
+//! Name lookup for the children of wide directories, so resolving a path
+//! doesn't scan thousands of siblings at every level.
+
+use crate::{FileNodes, SlabIndex};
+use hashbrown::HashMap;
+use std::sync::Mutex;
+
+/// Directories with fewer children are scanned; a map wouldn't pay for its
+/// memory there.
+pub(crate) const MIN_CHILDREN: usize = 64;
+
+type NameMap = HashMap<&'static str, SlabIndex>;
+
+/// Per-directory `name -> child` maps, built the first time a wide directory
+/// is looked up and kept in step with its children from then on.
+#[derive(Debug, Default)]
+pub(crate) struct ChildNames {
+    maps: Mutex<HashMap<SlabIndex, NameMap>>,
+}
+
+impl ChildNames {
+    /// The child of `parent` named `name`, in the name pool's encoding.
+    /// Builds the directory's map when it has none yet.
+    pub(crate) fn get(
+        &self,
+        nodes: &FileNodes,
+        parent: SlabIndex,
+        name: &str,
+    ) -> Option<SlabIndex> {
+        let mut maps = self.maps.lock().unwrap_or_else(|err| err.into_inner());
+        let map = maps.entry(parent).or_insert_with(|| {
+            let mut map = NameMap::with_capacity(nodes[parent].children.len());
+            for &child in &nodes[parent].children {
+                // The first of two same-named children wins, as in a scan.
+                map.entry(nodes[child].name()).or_insert(child);
+            }
+            map
+        });
+        map.get(name).copied()
+    }
+
+    /// Records `child` under `parent`, if `parent` has a map.
+    pub(crate) fn insert(&mut self, parent: SlabIndex, name: &'static str, child: SlabIndex) {
+        if let Some(map) = self.maps_mut().get_mut(&parent) {
+            map.entry(name).or_insert(child);
+        }
+    }
+
+    /// Drops `child` from `parent`'s map, if it has one. Should another child
+    /// share the name, the entry moves to the first of those a scan of
+    /// `nodes` would find.
+    pub(crate) fn remove(
+        &mut self,
+        nodes: &FileNodes,
+        parent: SlabIndex,
+        name: &str,
+        child: SlabIndex,
+    ) {
+        let Some(map) = self.maps_mut().get_mut(&parent) else {
+            return;
+        };
+        let Some((&key, mapped)) = map.get_key_value_mut(name) else {
+            return;
+        };
+        if *mapped != child {
+            return;
+        }
+        match nodes[parent]
+            .children
+            .iter()
+            .copied()
+            .find(|&sibling| sibling != child && nodes[sibling].name() == name)
+        {
+            Some(sibling) => *mapped = sibling,
+            None => {
+                map.remove(key);
+            }
+        }
+    }
+
+    /// Drops the map of a removed directory, whose slab index may be reused.
+    pub(crate) fn forget(&mut self, dir: SlabIndex) {
+        self.maps_mut().remove(&dir);
+    }
+
+    fn maps_mut(&mut self) -> &mut HashMap<SlabIndex, NameMap> {
+        self.maps.get_mut().unwrap_or_else(|err| err.into_inner())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{NAME_POOL, SearchCache, SlabIndex};
+use hashbrown::HashSet;
+
+/// One way the slab, the name index and the name pool disagree, as found by
+/// [`SearchCache::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// The node's name isn't the pooled copy of that name.
+    NameNotPooled { index: SlabIndex, name: String },
+    /// The name index doesn't list the node under its name.
+    MissingFromNameIndex { index: SlabIndex, name: String },
+    /// The name index lists `index` under `name`, but that slot is vacant or
+    /// holds a node with another name.
+    StaleNameIndexEntry { name: String, index: SlabIndex },
+    /// `parent` lists `child`, but that slot is vacant.
+    DanglingChild { parent: SlabIndex, child: SlabIndex },
+    /// `parent` lists `child`, but `child` names another parent, or none.
+    WrongParent {
+        parent: SlabIndex,
+        child: SlabIndex,
+        actual: Option<SlabIndex>,
+    },
+    /// `child` names `parent`, which is vacant or doesn't list it.
+    MissingFromParent { parent: SlabIndex, child: SlabIndex },
+    /// The node can't be reached from the root.
+    Orphan { index: SlabIndex },
+}
+
+impl SearchCache {
+    /// Cross-checks the slab against the name index and the name pool: every
+    /// node carries a pooled name and is indexed under it, every index entry
+    /// points at a live node of that name, parent and child links agree both
+    /// ways, and every node hangs off the root.
+    ///
+    /// Walks the whole index, so it's meant for tests and debugging rather
+    /// than for every update. Everything found is reported, not just the first
+    /// problem.
+    pub fn verify(&self) -> Result<(), Vec<Inconsistency>> {
+        let mut found = Vec::new();
+        let nodes = &self.file_nodes;
+
+        for (index, node) in nodes.iter() {
+            let name = node.name();
+            if NAME_POOL.get(name).map(str::as_ptr) != Some(name.as_ptr()) {
+                found.push(Inconsistency::NameNotPooled {
+                    index,
+                    name: name.to_string(),
+                });
+            }
+            if !self
+                .name_index
+                .get(name)
+                .is_some_and(|indices| indices.iter().any(|&x| x == index))
+            {
+                found.push(Inconsistency::MissingFromNameIndex {
+                    index,
+                    name: name.to_string(),
+                });
+            }
+            for &child in &node.children {
+                match nodes.get(child) {
+                    None => found.push(Inconsistency::DanglingChild {
+                        parent: index,
+                        child,
+                    }),
+                    Some(child_node) if child_node.parent() != Some(index) => {
+                        found.push(Inconsistency::WrongParent {
+                            parent: index,
+                            child,
+                            actual: child_node.parent(),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+            if let Some(parent) = node.parent()
+                && !nodes
+                    .get(parent)
+                    .is_some_and(|parent| parent.children.contains(&index))
+            {
+                found.push(Inconsistency::MissingFromParent {
+                    parent,
+                    child: index,
+                });
+            }
+        }
+
+        for (name, indices) in self.name_index.iter() {
+            for &index in indices.iter() {
+                if nodes.get(index).is_none_or(|node| node.name() != name) {
+                    found.push(Inconsistency::StaleNameIndexEntry {
+                        name: name.to_string(),
+                        index,
+                    });
+                }
+            }
+        }
+
+        // Only follow links the child agrees with, so a node listed by the
+        // wrong parent doesn't count as reached.
+        let root = nodes.root();
+        let mut reached = HashSet::new();
+        let mut stack = vec![root];
+        while let Some(index) = stack.pop() {
+            if !reached.insert(index) {
+                continue;
+            }
+            stack.extend(nodes[index].children.iter().copied().filter(|&child| {
+                nodes
+                    .get(child)
+                    .is_some_and(|child| child.parent() == Some(index))
+            }));
+        }
+        found.extend(
+            nodes
+                .iter()
+                .filter(|(index, _)| !reached.contains(index))
+                .map(|(index, _)| Inconsistency::Orphan { index }),
+        );
+
+        if found.is_empty() { Ok(()) } else { Err(found) }
+    }
+}
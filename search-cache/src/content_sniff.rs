@@ -0,0 +1,94 @@
+//! Recognizes common media and archive formats from their leading bytes, so
+//! `type:` can find files whose extension is missing or wrong.
+
+use std::{fs::File, io::Read, path::Path};
+
+/// Bytes read from the start of a file; every signature below fits in them.
+const HEADER_LEN: u64 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ContentKind {
+    Picture,
+    Video,
+    Audio,
+    Pdf,
+    Archive,
+}
+
+/// The kind of the file at `path` judging by its first [`HEADER_LEN`] bytes,
+/// or `None` if it can't be read or isn't recognized.
+pub(crate) fn sniff_file(path: &Path) -> Option<ContentKind> {
+    let mut header = Vec::with_capacity(HEADER_LEN as usize);
+    File::open(path)
+        .ok()?
+        .take(HEADER_LEN)
+        .read_to_end(&mut header)
+        .ok()?;
+    sniff(&header)
+}
+
+fn sniff(header: &[u8]) -> Option<ContentKind> {
+    let at = |signature: &[u8]| header.starts_with(signature);
+    if header.get(4..8) == Some(b"ftyp".as_slice()) {
+        return iso_brand(header.get(8..12)?);
+    }
+    if at(b"RIFF") {
+        return match header.get(8..12)? {
+            b"WEBP" => Some(ContentKind::Picture),
+            b"AVI " => Some(ContentKind::Video),
+            b"WAVE" => Some(ContentKind::Audio),
+            _ => None,
+        };
+    }
+    if at(b"FORM") {
+        return matches!(header.get(8..12)?, b"AIFF" | b"AIFC").then_some(ContentKind::Audio);
+    }
+    let kind = if at(b"\xFF\xD8\xFF")
+        || at(b"\x89PNG\r\n\x1A\n")
+        || at(b"GIF87a")
+        || at(b"GIF89a")
+        || at(b"II*\0")
+        || at(b"MM\0*")
+    {
+        ContentKind::Picture
+    } else if at(b"\x1A\x45\xDF\xA3") || at(b"FLV\x01") {
+        ContentKind::Video
+    } else if at(b"ID3")
+        || at(b"fLaC")
+        || at(b"OggS")
+        || matches!(
+            header.get(..2),
+            Some([0xFF, 0xFB | 0xF3 | 0xF2 | 0xF1 | 0xF9])
+        )
+    {
+        // The last test is an MP3 or ADTS frame sync.
+        ContentKind::Audio
+    } else if at(b"%PDF-") {
+        ContentKind::Pdf
+    } else if at(b"\x1F\x8B")
+        || at(b"BZh")
+        || at(b"\xFD7zXZ\0")
+        || at(b"7z\xBC\xAF\x27\x1C")
+        || at(b"Rar!\x1A\x07")
+        || at(b"\x28\xB5\x2F\xFD")
+    {
+        // Zip is left out: Office documents, jars and the like are zips too.
+        ContentKind::Archive
+    } else {
+        return None;
+    };
+    Some(kind)
+}
+
+/// Classifies an ISO base media file (MP4, QuickTime, HEIF, ...) by its major brand.
+fn iso_brand(brand: &[u8]) -> Option<ContentKind> {
+    match brand {
+        b"heic" | b"heix" | b"heim" | b"heis" | b"mif1" | b"msf1" | b"avif" => {
+            Some(ContentKind::Picture)
+        }
+        b"M4A " | b"M4B " => Some(ContentKind::Audio),
+        b"isom" | b"iso2" | b"mp41" | b"mp42" | b"avc1" | b"qt  " | b"M4V " | b"3gp4" | b"3gp5"
+        | b"3g2a" | b"dash" => Some(ContentKind::Video),
+        _ => None,
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Per-search measurements, reported in [`SearchOutcome::diagnostics`](crate::SearchOutcome)
+/// when [`SearchOptions::collect_diagnostics`](crate::SearchOptions) is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchDiagnostics {
+    /// Wall time spent evaluating the query.
+    pub elapsed: Duration,
+    /// Candidates examined: interned names scanned for name terms plus nodes
+    /// tested by filters. The same node may be counted once per term.
+    pub nodes_scanned: usize,
+    /// `lstat` calls made to fill in metadata missing from the index.
+    pub metadata_reads: usize,
+    /// Whether a `tag:` filter delegated to Spotlight (`mdfind`).
+    pub used_mdfind: bool,
+}
+
+/// Counters bumped by the evaluator. Updates are batched per term so the
+/// atomics stay out of per-node loops.
+#[derive(Debug, Default)]
+pub(crate) struct DiagnosticsCounters {
+    nodes_scanned: AtomicUsize,
+    metadata_reads: AtomicUsize,
+    used_mdfind: AtomicBool,
+}
+
+impl DiagnosticsCounters {
+    pub(crate) fn reset(&self) {
+        self.nodes_scanned.store(0, Ordering::Relaxed);
+        self.metadata_reads.store(0, Ordering::Relaxed);
+        self.used_mdfind.store(false, Ordering::Relaxed);
+    }
+
+    pub(crate) fn scanned(&self, count: usize) {
+        self.nodes_scanned.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn metadata_read(&self) {
+        self.metadata_reads(1);
+    }
+
+    pub(crate) fn metadata_reads(&self, count: usize) {
+        self.metadata_reads.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn used_mdfind(&self) {
+        self.used_mdfind.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self, elapsed: Duration) -> SearchDiagnostics {
+        SearchDiagnostics {
+            elapsed,
+            nodes_scanned: self.nodes_scanned.load(Ordering::Relaxed),
+            metadata_reads: self.metadata_reads.load(Ordering::Relaxed),
+            used_mdfind: self.used_mdfind.load(Ordering::Relaxed),
+        }
+    }
+}
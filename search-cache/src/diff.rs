@@ -0,0 +1,82 @@
+use crate::{SearchCache, SlabIndex, SlabNodeMetadataCompact};
+use fswalk::NodeFileType;
+use std::path::PathBuf;
+
+/// What changed from one cache to another, as reported by
+/// [`SearchCache::diff`]. Each list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheDiff {
+    /// Paths only the other cache holds. A new folder brings everything in it.
+    pub added: Vec<PathBuf>,
+    /// Paths only this cache holds.
+    pub removed: Vec<PathBuf>,
+    /// Paths both hold whose type changed, or, for anything but folders,
+    /// whose size or mtime did. Only nodes with metadata loaded on both sides
+    /// can show up here.
+    pub modified: Vec<PathBuf>,
+}
+
+impl CacheDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl SearchCache {
+    /// Compares this cache with a later one path by path. Nothing is read
+    /// from disk: metadata that neither cache loaded goes unchecked.
+    pub fn diff(&self, other: &SearchCache) -> CacheDiff {
+        let mut diff = CacheDiff::default();
+        let (root, other_root) = (self.file_nodes.root(), other.file_nodes.root());
+        let mut pairs = Vec::new();
+        if self.file_nodes[root].name() == other.file_nodes[other_root].name() {
+            pairs.push((root, other_root));
+        } else {
+            self.push_subtree_paths(root, &mut diff.removed);
+            other.push_subtree_paths(other_root, &mut diff.added);
+        }
+        while let Some((index, other_index)) = pairs.pop() {
+            if metadata_changed(
+                self.file_nodes[index].metadata,
+                other.file_nodes[other_index].metadata,
+            ) {
+                diff.modified.extend(other.node_path(other_index));
+            }
+            for &child in &self.file_nodes[index].children {
+                match other.child_named(other_index, &self.file_nodes[child].os_name()) {
+                    Some(other_child) => pairs.push((child, other_child)),
+                    None => self.push_subtree_paths(child, &mut diff.removed),
+                }
+            }
+            for &other_child in &other.file_nodes[other_index].children {
+                let name = other.file_nodes[other_child].os_name();
+                if self.child_named(index, &name).is_none() {
+                    other.push_subtree_paths(other_child, &mut diff.added);
+                }
+            }
+        }
+        diff.added.sort_unstable();
+        diff.removed.sort_unstable();
+        diff.modified.sort_unstable();
+        diff
+    }
+
+    fn push_subtree_paths(&self, index: SlabIndex, paths: &mut Vec<PathBuf>) {
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            paths.extend(self.node_path(index));
+            stack.extend(self.file_nodes[index].children.iter().copied());
+        }
+    }
+}
+
+/// A folder's size and mtime follow its entries, which are compared on
+/// their own.
+fn metadata_changed(old: SlabNodeMetadataCompact, new: SlabNodeMetadataCompact) -> bool {
+    let (Some(old), Some(new)) = (old.as_ref(), new.as_ref()) else {
+        return false;
+    };
+    old.r#type() != new.r#type()
+        || (old.r#type() != NodeFileType::Dir
+            && (old.size() != new.size() || old.mtime() != new.mtime()))
+}
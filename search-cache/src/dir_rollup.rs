@@ -0,0 +1,138 @@
+use crate::{SearchCache, SlabIndex, query::METADATA_FILL_BATCH};
+use fswalk::NodeFileType;
+use hashbrown::HashMap;
+use search_cancel::CancellationToken;
+use std::path::Path;
+
+/// Totals over everything below one folder, as computed by
+/// [`SearchCache::spawn_size_rollup`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirRollup {
+    /// Bytes in the regular files at any depth; folders and symlinks add none.
+    pub size: u64,
+    /// Entries at any depth, the same count `recursivecount:` compares.
+    pub entries: u64,
+}
+
+/// Rollups of the folders whose whole subtree has been summed. A change to
+/// the tree drops those of the folders above it; they are rebuilt on demand
+/// or by the next rollup.
+#[derive(Debug, Default)]
+pub(crate) struct DirRollups {
+    sums: HashMap<SlabIndex, DirRollup>,
+}
+
+impl DirRollups {
+    pub(crate) fn get(&self, index: SlabIndex) -> Option<DirRollup> {
+        self.sums.get(&index).copied()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.sums.clear();
+    }
+
+    pub(crate) fn remove(&mut self, index: SlabIndex) {
+        self.sums.remove(&index);
+    }
+}
+
+impl SearchCache {
+    /// Sums every folder in the index bottom-up so `dirsize:` and
+    /// `recursivecount:` are answered from memory afterwards. Missing file
+    /// metadata is stat'ed in parallel batches along the way.
+    ///
+    /// Meant to run while the cache is otherwise idle, with the token of the
+    /// latest search: once a new search cancels it, this returns `None` and
+    /// keeps the folders finished so far, each with correct totals. Calling it
+    /// again resumes from those.
+    pub fn spawn_size_rollup(&mut self, token: CancellationToken) -> Option<DirRollup> {
+        self.spawn_size_rollup_while(|| token.is_cancelled())
+    }
+
+    /// [`Self::spawn_size_rollup`] stopped by `proceed` instead of a token.
+    /// It is checked before each folder and each metadata batch, so a caller
+    /// can run the rollup in short slices between other work and resume it
+    /// with the next call.
+    pub fn spawn_size_rollup_while(
+        &mut self,
+        proceed: impl FnMut() -> Option<()>,
+    ) -> Option<DirRollup> {
+        self.roll_up(self.file_nodes.root(), proceed)
+    }
+
+    /// The cached totals of the folder at `index`, if a rollup has reached it
+    /// since the tree last changed.
+    pub fn dir_rollup(&self, index: SlabIndex) -> Option<DirRollup> {
+        self.dir_rollups.get(index)
+    }
+
+    /// Drops the rollups a change at `path` makes stale: those of the
+    /// deepest node the cache holds on the way to it and of every folder
+    /// above. Rollups elsewhere stay valid.
+    pub(crate) fn invalidate_rollups_above(&mut self, path: &Path) {
+        if self.dir_rollups.sums.is_empty() {
+            return;
+        }
+        let mut current = path
+            .ancestors()
+            .find_map(|ancestor| self.index_of_path(ancestor));
+        while let Some(index) = current {
+            self.dir_rollups.remove(index);
+            current = self.file_nodes[index].parent();
+        }
+    }
+
+    /// Totals of the folder at `index`, summing whatever part of its subtree
+    /// isn't cached yet. `proceed` is checked before each folder and each
+    /// metadata batch. `None` for non-folders or when `proceed` gives up.
+    pub(crate) fn roll_up(
+        &mut self,
+        index: SlabIndex,
+        mut proceed: impl FnMut() -> Option<()>,
+    ) -> Option<DirRollup> {
+        if self.file_nodes[index].file_type_hint() != NodeFileType::Dir {
+            return None;
+        }
+        let mut stack = vec![(index, false)];
+        while let Some((current, children_summed)) = stack.pop() {
+            if self.dir_rollups.sums.contains_key(&current) {
+                continue;
+            }
+            if !children_summed {
+                stack.push((current, true));
+                stack.extend(
+                    self.file_nodes[current]
+                        .children
+                        .iter()
+                        .filter(|&&child| {
+                            self.file_nodes[child].file_type_hint() == NodeFileType::Dir
+                        })
+                        .map(|&child| (child, false)),
+                );
+                continue;
+            }
+            proceed()?;
+            let children = self.file_nodes[current].children.to_vec();
+            self.fill_missing_metadata_in_batches(
+                &children,
+                true,
+                METADATA_FILL_BATCH,
+                &mut proceed,
+            )?;
+            let mut rollup = DirRollup::default();
+            for child in children {
+                rollup.entries += 1;
+                if let Some(below) = self.dir_rollups.get(child) {
+                    rollup.size += below.size;
+                    rollup.entries += below.entries;
+                } else if let Some(meta) = self.file_nodes[child].metadata.as_ref()
+                    && meta.r#type() == NodeFileType::File
+                {
+                    rollup.size += meta.size().max(0) as u64;
+                }
+            }
+            self.dir_rollups.sums.insert(current, rollup);
+        }
+        self.dir_rollups.get(index)
+    }
+}
@@ -0,0 +1,48 @@
+//! BSD file flags (those set with `chflags`) for the `flag:` filter. The
+//! index doesn't keep them, so each candidate is stat'ed when the filter runs.
+//! Only macOS has them; elsewhere no node carries a flag.
+
+use anyhow::{Result, bail};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FileFlag {
+    /// `uchg` or `schg`: the file can't be changed, renamed or deleted. The
+    /// Finder's "Locked" checkbox sets `uchg`.
+    Locked,
+    /// `hidden`: left out of the Finder, whatever the name looks like.
+    Hidden,
+}
+
+impl FileFlag {
+    pub(crate) fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "locked" | "uchg" | "immutable" => Ok(Self::Locked),
+            "hidden" => Ok(Self::Hidden),
+            _ => bail!("Unknown file flag: {raw}; expected locked or hidden"),
+        }
+    }
+
+    /// Whether the node at `path` has the flag. Symlinks are checked
+    /// themselves, not what they point to.
+    #[cfg(target_os = "macos")]
+    pub(crate) fn is_set(self, path: &Path) -> bool {
+        use std::os::macos::fs::MetadataExt;
+
+        // From <sys/stat.h>.
+        const UF_IMMUTABLE: u32 = 0x0000_0002;
+        const UF_HIDDEN: u32 = 0x0000_8000;
+        const SF_IMMUTABLE: u32 = 0x0002_0000;
+        let mask = match self {
+            Self::Locked => UF_IMMUTABLE | SF_IMMUTABLE,
+            Self::Hidden => UF_HIDDEN,
+        };
+        path.symlink_metadata()
+            .is_ok_and(|metadata| metadata.st_flags() & mask != 0)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub(crate) fn is_set(self, _path: &Path) -> bool {
+        false
+    }
+}
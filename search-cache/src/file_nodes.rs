@@ -1,5 +1,8 @@
 use crate::{SlabIndex, SlabNode, ThinSlab};
+use fswalk::name_to_os_str;
+use hashbrown::HashMap;
 use std::{
+    borrow::Cow,
     ffi::OsStr,
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
@@ -7,7 +10,8 @@ use std::{
 
 #[derive(Debug)]
 pub struct FileNodes {
-    path: PathBuf,
+    /// Walked roots, primary first. Never empty.
+    roots: Vec<PathBuf>,
     ignore_paths: Vec<PathBuf>,
     slab: ThinSlab<SlabNode>,
     root: SlabIndex,
@@ -15,13 +19,14 @@ pub struct FileNodes {
 
 impl FileNodes {
     pub(crate) fn new(
-        path: PathBuf,
+        roots: Vec<PathBuf>,
         ignore_paths: Vec<PathBuf>,
         slab: ThinSlab<SlabNode>,
         root: SlabIndex,
     ) -> Self {
+        assert!(!roots.is_empty(), "FileNodes requires at least one root");
         Self {
-            path,
+            roots,
             ignore_paths,
             slab,
             root,
@@ -36,19 +41,75 @@ impl FileNodes {
         let mut current = index;
         let mut segments = vec![];
         while let Some(parent) = self.slab.get(current)?.parent() {
-            segments.push(self.slab.get(current)?.name());
+            segments.push(self.slab.get(current)?.os_name());
             current = parent;
         }
         Some(
-            std::iter::once("/")
+            std::iter::once(Cow::Borrowed(OsStr::new("/")))
                 .chain(segments.into_iter().rev())
-                .map(OsStr::new)
                 .collect(),
         )
     }
 
+    /// [`Self::node_path`] for a batch of indices, in the same order.
+    ///
+    /// Directory paths are memoized by slab index while the batch is built, so
+    /// a page of results under a few folders reconstructs each shared prefix
+    /// once instead of walking every parent chain to the root.
+    pub fn node_paths(&self, indices: &[SlabIndex]) -> Vec<Option<PathBuf>> {
+        let mut dirs: HashMap<SlabIndex, PathBuf> = HashMap::new();
+        let mut pending: Vec<(SlabIndex, &str)> = Vec::new();
+        indices
+            .iter()
+            .map(|&index| {
+                pending.clear();
+                let mut current = index;
+                let mut path = loop {
+                    if let Some(path) = dirs.get(&current) {
+                        break path.clone();
+                    }
+                    let node = self.slab.get(current)?;
+                    let Some(parent) = node.parent() else {
+                        break PathBuf::from("/");
+                    };
+                    pending.push((current, node.name()));
+                    current = parent;
+                };
+                for &(node, name) in pending.iter().rev() {
+                    path.push(name_to_os_str(name));
+                    if node != index {
+                        dirs.insert(node, path.clone());
+                    }
+                }
+                Some(path)
+            })
+            .collect()
+    }
+
+    /// Parent chain of `index` from the slab root down to its direct parent.
+    ///
+    /// Empty for the root itself and for indices not in the slab.
+    pub fn ancestors(&self, index: SlabIndex) -> Vec<(SlabIndex, &'static str)> {
+        let mut chain = Vec::new();
+        let mut parent = self.slab.get(index).and_then(SlabNode::parent);
+        while let Some(current) = parent {
+            let Some(node) = self.slab.get(current) else {
+                break;
+            };
+            chain.push((current, node.name()));
+            parent = node.parent();
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// The primary root, which fsevents are watched from.
     pub(crate) fn path(&self) -> &Path {
-        &self.path
+        &self.roots[0]
+    }
+
+    pub(crate) fn roots(&self) -> &[PathBuf] {
+        &self.roots
     }
 
     pub(crate) fn ignore_paths(&self) -> &Vec<PathBuf> {
@@ -63,14 +124,14 @@ impl FileNodes {
         self.slab = slab;
     }
 
-    pub(crate) fn into_parts(self) -> (PathBuf, Vec<PathBuf>, SlabIndex, ThinSlab<SlabNode>) {
+    pub(crate) fn into_parts(self) -> (Vec<PathBuf>, Vec<PathBuf>, SlabIndex, ThinSlab<SlabNode>) {
         let Self {
-            path,
+            roots,
             ignore_paths,
             slab,
             root,
         } = self;
-        (path, ignore_paths, root, slab)
+        (roots, ignore_paths, root, slab)
     }
 }
 
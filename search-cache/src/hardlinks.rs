@@ -0,0 +1,116 @@
+use crate::SlabIndex;
+use hashbrown::HashMap;
+
+/// Device and inode of the files [`SearchCache::search_hardlinks`] has
+/// stat'ed, `None` for those it couldn't. Later searches compare them from
+/// memory; a node's entry goes with it when a change removes or rescans it.
+///
+/// [`SearchCache::search_hardlinks`]: crate::SearchCache::search_hardlinks
+#[derive(Debug, Default)]
+pub(crate) struct FileIds {
+    ids: HashMap<SlabIndex, Option<(u64, u64)>>,
+}
+
+impl FileIds {
+    pub(crate) fn remove(&mut self, index: SlabIndex) {
+        self.ids.remove(&index);
+    }
+}
+
+#[cfg(unix)]
+mod search {
+    use super::FileIds;
+    use crate::{SearchCache, SlabIndex, SlabNodeMetadataCompact, query::METADATA_FILL_BATCH};
+    use fswalk::NodeFileType;
+    use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+    use search_cancel::CancellationToken;
+    use std::{fs::Metadata, os::unix::fs::MetadataExt};
+
+    impl FileIds {
+        fn get(&self, index: SlabIndex) -> Option<Option<(u64, u64)>> {
+            self.ids.get(&index).copied()
+        }
+
+        fn record(&mut self, index: SlabIndex, metadata: Option<&Metadata>) {
+            self.ids.insert(
+                index,
+                metadata.map(|metadata| (metadata.dev(), metadata.ino())),
+            );
+        }
+    }
+
+    impl SearchCache {
+        /// Every indexed node that is a hard link to the same file as `of`,
+        /// `of` included, in slab order. Empty if `of` can't be stat'ed; just
+        /// `of` for anything but a regular file. Returns `None` if cancelled.
+        ///
+        /// Device and inode aren't part of the index, so they are read the
+        /// first time a search needs them and kept per node until a change
+        /// drops it. Candidates whose loaded size rules them out are skipped;
+        /// the rest are stat'ed in parallel batches, with cancellation checked
+        /// between them, filling in missing metadata along the way. The search
+        /// stops once the reference's link count is reached, and links outside
+        /// the index are never found.
+        pub fn search_hardlinks(
+            &mut self,
+            of: SlabIndex,
+            token: CancellationToken,
+        ) -> Option<Vec<SlabIndex>> {
+            let reference = self
+                .node_path(of)
+                .and_then(|path| path.symlink_metadata().ok());
+            self.file_ids.record(of, reference.as_ref());
+            let Some(reference) = reference else {
+                return Some(Vec::new());
+            };
+            if !reference.is_file() || reference.nlink() == 1 {
+                return Some(vec![of]);
+            }
+            let target = Some((reference.dev(), reference.ino()));
+            let mut links = Vec::new();
+            let mut unknown = Vec::new();
+            for (index, node) in self.file_nodes.iter() {
+                if node.file_type_hint() != NodeFileType::File
+                    || node
+                        .metadata
+                        .as_ref()
+                        .is_some_and(|metadata| metadata.size() as u64 != reference.len())
+                {
+                    continue;
+                }
+                match self.file_ids.get(index) {
+                    Some(id) if id == target => links.push(index),
+                    Some(_) => {}
+                    None => unknown.push(index),
+                }
+            }
+            for batch in unknown.chunks(METADATA_FILL_BATCH) {
+                if links.len() as u64 >= reference.nlink() {
+                    break;
+                }
+                token.is_cancelled()?;
+                let paths = self.node_paths(batch);
+                let loaded: Vec<_> = paths
+                    .par_iter()
+                    .map(|path| path.as_ref().and_then(|path| path.symlink_metadata().ok()))
+                    .collect();
+                self.diagnostics.metadata_reads(batch.len());
+                for (&index, metadata) in batch.iter().zip(loaded) {
+                    self.file_ids.record(index, metadata.as_ref());
+                    let Some(metadata) = metadata else {
+                        continue;
+                    };
+                    if (metadata.dev(), metadata.ino()) == (reference.dev(), reference.ino()) {
+                        links.push(index);
+                    }
+                    let node = &mut self.file_nodes[index];
+                    if node.metadata.as_ref().is_none() {
+                        node.metadata = SlabNodeMetadataCompact::some(metadata.into());
+                    }
+                }
+            }
+            links.sort_unstable();
+            Some(links)
+        }
+    }
+}
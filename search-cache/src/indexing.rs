@@ -0,0 +1,135 @@
+use crate::{SearchCache, SearchOptions, SearchOutcome, SlabIndex};
+use anyhow::Result;
+use fswalk::{WalkData, walk_it_without_root_chain};
+use search_cancel::CancellationToken;
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use tracing::warn;
+
+/// A [`SearchCache`] built one top-level entry of its root at a time, so it
+/// can answer queries while the rest of the walk is still running.
+///
+/// [`Self::index_next`] walks an entry without touching the cache and only
+/// locks it to link the finished subtree, so a search never sees a
+/// half-attached entry and is only blocked for that short step.
+pub struct IndexingCache {
+    cache: Mutex<SearchCache>,
+    root: SlabIndex,
+    /// Entries of the root still to walk, in name order. Held for the whole
+    /// walk of an entry so subtrees are appended in path order.
+    pending: Mutex<VecDeque<PathBuf>>,
+    complete: AtomicBool,
+    ignore_paths: Vec<PathBuf>,
+    cancel: Option<&'static AtomicBool>,
+}
+
+/// What [`IndexingCache::search_partial`] found so far.
+#[derive(Debug)]
+pub struct PartialOutcome {
+    pub outcome: SearchOutcome,
+    /// Whether the whole root was indexed when the search ran. While false,
+    /// entries indexed later may hold more matches.
+    pub complete: bool,
+}
+
+impl IndexingCache {
+    /// Starts with an empty `root`, which must be absolute. Nothing is walked
+    /// until [`Self::index_next`] or [`Self::index_all`] is called.
+    pub fn new(
+        root: &Path,
+        ignore_paths: Vec<PathBuf>,
+        cancel: Option<&'static AtomicBool>,
+    ) -> Self {
+        let (cache, root_index) = SearchCache::root_chain_only(root, &ignore_paths, cancel);
+        let mut entries: Vec<PathBuf> = match fs::read_dir(root) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .collect(),
+            Err(err) => {
+                warn!("failed to read indexing root {root:?}: {err}");
+                Vec::new()
+            }
+        };
+        // Same order as the walker sorts children, which the name index relies on.
+        entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+        Self {
+            cache: Mutex::new(cache),
+            root: root_index,
+            complete: AtomicBool::new(entries.is_empty()),
+            pending: Mutex::new(entries.into()),
+            ignore_paths,
+            cancel,
+        }
+    }
+
+    /// Walks the next top-level entry and links it into the cache.
+    ///
+    /// Returns `Some(true)` while entries remain, `Some(false)` once the root is
+    /// fully indexed, and `None` if the walk was cancelled.
+    pub fn index_next(&self) -> Option<bool> {
+        let mut pending = self.pending.lock().unwrap();
+        let Some(entry) = pending.pop_front() else {
+            return Some(false);
+        };
+        let walk_data = WalkData::new(&entry, &self.ignore_paths, false, self.cancel);
+        let node = walk_it_without_root_chain(&walk_data);
+        if self
+            .cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            return None;
+        }
+        let mut cache = self.cache.lock().unwrap();
+        // `None` means the entry is ignored or vanished since it was listed.
+        if let Some(node) = node {
+            cache.append_walked_subtree(self.root, &node);
+        }
+        let more = !pending.is_empty();
+        if !more {
+            self.complete.store(true, Ordering::Release);
+        }
+        Some(more)
+    }
+
+    /// Indexes every remaining entry. `None` if cancelled.
+    pub fn index_all(&self) -> Option<()> {
+        while self.index_next()? {}
+        Some(())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Acquire)
+    }
+
+    /// [`SearchCache::search_with_options`] over whatever is indexed so far.
+    /// `outcome.nodes` is `None` when the search itself was cancelled.
+    pub fn search_partial(
+        &self,
+        line: &str,
+        options: SearchOptions,
+        cancellation_token: CancellationToken,
+    ) -> Result<PartialOutcome> {
+        let mut cache = self.cache.lock().unwrap();
+        // Read under the cache lock so the flag matches the searched state.
+        let complete = self.is_complete();
+        let outcome = cache.search_with_options(line, options, cancellation_token)?;
+        Ok(PartialOutcome { outcome, complete })
+    }
+
+    /// Runs `f` with the cache locked, e.g. to resolve result paths.
+    pub fn with_cache<R>(&self, f: impl FnOnce(&mut SearchCache) -> R) -> R {
+        f(&mut self.cache.lock().unwrap())
+    }
+
+    /// The cache as indexed so far; complete if [`Self::is_complete`].
+    pub fn into_cache(self) -> SearchCache {
+        self.cache.into_inner().unwrap()
+    }
+}
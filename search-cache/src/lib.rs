@@ -1,31 +1,59 @@
 #![feature(str_from_raw_parts)]
+mod aliases;
 mod cache;
+mod child_names;
+mod consistency;
+mod content_sniff;
+mod diagnostics;
+mod diff;
+mod dir_rollup;
+mod file_flags;
 mod file_nodes;
+mod hardlinks;
 mod highlight;
+mod indexing;
+mod match_spans;
 mod metadata_cache;
 mod name_index;
 mod persistent;
+mod prefetch_thread;
 mod query;
+mod query_plan;
 mod query_preprocessor;
+mod quick_filter;
+mod search_iterator;
 mod segment;
 mod slab;
 mod slab_node;
+mod stats;
 mod type_and_size;
-mod search_iterator;
-mod prefetch_thread;
+mod volume_case;
+mod wal;
 
 pub use cache::*;
+pub use consistency::Inconsistency;
+pub use diagnostics::SearchDiagnostics;
+pub use diff::CacheDiff;
+pub use dir_rollup::DirRollup;
 pub use file_nodes::*;
-pub use fswalk::WalkData;
+pub use fswalk::{ThreadBudget, WalkData, WalkError, WalkOptions};
 pub use highlight::{derive_highlight_terms, extract_highlights_from_query};
+pub use indexing::{IndexingCache, PartialOutcome};
 pub use metadata_cache::*;
 pub use name_index::*;
 pub use persistent::*;
-pub use search_iterator::{SearchIterator, SearchBatch, IteratorState};
-pub use prefetch_thread::{PrefetchState, PrefetchMessage};
+pub use prefetch_thread::{PrefetchMessage, PrefetchState};
+// For the allocation benchmark in `tests/set_ops_allocations.rs`, which needs
+// its own binary to count allocations.
+#[doc(hidden)]
+pub use query::{difference_in_place, intersect_in_place};
+pub use query_plan::{PlanExpr, PlanSegment, QueryError, QueryPlan};
+pub use quick_filter::QuickFilter;
+pub use search_iterator::{IteratorState, SearchBatch, SearchIterator};
 pub use segment::*;
 pub use slab::*;
 pub use slab_node::*;
+pub use stats::{IndexStats, RootInfo};
 pub use type_and_size::*;
 
 #[cfg(test)]
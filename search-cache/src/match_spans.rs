@@ -0,0 +1,165 @@
+use crate::{
+    SearchOptions, SegmentMatcher, SegmentMatcherConcrete, build_name_matcher,
+    build_segment_matchers,
+};
+use anyhow::{Result, anyhow};
+use cardinal_syntax::{ArgumentKind, Expr, FilterKind, Term};
+use query_segmentation::{Segment, query_segmentation};
+use regex::RegexBuilder;
+use std::ops::Range;
+
+/// Collects matchers for the filename part of every positive name term in `expr`.
+///
+/// Negated terms are skipped because they never contribute to a hit, and
+/// filters only count when their argument is evaluated as a name phrase
+/// (`file:report`, `audio:live` ...).
+pub(crate) fn name_span_matchers(
+    expr: &Expr,
+    options: SearchOptions,
+) -> Result<Vec<SegmentMatcherConcrete>> {
+    let mut matchers = Vec::new();
+    collect_expr(expr, options, &mut matchers)?;
+    Ok(matchers)
+}
+
+/// Returns the merged, ascending byte ranges of `name` matched by any of `matchers`.
+pub(crate) fn name_match_spans(
+    name: &str,
+    matchers: &[SegmentMatcherConcrete],
+) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    for matcher in matchers {
+        matcher.match_ranges(name, &mut ranges);
+    }
+    merge_ranges(ranges)
+}
+
+fn collect_expr(
+    expr: &Expr,
+    options: SearchOptions,
+    out: &mut Vec<SegmentMatcherConcrete>,
+) -> Result<()> {
+    match expr {
+        Expr::Empty | Expr::Not(_) => Ok(()),
+        Expr::Term(term) => collect_term(term, options, out),
+        Expr::And(parts) | Expr::Or(parts) => parts
+            .iter()
+            .try_for_each(|part| collect_expr(part, options, out)),
+    }
+}
+
+fn collect_term(
+    term: &Term,
+    options: SearchOptions,
+    out: &mut Vec<SegmentMatcherConcrete>,
+) -> Result<()> {
+    match term {
+        Term::Word(text) => collect_phrase(text, options, out),
+        Term::Regex(pattern) => {
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(options.case_insensitive)
+                .build()
+                .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+            out.push(SegmentMatcherConcrete::Regex { regex });
+            Ok(())
+        }
+        Term::Filter(filter) => match (&filter.kind, &filter.argument) {
+            (
+                FilterKind::File
+                | FilterKind::Folder
+                | FilterKind::Audio
+                | FilterKind::Video
+                | FilterKind::Doc
+                | FilterKind::Exe
+                | FilterKind::Broken
+                | FilterKind::NoExtension,
+                Some(argument),
+            ) => collect_phrase(&argument.raw, options, out),
+            (FilterKind::Name, Some(argument)) => {
+                let literal = matches!(argument.kind, ArgumentKind::Phrase);
+                let matcher = build_name_matcher(&argument.raw, literal, options)
+                    .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+                out.push(matcher);
+                Ok(())
+            }
+            _ => Ok(()),
+        },
+    }
+}
+
+fn collect_phrase(
+    text: &str,
+    options: SearchOptions,
+    out: &mut Vec<SegmentMatcherConcrete>,
+) -> Result<()> {
+    // Only the last segment is matched against the node's own name.
+    let segments = query_segmentation(text);
+    let Some(last @ Segment::Concrete(_)) = segments.last() else {
+        return Ok(());
+    };
+    let matchers = build_segment_matchers(std::slice::from_ref(last), options)
+        .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+    out.extend(matchers.into_iter().filter_map(|matcher| match matcher {
+        SegmentMatcher::Concrete(concrete) => Some(concrete),
+        SegmentMatcher::Star | SegmentMatcher::GlobStar => None,
+    }));
+    Ok(())
+}
+
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_unstable_by_key(|range| (range.start, range.end));
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use cardinal_syntax::parse_query;
+
+    fn spans(query: &str, name: &str, options: SearchOptions) -> Vec<Range<usize>> {
+        let parsed = parse_query(query).expect("query should parse");
+        let matchers = name_span_matchers(&parsed.expr, options).expect("matchers should build");
+        name_match_spans(name, &matchers)
+    }
+
+    #[test]
+    fn merge_ranges_coalesces_overlaps_and_drops_empty() {
+        assert_eq!(
+            merge_ranges(vec![4..6, 0..2, 1..3, 5..5, 6..8]),
+            vec![0..3, 4..8]
+        );
+    }
+
+    #[test]
+    fn negated_terms_do_not_contribute() {
+        assert_eq!(
+            spans("!draft report", "report draft", SearchOptions::default()),
+            vec![0..6]
+        );
+    }
+
+    #[test]
+    fn prefix_and_suffix_segments_anchor_spans() {
+        let options = SearchOptions::default();
+        assert_eq!(spans("/rep", "report rep", options), vec![0..3]);
+        assert_eq!(spans("rep/", "report rep", options), vec![7..10]);
+    }
+
+    #[test]
+    fn path_terms_use_last_segment_only() {
+        assert_eq!(
+            spans("docs/read", "readme.md", SearchOptions::default()),
+            vec![0..4]
+        );
+        assert!(spans("docs/**", "readme.md", SearchOptions::default()).is_empty());
+    }
+}
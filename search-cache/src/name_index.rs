@@ -1,8 +1,13 @@
-use crate::{FileNodes, NAME_POOL, SlabIndex};
+use crate::{FileNodes, NAME_POOL, SlabIndex, query::extension_of};
+use hashbrown::HashMap;
 use itertools::Itertools;
 use search_cancel::CancellationToken;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, time::Instant};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Bound,
+    time::Instant,
+};
 use thin_vec::ThinVec;
 use tracing::info;
 
@@ -65,6 +70,10 @@ impl SortedSlabIndices {
 #[derive(Clone, Default)]
 pub struct NameIndex {
     map: BTreeMap<&'static str, SortedSlabIndices>,
+    /// Lowercased extension -> names in `map` carrying it. Buckets hold names
+    /// rather than slab indices so lookups expand through `map` and come out in
+    /// the same order as [`Self::all_indices`].
+    ext_index: HashMap<Box<str>, BTreeSet<&'static str>>,
 }
 
 impl NameIndex {
@@ -91,6 +100,80 @@ impl NameIndex {
             .ok()
     }
 
+    /// Indices of every name whose extension is one of `extensions`
+    /// (lowercase, without the dot), ordered like [`Self::all_indices`].
+    pub fn indices_with_extensions<'e>(
+        &self,
+        extensions: impl IntoIterator<Item = &'e str>,
+        cancellation_token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let mut names = BTreeSet::new();
+        for extension in extensions {
+            if let Some(bucket) = self.ext_index.get(extension) {
+                names.extend(bucket.iter().copied());
+            }
+        }
+        let mut indices = Vec::new();
+        for (i, name) in names.into_iter().enumerate() {
+            cancellation_token.is_cancelled_sparse(i)?;
+            if let Some(entry) = self.map.get(name) {
+                indices.extend(entry.iter().copied());
+            }
+        }
+        Some(indices)
+    }
+
+    /// Every indexed extension with the names carrying it, in no particular order.
+    pub fn extensions(&self) -> impl Iterator<Item = (&str, &BTreeSet<&'static str>)> {
+        self.ext_index
+            .iter()
+            .map(|(extension, names)| (&**extension, names))
+    }
+
+    /// Names indexed under `extension`, if any.
+    pub fn names_with_extension(&self, extension: &str) -> Option<&BTreeSet<&'static str>> {
+        self.ext_index.get(extension)
+    }
+
+    fn insert_name(&mut self, interned: &'static str, indices: SortedSlabIndices) {
+        self.map.insert(interned, indices);
+        if let Some(extension) = extension_of(interned) {
+            self.ext_index
+                .entry(extension.into_boxed_str())
+                .or_default()
+                .insert(interned);
+        }
+    }
+
+    fn remove_name(&mut self, name: &str) -> Option<SortedSlabIndices> {
+        let removed = self.map.remove(name)?;
+        if let Some(extension) = extension_of(name)
+            && let Some(bucket) = self.ext_index.get_mut(extension.as_str())
+        {
+            bucket.remove(name);
+            if bucket.is_empty() {
+                self.ext_index.remove(extension.as_str());
+            }
+        }
+        Some(removed)
+    }
+
+    /// Indexed names starting with `prefix`, in name order.
+    pub fn names_with_prefix<'p>(
+        &self,
+        prefix: &'p str,
+    ) -> impl Iterator<Item = (&'static str, &SortedSlabIndices)> + use<'_, 'p> {
+        self.map
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(name, _)| name.starts_with(prefix))
+            .map(|(&name, indices)| (name, indices))
+    }
+
+    /// Every indexed name with its nodes, in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &SortedSlabIndices)> {
+        self.map.iter().map(|(&name, indices)| (name, indices))
+    }
+
     pub fn get(&self, name: &str) -> Option<&SortedSlabIndices> {
         self.map.get(name)
     }
@@ -109,7 +192,7 @@ impl NameIndex {
             }
         } else {
             let interned = NAME_POOL.push(name);
-            self.map.insert(interned, SortedSlabIndices::new(index));
+            self.insert_name(interned, SortedSlabIndices::new(index));
         }
     }
 
@@ -118,7 +201,7 @@ impl NameIndex {
             existing.insert(index, slab);
         } else {
             let interned = NAME_POOL.push(name);
-            self.map.insert(interned, SortedSlabIndices::new(index));
+            self.insert_name(interned, SortedSlabIndices::new(index));
         }
     }
 
@@ -128,13 +211,13 @@ impl NameIndex {
         };
         let removed = indices.remove(index);
         if indices.is_empty() {
-            self.map.remove(name);
+            self.remove_name(name);
         }
         removed
     }
 
     pub fn remove(&mut self, name: &str) -> Option<SortedSlabIndices> {
-        self.map.remove(name)
+        self.remove_name(name)
     }
 
     pub(crate) fn as_persistent(&self) -> BTreeMap<Box<str>, SortedSlabIndices> {
@@ -153,16 +236,16 @@ impl NameIndex {
 
     pub fn construct_name_pool(data: BTreeMap<Box<str>, SortedSlabIndices>) -> Self {
         let name_pool_time = Instant::now();
-        let mut map = BTreeMap::new();
+        let mut index = Self::default();
         for (name, indices) in data {
             let interned = NAME_POOL.push(&name);
-            map.insert(interned, indices);
+            index.insert_name(interned, indices);
         }
         info!(
             "Name pool construction time: {:?}, count: {}",
             name_pool_time.elapsed(),
             NAME_POOL.len(),
         );
-        Self { map }
+        index
     }
 }
@@ -3,8 +3,9 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
+    fmt,
     fs::{self, File},
-    io::{BufReader, BufWriter},
+    io::{BufReader, BufWriter, Read, Write},
     path::{Path, PathBuf},
     thread::available_parallelism,
     time::Instant,
@@ -12,15 +13,66 @@ use std::{
 use tracing::info;
 use typed_num::Num;
 
-const LSF_VERSION: i64 = 5;
+const LSF_VERSION: i64 = 10;
+
+/// Start of every cache file, followed by [`LSF_VERSION`] as little-endian
+/// `i64` and then the zstd stream. Checked before anything is decoded.
+const CACHE_MAGIC: [u8; 8] = *b"CRDLCACH";
+const HEADER_LEN: usize = CACHE_MAGIC.len() + size_of::<i64>();
+
+/// Why a cache file was rejected before decoding. Either way the file is
+/// unusable and the caller should rewalk instead of reporting a failure.
+///
+/// Returned inside [`anyhow::Error`]; use `err.downcast_ref::<CacheError>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheError {
+    /// The file doesn't start with the cache header, e.g. it was written
+    /// before the header existed or isn't a cache at all.
+    MissingHeader,
+    /// Written by a different version of the format.
+    VersionMismatch { expected: i64, found: i64 },
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::MissingHeader => write!(f, "cache file has no format header"),
+            CacheError::VersionMismatch { expected, found } => write!(
+                f,
+                "cache format version {found} doesn't match expected version {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+/// How a cache file is zstd-compressed. The default is level 6 on every
+/// available core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionOptions {
+    /// zstd level, 1 (fastest) to 22 (smallest).
+    pub level: i32,
+    /// Compression worker threads; 0 compresses on the calling thread.
+    pub threads: u32,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        Self {
+            level: 6,
+            threads: available_parallelism().map(|x| x.get() as u32).unwrap_or(4),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct PersistentStorage {
     pub version: Num<LSF_VERSION>,
     /// The last event id of the cache.
     pub last_event_id: u64,
-    /// Root file path of the cache
-    pub path: PathBuf,
+    /// Root file paths of the cache, primary first
+    pub roots: Vec<PathBuf>,
     /// Ignore paths
     pub ignore_paths: Vec<PathBuf>,
     /// Root index of the slab
@@ -29,12 +81,22 @@ pub struct PersistentStorage {
     pub name_index: BTreeMap<Box<str>, SortedSlabIndices>,
     /// The number of rescans emitted before this snapshot.
     pub rescan_count: u64,
+    /// Directories a cancelled walk didn't finish listing; empty once the
+    /// cache is complete.
+    pub unfinished_dirs: Vec<PathBuf>,
+    /// The extension filter of the walk the cache was built by, so a reloaded
+    /// cache walks changed paths the same way. See `fswalk::WalkOptions`; the
+    /// thread budget is left to whoever loads it.
+    pub only_extensions: Option<Box<[Box<str>]>>,
+    /// Whether that walk left out hidden entries.
+    pub skip_hidden: bool,
 }
 
 pub fn read_cache_from_file(path: &Path) -> Result<PersistentStorage> {
     let cache_decode_time = Instant::now();
     let mut bytes = vec![0u8; 4 * 1024];
-    let input = File::open(path).context("Failed to open cache file")?;
+    let mut input = File::open(path).context("Failed to open cache file")?;
+    read_cache_header(&mut input)?;
     let input = zstd::Decoder::new(input).context("Failed to create zstd decoder")?;
     let mut input = BufReader::new(input);
     let storage: PersistentStorage = postcard::from_io((&mut input, &mut bytes))
@@ -44,21 +106,39 @@ pub fn read_cache_from_file(path: &Path) -> Result<PersistentStorage> {
     Ok(storage)
 }
 
+/// Checks the magic and format version that precede the compressed payload.
+fn read_cache_header(input: &mut impl Read) -> Result<()> {
+    let mut header = [0u8; HEADER_LEN];
+    if input.read_exact(&mut header).is_err() || header[..CACHE_MAGIC.len()] != CACHE_MAGIC {
+        return Err(CacheError::MissingHeader.into());
+    }
+    let found = i64::from_le_bytes(header[CACHE_MAGIC.len()..].try_into().unwrap());
+    if found != LSF_VERSION {
+        return Err(CacheError::VersionMismatch {
+            expected: LSF_VERSION,
+            found,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Writes the cache next to `path` first and renames it into place, so a
+/// reader (or the next launch after a crash) sees either the previous file or
+/// the complete new one.
 pub fn write_cache_to_file(path: &Path, storage: &PersistentStorage) -> Result<()> {
+    write_cache_to_file_with(path, storage, CompressionOptions::default())
+}
+
+/// [`write_cache_to_file`] with explicit compression settings.
+pub fn write_cache_to_file_with(
+    path: &Path,
+    storage: &PersistentStorage,
+    compression: CompressionOptions,
+) -> Result<()> {
     let cache_encode_time = Instant::now();
-    let _ = fs::create_dir_all(path.parent().unwrap());
-    let tmp_path = &path.with_extension(".sctmp");
-    {
-        let output = File::create(tmp_path).context("Failed to create cache file")?;
-        let mut output = zstd::Encoder::new(output, 6).context("Failed to create zstd encoder")?;
-        output
-            .multithread(available_parallelism().map(|x| x.get() as u32).unwrap_or(4))
-            .context("Failed to create parallel zstd encoder")?;
-        let output = output.auto_finish();
-        let mut output = BufWriter::new(output);
-        postcard::to_io(storage, &mut output).context("Failed to encode cache")?;
-    }
-    fs::rename(tmp_path, path).context("Failed to rename cache file")?;
+    let tmp_path = write_cache_to_tmp(path, storage, compression)?;
+    fs::rename(&tmp_path, path).context("Failed to rename cache file")?;
     info!("Cache encode time: {:?}", cache_encode_time.elapsed());
     info!(
         "Cache size: {} MB",
@@ -70,3 +150,53 @@ pub fn write_cache_to_file(path: &Path, storage: &PersistentStorage) -> Result<(
     );
     Ok(())
 }
+
+/// `<path>.tmp`, where a cache is staged before the rename.
+pub(crate) fn tmp_cache_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    PathBuf::from(tmp_path)
+}
+
+/// Encodes `storage` into [`tmp_cache_path`] and returns that path. On error
+/// the partial file is removed.
+pub(crate) fn write_cache_to_tmp(
+    path: &Path,
+    storage: &PersistentStorage,
+    compression: CompressionOptions,
+) -> Result<PathBuf> {
+    let _ = fs::create_dir_all(path.parent().unwrap());
+    let tmp_path = tmp_cache_path(path);
+    if let Err(err) = encode_cache(&tmp_path, storage, compression) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+    Ok(tmp_path)
+}
+
+/// The buffer, zstd stream and file are finished explicitly: dropping them
+/// would swallow a failed write and leave a truncated file to be renamed in.
+fn encode_cache(
+    tmp_path: &Path,
+    storage: &PersistentStorage,
+    compression: CompressionOptions,
+) -> Result<()> {
+    let mut output = File::create(tmp_path).context("Failed to create cache file")?;
+    output
+        .write_all(&CACHE_MAGIC)
+        .and_then(|()| output.write_all(&LSF_VERSION.to_le_bytes()))
+        .context("Failed to write cache header")?;
+    let mut output =
+        zstd::Encoder::new(output, compression.level).context("Failed to create zstd encoder")?;
+    output
+        .multithread(compression.threads)
+        .context("Failed to create parallel zstd encoder")?;
+    let mut output = BufWriter::new(output);
+    postcard::to_io(storage, &mut output).context("Failed to encode cache")?;
+    let output = output
+        .into_inner()
+        .map_err(|err| err.into_error())
+        .context("Failed to flush cache")?;
+    let file = output.finish().context("Failed to finish zstd stream")?;
+    file.sync_all().context("Failed to sync cache file")
+}
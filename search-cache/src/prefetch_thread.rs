@@ -9,17 +9,22 @@
 //! - 无惰性遍历，只有一个遍历源
 
 use crate::{
-    SearchCache, SearchOptions, SlabIndex,
-    build_segment_matchers, segment::SegmentMatcher,
+    SearchCache, SearchOptions, SlabIndex, build_segment_matchers,
     query_preprocessor::{expand_query_home_dirs, strip_query_quotes},
+    segment::SegmentMatcher,
 };
-use cardinal_syntax::{Expr, parse_query, optimize_query};
+use cardinal_syntax::{Expr, optimize_query, parse_query};
 use query_segmentation::query_segmentation;
 use search_cancel::CancellationToken;
-use std::sync::{Arc, RwLock, atomic::{AtomicBool, Ordering}};
-use std::sync::mpsc::{self, Receiver, Sender};
-use std::time::Instant;
-use tracing::{debug};
+use std::{
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    time::Instant,
+};
+use tracing::debug;
 
 /// 搜索结果数量回调函数类型（私有，仅在模块内部使用）
 type SearchResultNumCallback = Arc<dyn Fn(i64) + Send + Sync>;
@@ -77,10 +82,7 @@ impl PrefetchState {
     }
 
     /// 阻塞接收一批数据（带超时）
-    pub fn recv_timeout(
-        &mut self,
-        timeout: std::time::Duration,
-    ) -> Option<PrefetchMessage> {
+    pub fn recv_timeout(&mut self, timeout: std::time::Duration) -> Option<PrefetchMessage> {
         match self.receiver.recv_timeout(timeout) {
             Ok(msg) => Some(msg),
             Err(mpsc::RecvTimeoutError::Timeout) => None,
@@ -105,8 +107,7 @@ pub fn start_prefetch_thread_rwlock(
     let cache_guard = shared_cache.read().unwrap();
 
     // 解析查询
-    let parsed = parse_query(query)
-        .expect("Failed to parse query");
+    let parsed = parse_query(query).expect("Failed to parse query");
     let expanded = expand_query_home_dirs(parsed);
     let unquoted = strip_query_quotes(expanded);
     let optimized = optimize_query(unquoted);
@@ -145,7 +146,7 @@ pub fn start_prefetch_thread_rwlock(
         debug!("Prefetch thread started for iterator");
         let visit_time = Instant::now();
         // 后台线程执行完整的搜索遍历
-        let mut traversal_stack = vec![(root_index, Vec::<String>::new())];  // (节点索引，路径段)
+        let mut traversal_stack = vec![(root_index, Vec::<String>::new())]; // (节点索引，路径段)
         let mut current_pos = 0;
         let mut batch_buffer = Vec::with_capacity(batch_size);
         let mut last_log_pos = 0;
@@ -173,7 +174,10 @@ pub fn start_prefetch_thread_rwlock(
                     let _ = tx_for_thread.send(PrefetchMessage::Batch(batch_buffer));
                 }
                 let _ = tx_for_thread.send(PrefetchMessage::Done);
-                debug!("Prefetch thread completed, total_pos={}, matched={}", current_pos, matched_count);
+                debug!(
+                    "Prefetch thread completed, total_pos={}, matched={}",
+                    current_pos, matched_count
+                );
                 // 调用回调通知搜索结果数量
                 let callback_guard = on_search_complete_clone.read().unwrap();
                 if let Some(ref callback) = *callback_guard {
@@ -195,7 +199,13 @@ pub fn start_prefetch_thread_rwlock(
             if current_index != root_index {
                 // 匹配检查
                 let node_name: &str = node.name();
-                let matches = match_node_with_path(&optimized.expr, node_name, &current_path, path_matchers.as_deref(), options.case_insensitive);
+                let matches = match_node_with_path(
+                    &optimized.expr,
+                    node_name,
+                    &current_path,
+                    path_matchers.as_deref(),
+                    options.case_insensitive,
+                );
                 if matches {
                     batch_buffer.push(current_index);
                     matched_count += 1;
@@ -205,7 +215,10 @@ pub fn start_prefetch_thread_rwlock(
 
                 // 每遍历 10 万个节点打印一次进度
                 if current_pos - last_log_pos >= 100000 {
-                    debug!("Prefetch thread progress: pos={}/{}, matched: {}", current_pos, total_nodes, matched_count);
+                    debug!(
+                        "Prefetch thread progress: pos={}/{}, matched: {}",
+                        current_pos, total_nodes, matched_count
+                    );
                     last_log_pos = current_pos;
                 }
             }
@@ -224,8 +237,12 @@ pub fn start_prefetch_thread_rwlock(
 
             // 达到批处理大小时发送
             if batch_buffer.len() >= batch_size {
-                let buffer_to_send = std::mem::replace(&mut batch_buffer, Vec::with_capacity(batch_size));
-                if tx_for_thread.send(PrefetchMessage::Batch(buffer_to_send)).is_err() {
+                let buffer_to_send =
+                    std::mem::replace(&mut batch_buffer, Vec::with_capacity(batch_size));
+                if tx_for_thread
+                    .send(PrefetchMessage::Batch(buffer_to_send))
+                    .is_err()
+                {
                     // 接收端已断开，停止
                     debug!("Prefetch thread: channel disconnected, stopping");
                     break;
@@ -238,7 +255,10 @@ pub fn start_prefetch_thread_rwlock(
                     let _ = tx_for_thread.send(PrefetchMessage::Batch(batch_buffer));
                 }
                 let _ = tx_for_thread.send(PrefetchMessage::Done);
-                debug!("Prefetch thread completed, total_pos={}, matched={}", current_pos, matched_count);
+                debug!(
+                    "Prefetch thread completed, total_pos={}, matched={}",
+                    current_pos, matched_count
+                );
                 // 调用回调通知搜索结果数量
                 let callback_guard = on_search_complete_clone.read().unwrap();
                 if let Some(ref callback) = *callback_guard {
@@ -252,7 +272,14 @@ pub fn start_prefetch_thread_rwlock(
         debug!("Prefetch thread search time: {:?}", visit_time.elapsed());
     });
 
-    PrefetchState::new(rx, tx, handle, cancelled_clone, background_thread_done, on_search_complete)
+    PrefetchState::new(
+        rx,
+        tx,
+        handle,
+        cancelled_clone,
+        background_thread_done,
+        on_search_complete,
+    )
 }
 
 /// 辅助函数：匹配节点（支持路径段匹配）
@@ -291,7 +318,7 @@ fn match_path_segments(
     // 构建完整的路径段列表（包含文件名）
     let mut full_path: Vec<&str> = path_segments.iter().map(|s| s.as_str()).collect();
     full_path.push(node_name);
-    
+
     // 相对路径匹配：查找是否有连续的段匹配所有匹配器
     // 使用滑动窗口方式检查所有可能的起始位置
     for start_idx in 0..full_path.len() {
@@ -299,26 +326,22 @@ fn match_path_segments(
             return true;
         }
     }
-    
+
     false
 }
 
 /// 从指定位置开始匹配路径段
-fn match_from_position(
-    matchers: &[SegmentMatcher],
-    full_path: &[&str],
-    start_idx: usize,
-) -> bool {
+fn match_from_position(matchers: &[SegmentMatcher], full_path: &[&str], start_idx: usize) -> bool {
     let mut path_idx = start_idx;
     let mut matcher_idx = 0;
     let mut pending_globstar = false;
-    
+
     while matcher_idx < matchers.len() {
         if path_idx >= full_path.len() {
             // 路径已用完，检查是否还有 pending_globstar
             return pending_globstar;
         }
-        
+
         match &matchers[matcher_idx] {
             SegmentMatcher::GlobStar => {
                 pending_globstar = true;
@@ -357,7 +380,7 @@ fn match_from_position(
             }
         }
     }
-    
+
     // 所有匹配器都已匹配
     // 如果还有 pending_globstar，匹配剩余所有路径段（总是成功）
     // 否则，必须正好匹配到路径末尾
@@ -388,29 +411,31 @@ fn match_node_basic(expr: &Expr, node_name: &str, case_insensitive: bool) -> boo
                         search_text.contains(&pattern)
                     }
                 }
-                cardinal_syntax::Term::Filter(filter) => {
-                    match &filter.kind {
-                        cardinal_syntax::FilterKind::Ext => {
-                            if let Some(arg) = &filter.argument {
-                                let ext = if case_insensitive {
-                                    arg.raw.to_lowercase()
-                                } else {
-                                    arg.raw.clone()
-                                };
-                                node_name.ends_with(&format!(".{}", ext))
+                cardinal_syntax::Term::Filter(filter) => match &filter.kind {
+                    cardinal_syntax::FilterKind::Ext => {
+                        if let Some(arg) = &filter.argument {
+                            let ext = if case_insensitive {
+                                arg.raw.to_lowercase()
                             } else {
-                                false
-                            }
+                                arg.raw.clone()
+                            };
+                            node_name.ends_with(&format!(".{}", ext))
+                        } else {
+                            false
                         }
-                        _ => false,
                     }
-                }
+                    _ => false,
+                },
                 cardinal_syntax::Term::Regex(_) => false,
             }
         }
         Expr::Not(inner) => !match_node_basic(inner, node_name, case_insensitive),
-        Expr::And(parts) => parts.iter().all(|p| match_node_basic(p, node_name, case_insensitive)),
-        Expr::Or(parts) => parts.iter().any(|p| match_node_basic(p, node_name, case_insensitive)),
+        Expr::And(parts) => parts
+            .iter()
+            .all(|p| match_node_basic(p, node_name, case_insensitive)),
+        Expr::Or(parts) => parts
+            .iter()
+            .any(|p| match_node_basic(p, node_name, case_insensitive)),
     }
 }
 
@@ -435,7 +460,7 @@ mod tests {
 
     #[test]
     fn test_match_node_basic_simple() {
-        use cardinal_syntax::{parse_query, optimize_query};
+        use cardinal_syntax::{optimize_query, parse_query};
 
         let parsed = parse_query("test").unwrap();
         let optimized = optimize_query(parsed);
@@ -447,7 +472,7 @@ mod tests {
 
     #[test]
     fn test_match_node_basic_case_insensitive() {
-        use cardinal_syntax::{parse_query, optimize_query};
+        use cardinal_syntax::{optimize_query, parse_query};
 
         let parsed = parse_query("TEST").unwrap();
         let optimized = optimize_query(parsed);
@@ -459,17 +484,16 @@ mod tests {
     #[test]
     fn test_match_path_segments_simple() {
         // 测试简单路径匹配：foo/bar
-        let matchers = build_segment_matchers(
-            &query_segmentation("foo/bar"),
-            SearchOptions::default(),
-        ).unwrap();
-        
+        let matchers =
+            build_segment_matchers(&query_segmentation("foo/bar"), SearchOptions::default())
+                .unwrap();
+
         // 匹配：路径 ["foo"] + 文件名 "bar"
         assert!(match_path_segments(&matchers, &["foo".to_string()], "bar"));
-        
+
         // 不匹配：路径 ["baz"] + 文件名 "bar"
         assert!(!match_path_segments(&matchers, &["baz".to_string()], "bar"));
-        
+
         // 不匹配：路径 ["foo"] + 文件名 "baz"
         assert!(!match_path_segments(&matchers, &["foo".to_string()], "baz"));
     }
@@ -477,65 +501,92 @@ mod tests {
     #[test]
     fn test_match_path_segments_globstar() {
         // 测试 globstar: foo/**/bar
-        let matchers = build_segment_matchers(
-            &query_segmentation("foo/**/bar"),
-            SearchOptions::default(),
-        ).unwrap();
-        
+        let matchers =
+            build_segment_matchers(&query_segmentation("foo/**/bar"), SearchOptions::default())
+                .unwrap();
+
         // 匹配：foo/bar (globstar 匹配 0 个段)
         assert!(match_path_segments(&matchers, &["foo".to_string()], "bar"));
-        
+
         // 匹配：foo/x/bar (globstar 匹配 1 个段)
-        assert!(match_path_segments(&matchers, &["foo".to_string(), "x".to_string()], "bar"));
-        
+        assert!(match_path_segments(
+            &matchers,
+            &["foo".to_string(), "x".to_string()],
+            "bar"
+        ));
+
         // 匹配：foo/x/y/bar (globstar 匹配 2 个段)
-        assert!(match_path_segments(&matchers, &["foo".to_string(), "x".to_string(), "y".to_string()], "bar"));
-        
+        assert!(match_path_segments(
+            &matchers,
+            &["foo".to_string(), "x".to_string(), "y".to_string()],
+            "bar"
+        ));
+
         // 不匹配：baz/x/bar (第一段不匹配)
-        assert!(!match_path_segments(&matchers, &["baz".to_string(), "x".to_string()], "bar"));
+        assert!(!match_path_segments(
+            &matchers,
+            &["baz".to_string(), "x".to_string()],
+            "bar"
+        ));
     }
 
     #[test]
     fn test_match_path_segments_wildcard() {
         // 测试通配符：*.rs
-        let matchers = build_segment_matchers(
-            &query_segmentation("*.rs"),
-            SearchOptions::default(),
-        ).unwrap();
-        
+        let matchers =
+            build_segment_matchers(&query_segmentation("*.rs"), SearchOptions::default()).unwrap();
+
         // 匹配：任何 .rs 文件
         assert!(match_path_segments(&matchers, &[], "test.rs"));
         assert!(match_path_segments(&matchers, &[], "lib.rs"));
-        
+
         // 不匹配：非 .rs 文件
         assert!(!match_path_segments(&matchers, &[], "test.txt"));
     }
 
     #[test]
     fn test_match_node_with_path() {
-        use cardinal_syntax::{parse_query, optimize_query};
+        use cardinal_syntax::{optimize_query, parse_query};
 
         let parsed = parse_query("test").unwrap();
         let optimized = optimize_query(parsed);
 
         // 无路径段匹配器，仅文件名匹配
-        assert!(match_node_with_path(&optimized.expr, "test_file", &[], None, false));
-        assert!(match_node_with_path(&optimized.expr, "my_test", &[], None, false));
-        assert!(!match_node_with_path(&optimized.expr, "other", &[], None, false));
+        assert!(match_node_with_path(
+            &optimized.expr,
+            "test_file",
+            &[],
+            None,
+            false
+        ));
+        assert!(match_node_with_path(
+            &optimized.expr,
+            "my_test",
+            &[],
+            None,
+            false
+        ));
+        assert!(!match_node_with_path(
+            &optimized.expr,
+            "other",
+            &[],
+            None,
+            false
+        ));
     }
 
     #[test]
     fn test_match_path_with_directory() {
         use crate::SearchCache;
-        use tempdir::TempDir;
         use std::fs;
-        
+        use tempdir::TempDir;
+
         let temp_dir = TempDir::new("test_match_path_with_directory").unwrap();
         let dir = temp_dir.path();
         fs::create_dir_all(dir.join("foo/bar")).unwrap();
-        
+
         let cache = SearchCache::walk_fs(dir);
-        
+
         // 验证缓存包含预期的节点
         assert!(cache.get_total_files() >= 2);
     }
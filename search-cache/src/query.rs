@@ -1,30 +1,88 @@
 use crate::{
-    SearchCache, SearchOptions, SegmentKind, SegmentMatcher, SegmentMatcherConcrete, SlabIndex,
-    SlabNodeMetadataCompact, build_segment_matchers, cache::NAME_POOL,
+    EmptyBehavior, Fold, SearchCache, SearchOptions, SegmentKind, SegmentMatcher,
+    SegmentMatcherConcrete, SlabIndex, SlabNodeMetadataCompact, build_name_matcher,
+    build_path_matcher, build_segment_matchers,
+    cache::NAME_POOL,
+    content_sniff::{ContentKind, sniff_file},
+    file_flags::FileFlag,
 };
 use anyhow::{Result, anyhow, bail};
 use cardinal_syntax::{
     ArgumentKind, ComparisonOp, Expr, Filter, FilterArgument, FilterKind, RangeSeparator, Term,
 };
-use file_tags::{read_tags_from_path, search_tags_using_mdfind};
-use fswalk::NodeFileType;
-use hashbrown::HashSet;
-use jiff::{Timestamp, civil::Date, tz::TimeZone};
+use file_tags::{
+    check_uti, read_content_types, read_tags_from_path, search_content_types_using_mdfind,
+    search_tags_using_mdfind,
+};
+use fswalk::{NodeFileType, NodeMetadata};
+use hashbrown::{HashMap, HashSet};
+use jiff::{
+    Span, Timestamp,
+    civil::{Date, Weekday},
+    tz::TimeZone,
+};
 use memchr::arch::all::rabinkarp;
 use query_segmentation::query_segmentation;
-use rayon::iter::{ParallelBridge, ParallelIterator};
+use rayon::{
+    iter::{IntoParallelRefIterator, ParallelBridge, ParallelIterator},
+    slice::ParallelSliceMut,
+};
 use regex::RegexBuilder;
 use search_cancel::CancellationToken;
-use std::{collections::BTreeSet, fs::File, io::Read, path::Path};
+use std::{
+    cmp::Ordering,
+    collections::BTreeSet,
+    fs::File,
+    io::{ErrorKind, Read},
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 pub(crate) const CONTENT_BUFFER_BYTES: usize = 64 * 1024;
 
+/// First day of the week for the `thisweek` / `lastweek` date keywords (ISO 8601).
+/// Weeks run from this day's local midnight through the end of the sixth day after it.
+pub(crate) const WEEK_START: Weekday = Weekday::Monday;
+
 /// Threshold for switching from iterating file metadata to using Spotlight (mdfind).
 /// When the base set exceeds this size, Spotlight's indexed search is faster than
 /// reading xattr metadata for each file individually.
 const TAG_FILTER_MDFIND_THRESHOLD: usize = 10000;
+/// How long `mdfind` may run before the tag filter gives up on it.
+const TAG_FILTER_MDFIND_TIMEOUT: Duration = Duration::from_secs(30);
+/// Paths `uti:` hands to one `mdls` run; cancellation is checked between runs.
+const UTI_FILTER_MDLS_BATCH: usize = 256;
+/// Nodes whose paths `path:` and full-path words rebuild at once, bounding the
+/// paths held alive.
+const PATH_FILTER_BATCH: usize = 4096;
 
 impl SearchCache {
+    /// [`Self::evaluate_expr`] for a whole query, run within
+    /// [`SearchOptions::thread_budget`]. An empty query follows
+    /// [`SearchOptions::empty_query`].
+    ///
+    /// The hits come back sorted by [`SlabIndex`] without duplicates, whichever
+    /// indexes and scans produced them, so equal queries give equal results.
+    pub(crate) fn evaluate_query(
+        &mut self,
+        expr: &Expr,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        if matches!(expr, Expr::Empty) && options.empty_query == EmptyBehavior::MatchNone {
+            return Ok(Some(Vec::new()));
+        }
+        options.thread_budget.install(|| {
+            let nodes = self.evaluate_expr(expr, options, token)?;
+            Ok(nodes.map(|mut nodes| {
+                nodes.par_sort_unstable();
+                nodes.dedup();
+                nodes
+            }))
+        })
+    }
+
     pub(crate) fn evaluate_expr(
         &mut self,
         expr: &Expr,
@@ -68,7 +126,7 @@ impl SearchCache {
                     };
                     current = Some(match current {
                         Some(mut existing) => {
-                            if intersect_in_place(&mut existing, &nodes, token).is_none() {
+                            if intersect_in_place(&mut existing, nodes, token).is_none() {
                                 return Ok(None);
                             }
                             existing
@@ -116,7 +174,7 @@ impl SearchCache {
             }
         };
         if let Some(negated) = self.evaluate_expr(inner, options, token)? {
-            if difference_in_place(&mut universe, &negated, token).is_none() {
+            if difference_in_place(&mut universe, negated, token).is_none() {
                 return Ok(None);
             }
         } else {
@@ -132,6 +190,9 @@ impl SearchCache {
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         match term {
+            Term::Word(text) if options.match_full_path => {
+                self.evaluate_full_path_word(text, options, token)
+            }
             Term::Word(text) => self.evaluate_phrase(text, options, token),
             Term::Regex(pattern) => self.evaluate_regex(pattern, options, token),
             Term::Filter(filter) => self.evaluate_filter(filter, None, options, token),
@@ -153,6 +214,22 @@ impl SearchCache {
         Ok(self.execute_matchers(&matchers, token))
     }
 
+    /// A plain word under [`SearchOptions::match_full_path`]: a substring of
+    /// the whole path instead of path segments matched name by name.
+    fn evaluate_full_path_word(
+        &self,
+        text: &str,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let matcher = build_path_matcher(text, options)
+            .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+        let Some(nodes) = self.search_empty(token) else {
+            return Ok(None);
+        };
+        Ok(self.filter_by_path(nodes, &matcher, token))
+    }
+
     fn execute_matchers(
         &self,
         matchers: &[SegmentMatcher],
@@ -229,6 +306,7 @@ impl SearchCache {
         matcher: &SegmentMatcherConcrete,
         token: CancellationToken,
     ) -> Option<Vec<SlabIndex>> {
+        self.diagnostics.scanned(NAME_POOL.len());
         let names: BTreeSet<_> = match matcher {
             SegmentMatcherConcrete::Plain { kind, needle } => match kind {
                 SegmentKind::Substr => NAME_POOL.search_substr(needle, token),
@@ -237,6 +315,9 @@ impl SearchCache {
                 SegmentKind::Exact => NAME_POOL.search_exact(needle, token),
             },
             SegmentMatcherConcrete::Regex { regex } => NAME_POOL.search_regex(regex, token),
+            SegmentMatcherConcrete::Folded { .. } => {
+                NAME_POOL.search_by(|name| matcher.matches(name), token)
+            }
         }?;
         let mut nodes = Vec::with_capacity(names.len());
         for (i, name) in names.iter().enumerate() {
@@ -257,6 +338,8 @@ impl SearchCache {
         let mut new_node_set = Vec::new();
         for (i, &node) in parents.iter().enumerate() {
             token.is_cancelled_sparse(i)?;
+            self.diagnostics
+                .scanned(self.file_nodes[node].children.len());
             let mut child_matches = self.file_nodes[node]
                 .children
                 .iter()
@@ -308,6 +391,7 @@ impl SearchCache {
         for &node in parents {
             token.is_cancelled_sparse(visited)?;
             let descendants = self.all_subnodes(node, token)?;
+            self.diagnostics.scanned(descendants.len());
             for descendant in descendants {
                 token.is_cancelled_sparse(visited)?;
                 visited += 1;
@@ -379,39 +463,58 @@ impl SearchCache {
                 options,
                 token,
             ),
+            FilterKind::Broken => {
+                self.evaluate_broken_filter(base, filter.argument.as_ref(), options, token)
+            }
+            FilterKind::Flag => {
+                let argument = required_argument(filter)?;
+                self.evaluate_flag_filter(argument, base, token)
+            }
+            FilterKind::NoExtension => {
+                self.evaluate_no_extension_filter(base, filter.argument.as_ref(), options, token)
+            }
+            FilterKind::Name => {
+                let argument = required_argument(filter)?;
+                self.evaluate_name_filter(argument, base, options, token)
+            }
+            FilterKind::StartsWith => {
+                let argument = required_argument(filter)?;
+                self.evaluate_starts_with_filter(argument, base, token)
+            }
             FilterKind::Ext => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("ext: requires at least one extension"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_extension_filter(argument, base, token)
             }
+            FilterKind::SameExtension => {
+                let argument = required_argument(filter)?;
+                self.evaluate_same_extension_filter(argument, base, token)
+            }
             FilterKind::Parent => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("parent: requires a folder path"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_parent_filter(argument, base, token)
             }
+            FilterKind::Siblings => {
+                let argument = required_argument(filter)?;
+                self.evaluate_siblings_filter(argument, base, token)
+            }
             FilterKind::InFolder => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("infolder: requires a folder path"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_infolder_filter(argument, base, token)
             }
             FilterKind::NoSubfolders => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("nosubfolders: requires a folder path"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_nosubfolders_filter(argument, base, token)
             }
+            FilterKind::PathPrefix => {
+                let argument = required_argument(filter)?;
+                self.evaluate_path_prefix_filter(argument, base, token)
+            }
+            FilterKind::Path => {
+                let argument = required_argument(filter)?;
+                self.evaluate_path_glob_filter(argument, base, options, token)
+            }
             FilterKind::Type => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("type: requires a category"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_named_type_filter(&argument.raw, base, options, token)
             }
             FilterKind::Audio => {
@@ -427,46 +530,62 @@ impl SearchCache {
                 self.evaluate_type_macro("exe", base, filter.argument.as_ref(), options, token)
             }
             FilterKind::Size => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("size: requires a value"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_size_filter(argument, base, token)
             }
+            FilterKind::NameLength => {
+                let argument = required_argument(filter)?;
+                self.evaluate_name_length_filter(argument, base, token)
+            }
+            FilterKind::RecursiveCount => {
+                let argument = required_argument(filter)?;
+                self.evaluate_recursive_count_filter(argument, base, token)
+            }
+            FilterKind::DirSize => {
+                let argument = required_argument(filter)?;
+                self.evaluate_dir_size_filter(argument, base, token)
+            }
             FilterKind::DateModified => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("dm: requires a date or range"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_date_filter(DateField::Modified, argument, base, token)
             }
             FilterKind::DateCreated => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("dc: requires a date or range"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_date_filter(DateField::Created, argument, base, token)
             }
+            FilterKind::NewerThan => {
+                let argument = required_argument(filter)?;
+                self.evaluate_reference_mtime_filter(Ordering::Greater, argument, base, token)
+            }
+            FilterKind::OlderThan => {
+                let argument = required_argument(filter)?;
+                self.evaluate_reference_mtime_filter(Ordering::Less, argument, base, token)
+            }
+            FilterKind::Stale => {
+                let argument = required_argument(filter)?;
+                self.evaluate_stale_filter(argument, base, token)
+            }
             FilterKind::Content => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("content: requires a value"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_content_filter(argument, base, options, token)
             }
             FilterKind::Tag => {
-                let argument = filter
-                    .argument
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("tag: requires a value"))?;
+                let argument = required_argument(filter)?;
                 self.evaluate_tag_filter(argument, base, options, token)
             }
+            FilterKind::Uti => {
+                let argument = required_argument(filter)?;
+                self.evaluate_uti_filter(argument, base, token)
+            }
+            FilterKind::Duplicate => {
+                self.evaluate_duplicate_filter(base, filter.argument.as_ref(), options, token)
+            }
             _ => bail!("Filter {:?} is not supported yet", filter.kind),
         }
     }
 
     fn evaluate_type_filter(
-        &self,
+        &mut self,
         file_type: NodeFileType,
         base: Option<Vec<SlabIndex>>,
         argument: Option<&FilterArgument>,
@@ -489,16 +608,106 @@ impl SearchCache {
             let Some(matches) = self.evaluate_phrase(&arg.raw, options, token)? else {
                 return Ok(None);
             };
-            if intersect_in_place(&mut nodes, &matches, token).is_none() {
+            if intersect_in_place(&mut nodes, matches, token).is_none() {
                 return Ok(None);
             }
         }
 
+        // Nodes walked without metadata report `File`, which hides symlinks.
+        // Only a symlink lookup pays for stat'ing those, all in one pass.
+        if file_type == NodeFileType::Symlink
+            && self.fill_missing_metadata(&nodes, true, token).is_none()
+        {
+            return Ok(None);
+        }
+        self.diagnostics.scanned(nodes.len());
         Ok(filter_nodes(nodes, token, |index| {
             self.file_nodes[index].file_type_hint() == file_type
         }))
     }
 
+    /// `broken:` keeps symlinks whose target is missing. Targets are only
+    /// followed for nodes that already passed the symlink check.
+    fn evaluate_broken_filter(
+        &mut self,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(links) =
+            self.evaluate_type_filter(NodeFileType::Symlink, base, argument, options, token)?
+        else {
+            return Ok(None);
+        };
+        Ok(filter_nodes(links, token, |index| {
+            let path = self
+                .node_path(index)
+                .expect("node index is not present in slab");
+            self.diagnostics.metadata_read();
+            matches!(std::fs::metadata(path), Err(err) if err.kind() == ErrorKind::NotFound)
+        }))
+    }
+
+    fn evaluate_flag_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let flag = FileFlag::parse(&argument.raw)?;
+        // BSD flags only exist on macOS; elsewhere no node carries one.
+        if !cfg!(target_os = "macos") {
+            return Ok(token.is_cancelled().map(|()| Vec::new()));
+        }
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        self.diagnostics.scanned(nodes.len());
+        Ok(filter_nodes(nodes, token, |index| {
+            self.node_path(index).is_some_and(|path| flag.is_set(&path))
+        }))
+    }
+
+    /// `noext:` keeps files without an extension, as [`extension_of`] sees it.
+    fn evaluate_no_extension_filter(
+        &mut self,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(files) =
+            self.evaluate_type_filter(NodeFileType::File, base, argument, options, token)?
+        else {
+            return Ok(None);
+        };
+        Ok(filter_nodes(files, token, |index| {
+            extension_of(self.file_nodes[index].name()).is_none()
+        }))
+    }
+
+    /// `name:` compares the whole file name. `name:"report*.txt"` only matches
+    /// a file literally called `report*.txt`; unquoted, `*` and `?` are wildcards.
+    fn evaluate_name_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let literal = matches!(argument.kind, ArgumentKind::Phrase);
+        let matcher = build_name_matcher(&argument.raw, literal, options)
+            .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+        let Some(base) = base else {
+            return Ok(self.match_initial_segment(&matcher, token));
+        };
+        self.diagnostics.scanned(base.len());
+        Ok(filter_nodes(base, token, |index| {
+            matcher.matches(self.file_nodes[index].name())
+        }))
+    }
+
     fn evaluate_extension_filter(
         &self,
         argument: &FilterArgument,
@@ -509,10 +718,11 @@ impl SearchCache {
         if extensions.is_empty() {
             bail!("ext: requires non-empty extensions");
         }
-        let Some(nodes) = self.nodes_from_base(base, token) else {
-            return Ok(None);
+        let Some(base) = base else {
+            return Ok(self.files_with_extensions(extensions.iter().map(String::as_str), token));
         };
-        Ok(filter_nodes(nodes, token, |index| {
+        self.diagnostics.scanned(base.len());
+        Ok(filter_nodes(base, token, |index| {
             let node = &self.file_nodes[index];
             if node.file_type_hint() != NodeFileType::File {
                 return false;
@@ -523,26 +733,86 @@ impl SearchCache {
         }))
     }
 
+    /// `sameext:<path>`: files with the same extension as the referenced file,
+    /// which must be in the index.
+    fn evaluate_same_extension_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(reference) = self.index_of_path(Path::new(&argument.raw)) else {
+            bail!("sameext: {:?} is not found in file system", argument.raw);
+        };
+        let Some(extension) = extension_of(self.file_nodes[reference].name()) else {
+            bail!("sameext: {:?} has no extension", argument.raw);
+        };
+        let extension = FilterArgument {
+            raw: extension,
+            kind: ArgumentKind::Bare,
+        };
+        self.evaluate_extension_filter(&extension, base, token)
+    }
+
     fn evaluate_parent_filter(
         &self,
         argument: &FilterArgument,
         base: Option<Vec<SlabIndex>>,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let Some(target) = self.node_index_for_path(Path::new(&argument.raw)) else {
+        let Some(target) = self.index_of_path(Path::new(&argument.raw)) else {
             bail!(
                 "Parent filter {:?} is not found in file system",
                 argument.raw
             );
         };
-        let children = self.file_nodes[target].children.to_vec();
-        if let Some(mut nodes) = base {
-            if intersect_in_place(&mut nodes, &children, token).is_none() {
-                return Ok(None);
+        match base {
+            // Direct children only: compare each candidate's parent with the target
+            // instead of walking ancestors or hashing the whole child list.
+            Some(nodes) => {
+                self.diagnostics.scanned(nodes.len());
+                Ok(filter_nodes(nodes, token, |index| {
+                    self.file_nodes[index].parent() == Some(target)
+                }))
             }
-            Ok(Some(nodes))
-        } else {
-            Ok(Some(children))
+            None => Ok(Some(self.file_nodes[target].children.to_vec())),
+        }
+    }
+
+    /// The reference's parent's children, minus the reference; `parent:` on
+    /// the folder keeps it.
+    fn evaluate_siblings_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(reference) = self.index_of_path(Path::new(&argument.raw)) else {
+            bail!(
+                "Siblings filter {:?} is not found in file system",
+                argument.raw
+            );
+        };
+        // The root has no parent, and so no siblings.
+        let Some(parent) = self.file_nodes[reference].parent() else {
+            return Ok(Some(Vec::new()));
+        };
+        let is_sibling = |index: SlabIndex| {
+            index != reference && self.file_nodes[index].parent() == Some(parent)
+        };
+        match base {
+            Some(nodes) => {
+                self.diagnostics.scanned(nodes.len());
+                Ok(filter_nodes(nodes, token, is_sibling))
+            }
+            None => Ok(Some(
+                self.file_nodes[parent]
+                    .children
+                    .iter()
+                    .copied()
+                    .filter(|&index| index != reference)
+                    .collect(),
+            )),
         }
     }
 
@@ -552,7 +822,7 @@ impl SearchCache {
         base: Option<Vec<SlabIndex>>,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let Some(target) = self.node_index_for_path(Path::new(&argument.raw)) else {
+        let Some(target) = self.index_of_path(Path::new(&argument.raw)) else {
             bail!(
                 "Parent filter {:?} is not found in file system",
                 argument.raw
@@ -562,7 +832,7 @@ impl SearchCache {
             return Ok(None);
         };
         if let Some(mut nodes) = base {
-            if intersect_in_place(&mut nodes, &children, token).is_none() {
+            if intersect_in_place(&mut nodes, children, token).is_none() {
                 return Ok(None);
             }
             Ok(Some(nodes))
@@ -577,7 +847,7 @@ impl SearchCache {
         base: Option<Vec<SlabIndex>>,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let Some(target) = self.node_index_for_path(Path::new(&argument.raw)) else {
+        let Some(target) = self.index_of_path(Path::new(&argument.raw)) else {
             bail!(
                 "nosubfolders filter {:?} is not found in file system",
                 argument.raw
@@ -595,11 +865,119 @@ impl SearchCache {
             self.file_nodes[target].children.to_vec()
         };
 
+        self.diagnostics.scanned(nodes.len());
         Ok(filter_nodes(nodes, token, |index| {
             self.keep_node_for_nosubfolders(index, target)
         }))
     }
 
+    /// `pathprefix:` compares the node's reconstructed path with the argument
+    /// as a plain string, so `/a/Doc` matches `/a/Documents` and everything in
+    /// it. Nothing has to exist at the prefix itself.
+    fn evaluate_path_prefix_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        if argument.raw.is_empty() {
+            bail!("pathprefix: requires a path prefix");
+        }
+        let Some(nodes) = base.or_else(|| self.search_empty(token)) else {
+            return Ok(None);
+        };
+        self.diagnostics.scanned(nodes.len());
+        let mut matcher = PathPrefixMatcher::new(argument.raw.as_bytes());
+        Ok(filter_nodes(nodes, token, |index| {
+            matcher.matches(self, index)
+        }))
+    }
+
+    /// `path:` matches its argument against the whole reconstructed path, the
+    /// way `name:` does against the name. `*` crosses `/`, so
+    /// `!path:*node_modules*` drops everything inside such folders. Paths are
+    /// rebuilt a batch at a time, sharing parent paths within the batch.
+    fn evaluate_path_glob_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        if argument.raw.is_empty() {
+            bail!("path: requires a glob");
+        }
+        let literal = matches!(argument.kind, ArgumentKind::Phrase);
+        let matcher = build_name_matcher(&argument.raw, literal, options)
+            .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        Ok(self.filter_by_path(nodes, &matcher, token))
+    }
+
+    /// Keeps the nodes whose reconstructed path `matcher` accepts. Paths are
+    /// rebuilt a batch at a time so shared parent prefixes are built once.
+    fn filter_by_path(
+        &self,
+        nodes: Vec<SlabIndex>,
+        matcher: &SegmentMatcherConcrete,
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        self.diagnostics.scanned(nodes.len());
+        let mut matched = Vec::new();
+        for batch in nodes.chunks(PATH_FILTER_BATCH) {
+            token.is_cancelled()?;
+            let paths = self.file_nodes.node_paths(batch);
+            matched.extend(batch.iter().zip(paths).filter_map(|(&index, path)| {
+                path.is_some_and(|path| matcher.matches(&path.to_string_lossy()))
+                    .then_some(index)
+            }));
+        }
+        Some(matched)
+    }
+
+    /// `dupe:` keeps the candidates whose name another candidate shares, so
+    /// `file: dupe:` lists files that have a namesake elsewhere. An argument
+    /// narrows the candidates first, like `file:`'s.
+    fn evaluate_duplicate_filter(
+        &mut self,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(mut nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        if let Some(argument) = argument {
+            let Some(matches) = self.evaluate_phrase(&argument.raw, options, token)? else {
+                return Ok(None);
+            };
+            if intersect_in_place(&mut nodes, matches, token).is_none() {
+                return Ok(None);
+            }
+        }
+        self.diagnostics.scanned(nodes.len());
+        let mut names = Vec::with_capacity(nodes.len());
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for (i, &index) in nodes.iter().enumerate() {
+            if token.is_cancelled_sparse(i).is_none() {
+                return Ok(None);
+            }
+            let name = fold_text(self.file_nodes[index].name().to_string(), options);
+            *counts.entry_ref(&name).or_default() += 1;
+            names.push(name);
+        }
+        Ok(Some(
+            nodes
+                .into_iter()
+                .zip(names)
+                .filter_map(|(index, name)| (counts[&name] > 1).then_some(index))
+                .collect(),
+        ))
+    }
+
     fn keep_node_for_nosubfolders(&self, index: SlabIndex, root: SlabIndex) -> bool {
         index == root || {
             let node = &self.file_nodes[index];
@@ -608,25 +986,18 @@ impl SearchCache {
     }
 
     fn evaluate_named_type_filter(
-        &self,
+        &mut self,
         raw: &str,
         base: Option<Vec<SlabIndex>>,
         options: SearchOptions,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let name = raw.trim();
-        if name.is_empty() {
-            bail!("type: requires a category");
-        }
-        let normalized = name.to_ascii_lowercase();
-        let Some(target) = lookup_type_group(&normalized) else {
-            bail!("Unknown type category: {name}");
-        };
+        let target = type_category(raw)?;
         self.apply_type_group(target, base, options, token)
     }
 
     fn evaluate_type_macro(
-        &self,
+        &mut self,
         name: &'static str,
         base: Option<Vec<SlabIndex>>,
         argument: Option<&FilterArgument>,
@@ -648,14 +1019,14 @@ impl SearchCache {
         let Some(matches) = self.evaluate_phrase(&argument.raw, options, token)? else {
             return Ok(None);
         };
-        if intersect_in_place(&mut nodes, &matches, token).is_none() {
+        if intersect_in_place(&mut nodes, matches, token).is_none() {
             return Ok(None);
         }
         Ok(Some(nodes))
     }
 
     fn apply_type_group(
-        &self,
+        &mut self,
         target: TypeFilterTarget,
         base: Option<Vec<SlabIndex>>,
         options: SearchOptions,
@@ -665,7 +1036,12 @@ impl SearchCache {
             TypeFilterTarget::NodeType(file_type) => {
                 self.evaluate_type_filter(file_type, base, None, options, token)
             }
-            TypeFilterTarget::Extensions(list) => self.filter_static_extensions(list, base, token),
+            TypeFilterTarget::Extensions(list, Some(kind)) if options.sniff_content => {
+                self.filter_extensions_or_content(list, kind, base, token)
+            }
+            TypeFilterTarget::Extensions(list, _) => {
+                self.filter_static_extensions(list, base, token)
+            }
         }
     }
 
@@ -678,10 +1054,11 @@ impl SearchCache {
         if extensions.is_empty() {
             return Ok(Some(Vec::new()));
         }
-        let Some(nodes) = self.nodes_from_base(base, token) else {
-            return Ok(None);
+        let Some(base) = base else {
+            return Ok(self.files_with_extensions(extensions.iter().copied(), token));
         };
-        Ok(filter_nodes(nodes, token, |index| {
+        self.diagnostics.scanned(base.len());
+        Ok(filter_nodes(base, token, |index| {
             let node = &self.file_nodes[index];
             if node.file_type_hint() != NodeFileType::File {
                 return false;
@@ -694,6 +1071,49 @@ impl SearchCache {
         }))
     }
 
+    /// Like [`Self::filter_static_extensions`], but files whose extension isn't
+    /// in the list are kept when their first bytes show they are `kind`. Only
+    /// those files are opened, and known-empty ones are skipped.
+    fn filter_extensions_or_content(
+        &self,
+        extensions: &'static [&'static str],
+        kind: ContentKind,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        self.diagnostics.scanned(nodes.len());
+        Ok(filter_nodes(nodes, token, |index| {
+            let node = &self.file_nodes[index];
+            if node.file_type_hint() != NodeFileType::File {
+                return false;
+            }
+            if extension_of(node.name()).is_some_and(|ext| extensions.contains(&ext.as_str())) {
+                return true;
+            }
+            if node.metadata.as_ref().is_some_and(|meta| meta.size() == 0) {
+                return false;
+            }
+            self.node_path(index).and_then(|path| sniff_file(&path)) == Some(kind)
+        }))
+    }
+
+    /// Looks `extensions` up in the extension index instead of scanning every
+    /// node; only the hits are checked for being files.
+    fn files_with_extensions<'e>(
+        &self,
+        extensions: impl IntoIterator<Item = &'e str>,
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let nodes = self.name_index.indices_with_extensions(extensions, token)?;
+        self.diagnostics.scanned(nodes.len());
+        filter_nodes(nodes, token, |index| {
+            self.file_nodes[index].file_type_hint() == NodeFileType::File
+        })
+    }
+
     fn evaluate_size_filter(
         &mut self,
         argument: &FilterArgument,
@@ -704,6 +1124,10 @@ impl SearchCache {
         let Some(nodes) = self.nodes_from_base(base, token) else {
             return Ok(None);
         };
+        if self.fill_missing_metadata(&nodes, true, token).is_none() {
+            return Ok(None);
+        }
+        self.diagnostics.scanned(nodes.len());
         Ok(filter_nodes(nodes, token, |index| {
             let node = &self.file_nodes[index];
             if node.file_type_hint() != NodeFileType::File {
@@ -719,6 +1143,128 @@ impl SearchCache {
         }))
     }
 
+    /// `startswith:`: names beginning with the argument, both sides folded
+    /// like the buckets of [`SearchCache::alpha_buckets`].
+    fn evaluate_starts_with_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let prefix = argument.raw.to_lowercase();
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        self.diagnostics.scanned(nodes.len());
+        Ok(filter_nodes(nodes, token, |index| {
+            self.file_nodes[index]
+                .name()
+                .to_lowercase()
+                .starts_with(&prefix)
+        }))
+    }
+
+    fn evaluate_name_length_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let predicate = parse_name_length(argument)?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        self.diagnostics.scanned(nodes.len());
+        Ok(filter_nodes(nodes, token, |index| {
+            predicate.matches(self.file_nodes[index].name().chars().count() as u64)
+        }))
+    }
+
+    /// `recursivecount:`: folders by how many entries sit anywhere below them.
+    fn evaluate_recursive_count_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let predicate = parse_count(argument, "recursivecount", "entries")?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        self.diagnostics.scanned(nodes.len());
+        // Shared across candidates, so nested folders are only counted once.
+        let mut counts = HashMap::new();
+        Ok(filter_nodes(nodes, token, |index| {
+            self.file_nodes[index].file_type_hint() == NodeFileType::Dir
+                && predicate.matches(self.descendant_count(index, &mut counts))
+        }))
+    }
+
+    /// Entries anywhere below `index`, summed over the slab or taken from a
+    /// [`DirRollup`](crate::DirRollup). `counts` memoizes the folders already
+    /// counted; leaves are never stored in it.
+    fn descendant_count(&self, index: SlabIndex, counts: &mut HashMap<SlabIndex, u64>) -> u64 {
+        let mut stack = vec![(index, false)];
+        while let Some((current, children_counted)) = stack.pop() {
+            if counts.contains_key(&current) {
+                continue;
+            }
+            if let Some(rollup) = self.dir_rollups.get(current) {
+                counts.insert(current, rollup.entries);
+                continue;
+            }
+            let children = &self.file_nodes[current].children;
+            if children_counted {
+                let count = children
+                    .iter()
+                    .map(|child| 1 + counts.get(child).copied().unwrap_or(0))
+                    .sum();
+                counts.insert(current, count);
+            } else {
+                stack.push((current, true));
+                stack.extend(
+                    children
+                        .iter()
+                        .filter(|&&child| !self.file_nodes[child].children.is_empty())
+                        .map(|&child| (child, false)),
+                );
+            }
+        }
+        counts[&index]
+    }
+
+    /// `dirsize:`: folders by the bytes in the files anywhere below them.
+    /// Subtrees not covered by [`SearchCache::spawn_size_rollup`] are summed
+    /// here and cached.
+    fn evaluate_dir_size_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let predicate = SizePredicate::parse(argument)?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        self.diagnostics.scanned(nodes.len());
+        let mut matched = Vec::new();
+        for (i, index) in nodes.into_iter().enumerate() {
+            if token.is_cancelled_sparse(i).is_none() {
+                return Ok(None);
+            }
+            if self.file_nodes[index].file_type_hint() != NodeFileType::Dir {
+                continue;
+            }
+            let Some(rollup) = self.roll_up(index, || token.is_cancelled()) else {
+                return Ok(None);
+            };
+            if predicate.matches(rollup.size) {
+                matched.push(index);
+            }
+        }
+        Ok(Some(matched))
+    }
+
     fn evaluate_date_filter(
         &mut self,
         field: DateField,
@@ -726,11 +1272,15 @@ impl SearchCache {
         base: Option<Vec<SlabIndex>>,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let context = DateContext::capture();
+        let context = DateContext::capture(self.index_timestamp);
         let predicate = DatePredicate::parse(argument, &context)?;
         let Some(nodes) = self.nodes_from_base(base, token) else {
             return Ok(None);
         };
+        if self.fill_missing_metadata(&nodes, false, token).is_none() {
+            return Ok(None);
+        }
+        self.diagnostics.scanned(nodes.len());
         Ok(filter_nodes(nodes, token, |index| {
             let Some(timestamp) = self.node_timestamp(index, field) else {
                 return false;
@@ -739,6 +1289,92 @@ impl SearchCache {
         }))
     }
 
+    /// `newerthan:<path>` / `olderthan:<path>`: nodes whose mtime compares as
+    /// `wanted` with the reference's. The reference is read from disk, so it
+    /// doesn't have to be indexed; nodes with an unknown mtime never match.
+    fn evaluate_reference_mtime_filter(
+        &mut self,
+        wanted: Ordering,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let keyword = match wanted {
+            Ordering::Greater => "newerthan",
+            _ => "olderthan",
+        };
+        let metadata = std::fs::metadata(&argument.raw)
+            .map_err(|err| anyhow!("{keyword}: can't read {:?}: {err}", argument.raw))?;
+        let Some(reference) = NodeMetadata::from(metadata).mtime else {
+            bail!("{keyword}: {:?} has no modification time", argument.raw);
+        };
+        let reference = reference.get() as i64;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        if self.fill_missing_metadata(&nodes, false, token).is_none() {
+            return Ok(None);
+        }
+        self.diagnostics.scanned(nodes.len());
+        Ok(filter_nodes(nodes, token, |index| {
+            self.node_timestamp(index, DateField::Modified)
+                .is_some_and(|mtime| mtime.cmp(&reference) == wanted)
+        }))
+    }
+
+    /// `stale:<duration>`: files whose mtime and atime both lie further back
+    /// than the duration. The index keeps no atime, so it's read from disk the
+    /// first time a file is old enough by mtime and kept in
+    /// [`AccessTimes`] until a change to the file drops it.
+    fn evaluate_stale_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let cutoff = stale_cutoff(argument)?;
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        if self.fill_missing_metadata(&nodes, false, token).is_none() {
+            return Ok(None);
+        }
+        self.diagnostics.scanned(nodes.len());
+        let Some(unmodified) = filter_nodes(nodes, token, |index| {
+            self.file_nodes[index].file_type_hint() == NodeFileType::File
+                && self
+                    .node_timestamp(index, DateField::Modified)
+                    .is_some_and(|mtime| mtime < cutoff)
+        }) else {
+            return Ok(None);
+        };
+        let unread: Vec<SlabIndex> = unmodified
+            .iter()
+            .copied()
+            .filter(|&index| self.access_times.get(index).is_none())
+            .collect();
+        for batch in unread.chunks(METADATA_FILL_BATCH) {
+            if token.is_cancelled().is_none() {
+                return Ok(None);
+            }
+            let paths = self.node_paths(batch);
+            let loaded: Vec<_> = paths
+                .par_iter()
+                .map(|path| path.as_deref().and_then(accessed_at))
+                .collect();
+            self.diagnostics.metadata_reads(batch.len());
+            for (&index, atime) in batch.iter().zip(loaded) {
+                self.access_times.insert(index, atime);
+            }
+        }
+        Ok(filter_nodes(unmodified, token, |index| {
+            self.access_times
+                .get(index)
+                .flatten()
+                .is_some_and(|atime| atime < cutoff)
+        }))
+    }
+
     fn evaluate_content_filter(
         &mut self,
         argument: &FilterArgument,
@@ -761,6 +1397,7 @@ impl SearchCache {
             return Ok(None);
         };
 
+        self.diagnostics.scanned(nodes.len());
         let matched_indices = nodes
             .into_iter()
             .filter(|index| self.file_nodes[*index].file_type_hint() == NodeFileType::File)
@@ -807,14 +1444,10 @@ impl SearchCache {
                 bail!("tag: does not support ranges or comparisons");
             }
         };
-        let needles = if options.case_insensitive {
-            raw_needles
-                .into_iter()
-                .map(|value| value.to_ascii_lowercase())
-                .collect()
-        } else {
-            raw_needles
-        };
+        let needles: Vec<String> = raw_needles
+            .into_iter()
+            .map(|value| fold_text(value, options))
+            .collect();
 
         let Some(nodes) = self.nodes_from_base(base.clone(), token) else {
             return Ok(None);
@@ -823,21 +1456,27 @@ impl SearchCache {
         // If base is a small set, filtering it by accessing file metadata;
         // otherwise use mdfind to quickly narrow down.
         let matched_indices = if nodes.len() <= TAG_FILTER_MDFIND_THRESHOLD {
+            self.diagnostics.scanned(nodes.len());
             nodes
                 .into_iter()
                 .filter_map(|index| self.node_path(index).map(|path| (index, path)))
                 .par_bridge()
                 .filter_map(|(index, path)| {
-                    self.node_tags_match_any(&path, &needles, options.case_insensitive, token)?
+                    self.node_tags_match_any(&path, &needles, options, token)?
                         .then_some(index)
                 })
                 .collect()
         } else {
-            let spotlight_indices: Vec<SlabIndex> =
-                search_tags_using_mdfind(needles, options.case_insensitive)?
-                    .into_iter()
-                    .filter_map(|path| self.node_index_for_path(&path))
-                    .collect();
+            self.diagnostics.used_mdfind();
+            let spotlight_indices: Vec<SlabIndex> = search_tags_using_mdfind(
+                needles,
+                options.case_insensitive,
+                options.accent_insensitive,
+                Some(TAG_FILTER_MDFIND_TIMEOUT),
+            )?
+            .into_iter()
+            .filter_map(|path| self.index_of_path(&path))
+            .collect();
 
             match base {
                 Some(base) => {
@@ -853,6 +1492,67 @@ impl SearchCache {
         Ok(token.is_cancelled().map(|()| matched_indices))
     }
 
+    /// Keeps the nodes whose Spotlight content type conforms to one of the
+    /// UTIs in `argument`. Trees already in [`ContentTypes`] answer from
+    /// memory; the rest are read with `mdls` batch by batch and kept until a
+    /// change drops them. When many are missing the search is narrowed with
+    /// `mdfind` instead, as for `tag:`.
+    fn evaluate_uti_filter(
+        &mut self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let needles = uti_needles(argument)?;
+        if !cfg!(target_os = "macos") {
+            bail!("uti: needs Spotlight, which is only available on macOS");
+        }
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        let unread: Vec<SlabIndex> = nodes
+            .iter()
+            .copied()
+            .filter(|&index| self.content_types.get(index).is_none())
+            .collect();
+
+        if unread.len() > TAG_FILTER_MDFIND_THRESHOLD {
+            self.diagnostics.used_mdfind();
+            let allowed: HashSet<SlabIndex> =
+                search_content_types_using_mdfind(&needles, Some(TAG_FILTER_MDFIND_TIMEOUT))?
+                    .into_iter()
+                    .filter_map(|path| self.index_of_path(&path))
+                    .collect();
+            let mut matched = nodes;
+            matched.retain(|index| allowed.contains(index));
+            return Ok(token.is_cancelled().map(|()| matched));
+        }
+
+        self.diagnostics.scanned(nodes.len());
+        for batch in unread.chunks(UTI_FILTER_MDLS_BATCH) {
+            if token.is_cancelled().is_none() {
+                return Ok(None);
+            }
+            let (indices, paths): (Vec<SlabIndex>, Vec<PathBuf>) = batch
+                .iter()
+                .filter_map(|&index| self.node_path(index).map(|path| (index, path)))
+                .unzip();
+            let trees = read_content_types(&paths, Some(TAG_FILTER_MDFIND_TIMEOUT))?;
+            for (index, tree) in indices.into_iter().zip(trees) {
+                self.content_types.insert(index, tree);
+            }
+        }
+        Ok(filter_nodes(nodes, token, |index| {
+            self.content_types.get(index).is_some_and(|tree| {
+                tree.iter().any(|uti| {
+                    needles
+                        .iter()
+                        .any(|needle| uti.eq_ignore_ascii_case(needle))
+                })
+            })
+        }))
+    }
+
     /// user need to ensure that needle is lowercased when case_insensitive is set
     fn node_content_matches(
         &self,
@@ -949,12 +1649,19 @@ impl SearchCache {
         &self,
         path: &Path,
         needles: &[String],
-        case_insensitive: bool,
+        options: SearchOptions,
         token: CancellationToken,
     ) -> Option<bool> {
         token.is_cancelled()?;
 
-        let tags = read_tags_from_path(path, case_insensitive)?;
+        let folds = Fold::from_options(options).is_some();
+        let mut tags = read_tags_from_path(path, options.case_insensitive && !folds)?;
+        if folds {
+            tags = tags
+                .into_iter()
+                .map(|tag| fold_text(tag, options))
+                .collect();
+        }
         let matched = tags
             .iter()
             .any(|tag| needles.iter().any(|needle| tag.contains(needle)));
@@ -982,7 +1689,7 @@ impl SearchCache {
         .map(|value| value.get() as i64)
     }
 
-    fn ensure_metadata(&mut self, index: SlabIndex) -> SlabNodeMetadataCompact {
+    pub(crate) fn ensure_metadata(&mut self, index: SlabIndex) -> SlabNodeMetadataCompact {
         let current = self.file_nodes[index].metadata;
         if current.is_some() {
             return current;
@@ -990,13 +1697,87 @@ impl SearchCache {
         let path = self
             .node_path(index)
             .expect("node index is not present in slab");
-        let metadata = match std::fs::symlink_metadata(&path) {
-            Ok(data) => SlabNodeMetadataCompact::some(data.into()),
-            Err(_) => SlabNodeMetadataCompact::unaccessible(),
-        };
+        self.diagnostics.metadata_read();
+        let metadata = read_metadata(&path);
         self.file_nodes[index].metadata = metadata;
         metadata
     }
+
+    /// Stats the nodes in `nodes` that have no metadata yet, in parallel, so
+    /// a filter looping over them afterwards finds it in place. With
+    /// `files_only`, other node types are left alone. Returns `None` if the
+    /// search was cancelled; nodes not reached by then stay untouched.
+    fn fill_missing_metadata(
+        &mut self,
+        nodes: &[SlabIndex],
+        files_only: bool,
+        token: CancellationToken,
+    ) -> Option<()> {
+        self.fill_missing_metadata_in_batches(nodes, files_only, METADATA_FILL_BATCH, || {
+            token.is_cancelled()
+        })
+    }
+
+    /// [`Self::fill_missing_metadata`] with the batch length and the check
+    /// run before each batch spelled out.
+    pub(crate) fn fill_missing_metadata_in_batches(
+        &mut self,
+        nodes: &[SlabIndex],
+        files_only: bool,
+        batch_len: usize,
+        mut proceed: impl FnMut() -> Option<()>,
+    ) -> Option<()> {
+        let missing: Vec<SlabIndex> = nodes
+            .iter()
+            .copied()
+            .filter(|&index| {
+                let node = &self.file_nodes[index];
+                node.metadata.is_none()
+                    && (!files_only || node.file_type_hint() == NodeFileType::File)
+            })
+            .collect();
+        for batch in missing.chunks(batch_len) {
+            proceed()?;
+            let paths: Vec<PathBuf> = batch
+                .iter()
+                .map(|&index| {
+                    self.node_path(index)
+                        .expect("node index is not present in slab")
+                })
+                .collect();
+            let loaded: Vec<_> = paths.par_iter().map(|path| read_metadata(path)).collect();
+            self.diagnostics.metadata_reads(batch.len());
+            for (&index, metadata) in batch.iter().zip(loaded) {
+                self.file_nodes[index].metadata = metadata;
+            }
+        }
+        Some(())
+    }
+}
+
+/// Nodes stat'ed per parallel batch by [`SearchCache::fill_missing_metadata`];
+/// cancellation is checked between batches.
+pub(crate) const METADATA_FILL_BATCH: usize = 1024;
+
+fn read_metadata(path: &Path) -> SlabNodeMetadataCompact {
+    match std::fs::symlink_metadata(path) {
+        Ok(data) => SlabNodeMetadataCompact::some(data.into()),
+        Err(_) => SlabNodeMetadataCompact::unaccessible(),
+    }
+}
+
+/// Brings tags and `tag:` needles, or the names `dupe:` compares, to the same
+/// form: the [`Fold`] first, then ASCII lowercasing if case is still left to do.
+fn fold_text(text: String, options: SearchOptions) -> String {
+    let text = match Fold::from_options(options) {
+        Some(fold) => fold.apply(&text),
+        None => text,
+    };
+    if options.simple_case_fold() {
+        text.to_ascii_lowercase()
+    } else {
+        text
+    }
 }
 
 fn normalize_extensions(argument: &FilterArgument) -> HashSet<String> {
@@ -1023,16 +1804,100 @@ fn normalize_extension(raw: &str) -> Option<String> {
     if trimmed.is_empty() {
         None
     } else {
-        Some(trimmed.to_ascii_lowercase())
+        Some(fold_extension(trimmed))
     }
 }
 
-fn extension_of(name: &str) -> Option<String> {
+/// The single point where extension case is folded. Both sides of every
+/// extension comparison pass through here: the query's `ext:` items (each
+/// `;`-separated item on its own, via [`normalize_extension`]) and node names
+/// (via [`extension_of`], which also keys the extension index). The static
+/// `type:` groups are written pre-folded. A case-sensitive mode would switch
+/// this one function, not its callers.
+pub(crate) fn fold_extension(extension: &str) -> String {
+    extension.to_ascii_lowercase()
+}
+
+/// The first character of `name`, lowercased, or `None` for an empty name.
+pub(crate) fn first_letter(name: &str) -> Option<char> {
+    name.chars().next()?.to_lowercase().next()
+}
+
+/// Folded text after the last `.`. A leading dot only marks a hidden
+/// file, so `.gitignore` has no extension while `.hidden.txt` has `txt`.
+pub(crate) fn extension_of(name: &str) -> Option<String> {
     let pos = name.rfind('.')?;
-    if pos + 1 >= name.len() {
+    if pos == 0 || pos + 1 >= name.len() {
         return None;
     }
-    Some(name[pos + 1..].to_ascii_lowercase())
+    Some(fold_extension(&name[pos + 1..]))
+}
+
+/// Where a path stands against a `pathprefix:` string.
+#[derive(Clone)]
+enum PrefixState {
+    /// The path starts with the prefix, and so does every path below it.
+    Inside,
+    /// Neither the path nor anything below it can start with the prefix.
+    Outside,
+    /// The path is itself a prefix of the wanted string; children decide.
+    Partial(PathBuf),
+}
+
+impl PrefixState {
+    fn classify(path: PathBuf, prefix: &[u8]) -> Self {
+        let bytes = path.as_os_str().as_bytes();
+        if bytes.starts_with(prefix) {
+            PrefixState::Inside
+        } else if prefix.starts_with(bytes) {
+            PrefixState::Partial(path)
+        } else {
+            PrefixState::Outside
+        }
+    }
+}
+
+/// Decides `pathprefix:` per node while remembering each ancestor's state, so
+/// paths are only rebuilt down to where the prefix is settled and nodes in the
+/// same folder reuse their parent's verdict.
+struct PathPrefixMatcher<'p> {
+    prefix: &'p [u8],
+    dirs: HashMap<SlabIndex, PrefixState>,
+}
+
+impl<'p> PathPrefixMatcher<'p> {
+    fn new(prefix: &'p [u8]) -> Self {
+        Self {
+            prefix,
+            dirs: HashMap::new(),
+        }
+    }
+
+    fn matches(&mut self, cache: &SearchCache, index: SlabIndex) -> bool {
+        let mut pending = Vec::new();
+        let mut current = index;
+        let mut state = loop {
+            if let Some(state) = self.dirs.get(&current) {
+                break state.clone();
+            }
+            let node = &cache.file_nodes[current];
+            let Some(parent) = node.parent() else {
+                break PrefixState::classify(PathBuf::from("/"), self.prefix);
+            };
+            pending.push(current);
+            current = parent;
+        };
+        for &node in pending.iter().rev() {
+            if let PrefixState::Partial(path) = state {
+                let path = path.join(cache.file_nodes[node].os_name());
+                state = PrefixState::classify(path, self.prefix);
+            }
+            if node != index {
+                self.dirs.insert(node, state.clone());
+            }
+        }
+        matches!(state, PrefixState::Inside)
+    }
 }
 
 fn dedup_indices_in_place(indices: &mut Vec<SlabIndex>) {
@@ -1043,7 +1908,19 @@ fn dedup_indices_in_place(indices: &mut Vec<SlabIndex>) {
 #[derive(Clone, Copy)]
 enum TypeFilterTarget {
     NodeType(NodeFileType),
-    Extensions(&'static [&'static str]),
+    /// Files with one of the extensions, or, with
+    /// [`SearchOptions::sniff_content`], whose first bytes show they are the
+    /// given kind.
+    Extensions(&'static [&'static str], Option<ContentKind>),
+}
+
+fn type_category(raw: &str) -> Result<TypeFilterTarget> {
+    let name = raw.trim();
+    if name.is_empty() {
+        bail!("type: requires a category");
+    }
+    let normalized = name.to_ascii_lowercase();
+    lookup_type_group(&normalized).ok_or_else(|| anyhow!("Unknown type category: {name}"))
 }
 
 fn lookup_type_group(name: &str) -> Option<TypeFilterTarget> {
@@ -1052,31 +1929,40 @@ fn lookup_type_group(name: &str) -> Option<TypeFilterTarget> {
         "folder" | "folders" | "dir" | "directory" => {
             Some(TypeFilterTarget::NodeType(NodeFileType::Dir))
         }
-        "picture" | "pictures" | "image" | "images" | "photo" | "photos" => {
-            Some(TypeFilterTarget::Extensions(PICTURE_EXTENSIONS))
-        }
-        "video" | "videos" | "movie" | "movies" => {
-            Some(TypeFilterTarget::Extensions(VIDEO_EXTENSIONS))
-        }
-        "audio" | "audios" | "music" | "song" | "songs" => {
-            Some(TypeFilterTarget::Extensions(AUDIO_EXTENSIONS))
-        }
+        "symlink" | "symlinks" | "link" | "links" => {
+            Some(TypeFilterTarget::NodeType(NodeFileType::Symlink))
+        }
+        "picture" | "pictures" | "image" | "images" | "photo" | "photos" => Some(
+            TypeFilterTarget::Extensions(PICTURE_EXTENSIONS, Some(ContentKind::Picture)),
+        ),
+        "video" | "videos" | "movie" | "movies" => Some(TypeFilterTarget::Extensions(
+            VIDEO_EXTENSIONS,
+            Some(ContentKind::Video),
+        )),
+        "audio" | "audios" | "music" | "song" | "songs" => Some(TypeFilterTarget::Extensions(
+            AUDIO_EXTENSIONS,
+            Some(ContentKind::Audio),
+        )),
         "doc" | "docs" | "document" | "documents" | "text" | "office" => {
-            Some(TypeFilterTarget::Extensions(DOCUMENT_EXTENSIONS))
+            Some(TypeFilterTarget::Extensions(DOCUMENT_EXTENSIONS, None))
         }
         "presentation" | "presentations" | "ppt" | "slides" => {
-            Some(TypeFilterTarget::Extensions(PRESENTATION_EXTENSIONS))
+            Some(TypeFilterTarget::Extensions(PRESENTATION_EXTENSIONS, None))
         }
         "spreadsheet" | "spreadsheets" | "xls" | "excel" | "sheet" | "sheets" => {
-            Some(TypeFilterTarget::Extensions(SPREADSHEET_EXTENSIONS))
-        }
-        "pdf" => Some(TypeFilterTarget::Extensions(PDF_EXTENSIONS)),
-        "archive" | "archives" | "compressed" | "zip" => {
-            Some(TypeFilterTarget::Extensions(ARCHIVE_EXTENSIONS))
-        }
-        "code" | "source" | "dev" => Some(TypeFilterTarget::Extensions(CODE_EXTENSIONS)),
+            Some(TypeFilterTarget::Extensions(SPREADSHEET_EXTENSIONS, None))
+        }
+        "pdf" => Some(TypeFilterTarget::Extensions(
+            PDF_EXTENSIONS,
+            Some(ContentKind::Pdf),
+        )),
+        "archive" | "archives" | "compressed" | "zip" => Some(TypeFilterTarget::Extensions(
+            ARCHIVE_EXTENSIONS,
+            Some(ContentKind::Archive),
+        )),
+        "code" | "source" | "dev" => Some(TypeFilterTarget::Extensions(CODE_EXTENSIONS, None)),
         "exe" | "exec" | "executable" | "executables" | "program" | "programs" | "app" | "apps" => {
-            Some(TypeFilterTarget::Extensions(EXECUTABLE_EXTENSIONS))
+            Some(TypeFilterTarget::Extensions(EXECUTABLE_EXTENSIONS, None))
         }
         _ => None,
     }
@@ -1122,15 +2008,18 @@ enum DateField {
 struct DateContext {
     tz: TimeZone,
     today: Date,
+    /// See [`SearchCache::index_timestamp`].
+    index_timestamp: Option<i64>,
 }
 
 impl DateContext {
-    fn capture() -> Self {
+    fn capture(index_timestamp: Option<i64>) -> Self {
         let tz = TimeZone::system();
         let zoned = Timestamp::now().to_zoned(tz.clone());
         Self {
             tz,
             today: zoned.date(),
+            index_timestamp,
         }
     }
 }
@@ -1236,6 +2125,15 @@ fn parse_date_value(raw: &str, context: &DateContext) -> Result<DateValue> {
     if trimmed.is_empty() {
         bail!("date filters require a value");
     }
+    if trimmed.eq_ignore_ascii_case("sincelastindex") {
+        let Some(start) = context.index_timestamp else {
+            bail!("sincelastindex needs an index loaded from disk");
+        };
+        return Ok(DateValue {
+            start,
+            end: i64::MAX,
+        });
+    }
     if let Some(range) = keyword_range(trimmed, context) {
         return Ok(range);
     }
@@ -1264,13 +2162,13 @@ fn keyword_range(keyword: &str, context: &DateContext) -> Option<DateValue> {
             day_bounds(date, context).map(|(s, e)| DateValue { start: s, end: e })
         }
         "thisweek" => {
-            let weekday_offset = i64::from(today.weekday().to_monday_zero_offset());
+            let weekday_offset = i64::from(today.weekday().since(WEEK_START));
             let start = shift_days(today, -weekday_offset)?;
             let end = shift_days(start, 6)?;
             range_from_dates(start, end, context)
         }
         "lastweek" => {
-            let weekday_offset = i64::from(today.weekday().to_monday_zero_offset()) + 7;
+            let weekday_offset = i64::from(today.weekday().since(WEEK_START)) + 7;
             let start = shift_days(today, -weekday_offset)?;
             let end = shift_days(start, 6)?;
             range_from_dates(start, end, context)
@@ -1494,6 +2392,48 @@ impl SizePredicate {
     }
 }
 
+/// A `namelen:` argument: character counts with the comparisons and `..`
+/// ranges `size:` takes, but no units or keywords.
+fn parse_name_length(argument: &FilterArgument) -> Result<SizePredicate> {
+    parse_count(argument, "namelen", "characters")
+}
+
+/// A plain-number argument to the count filter `name`, with the comparisons
+/// and `..` ranges `size:` takes. `unit` names what is counted in errors.
+fn parse_count(argument: &FilterArgument, name: &str, unit: &str) -> Result<SizePredicate> {
+    let count = |value: &str| {
+        value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| anyhow!("{name}: {value:?} isn't a number of {unit}"))
+    };
+    let kind = match &argument.kind {
+        ArgumentKind::Comparison(comp) => SizePredicateKind::Comparison {
+            op: comp.op,
+            value: count(&comp.value)?,
+        },
+        ArgumentKind::Range(range) => {
+            if range.separator != RangeSeparator::Dots {
+                bail!("{name}: only .. ranges are supported");
+            }
+            let min = range.start.as_deref().map(count).transpose()?;
+            let max = range.end.as_deref().map(count).transpose()?;
+            if let (Some(min), Some(max)) = (min, max)
+                && min > max
+            {
+                bail!("{name} range start must be less than or equal to the end");
+            }
+            SizePredicateKind::Range { min, max }
+        }
+        ArgumentKind::List(_) => bail!("{name}: lists are not supported"),
+        _ => SizePredicateKind::Comparison {
+            op: ComparisonOp::Eq,
+            value: count(&argument.raw)?,
+        },
+    };
+    Ok(SizePredicate { kind })
+}
+
 struct SizeKeywordRange {
     min: Option<u64>,
     max: Option<u64>,
@@ -1584,6 +2524,188 @@ fn size_unit_multiplier(unit: &str) -> Result<u64> {
     Ok(multiplier)
 }
 
+/// The moment a `stale:` duration such as `30d`, `6mo` or `1y` reaches back
+/// to, in Unix seconds. Months and years follow the local calendar.
+fn stale_cutoff(argument: &FilterArgument) -> Result<i64> {
+    let raw = argument.raw.trim();
+    let digits = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (count, unit) = raw.split_at(digits);
+    let count: i64 = count
+        .parse()
+        .map_err(|_| anyhow!("stale: expects a duration like 30d, 6mo or 1y, got {raw:?}"))?;
+    let span = match unit.to_ascii_lowercase().as_str() {
+        "h" | "hour" | "hours" => Span::new().try_hours(count),
+        "d" | "day" | "days" => Span::new().try_days(count),
+        "w" | "week" | "weeks" => Span::new().try_weeks(count),
+        "mo" | "month" | "months" => Span::new().try_months(count),
+        "y" | "year" | "years" => Span::new().try_years(count),
+        _ => bail!("stale: unknown duration unit {unit:?}; use h, d, w, mo or y"),
+    }
+    .map_err(|err| anyhow!("stale: {raw:?} is out of range: {err}"))?;
+    let cutoff = Timestamp::now()
+        .to_zoned(TimeZone::system())
+        .checked_sub(span)
+        .map_err(|err| anyhow!("stale: {raw:?} is out of range: {err}"))?;
+    Ok(cutoff.timestamp().as_second())
+}
+
+/// The UTIs a `uti:` argument lists, e.g. `public.image;public.movie`.
+fn uti_needles(argument: &FilterArgument) -> Result<Vec<String>> {
+    let values: Vec<&str> = match &argument.kind {
+        ArgumentKind::Bare | ArgumentKind::Phrase => vec![argument.raw.as_str()],
+        ArgumentKind::List(values) => values.iter().map(String::as_str).collect(),
+        ArgumentKind::Range(_) | ArgumentKind::Comparison(_) => {
+            bail!("uti: does not support ranges or comparisons");
+        }
+    };
+    let needles: Vec<String> = values
+        .into_iter()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect();
+    if needles.is_empty() {
+        bail!("uti: requires a content type");
+    }
+    if let Some(needle) = needles.iter().find(|needle| check_uti(needle).is_err()) {
+        bail!("uti: {needle:?} is not a content type like public.image");
+    }
+    Ok(needles)
+}
+
+/// Access times `stale:` has read, `None` for files it couldn't stat. A
+/// node's entry goes with it when a change removes or rescans it; reads that
+/// raised the atime since then aren't seen, as they send no event.
+#[derive(Debug, Default)]
+pub(crate) struct AccessTimes {
+    atimes: HashMap<SlabIndex, Option<i64>>,
+}
+
+impl AccessTimes {
+    fn get(&self, index: SlabIndex) -> Option<Option<i64>> {
+        self.atimes.get(&index).copied()
+    }
+
+    fn insert(&mut self, index: SlabIndex, atime: Option<i64>) {
+        self.atimes.insert(index, atime);
+    }
+
+    pub(crate) fn remove(&mut self, index: SlabIndex) {
+        self.atimes.remove(&index);
+    }
+}
+
+/// Content type trees `uti:` has read with `mdls`, empty for files Spotlight
+/// knows no type for. A node's entry goes with it when a change removes or
+/// rescans it.
+#[derive(Debug, Default)]
+pub(crate) struct ContentTypes {
+    trees: HashMap<SlabIndex, Vec<String>>,
+}
+
+impl ContentTypes {
+    fn get(&self, index: SlabIndex) -> Option<&[String]> {
+        self.trees.get(&index).map(Vec::as_slice)
+    }
+
+    fn insert(&mut self, index: SlabIndex, tree: Vec<String>) {
+        self.trees.insert(index, tree);
+    }
+
+    pub(crate) fn remove(&mut self, index: SlabIndex) {
+        self.trees.remove(&index);
+    }
+}
+
+/// Last access time of `path` in Unix seconds, without following symlinks.
+fn accessed_at(path: &Path) -> Option<i64> {
+    let accessed = std::fs::symlink_metadata(path).ok()?.accessed().ok()?;
+    let since_epoch = accessed.duration_since(std::time::UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_secs()).ok()
+}
+
+/// Returns the filter's argument, or the error a bare `size:` style filter
+/// reports when it needs one.
+fn required_argument(filter: &Filter) -> Result<&FilterArgument> {
+    let (keyword, requirement) = match filter.kind {
+        FilterKind::Name => ("name", "a file name"),
+        FilterKind::StartsWith => ("startswith", "a letter or prefix"),
+        FilterKind::Ext => ("ext", "at least one extension"),
+        FilterKind::SameExtension => ("sameext", "a file path"),
+        FilterKind::Parent => ("parent", "a folder path"),
+        FilterKind::Siblings => ("siblings", "a path"),
+        FilterKind::Flag => ("flag", "a flag name"),
+        FilterKind::InFolder => ("infolder", "a folder path"),
+        FilterKind::NoSubfolders => ("nosubfolders", "a folder path"),
+        FilterKind::PathPrefix => ("pathprefix", "a path prefix"),
+        FilterKind::Path => ("path", "a glob"),
+        FilterKind::Type => ("type", "a category"),
+        FilterKind::Size => ("size", "a value"),
+        FilterKind::NameLength => ("namelen", "a length"),
+        FilterKind::RecursiveCount => ("recursivecount", "a count"),
+        FilterKind::DirSize => ("dirsize", "a value"),
+        FilterKind::DateModified => ("dm", "a date or range"),
+        FilterKind::DateCreated => ("dc", "a date or range"),
+        FilterKind::NewerThan => ("newerthan", "a file path"),
+        FilterKind::OlderThan => ("olderthan", "a file path"),
+        FilterKind::Stale => ("stale", "a duration"),
+        FilterKind::Content => ("content", "a value"),
+        FilterKind::Tag => ("tag", "a value"),
+        FilterKind::Uti => ("uti", "a content type"),
+        _ => ("filter", "an argument"),
+    };
+    filter
+        .argument
+        .as_ref()
+        .ok_or_else(|| anyhow!("{keyword}: requires {requirement}"))
+}
+
+/// Checks a filter's argument the way evaluation would, without touching the index.
+pub(crate) fn validate_filter(filter: &Filter) -> Result<()> {
+    match filter.kind {
+        FilterKind::File
+        | FilterKind::Folder
+        | FilterKind::Audio
+        | FilterKind::Video
+        | FilterKind::Doc
+        | FilterKind::Exe
+        | FilterKind::Broken
+        | FilterKind::NoExtension
+        | FilterKind::Duplicate => Ok(()),
+        FilterKind::Name
+        | FilterKind::StartsWith
+        | FilterKind::Ext
+        | FilterKind::SameExtension
+        | FilterKind::Parent
+        | FilterKind::Siblings
+        | FilterKind::InFolder
+        | FilterKind::NoSubfolders
+        | FilterKind::PathPrefix
+        | FilterKind::Path
+        | FilterKind::NewerThan
+        | FilterKind::OlderThan
+        | FilterKind::Content
+        | FilterKind::Tag => required_argument(filter).map(drop),
+        FilterKind::Type => type_category(&required_argument(filter)?.raw).map(drop),
+        FilterKind::Flag => FileFlag::parse(&required_argument(filter)?.raw).map(drop),
+        FilterKind::Size | FilterKind::DirSize => {
+            SizePredicate::parse(required_argument(filter)?).map(drop)
+        }
+        FilterKind::NameLength => parse_name_length(required_argument(filter)?).map(drop),
+        FilterKind::RecursiveCount => {
+            parse_count(required_argument(filter)?, "recursivecount", "entries").map(drop)
+        }
+        FilterKind::Stale => stale_cutoff(required_argument(filter)?).map(drop),
+        FilterKind::Uti => uti_needles(required_argument(filter)?).map(drop),
+        FilterKind::DateModified | FilterKind::DateCreated => {
+            // Whether the cache has an index timestamp is only known at search time.
+            DatePredicate::parse(required_argument(filter)?, &DateContext::capture(Some(0)))
+                .map(drop)
+        }
+        _ => bail!("Filter {:?} is not supported yet", filter.kind),
+    }
+}
+
 fn filter_nodes(
     nodes: Vec<SlabIndex>,
     token: CancellationToken,
@@ -1602,43 +2724,77 @@ fn filter_nodes(
     Some(filtered)
 }
 
-fn intersect_in_place(
+// Intermediate results keep the order of whatever produced them: name order
+// for index scans, tree order for subtree walks, and a filter's output keeps
+// the order of its base. Operands are therefore not sorted by slab index in
+// general. The order these operations leave doesn't reach callers, as
+// `evaluate_query` sorts the final hits by slab index. The set operations
+// below take the right-hand side by value and sort it in place so it can
+// double as the lookup buffer; the left-hand side is compacted in place, so
+// neither allocates.
+
+pub fn intersect_in_place(
     values: &mut Vec<SlabIndex>,
-    rhs: &[SlabIndex],
+    mut rhs: Vec<SlabIndex>,
     token: CancellationToken,
 ) -> Option<()> {
     if values.is_empty() {
         return Some(());
     }
-    let rhs_set: HashSet<SlabIndex> = rhs.iter().copied().collect();
-    let mut filtered = Vec::with_capacity(values.len().min(rhs.len()));
-    for (i, index) in values.iter().copied().enumerate() {
-        token.is_cancelled_sparse(i)?;
-        if rhs_set.contains(&index) {
-            filtered.push(index);
-        }
-    }
-    *values = filtered;
-    Some(())
+    rhs.sort_unstable();
+    retain_by_membership(values, &rhs, true, token)
 }
 
-fn difference_in_place(
+pub fn difference_in_place(
     values: &mut Vec<SlabIndex>,
-    rhs: &[SlabIndex],
+    mut rhs: Vec<SlabIndex>,
     token: CancellationToken,
 ) -> Option<()> {
     if values.is_empty() || rhs.is_empty() {
         return Some(());
     }
-    let rhs_set: HashSet<SlabIndex> = rhs.iter().copied().collect();
-    let mut filtered = Vec::with_capacity(values.len());
-    for (i, index) in values.iter().copied().enumerate() {
-        token.is_cancelled_sparse(i)?;
-        if !rhs_set.contains(&index) {
-            filtered.push(index);
+    rhs.sort_unstable();
+    retain_by_membership(values, &rhs, false, token)
+}
+
+/// Keeps the entries of `values` whose membership in the sorted `rhs` equals
+/// `keep_members`, preserving their order.
+///
+/// A two-pointer merge is used when `values` happens to be sorted as well
+/// (e.g. both sides came from the same subtree walk); otherwise each entry is
+/// binary searched.
+fn retain_by_membership(
+    values: &mut Vec<SlabIndex>,
+    rhs: &[SlabIndex],
+    keep_members: bool,
+    token: CancellationToken,
+) -> Option<()> {
+    debug_assert!(rhs.is_sorted());
+    let mut kept = 0;
+    if values.is_sorted() {
+        let mut cursor = 0;
+        for i in 0..values.len() {
+            token.is_cancelled_sparse(i)?;
+            let index = values[i];
+            while cursor < rhs.len() && rhs[cursor] < index {
+                cursor += 1;
+            }
+            if (rhs.get(cursor) == Some(&index)) == keep_members {
+                values[kept] = index;
+                kept += 1;
+            }
+        }
+    } else {
+        for i in 0..values.len() {
+            token.is_cancelled_sparse(i)?;
+            let index = values[i];
+            if rhs.binary_search(&index).is_ok() == keep_members {
+                values[kept] = index;
+                kept += 1;
+            }
         }
     }
-    *values = filtered;
+    values.truncate(kept);
     Some(())
 }
 
@@ -0,0 +1,242 @@
+use crate::{
+    SearchCache, SearchOptions, build_segment_matchers, cache::rewrite_query,
+    query::validate_filter,
+};
+use cardinal_syntax::{Expr, Filter, FilterKind, ParseError, Term, parse_query};
+use query_segmentation::{Segment, SegmentConcrete, query_segmentation};
+use regex::RegexBuilder;
+use std::{fmt, ops::Range};
+
+/// Structured description of a query, as returned by [`SearchCache::validate_query`].
+///
+/// The tree keeps the order the user typed; evaluation later reorders filters
+/// by cost, which is an implementation detail the plan doesn't expose.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlan {
+    pub expr: PlanExpr,
+}
+
+impl QueryPlan {
+    /// Every filter in the plan in query order, including negated ones.
+    pub fn filters(&self) -> Vec<&Filter> {
+        let mut filters = Vec::new();
+        self.expr.collect_filters(&mut filters);
+        filters
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanExpr {
+    /// Matches everything (blank query or group).
+    Empty,
+    /// A name or path phrase, split into the segments matched against the tree.
+    Name(Vec<PlanSegment>),
+    Regex(String),
+    Filter(Filter),
+    Not(Box<PlanExpr>),
+    And(Vec<PlanExpr>),
+    Or(Vec<PlanExpr>),
+}
+
+impl PlanExpr {
+    fn collect_filters<'p>(&'p self, out: &mut Vec<&'p Filter>) {
+        match self {
+            PlanExpr::Empty | PlanExpr::Name(_) | PlanExpr::Regex(_) => {}
+            PlanExpr::Filter(filter) => out.push(filter),
+            PlanExpr::Not(inner) => inner.collect_filters(out),
+            PlanExpr::And(parts) | PlanExpr::Or(parts) => {
+                parts.iter().for_each(|part| part.collect_filters(out))
+            }
+        }
+    }
+}
+
+/// Owned counterpart of [`query_segmentation::Segment`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanSegment {
+    Substr(String),
+    Prefix(String),
+    Suffix(String),
+    Exact(String),
+    Star,
+    GlobStar,
+}
+
+impl From<&Segment<'_>> for PlanSegment {
+    fn from(segment: &Segment<'_>) -> Self {
+        match segment {
+            Segment::Concrete(SegmentConcrete::Substr(value)) => Self::Substr(value.to_string()),
+            Segment::Concrete(SegmentConcrete::Prefix(value)) => Self::Prefix(value.to_string()),
+            Segment::Concrete(SegmentConcrete::Suffix(value)) => Self::Suffix(value.to_string()),
+            Segment::Concrete(SegmentConcrete::Exact(value)) => Self::Exact(value.to_string()),
+            Segment::Star => Self::Star,
+            Segment::GlobStar => Self::GlobStar,
+        }
+    }
+}
+
+/// Why a query was rejected, with the byte range of the input it points at.
+///
+/// Syntax errors point at the offending character (an empty range at the end
+/// of input when something is missing). Invalid filter arguments and regexes
+/// point at the text of the term when it can be found verbatim in the input,
+/// otherwise at the whole query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (at bytes {}..{})",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl QueryError {
+    fn from_parse(err: ParseError, line: &str) -> Self {
+        let start = err.position.min(line.len());
+        let end = line[start..]
+            .chars()
+            .next()
+            .map_or(start, |ch| start + ch.len_utf8());
+        Self {
+            message: err.message,
+            span: start..end,
+        }
+    }
+}
+
+impl SearchCache {
+    /// Parses and checks `line` without evaluating it, returning the structure
+    /// the search would run. Aliases registered with
+    /// [`SearchCache::register_alias`] are expanded first, as a search does.
+    ///
+    /// Nothing is read from the index or the filesystem, so this is cheap
+    /// enough to call on every keystroke. A query that validates can still fail
+    /// at search time for reasons that depend on the files (e.g. an
+    /// unreadable `content:` target).
+    pub fn validate_query(&self, line: &str) -> Result<QueryPlan, QueryError> {
+        self.parse_query_collect_errors(line)
+            .map_err(|mut errors| errors.swap_remove(0))
+    }
+
+    /// Like [`SearchCache::validate_query`], but keeps checking after an
+    /// invalid term and reports every error, in query order.
+    ///
+    /// Only semantic errors (filter arguments, regexes) are accumulated: a
+    /// syntax error stops the parser, so it is always reported alone, as is
+    /// an alias that can't be expanded.
+    pub fn parse_query_collect_errors(&self, line: &str) -> Result<QueryPlan, Vec<QueryError>> {
+        let parsed = parse_query(line).map_err(|err| vec![QueryError::from_parse(err, line)])?;
+        let query =
+            rewrite_query(parsed, &self.aliases, SearchOptions::default()).map_err(|err| {
+                vec![QueryError {
+                    message: err.to_string(),
+                    span: 0..line.len(),
+                }]
+            })?;
+        let mut locator = SpanLocator {
+            line,
+            cursor: 0,
+            errors: Vec::new(),
+        };
+        let expr = plan_expr(&query.expr, &mut locator);
+        if locator.errors.is_empty() {
+            Ok(QueryPlan { expr })
+        } else {
+            Err(locator.errors)
+        }
+    }
+}
+
+/// Finds the input text of successive terms so semantic errors get a span.
+struct SpanLocator<'l> {
+    line: &'l str,
+    cursor: usize,
+    errors: Vec<QueryError>,
+}
+
+impl SpanLocator<'_> {
+    fn locate(&mut self, needle: &str) -> Option<Range<usize>> {
+        if needle.is_empty() {
+            return None;
+        }
+        let start = self.cursor + self.line[self.cursor..].find(needle)?;
+        let end = start + needle.len();
+        self.cursor = end;
+        Some(start..end)
+    }
+
+    fn error(&mut self, needle: &str, message: String) {
+        let span = self.locate(needle).unwrap_or(0..self.line.len());
+        self.errors.push(QueryError { message, span });
+    }
+}
+
+/// Invalid terms are recorded on the locator and still planned as written, so
+/// the terms after them are checked too.
+fn plan_expr(expr: &Expr, locator: &mut SpanLocator<'_>) -> PlanExpr {
+    match expr {
+        Expr::Empty => PlanExpr::Empty,
+        Expr::Term(term) => plan_term(term, locator),
+        Expr::Not(inner) => PlanExpr::Not(Box::new(plan_expr(inner, locator))),
+        Expr::And(parts) => PlanExpr::And(plan_parts(parts, locator)),
+        Expr::Or(parts) => PlanExpr::Or(plan_parts(parts, locator)),
+    }
+}
+
+fn plan_parts(parts: &[Expr], locator: &mut SpanLocator<'_>) -> Vec<PlanExpr> {
+    parts.iter().map(|part| plan_expr(part, locator)).collect()
+}
+
+fn plan_term(term: &Term, locator: &mut SpanLocator<'_>) -> PlanExpr {
+    match term {
+        Term::Word(text) => PlanExpr::Name(plan_phrase(text, locator)),
+        Term::Regex(pattern) => {
+            match RegexBuilder::new(pattern).build() {
+                Ok(_) => {
+                    locator.locate(pattern);
+                }
+                Err(err) => locator.error(pattern, format!("Invalid regex pattern: {err}")),
+            }
+            PlanExpr::Regex(pattern.clone())
+        }
+        Term::Filter(filter) => {
+            let needle = filter.argument.as_ref().map_or("", |arg| arg.raw.as_str());
+            if let Err(err) = validate_filter(filter) {
+                locator.error(needle, err.to_string());
+            } else if let (
+                FilterKind::File
+                | FilterKind::Folder
+                | FilterKind::Audio
+                | FilterKind::Video
+                | FilterKind::Doc
+                | FilterKind::Exe,
+                Some(_),
+            ) = (&filter.kind, &filter.argument)
+            {
+                plan_phrase(needle, locator);
+            } else {
+                locator.locate(needle);
+            }
+            PlanExpr::Filter(filter.clone())
+        }
+    }
+}
+
+fn plan_phrase(text: &str, locator: &mut SpanLocator<'_>) -> Vec<PlanSegment> {
+    let segments = query_segmentation(text);
+    if let Err(err) = build_segment_matchers(&segments, SearchOptions::default()) {
+        locator.error(text, format!("Invalid regex pattern: {err}"));
+    } else {
+        locator.locate(text);
+    }
+    segments.iter().map(PlanSegment::from).collect()
+}
@@ -105,6 +105,40 @@ fn strip_term_quotes(term: Term) -> Term {
     }
 }
 
+/// Joins runs of adjacent words under an AND back into one space-separated
+/// word, so `my report` is looked up as the name fragment `my report` rather
+/// than as two. Filters, regexes, negations and `|` break a run. Runs of
+/// spaces come back as one, since the parser doesn't keep them.
+pub(crate) fn join_spaced_words(mut query: Query) -> Query {
+    query.expr = join_expr_words(query.expr);
+    query
+}
+
+fn join_expr_words(expr: Expr) -> Expr {
+    match expr {
+        Expr::Not(inner) => Expr::Not(Box::new(join_expr_words(*inner))),
+        Expr::Or(parts) => Expr::Or(parts.into_iter().map(join_expr_words).collect()),
+        Expr::And(parts) => {
+            let mut joined: Vec<Expr> = Vec::with_capacity(parts.len());
+            for part in parts.into_iter().map(join_expr_words) {
+                match (joined.last_mut(), part) {
+                    (Some(Expr::Term(Term::Word(run))), Expr::Term(Term::Word(word))) => {
+                        run.push(' ');
+                        run.push_str(&word);
+                    }
+                    (_, part) => joined.push(part),
+                }
+            }
+            if joined.len() == 1 {
+                joined.pop().expect("one part left")
+            } else {
+                Expr::And(joined)
+            }
+        }
+        expr => expr,
+    }
+}
+
 pub fn strip_query_quotes_text(value: &str) -> String {
     if !value.contains('"') && !value.contains('\\') {
         return value.to_string();
@@ -140,7 +174,15 @@ fn filter_requires_path(kind: &FilterKind) -> bool {
     // Only expand filters whose semantics require filesystem-like paths.
     matches!(
         kind,
-        FilterKind::Parent | FilterKind::InFolder | FilterKind::NoSubfolders
+        FilterKind::Parent
+            | FilterKind::Siblings
+            | FilterKind::InFolder
+            | FilterKind::NoSubfolders
+            | FilterKind::PathPrefix
+            | FilterKind::Path
+            | FilterKind::SameExtension
+            | FilterKind::NewerThan
+            | FilterKind::OlderThan
     )
 }
 
@@ -0,0 +1,37 @@
+//! Canned searches for one-click smart folders, so frontends don't spell out
+//! query syntax themselves.
+
+/// A smart folder run by [`SearchCache::quick_filter`](crate::SearchCache::quick_filter).
+/// Each one is a fixed query; [`Self::query`] returns it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuickFilter {
+    /// Anything that landed in the Downloads folder during the past week:
+    /// `infolder:~/Downloads dc:pastweek`.
+    RecentDownloads,
+    /// Audio, video and pictures over 100 MB:
+    /// `file: size:>100mb <audio:|video:|type:picture>`.
+    LargeMedia,
+    /// Documents changed since midnight: `doc: dm:today`.
+    TodaysWork,
+    /// Files sharing their name with another file: `file: dupe:`.
+    DuplicateNames,
+}
+
+impl QuickFilter {
+    pub const ALL: [QuickFilter; 4] = [
+        QuickFilter::RecentDownloads,
+        QuickFilter::LargeMedia,
+        QuickFilter::TodaysWork,
+        QuickFilter::DuplicateNames,
+    ];
+
+    /// The query this filter runs.
+    pub const fn query(self) -> &'static str {
+        match self {
+            QuickFilter::RecentDownloads => "infolder:~/Downloads dc:pastweek",
+            QuickFilter::LargeMedia => "file: size:>100mb <audio:|video:|type:picture>",
+            QuickFilter::TodaysWork => "doc: dm:today",
+            QuickFilter::DuplicateNames => "file: dupe:",
+        }
+    }
+}
@@ -8,14 +8,15 @@
 //! - next_batch 从通道接收结果
 //! - 无惰性遍历，只有一个遍历源
 
-use crate::prefetch_thread::{
-    PrefetchState, PrefetchMessage,
-    start_prefetch_thread_rwlock,
+use crate::{
+    SearchCache, SearchOptions, SlabIndex,
+    prefetch_thread::{PrefetchMessage, PrefetchState, start_prefetch_thread_rwlock},
 };
-use crate::{SearchCache, SearchOptions, SlabIndex};
 use search_cancel::CancellationToken;
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 use tracing::info;
 
 /// 搜索结果数量回调函数类型
@@ -105,7 +106,8 @@ impl SearchIterator {
         drop(cache_guard);
 
         // 创建回调存储
-        let on_search_complete: Arc<RwLock<Option<SearchResultNumCallback>>> = Arc::new(RwLock::new(Some(Arc::new(on_search_complete))));
+        let on_search_complete: Arc<RwLock<Option<SearchResultNumCallback>>> =
+            Arc::new(RwLock::new(Some(Arc::new(on_search_complete))));
 
         // 启动后台遍历线程
         let prefetch_state = start_prefetch_thread_rwlock(
@@ -128,10 +130,7 @@ impl SearchIterator {
     /// 获取下一批结果（纯后台遍历模式）
     ///
     /// 从后台遍历线程的通道读取结果
-    pub fn next_batch(
-        &mut self,
-        max_count: usize,
-    ) -> SearchBatch {
+    pub fn next_batch(&mut self, max_count: usize) -> SearchBatch {
         // 检查是否已取消
         if self.state.cancelled {
             return SearchBatch {
@@ -144,7 +143,9 @@ impl SearchIterator {
         let mut result_indices = Vec::with_capacity(max_count);
 
         // 1. 首先从预取缓冲区返回已有的结果
-        while result_indices.len() < max_count && self.prefetch_state.buffer_pos < self.prefetch_state.buffer.len() {
+        while result_indices.len() < max_count
+            && self.prefetch_state.buffer_pos < self.prefetch_state.buffer.len()
+        {
             result_indices.push(self.prefetch_state.buffer[self.prefetch_state.buffer_pos]);
             self.prefetch_state.buffer_pos += 1;
             self.state.yielded_count += 1;
@@ -179,7 +180,9 @@ impl SearchIterator {
         }
 
         // 3. 最后从预取缓冲区取数据
-        while result_indices.len() < max_count && self.prefetch_state.buffer_pos < self.prefetch_state.buffer.len() {
+        while result_indices.len() < max_count
+            && self.prefetch_state.buffer_pos < self.prefetch_state.buffer.len()
+        {
             result_indices.push(self.prefetch_state.buffer[self.prefetch_state.buffer_pos]);
             self.prefetch_state.buffer_pos += 1;
             self.state.yielded_count += 1;
@@ -187,7 +190,8 @@ impl SearchIterator {
 
         SearchBatch {
             indices: result_indices,
-            has_more: !self.prefetch_state.prefetch_done || self.prefetch_state.buffer_pos < self.prefetch_state.buffer.len(),
+            has_more: !self.prefetch_state.prefetch_done
+                || self.prefetch_state.buffer_pos < self.prefetch_state.buffer.len(),
             search_completed: self.state.search_completed,
         }
     }
@@ -209,7 +213,9 @@ impl SearchIterator {
 
     /// 检查后台遍历线程是否完成
     pub fn is_background_thread_done(&self) -> bool {
-        self.prefetch_state.background_thread_done.load(std::sync::atomic::Ordering::Relaxed)
+        self.prefetch_state
+            .background_thread_done
+            .load(std::sync::atomic::Ordering::Relaxed)
     }
 
     /// 检查是否有预取数据可用
@@ -244,8 +250,8 @@ fn generate_unique_id() -> u64 {
 mod tests {
     use super::*;
     use crate::SearchCache;
-    use tempdir::TempDir;
     use std::fs;
+    use tempdir::TempDir;
 
     /// 创建测试用的临时目录和文件
     fn setup_test_cache() -> (TempDir, Arc<RwLock<SearchCache>>) {
@@ -295,7 +301,8 @@ mod tests {
             10,
             CancellationToken::noop(),
             |_| {}, // 空回调
-        ).unwrap();
+        )
+        .unwrap();
 
         // 第一批获取 - 应该能获取到至少 1 个结果
         let batch1 = iterator.next_batch(10);
@@ -314,7 +321,8 @@ mod tests {
             10,
             CancellationToken::noop(),
             |_| {}, // 空回调
-        ).unwrap();
+        )
+        .unwrap();
 
         let batch = iterator.next_batch(10);
         // 预取模式下，搜索会完成但可能没有结果
@@ -1,9 +1,106 @@
+use crate::volume_case;
+use fswalk::ThreadBudget;
 use query_segmentation::{Segment, SegmentConcrete};
 use regex::{Regex, RegexBuilder};
+use std::{ops::Range, path::Path};
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy)]
 pub struct SearchOptions {
     pub case_insensitive: bool,
+    /// With `case_insensitive`, compare names and tags after full Unicode
+    /// lowercasing (`STRASSE` ~ `Straße`, `istanbul` ~ `İstanbul`) instead of
+    /// the default folding. Every candidate is folded, so this is slower.
+    pub unicode_case_fold: bool,
+    /// Compare names and tags with combining diacritics stripped, so `resume`
+    /// finds `Résumé`. Independent of case folding: without
+    /// `case_insensitive`, `Resume` still doesn't match `résumé`.
+    pub accent_insensitive: bool,
+    /// Match plain words against each node's whole path rather than its name,
+    /// so `Users/demo` finds everything under `/Users/demo`. Every path is
+    /// rebuilt per search, so this is much slower than name matching.
+    pub match_full_path: bool,
+    /// Treat the spaces between plain words as part of one name fragment, so
+    /// `my report` finds `my report.txt` but not `report for my team`.
+    /// Filters and operators still split the query as usual.
+    pub space_is_literal: bool,
+    /// Let `type:` groups with well-known file signatures (pictures, video,
+    /// audio, PDF, archives) also match files whose extension doesn't fit,
+    /// by reading their first bytes. Only those files are read, 16 bytes
+    /// each, but that is still one open per candidate.
+    pub sniff_content: bool,
+    /// Fill [`SearchOutcome::diagnostics`](crate::SearchOutcome) with timing
+    /// and scan counts for this search.
+    pub collect_diagnostics: bool,
+    /// Threads the parallel filters (`content:`, `tag:`) may use.
+    pub thread_budget: ThreadBudget,
+    /// Queries with fewer characters than this, surrounding whitespace aside,
+    /// return no nodes without scanning anything and set
+    /// [`SearchOutcome::below_min_length`](crate::SearchOutcome). The empty
+    /// query is never held back; [`Self::empty_query`] decides what it
+    /// returns. Defaults to 1.
+    pub min_query_len: usize,
+    /// What a query with nothing to match returns: the empty string, only
+    /// whitespace, or only empty groups.
+    pub empty_query: EmptyBehavior,
+}
+
+/// See [`SearchOptions::empty_query`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyBehavior {
+    /// No nodes, so a cleared search box doesn't pull in the whole index.
+    #[default]
+    MatchNone,
+    /// Every node in the index.
+    MatchAll,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            case_insensitive: false,
+            unicode_case_fold: false,
+            accent_insensitive: false,
+            match_full_path: false,
+            space_is_literal: false,
+            sniff_content: false,
+            collect_diagnostics: false,
+            thread_budget: ThreadBudget::default(),
+            min_query_len: 1,
+            empty_query: EmptyBehavior::MatchNone,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// The defaults, with [`Self::case_insensitive`] following the volume
+    /// holding `path`: on for APFS and HFS+ as usually formatted, off for most
+    /// Linux file systems. Probing writes a scratch file next to `path` once
+    /// per volume; where that fails (no such path, read-only volume), macOS
+    /// is assumed case-insensitive and everything else case-sensitive.
+    pub fn for_volume(path: &Path) -> Self {
+        Self {
+            case_insensitive: volume_case::is_case_insensitive(path)
+                .unwrap_or(cfg!(target_os = "macos")),
+            ..Self::default()
+        }
+    }
+
+    /// Whether `line` is too short to run under [`Self::min_query_len`].
+    pub(crate) fn gates(&self, line: &str) -> bool {
+        let len = line.trim().chars().count();
+        len > 0 && len < self.min_query_len
+    }
+
+    pub(crate) fn unicode_fold(&self) -> bool {
+        self.case_insensitive && self.unicode_case_fold
+    }
+
+    /// Case-insensitive without [`Self::unicode_fold`], so case is still up to
+    /// the regex (names) or ASCII lowercasing (tags), even after a [`Fold`].
+    pub(crate) fn simple_case_fold(&self) -> bool {
+        self.case_insensitive && !self.unicode_case_fold
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -23,8 +120,18 @@ pub(crate) enum SegmentMatcher {
 
 #[derive(Clone, Debug)]
 pub(crate) enum SegmentMatcherConcrete {
-    Plain { kind: SegmentKind, needle: String },
-    Regex { regex: Regex },
+    Plain {
+        kind: SegmentKind,
+        needle: String,
+    },
+    Regex {
+        regex: Regex,
+    },
+    /// Matches `inner` against the candidate rewritten by `fold`.
+    Folded {
+        inner: Box<SegmentMatcherConcrete>,
+        fold: Fold,
+    },
 }
 
 impl SegmentMatcherConcrete {
@@ -37,7 +144,130 @@ impl SegmentMatcherConcrete {
                 SegmentKind::Exact => candidate == needle,
             },
             SegmentMatcherConcrete::Regex { regex } => regex.is_match(candidate),
+            SegmentMatcherConcrete::Folded { inner, fold } => inner.matches(&fold.apply(candidate)),
+        }
+    }
+
+    /// Appends the byte ranges of `candidate` matched by this segment to `out`.
+    pub(crate) fn match_ranges(&self, candidate: &str, out: &mut Vec<Range<usize>>) {
+        match self {
+            SegmentMatcherConcrete::Plain { kind, needle } => match kind {
+                SegmentKind::Substr => out.extend(
+                    candidate
+                        .match_indices(needle.as_str())
+                        .map(|(start, matched)| start..start + matched.len()),
+                ),
+                SegmentKind::Prefix => {
+                    if candidate.starts_with(needle.as_str()) {
+                        out.push(0..needle.len());
+                    }
+                }
+                SegmentKind::Suffix => {
+                    if candidate.ends_with(needle.as_str()) {
+                        out.push(candidate.len() - needle.len()..candidate.len());
+                    }
+                }
+                SegmentKind::Exact => {
+                    if candidate == needle {
+                        out.push(0..candidate.len());
+                    }
+                }
+            },
+            SegmentMatcherConcrete::Regex { regex } => {
+                out.extend(regex.find_iter(candidate).map(|found| found.range()))
+            }
+            SegmentMatcherConcrete::Folded { inner, fold } => {
+                let (folded, sources) = fold.apply_with_sources(candidate);
+                let mut folded_ranges = Vec::new();
+                inner.match_ranges(&folded, &mut folded_ranges);
+                // Map folded byte ranges back onto whole source characters.
+                out.extend(
+                    folded_ranges
+                        .into_iter()
+                        .filter(|range| !range.is_empty())
+                        .map(|range| {
+                            let last = sources[range.end - 1];
+                            let end =
+                                last + candidate[last..].chars().next().map_or(0, char::len_utf8);
+                            sources[range.start]..end
+                        }),
+                );
+            }
+        }
+    }
+}
+
+/// The rewrites a [`SegmentMatcherConcrete::Folded`] matcher applies to both
+/// needle and candidate. Accents are stripped before case is folded, so `É`
+/// becomes `e` rather than `E`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Fold {
+    unicode_case: bool,
+    accents: bool,
+}
+
+impl Fold {
+    /// `None` when `options` compare text as-is or with the regex's own
+    /// case-insensitivity.
+    pub(crate) fn from_options(options: SearchOptions) -> Option<Self> {
+        let fold = Fold {
+            unicode_case: options.unicode_fold(),
+            accents: options.accent_insensitive,
+        };
+        (fold.unicode_case || fold.accents).then_some(fold)
+    }
+
+    pub(crate) fn apply(self, text: &str) -> String {
+        let mut folded = String::with_capacity(text.len());
+        for ch in text.chars() {
+            self.push(ch, &mut folded);
         }
+        folded
+    }
+
+    /// Like [`Self::apply`], also returning the source byte offset of the
+    /// character each folded byte came from.
+    fn apply_with_sources(self, text: &str) -> (String, Vec<usize>) {
+        let mut folded = String::with_capacity(text.len());
+        let mut sources = Vec::with_capacity(text.len());
+        for (offset, ch) in text.char_indices() {
+            self.push(ch, &mut folded);
+            sources.resize(folded.len(), offset);
+        }
+        (folded, sources)
+    }
+
+    fn push(self, ch: char, out: &mut String) {
+        if !self.accents {
+            self.push_case(ch, out);
+            return;
+        }
+        // Canonical decomposition splits `é` into `e` and U+0301; dropping
+        // the combining marks leaves the base letters.
+        decompose_canonical(ch, |part| {
+            if !is_combining_mark(part) {
+                self.push_case(part, out);
+            }
+        });
+    }
+
+    fn push_case(self, ch: char, out: &mut String) {
+        if self.unicode_case {
+            push_unicode_folded(ch, out);
+        } else {
+            out.push(ch);
+        }
+    }
+}
+
+/// Full Unicode lowercasing used by [`SearchOptions::unicode_case_fold`],
+/// plus the expansions `to_lowercase` leaves alone (`ß` -> `ss`, `İ` -> `i`).
+fn push_unicode_folded(ch: char, out: &mut String) {
+    match ch {
+        'ß' | 'ẞ' => out.push_str("ss"),
+        'İ' => out.push('i'),
+        'ς' => out.push('σ'),
+        _ => out.extend(ch.to_lowercase()),
     }
 }
 
@@ -77,10 +307,59 @@ fn build_concrete_segment_matcher(
     segment: &SegmentConcrete<'_>,
     options: SearchOptions,
 ) -> Result<SegmentMatcher, regex::Error> {
-    let kind = segment_kind(segment);
-    let value = segment_value(segment);
-    let is_wildcard = value.contains('*') || value.contains('?');
-    if options.case_insensitive || is_wildcard {
+    build_folded_matcher(segment_kind(segment), segment_value(segment), true, options)
+        .map(SegmentMatcher::Concrete)
+}
+
+/// Matches the whole file name for `name:`, or the whole path for `path:`. A
+/// `literal` value keeps `*` and `?` as plain characters instead of wildcards.
+pub(crate) fn build_name_matcher(
+    value: &str,
+    literal: bool,
+    options: SearchOptions,
+) -> Result<SegmentMatcherConcrete, regex::Error> {
+    build_folded_matcher(SegmentKind::Exact, value, !literal, options)
+}
+
+/// Matches a plain word anywhere in a whole path for
+/// [`SearchOptions::match_full_path`]. `/` is an ordinary character here,
+/// and wildcards anchor the pattern at both ends as they do for names.
+pub(crate) fn build_path_matcher(
+    value: &str,
+    options: SearchOptions,
+) -> Result<SegmentMatcherConcrete, regex::Error> {
+    build_folded_matcher(SegmentKind::Substr, value, true, options)
+}
+
+fn build_folded_matcher(
+    kind: SegmentKind,
+    value: &str,
+    wildcards: bool,
+    options: SearchOptions,
+) -> Result<SegmentMatcherConcrete, regex::Error> {
+    if let Some(fold) = Fold::from_options(options) {
+        let inner = build_pattern_matcher(
+            kind,
+            &fold.apply(value),
+            options.simple_case_fold(),
+            wildcards,
+        )?;
+        return Ok(SegmentMatcherConcrete::Folded {
+            inner: Box::new(inner),
+            fold,
+        });
+    }
+    build_pattern_matcher(kind, value, options.case_insensitive, wildcards)
+}
+
+fn build_pattern_matcher(
+    kind: SegmentKind,
+    value: &str,
+    case_insensitive: bool,
+    wildcards: bool,
+) -> Result<SegmentMatcherConcrete, regex::Error> {
+    let is_wildcard = wildcards && (value.contains('*') || value.contains('?'));
+    if case_insensitive || is_wildcard {
         let pattern = if is_wildcard {
             // Wildcard pattern is /exact/ by default, so we don't need to
             // adjust it based on SegmentKind.
@@ -95,15 +374,15 @@ fn build_concrete_segment_matcher(
             }
         };
         let mut builder = RegexBuilder::new(&pattern);
-        builder.case_insensitive(options.case_insensitive);
+        builder.case_insensitive(case_insensitive);
         builder
             .build()
-            .map(|regex| SegmentMatcher::Concrete(SegmentMatcherConcrete::Regex { regex }))
+            .map(|regex| SegmentMatcherConcrete::Regex { regex })
     } else {
-        Ok(SegmentMatcher::Concrete(SegmentMatcherConcrete::Plain {
+        Ok(SegmentMatcherConcrete::Plain {
             kind,
             needle: value.to_string(),
-        }))
+        })
     }
 }
 
@@ -128,11 +407,20 @@ fn segment_value<'s>(segment: &SegmentConcrete<'s>) -> &'s str {
 #[cfg(test)]
 mod tests {
     use super::{
-        SearchOptions, SegmentKind, SegmentMatcher, SegmentMatcherConcrete, build_segment_matchers,
-        segment_kind, segment_value, wildcard_to_regex,
+        Fold, SearchOptions, SegmentKind, SegmentMatcher, SegmentMatcherConcrete,
+        build_segment_matchers, segment_kind, segment_value, wildcard_to_regex,
     };
     use query_segmentation::{Segment, SegmentConcrete};
 
+    const UNICODE_CASE: Fold = Fold {
+        unicode_case: true,
+        accents: false,
+    };
+
+    fn unicode_fold(text: &str) -> String {
+        UNICODE_CASE.apply(text)
+    }
+
     // --- wildcard_to_regex edge cases ---
 
     #[test]
@@ -243,6 +531,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         assert_eq!(matchers.len(), 4);
@@ -270,6 +559,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         assert_eq!(matchers.len(), 4);
@@ -295,6 +585,7 @@ mod tests {
         let segments = [Segment::exact("foo*bar?baz")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         assert_eq!(matchers.len(), 1);
@@ -311,6 +602,7 @@ mod tests {
         let segments = [Segment::substr("A*B")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         match &matchers[0] {
@@ -327,6 +619,7 @@ mod tests {
         let segments = [Segment::substr("A*B")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).expect("ok");
         match &matchers[0] {
@@ -387,6 +680,7 @@ mod tests {
         let segments = [Segment::substr("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -402,6 +696,7 @@ mod tests {
         let segments = [Segment::prefix("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -418,6 +713,7 @@ mod tests {
         let segments = [Segment::suffix("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -434,6 +730,7 @@ mod tests {
         let segments = [Segment::exact("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
         match m {
@@ -457,6 +754,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         assert_eq!(matchers.len(), 4);
@@ -488,6 +786,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         for m in matchers {
@@ -505,6 +804,7 @@ mod tests {
         let segments = [Segment::exact("a+b*(c?)")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -525,6 +825,7 @@ mod tests {
         let segments = [Segment::substr("Café")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -540,6 +841,7 @@ mod tests {
         let segments = [Segment::exact("Café")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -562,6 +864,7 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         assert_eq!(matchers.len(), 3);
@@ -594,6 +897,7 @@ mod tests {
         let segments = [Segment::exact(&long)];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         assert_eq!(matchers.len(), 1);
@@ -612,6 +916,7 @@ mod tests {
         let segments = [Segment::exact("a*b*c?d")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -630,6 +935,7 @@ mod tests {
         let segments = [Segment::substr("mid")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let matchers = build_segment_matchers(&segments, opts).unwrap();
         match &matchers[0] {
@@ -647,6 +953,73 @@ mod tests {
         }
     }
 
+    // --- unicode_case_fold ---
+
+    #[test]
+    fn unicode_fold_expands_sharp_s_and_dotted_i() {
+        assert_eq!(unicode_fold("Straße"), "strasse");
+        assert_eq!(unicode_fold("İSTANBUL"), "istanbul");
+        assert_eq!(unicode_fold("ÉCOLE"), "école");
+    }
+
+    #[test]
+    fn unicode_fold_only_applies_when_case_insensitive() {
+        let segments = [Segment::substr("STRASSE")];
+        let opts = SearchOptions {
+            case_insensitive: false,
+            unicode_case_fold: true,
+            ..Default::default()
+        };
+        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        assert!(matches!(
+            &matchers[0],
+            SegmentMatcher::Concrete(SegmentMatcherConcrete::Plain { .. })
+        ));
+    }
+
+    #[test]
+    fn folded_matcher_matches_full_case_variants() {
+        let segments = [Segment::substr("STRASSE"), Segment::exact("istanbul")];
+        let unicode = SearchOptions {
+            case_insensitive: true,
+            unicode_case_fold: true,
+            ..Default::default()
+        };
+        let ascii = SearchOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+        let folded = build_segment_matchers(&segments, unicode).unwrap();
+        let regex = build_segment_matchers(&segments, ascii).unwrap();
+        let (
+            SegmentMatcher::Concrete(folded_street),
+            SegmentMatcher::Concrete(folded_city),
+            SegmentMatcher::Concrete(regex_street),
+            SegmentMatcher::Concrete(regex_city),
+        ) = (&folded[0], &folded[1], &regex[0], &regex[1])
+        else {
+            panic!("expected concrete matchers");
+        };
+        assert!(folded_street.matches("Hauptstraße.txt"));
+        assert!(folded_city.matches("İstanbul"));
+        assert!(!regex_street.matches("Hauptstraße.txt"));
+        assert!(!regex_city.matches("İstanbul"));
+    }
+
+    #[test]
+    fn folded_match_ranges_cover_source_characters() {
+        let matcher = SegmentMatcherConcrete::Folded {
+            inner: Box::new(SegmentMatcherConcrete::Plain {
+                kind: SegmentKind::Substr,
+                needle: "strasse".to_string(),
+            }),
+            fold: UNICODE_CASE,
+        };
+        let mut ranges = Vec::new();
+        matcher.match_ranges("Haupt-Straße", &mut ranges);
+        assert_eq!(ranges, vec![6.."Haupt-Straße".len()]);
+    }
+
     fn expect_concrete<'a>(segment: &'a Segment<'a>) -> &'a SegmentConcrete<'a> {
         match segment {
             Segment::Concrete(concrete) => concrete,
@@ -654,4 +1027,49 @@ mod tests {
             Segment::Star => panic!("expected concrete segment"),
         }
     }
+
+    // --- accent_insensitive ---
+
+    #[test]
+    fn accent_fold_strips_marks_before_folding_case() {
+        let accents = Fold {
+            unicode_case: false,
+            accents: true,
+        };
+        assert_eq!(accents.apply("Résumé"), "Resume");
+        // Already decomposed input loses its combining marks too.
+        assert_eq!(accents.apply("Re\u{301}sume\u{301}"), "Resume");
+        let both = Fold {
+            unicode_case: true,
+            accents: true,
+        };
+        assert_eq!(both.apply("ÉCOLE Straße"), "ecole strasse");
+    }
+
+    #[test]
+    fn accent_insensitive_keeps_case_sensitivity_separate() {
+        let segments = [Segment::substr("resume")];
+        let matcher = |case_insensitive| {
+            let opts = SearchOptions {
+                case_insensitive,
+                accent_insensitive: true,
+                ..Default::default()
+            };
+            match build_segment_matchers(&segments, opts).unwrap().remove(0) {
+                SegmentMatcher::Concrete(matcher) => matcher,
+                other => panic!("expected a concrete matcher, got {other:?}"),
+            }
+        };
+        let sensitive = matcher(false);
+        assert!(sensitive.matches("my résumé.pdf"));
+        assert!(!sensitive.matches("Résumé.pdf"));
+        let insensitive = matcher(true);
+        assert!(insensitive.matches("Résumé.pdf"));
+        assert!(insensitive.matches("RESUMÉ.pdf"));
+
+        // Highlights cover the accented source characters.
+        let mut ranges = Vec::new();
+        insensitive.match_ranges("Résumé.pdf", &mut ranges);
+        assert_eq!(ranges, vec![0.."Résumé".len()]);
+    }
 }
@@ -1,11 +1,17 @@
 use crate::{NAME_POOL, OptionSlabIndex, SlabIndex, State, StateTypeSize};
-use fswalk::NodeFileType;
+use fswalk::{NodeFileType, name_to_os_str};
 use serde::{
     Deserialize, Serialize,
     de::{self, SeqAccess, Visitor},
     ser::SerializeTuple,
 };
-use std::{fmt, num::NonZeroU32};
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    fmt,
+    num::NonZeroU32,
+    sync::atomic::{AtomicU32, Ordering},
+};
 use thin_vec::ThinVec;
 
 #[derive(Debug, Clone, Copy)]
@@ -96,11 +102,36 @@ impl NameAndParent {
     }
 }
 
+/// Next [`SlabNode::index_seq`]. Loading a cache moves it past every stored
+/// sequence, so nodes learned after a restart still sort after the old ones.
+static NEXT_INDEX_SEQ: AtomicU32 = AtomicU32::new(0);
+
+/// Takes the next sequence. Once the counter runs out every newer node gets
+/// `u32::MAX`, tying among themselves but still after the older ones.
+fn next_index_seq() -> u32 {
+    match NEXT_INDEX_SEQ.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |seq| {
+        seq.checked_add(1)
+    }) {
+        Ok(seq) | Err(seq) => seq,
+    }
+}
+
+fn deserialize_index_seq<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let seq = u32::deserialize(deserializer)?;
+    NEXT_INDEX_SEQ.fetch_max(seq.saturating_add(1), Ordering::Relaxed);
+    Ok(seq)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SlabNode {
     name_and_parent: NameAndParent,
     pub children: ThinVec<SlabIndex>,
     pub metadata: SlabNodeMetadataCompact,
+    #[serde(deserialize_with = "deserialize_index_seq")]
+    index_seq: u32,
 }
 
 impl SlabNode {
@@ -108,6 +139,12 @@ impl SlabNode {
         self.name_and_parent.as_str()
     }
 
+    /// The on-disk file name, which differs from [`Self::name`] only for names
+    /// that aren't valid UTF-8. Use it to build or compare paths.
+    pub fn os_name(&self) -> Cow<'static, OsStr> {
+        name_to_os_str(self.name())
+    }
+
     pub fn parent(&self) -> Option<SlabIndex> {
         self.name_and_parent.parent()
     }
@@ -120,6 +157,18 @@ impl SlabNode {
         self.metadata.state()
     }
 
+    /// When the node entered the index, as a process-wide counter that only
+    /// grows. Unrelated to mtime: a rescanned node counts as newly indexed.
+    pub fn index_seq(&self) -> u32 {
+        self.index_seq
+    }
+
+    /// Swaps in a new name, keeping the parent. Whatever indexes the node by
+    /// name has to be updated alongside.
+    pub(crate) fn rename(&mut self, name: &'static str) {
+        self.name_and_parent = NameAndParent::new(name, self.name_and_parent.parent);
+    }
+
     pub fn add_children(&mut self, children: SlabIndex) {
         if !self.children.contains(&children) {
             self.children.push(children);
@@ -135,6 +184,7 @@ impl SlabNode {
             name_and_parent: NameAndParent::new(name, OptionSlabIndex::from_option(parent)),
             children: ThinVec::new(),
             metadata,
+            index_seq: next_index_seq(),
         }
     }
 }
@@ -239,4 +289,6 @@ impl SlabNodeMetadataCompact {
 pub struct SearchResultNode {
     pub path: std::path::PathBuf,
     pub metadata: SlabNodeMetadataCompact,
+    /// [`SlabNode::index_seq`], or 0 for a node that's gone.
+    pub index_seq: u32,
 }
@@ -0,0 +1,149 @@
+use crate::{
+    NAME_POOL, SearchCache, SlabIndex,
+    query::{extension_of, first_letter},
+};
+use fswalk::NodeFileType;
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Index totals reported by [`SearchCache::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    pub files: usize,
+    pub directories: usize,
+    /// Only symlinks whose metadata is loaded; without it a node can't be told
+    /// apart from a regular file and is counted in `files`.
+    pub symlinks: usize,
+    /// Distinct names across the indexed nodes.
+    pub unique_names: usize,
+    /// [`NamePool::memory_bytes`](namepool::NamePool::memory_bytes) of the
+    /// process-wide pool, which every cache shares.
+    pub name_pool_bytes: usize,
+}
+
+/// One indexed root with the entries below it, as listed by
+/// [`SearchCache::roots`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootInfo {
+    pub path: PathBuf,
+    /// Everything below the root that isn't a folder.
+    pub total_files: usize,
+    /// Folders below the root, not counting the root itself.
+    pub total_dirs: usize,
+}
+
+impl SearchCache {
+    /// The walked roots, primary first. Nodes below overlapping roots count
+    /// towards each of them.
+    pub fn roots(&self) -> Vec<RootInfo> {
+        self.file_nodes
+            .roots()
+            .iter()
+            .map(|path| {
+                let mut info = RootInfo {
+                    path: path.clone(),
+                    total_files: 0,
+                    total_dirs: 0,
+                };
+                let mut pending: Vec<_> = self
+                    .index_of_path(path)
+                    .map(|root| self.file_nodes[root].children.to_vec())
+                    .unwrap_or_default();
+                while let Some(index) = pending.pop() {
+                    let node = &self.file_nodes[index];
+                    pending.extend(node.children.iter().copied());
+                    match node.file_type_hint() {
+                        NodeFileType::Dir => info.total_dirs += 1,
+                        _ => info.total_files += 1,
+                    }
+                }
+                info
+            })
+            .collect()
+    }
+
+    /// Counts the indexed nodes by type. Reads only what the slab already
+    /// holds, so no metadata is fetched.
+    pub fn stats(&self) -> IndexStats {
+        let mut stats = IndexStats {
+            unique_names: self.name_index.len(),
+            name_pool_bytes: NAME_POOL.memory_bytes(),
+            ..IndexStats::default()
+        };
+        for (_, node) in self.file_nodes.iter() {
+            match node.file_type_hint() {
+                NodeFileType::Dir => stats.directories += 1,
+                NodeFileType::Symlink => stats.symlinks += 1,
+                NodeFileType::File | NodeFileType::Unknown => stats.files += 1,
+            }
+        }
+        stats
+    }
+
+    /// Files per lowercased extension, over the whole index or only below
+    /// `base`. Files without an extension aren't counted.
+    pub fn extension_histogram(&self, base: Option<SlabIndex>) -> BTreeMap<String, usize> {
+        let is_file = |index: &SlabIndex| {
+            self.file_nodes
+                .get(*index)
+                .is_some_and(|node| node.file_type_hint() == NodeFileType::File)
+        };
+        let Some(base) = base else {
+            return self
+                .name_index
+                .extensions()
+                .map(|(extension, names)| {
+                    let files = names
+                        .iter()
+                        .filter_map(|name| self.name_index.get(name))
+                        .map(|indices| indices.iter().filter(|index| is_file(index)).count())
+                        .sum();
+                    (extension.to_string(), files)
+                })
+                .filter(|(_, files)| *files > 0)
+                .collect();
+        };
+        let mut histogram = BTreeMap::new();
+        let mut pending = vec![base];
+        while let Some(index) = pending.pop() {
+            let Some(node) = self.file_nodes.get(index) else {
+                continue;
+            };
+            pending.extend(node.children.iter().copied());
+            if node.file_type_hint() == NodeFileType::File
+                && let Some(extension) = extension_of(node.name())
+            {
+                *histogram.entry(extension).or_default() += 1;
+            }
+        }
+        histogram
+    }
+
+    /// Nodes per lowercased first letter of their name, for an A–Z index over
+    /// the whole index or only below `base`. `startswith:` jumps to a bucket.
+    pub fn alpha_buckets(&self, base: Option<SlabIndex>) -> BTreeMap<char, usize> {
+        let mut buckets = BTreeMap::new();
+        let Some(base) = base else {
+            for (name, indices) in self.name_index.names_with_prefix("") {
+                if let Some(letter) = first_letter(name) {
+                    *buckets.entry(letter).or_default() += indices.len();
+                }
+            }
+            return buckets;
+        };
+        let mut pending: Vec<_> = self
+            .file_nodes
+            .get(base)
+            .map(|node| node.children.to_vec())
+            .unwrap_or_default();
+        while let Some(index) = pending.pop() {
+            let Some(node) = self.file_nodes.get(index) else {
+                continue;
+            };
+            pending.extend(node.children.iter().copied());
+            if let Some(letter) = first_letter(node.name()) {
+                *buckets.entry(letter).or_default() += 1;
+            }
+        }
+        buckets
+    }
+}
@@ -1,5 +1,10 @@
 use super::prelude::*;
-use cardinal_sdk::{EventFlag, FsEvent};
+use crate::{
+    CacheError, CompressionOptions,
+    persistent::{read_cache_from_file, tmp_cache_path, write_cache_to_tmp},
+};
+use cardinal_sdk::{EventFlag, FsEvent, current_event_id};
+use fswalk::WalkData;
 
 #[test]
 fn test_search_empty_returns_all_nodes() {
@@ -33,7 +38,7 @@ fn test_remove_node_path_nonexistent_returns_none() {
     // create then delete file and ensure second scan removal returns None
     let file = tmp.path().join("temp_remove.txt");
     fs::write(&file, b"x").unwrap();
-    let id = cache.last_event_id() + 1;
+    let id = current_event_id() + 1;
     cache
         .handle_fs_events(vec![FsEvent {
             path: file.clone(),
@@ -92,3 +97,217 @@ fn test_persistent_roundtrip() {
         SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, &Vec::new(), None).unwrap();
     assert_eq!(loaded.get_total_files(), original_total);
 }
+
+#[test]
+fn test_fresh_walk_has_no_last_event_id() {
+    let tmp = TempDir::new("event_id_fresh").unwrap();
+    let before = current_event_id();
+    let cache = SearchCache::walk_fs(tmp.path());
+    let after = current_event_id();
+    assert_eq!(cache.last_event_id(), None);
+    // Watching resumes from before the walk, so changes made meanwhile replay.
+    let id = cache.resume_event_id();
+    assert!(
+        (before..=after).contains(&id),
+        "{id} not in {before}..={after}"
+    );
+
+    let walk_data = WalkData::simple(tmp.path(), false).keep_partial(true);
+    let partial = SearchCache::walk_fs_cancellable(&walk_data, None).unwrap();
+    assert_eq!(partial.cache.last_event_id(), None);
+    assert!(partial.cache.resume_event_id() <= current_event_id());
+}
+
+#[test]
+fn test_reload_restores_event_id_current_at_flush() {
+    let tmp = TempDir::new("event_id_reload").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    let cache = SearchCache::walk_fs(tmp.path());
+
+    let before = current_event_id();
+    cache.flush_to_file(&cache_path).unwrap();
+    let after = current_event_id();
+
+    let loaded =
+        SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, &Vec::new(), None).unwrap();
+    let stored = loaded
+        .last_event_id()
+        .expect("loaded cache keeps its event id");
+    assert!(
+        (before..=after).contains(&stored),
+        "{stored} not in {before}..={after}"
+    );
+    assert_eq!(loaded.resume_event_id(), stored);
+}
+
+#[test]
+fn test_reload_restores_last_applied_event_id() {
+    let tmp = TempDir::new("event_id_applied").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let file = tmp.path().join("new.txt");
+    fs::write(&file, b"x").unwrap();
+    let id = current_event_id() + 1;
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: file,
+            id,
+            flag: EventFlag::ItemCreated,
+        }])
+        .unwrap();
+    assert_eq!(cache.last_event_id(), Some(id));
+
+    cache.flush_to_file(&cache_path).unwrap();
+    let loaded =
+        SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, &Vec::new(), None).unwrap();
+    assert_eq!(loaded.last_event_id(), Some(id));
+}
+
+#[test]
+fn test_interrupted_flush_keeps_previous_cache() {
+    let tmp = TempDir::new("flush_atomic").unwrap();
+    let root = tmp.path().join("root");
+    fs::create_dir(&root).unwrap();
+    fs::write(root.join("a.bin"), b"data").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    let cache = SearchCache::walk_fs(&root);
+    let good_total = cache.get_total_files();
+    cache.flush_to_file(&cache_path).unwrap();
+
+    // Stage a newer snapshot but stop before the rename, as a kill would.
+    let mut staged = read_cache_from_file(&cache_path).unwrap();
+    staged.rescan_count = 7;
+    let staged_path =
+        write_cache_to_tmp(&cache_path, &staged, CompressionOptions::default()).unwrap();
+    assert_eq!(staged_path, tmp_cache_path(&cache_path));
+    let loaded =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, &Vec::new(), None).unwrap();
+    assert_eq!(loaded.rescan_count(), 0);
+    assert_eq!(loaded.get_total_files(), good_total);
+
+    // A truncated leftover from a killed write doesn't affect the next flush.
+    fs::write(&staged_path, b"partial").unwrap();
+    fs::write(root.join("b.bin"), b"data").unwrap();
+    let cache = SearchCache::walk_fs(&root);
+    let new_total = cache.get_total_files();
+    cache.flush_to_file(&cache_path).unwrap();
+    assert!(!staged_path.exists());
+    let loaded =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, &Vec::new(), None).unwrap();
+    assert_eq!(loaded.get_total_files(), new_total);
+    assert_eq!(new_total, good_total + 1);
+}
+
+/// Loads the cache like the app does on launch, rewalking when it's unusable.
+fn load_or_rewalk(root: &std::path::Path, cache_path: &std::path::Path) -> (SearchCache, bool) {
+    match SearchCache::try_read_persistent_cache(root, cache_path, &Vec::new(), None) {
+        Ok(cache) => (cache, false),
+        Err(err) => {
+            assert!(err.downcast_ref::<CacheError>().is_some(), "{err:#}");
+            (SearchCache::walk_fs(root), true)
+        }
+    }
+}
+
+#[test]
+fn test_cache_from_older_version_is_rejected_and_rewalked() {
+    let tmp = TempDir::new("cache_version").unwrap();
+    let root = tmp.path().join("root");
+    fs::create_dir(&root).unwrap();
+    fs::write(root.join("a.bin"), b"data").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    SearchCache::walk_fs(&root)
+        .flush_to_file(&cache_path)
+        .unwrap();
+
+    // Rewrite the version that follows the 8-byte magic.
+    let mut bytes = fs::read(&cache_path).unwrap();
+    let expected = i64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    bytes[8..16].copy_from_slice(&(expected - 1).to_le_bytes());
+    fs::write(&cache_path, &bytes).unwrap();
+
+    let err =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, &Vec::new(), None).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<CacheError>(),
+        Some(&CacheError::VersionMismatch {
+            expected,
+            found: expected - 1
+        })
+    );
+
+    let (mut cache, rewalked) = load_or_rewalk(&root, &cache_path);
+    assert!(rewalked);
+    assert_eq!(cache.search("a.bin").unwrap().len(), 1);
+    cache.flush_to_file(&cache_path).unwrap();
+    let (_, rewalked) = load_or_rewalk(&root, &cache_path);
+    assert!(!rewalked);
+}
+
+#[test]
+fn test_cache_without_header_is_rejected() {
+    let tmp = TempDir::new("cache_header").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+
+    // What a cache looked like before the header: a bare zstd stream.
+    fs::write(&cache_path, zstd::encode_all(&b"old slab"[..], 6).unwrap()).unwrap();
+    let Err(err) = read_cache_from_file(&cache_path) else {
+        panic!("headerless cache should be rejected");
+    };
+    assert_eq!(
+        err.downcast_ref::<CacheError>(),
+        Some(&CacheError::MissingHeader)
+    );
+
+    fs::write(&cache_path, b"CRD").unwrap();
+    let Err(err) = read_cache_from_file(&cache_path) else {
+        panic!("headerless cache should be rejected");
+    };
+    assert_eq!(
+        err.downcast_ref::<CacheError>(),
+        Some(&CacheError::MissingHeader)
+    );
+}
+
+#[test]
+fn test_compression_levels_reload_identically() {
+    let tmp = TempDir::new("cache_levels").unwrap();
+    let root = tmp.path().join("root");
+    fs::create_dir(&root).unwrap();
+    for i in 0..2_000 {
+        fs::write(root.join(format!("quarterly_report_draft_{i:05}.txt")), b"").unwrap();
+    }
+    let flush = |level: i32, name: &str| {
+        let cache_path = tmp.path().join(name);
+        SearchCache::walk_fs(&root)
+            .flush_to_file_with(&cache_path, CompressionOptions { level, threads: 0 })
+            .unwrap();
+        cache_path
+    };
+    let fast = flush(1, "fast.zstd");
+    let small = flush(19, "small.zstd");
+
+    let mut paths = Vec::new();
+    for cache_path in [&fast, &small] {
+        let mut cache =
+            SearchCache::try_read_persistent_cache(&root, cache_path, &Vec::new(), None).unwrap();
+        let mut found: Vec<PathBuf> = cache
+            .search("quarterly_report")
+            .unwrap()
+            .into_iter()
+            .map(|index| cache.node_path(index).unwrap())
+            .collect();
+        found.sort();
+        paths.push((cache.get_total_files(), found));
+    }
+    assert_eq!(paths[0], paths[1]);
+    assert_eq!(paths[0].1.len(), 2_000);
+
+    let size = |path: &PathBuf| fs::metadata(path).unwrap().len();
+    assert!(
+        size(&small) < size(&fast),
+        "{} >= {}",
+        size(&small),
+        size(&fast)
+    );
+}
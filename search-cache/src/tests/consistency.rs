@@ -0,0 +1,145 @@
+use super::prelude::*;
+use crate::Inconsistency;
+use cardinal_sdk::{EventFlag, FsEvent, current_event_id};
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let tmp = TempDir::new("consistency").unwrap();
+    fs::create_dir_all(tmp.path().join("dir/sub")).unwrap();
+    for name in ["a.txt", "dir/b.txt", "dir/sub/c.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let cache = SearchCache::walk_fs(tmp.path());
+    (tmp, cache)
+}
+
+fn only(cache: &mut SearchCache, name: &str) -> crate::SlabIndex {
+    let found = cache.search(name).unwrap();
+    assert_eq!(found.len(), 1, "{name}");
+    found[0]
+}
+
+#[test]
+fn walked_and_updated_caches_verify_clean() {
+    let (tmp, mut cache) = build_cache();
+    assert_eq!(cache.verify(), Ok(()));
+
+    fs::write(tmp.path().join("dir/new.txt"), b"x").unwrap();
+    fs::remove_dir_all(tmp.path().join("dir/sub")).unwrap();
+    let id = current_event_id() + 1;
+    cache
+        .handle_fs_events(vec![
+            FsEvent {
+                path: tmp.path().join("dir/new.txt"),
+                id,
+                flag: EventFlag::ItemCreated,
+            },
+            FsEvent {
+                path: tmp.path().join("dir/sub"),
+                id: id + 1,
+                flag: EventFlag::ItemRemoved,
+            },
+        ])
+        .unwrap();
+    assert!(cache.search("c.txt").unwrap().is_empty());
+    only(&mut cache, "new.txt");
+    assert_eq!(cache.verify(), Ok(()));
+}
+
+#[test]
+fn detached_subtree_is_reported_as_orphaned() {
+    let (_tmp, mut cache) = build_cache();
+    let dir = only(&mut cache, "dir");
+    let top = cache.file_nodes[dir].parent().unwrap();
+    let (b, sub, c) = (
+        only(&mut cache, "b.txt"),
+        only(&mut cache, "sub"),
+        only(&mut cache, "c.txt"),
+    );
+    cache.file_nodes[top].children.retain(|&child| child != dir);
+
+    let mut found = cache.verify().unwrap_err();
+    assert_eq!(
+        found.remove(0),
+        Inconsistency::MissingFromParent {
+            parent: top,
+            child: dir
+        }
+    );
+    found.sort_by_key(|inconsistency| match inconsistency {
+        Inconsistency::Orphan { index } => *index,
+        other => panic!("unexpected {other:?}"),
+    });
+    let mut orphans = vec![dir, b, sub, c];
+    orphans.sort();
+    assert_eq!(
+        found,
+        orphans
+            .into_iter()
+            .map(|index| Inconsistency::Orphan { index })
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn links_listed_one_way_are_reported() {
+    let (_tmp, mut cache) = build_cache();
+    let (a, dir) = (only(&mut cache, "a.txt"), only(&mut cache, "dir"));
+    let top = cache.file_nodes[dir].parent().unwrap();
+    // `dir` claims `a.txt`, which still names the walked folder as its parent.
+    cache.file_nodes[dir].children.push(a);
+    // The walked folder lists a slot that was never filled.
+    let vacant = crate::SlabIndex::new(cache.file_nodes.len() + 10);
+    cache.file_nodes[top].children.push(vacant);
+
+    assert_eq!(
+        cache.verify(),
+        Err(vec![
+            Inconsistency::DanglingChild {
+                parent: top,
+                child: vacant
+            },
+            Inconsistency::WrongParent {
+                parent: dir,
+                child: a,
+                actual: Some(top)
+            },
+        ])
+    );
+}
+
+#[test]
+fn name_index_and_pool_mismatches_are_reported() {
+    let (_tmp, mut cache) = build_cache();
+    let a = only(&mut cache, "a.txt");
+    assert!(cache.name_index.remove_index("a.txt", a));
+    assert_eq!(
+        cache.verify(),
+        Err(vec![Inconsistency::MissingFromNameIndex {
+            index: a,
+            name: "a.txt".to_string()
+        }])
+    );
+
+    let (_tmp, mut cache) = build_cache();
+    let b = only(&mut cache, "b.txt");
+    // A name that was never interned, and isn't indexed either.
+    let stray: &'static str = Box::leak("stray.txt".to_string().into_boxed_str());
+    cache.file_nodes[b].rename(stray);
+    assert_eq!(
+        cache.verify(),
+        Err(vec![
+            Inconsistency::NameNotPooled {
+                index: b,
+                name: "stray.txt".to_string()
+            },
+            Inconsistency::MissingFromNameIndex {
+                index: b,
+                name: "stray.txt".to_string()
+            },
+            Inconsistency::StaleNameIndexEntry {
+                name: "b.txt".to_string(),
+                index: b
+            },
+        ])
+    );
+}
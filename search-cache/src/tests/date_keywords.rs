@@ -2,6 +2,27 @@ use super::{
     prelude::*,
     support::{SECONDS_PER_DAY, assert_file_hits, set_file_times, ts_for_date},
 };
+use crate::query::WEEK_START;
+use jiff::{ToSpan, civil::Date, tz::TimeZone};
+use std::{
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+};
+
+fn local_noon(date: Date) -> i64 {
+    TimeZone::system()
+        .to_zoned(date.at(12, 0, 0, 0))
+        .expect("valid local date")
+        .timestamp()
+        .as_second()
+}
+
+fn write_with_mtime(path: &Path, modified: i64) {
+    fs::write(path, b"x").unwrap();
+    let file = fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(UNIX_EPOCH + Duration::from_secs(modified as u64))
+        .unwrap();
+}
 
 #[test]
 fn test_date_filters_cover_keywords_and_ranges() {
@@ -73,3 +94,90 @@ fn date_filter_reuses_existing_and_base() {
         "date filter should not touch nodes excluded by earlier ext: filters",
     );
 }
+
+#[test]
+fn relative_keywords_follow_local_calendar_buckets() {
+    let today = Timestamp::now().to_zoned(TimeZone::system()).date();
+    let week_start = today - i64::from(today.weekday().since(WEEK_START)).days();
+    let month_start = today.first_of_month();
+    let last_month_start = (month_start - 1.day()).first_of_month();
+    let year_start = today.first_of_year();
+    let last_year_start = (year_start - 1.day()).first_of_year();
+
+    let files = [
+        ("today.txt", today),
+        ("yesterday.txt", today - 1.day()),
+        ("last_week.txt", week_start - 4.days()),
+        ("last_month.txt", last_month_start + 14.days()),
+        ("last_year.txt", last_year_start + 160.days()),
+        ("ancient.txt", Date::constant(2001, 3, 3)),
+    ];
+    let buckets = [
+        ("today", today, today),
+        ("yesterday", today - 1.day(), today - 1.day()),
+        ("thisweek", week_start, week_start + 6.days()),
+        ("lastweek", week_start - 7.days(), week_start - 1.day()),
+        ("thismonth", month_start, today.last_of_month()),
+        ("lastmonth", last_month_start, month_start - 1.day()),
+        ("thisyear", year_start, today.last_of_year()),
+        ("lastyear", last_year_start, year_start - 1.day()),
+    ];
+
+    let tmp = TempDir::new("date_keyword_buckets").unwrap();
+    for (name, date) in files {
+        write_with_mtime(&tmp.path().join(name), local_noon(date));
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let expected = |start: Date, end: Date| -> Vec<&str> {
+        files
+            .iter()
+            .filter(|(_, date)| (start..=end).contains(date))
+            .map(|(name, _)| *name)
+            .collect()
+    };
+    for (keyword, start, end) in buckets {
+        let hits = cache.search(&format!("dm:{keyword}")).unwrap();
+        assert_file_hits(&cache, &hits, &expected(start, end));
+    }
+
+    // Creation times can't be set on disk portably, so seed them into the cache.
+    for (name, date) in files {
+        let index = cache.search(name).unwrap()[0];
+        set_file_times(&mut cache, index, local_noon(date), ts_for_date(2001, 3, 3));
+    }
+    for (keyword, start, end) in buckets {
+        let hits = cache.search(&format!("dc:{keyword}")).unwrap();
+        assert_file_hits(&cache, &hits, &expected(start, end));
+    }
+}
+
+#[test]
+fn test_since_last_index_matches_files_modified_after_the_index() {
+    let tmp = TempDir::new("date_since_last_index").unwrap();
+    for name in ["before.txt", "at_index.txt", "after.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    // A fresh walk has nothing to compare against.
+    assert_eq!(cache.index_timestamp(), None);
+    let err = cache.search("dm:sincelastindex").unwrap_err();
+    assert!(err.to_string().contains("loaded from disk"), "{err}");
+
+    let indexed_at = ts_for_date(2024, 6, 1);
+    cache.index_timestamp = Some(indexed_at);
+    for (name, mtime) in [
+        ("before.txt", indexed_at - SECONDS_PER_DAY),
+        ("at_index.txt", indexed_at),
+        ("after.txt", indexed_at + 60),
+    ] {
+        let index = cache.search(name).unwrap()[0];
+        set_file_times(&mut cache, index, mtime, mtime);
+    }
+
+    let hits = cache.search("dm:sincelastindex").unwrap();
+    assert_file_hits(&cache, &hits, &["at_index.txt", "after.txt"]);
+    let hits = cache.search("dm:SinceLastIndex after").unwrap();
+    assert_file_hits(&cache, &hits, &["after.txt"]);
+}
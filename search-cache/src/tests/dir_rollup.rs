@@ -0,0 +1,77 @@
+use super::prelude::*;
+use crate::DirRollup;
+
+#[test]
+fn abandoned_rollup_keeps_correct_sums_for_finished_folders() {
+    let tmp = TempDir::new("dir_rollup_cancel").unwrap();
+    for dir in ["a/deep", "b", "c"] {
+        fs::create_dir_all(tmp.path().join(dir)).unwrap();
+    }
+    for (file, len) in [("a/deep/x", 7), ("a/y", 3), ("b/z", 11), ("c/w", 13)] {
+        fs::write(tmp.path().join(file), vec![0u8; len]).unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let root = cache.index_of_path(tmp.path()).unwrap();
+
+    // Give up after a few checks; the root itself can't be finished by then.
+    let mut checks = 0;
+    let outcome = cache.roll_up(cache.file_nodes.root(), || {
+        checks += 1;
+        (checks <= 4).then_some(())
+    });
+    assert!(outcome.is_none());
+    assert_eq!(cache.dir_rollup(root), None);
+
+    let expected = [
+        (
+            "a/deep",
+            DirRollup {
+                size: 7,
+                entries: 1,
+            },
+        ),
+        (
+            "a",
+            DirRollup {
+                size: 10,
+                entries: 3,
+            },
+        ),
+        (
+            "b",
+            DirRollup {
+                size: 11,
+                entries: 1,
+            },
+        ),
+        (
+            "c",
+            DirRollup {
+                size: 13,
+                entries: 1,
+            },
+        ),
+    ];
+    let finished: Vec<_> = expected
+        .iter()
+        .filter_map(|&(path, want)| {
+            let index = cache.index_of_path(&tmp.path().join(path)).unwrap();
+            cache.dir_rollup(index).map(|got| (path, got, want))
+        })
+        .collect();
+    assert!(!finished.is_empty());
+    for (path, got, want) in finished {
+        assert_eq!(got, want, "{path}");
+    }
+
+    // Running again picks up where it stopped.
+    let total = cache.spawn_size_rollup(CancellationToken::noop()).unwrap();
+    assert_eq!(
+        cache.dir_rollup(root),
+        Some(DirRollup {
+            size: 34,
+            entries: 8
+        })
+    );
+    assert_eq!(total.size, 34);
+}
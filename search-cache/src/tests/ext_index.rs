@@ -0,0 +1,124 @@
+use super::prelude::*;
+use crate::{SlabIndex, query::extension_of};
+use std::collections::BTreeSet;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let tmp = TempDir::new("ext_index").unwrap();
+    fs::create_dir_all(tmp.path().join("photos/raw")).unwrap();
+    fs::create_dir(tmp.path().join("archive.zip")).unwrap();
+    for name in [
+        "a.JPG",
+        "b.jpg",
+        "notes.txt",
+        "photos/c.png",
+        "photos/raw/d.jpg",
+        "photos/raw/e.arw",
+        "README",
+    ] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let cache = SearchCache::walk_fs(tmp.path());
+    (tmp, cache)
+}
+
+/// The pre-index behaviour: test every node's extension in name-index order.
+fn scan_for_extensions(cache: &SearchCache, extensions: &[&str]) -> Vec<SlabIndex> {
+    cache
+        .search_empty(CancellationToken::noop())
+        .unwrap()
+        .into_iter()
+        .filter(|&index| {
+            let node = &cache.file_nodes[index];
+            node.file_type_hint() == NodeFileType::File
+                && extension_of(node.name()).is_some_and(|ext| extensions.contains(&ext.as_str()))
+        })
+        .collect()
+}
+
+#[test]
+fn ext_index_is_populated_after_walk() {
+    let (_tmp, cache) = build_cache();
+    let jpg: Vec<&str> = cache
+        .name_index
+        .names_with_extension("jpg")
+        .expect("jpg bucket")
+        .iter()
+        .copied()
+        .collect();
+    assert_eq!(jpg, ["a.JPG", "b.jpg", "d.jpg"]);
+    assert!(cache.name_index.names_with_extension("zip").is_some());
+    assert!(cache.name_index.names_with_extension("").is_none());
+}
+
+#[test]
+fn index_backed_ext_matches_scanning_path() {
+    let (_tmp, mut cache) = build_cache();
+    for (query, extensions) in [
+        ("ext:jpg", &["jpg"][..]),
+        ("ext:JPG;png", &["jpg", "png"][..]),
+        // `archive.zip` is a folder, so the bucket hit must be dropped.
+        ("ext:zip", &["zip"][..]),
+        ("ext:missing", &["missing"][..]),
+    ] {
+        assert_eq!(
+            cache.search(query).unwrap(),
+            scan_for_extensions(&cache, extensions),
+            "{query}"
+        );
+    }
+}
+
+#[test]
+fn index_backed_type_group_matches_scanning_path() {
+    let (_tmp, mut cache) = build_cache();
+    let hits = cache.search("type:picture").unwrap();
+    assert_eq!(hits.len(), 5);
+    assert_eq!(
+        hits,
+        scan_for_extensions(&cache, &["jpg", "png", "arw"]),
+        "type:picture should union the jpg, png and arw buckets"
+    );
+}
+
+#[test]
+fn mixed_case_ext_list_folds_every_item() {
+    let tmp = TempDir::new("ext_mixed_case").unwrap();
+    for name in ["one.jpg", "two.PNG", "three.Gif", "four.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let names = |cache: &mut SearchCache, query: &str| -> BTreeSet<String> {
+        cache
+            .search(query)
+            .unwrap()
+            .into_iter()
+            .map(|index| cache.file_nodes[index].name().to_string())
+            .collect()
+    };
+    let expected = |names: &[&str]| -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    };
+
+    // Without a base the extension index answers; with one, each node is
+    // checked. Both must fold every item of the list the same way.
+    for query in [
+        "ext:JPG;png",
+        "ext:jpg;PNG",
+        "o ext:JPG;png",
+        "o ext:.Jpg;.pNg",
+    ] {
+        assert_eq!(
+            names(&mut cache, query),
+            expected(&["one.jpg", "two.PNG"]),
+            "{query}"
+        );
+    }
+    assert_eq!(
+        names(&mut cache, "ext:JPG;Png;gif"),
+        expected(&["one.jpg", "two.PNG", "three.Gif"])
+    );
+    assert_eq!(
+        names(&mut cache, "e type:Picture"),
+        expected(&["one.jpg", "three.Gif"])
+    );
+}
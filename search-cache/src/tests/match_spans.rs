@@ -0,0 +1,129 @@
+#![allow(clippy::single_range_in_vec_init)]
+
+use super::prelude::*;
+use crate::SearchOptions;
+use std::ops::Range;
+
+fn spans_by_name(
+    cache: &mut SearchCache,
+    query: &str,
+    options: SearchOptions,
+) -> Vec<(String, Vec<Range<usize>>)> {
+    let hits = cache
+        .search_with_spans(query, options, CancellationToken::noop())
+        .expect("search should succeed")
+        .expect("noop token should not cancel");
+    let mut named: Vec<_> = hits
+        .into_iter()
+        .map(|(index, spans)| (cache.file_nodes[index].name().to_string(), spans))
+        .collect();
+    named.sort_by(|a, b| a.0.cmp(&b.0));
+    named
+}
+
+#[test]
+fn substring_query_reports_every_occurrence() {
+    let tmp = TempDir::new("spans_substr").unwrap();
+    fs::write(tmp.path().join("report_report.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("weekly-report.md"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let hits = spans_by_name(&mut cache, "report", SearchOptions::default());
+    assert_eq!(
+        hits,
+        vec![
+            ("report_report.txt".to_string(), vec![0..6, 7..13]),
+            ("weekly-report.md".to_string(), vec![7..13]),
+        ]
+    );
+}
+
+#[test]
+fn unicode_names_use_byte_offsets() {
+    let tmp = TempDir::new("spans_unicode").unwrap();
+    fs::write(tmp.path().join("résumé_café.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("日本語メモ.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let hits = spans_by_name(&mut cache, "café", SearchOptions::default());
+    let name = "résumé_café.txt";
+    let start = name.find("café").unwrap();
+    assert_eq!(
+        hits,
+        vec![(name.to_string(), vec![start..start + "café".len()])]
+    );
+    assert!(name.is_char_boundary(start) && name.is_char_boundary(start + "café".len()));
+
+    let hits = spans_by_name(&mut cache, "メモ", SearchOptions::default());
+    assert_eq!(hits, vec![("日本語メモ.txt".to_string(), vec![9..15])]);
+}
+
+#[test]
+fn case_insensitive_spans_point_at_original_text() {
+    let tmp = TempDir::new("spans_case").unwrap();
+    fs::write(tmp.path().join("Quarterly_CAFÉ.pdf"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    let hits = spans_by_name(&mut cache, "café quarterly", options);
+    let name = "Quarterly_CAFÉ.pdf";
+    assert_eq!(
+        hits,
+        vec![(name.to_string(), vec![0..9, 10..name.len() - 4])]
+    );
+}
+
+#[test]
+fn filter_only_queries_have_empty_spans() {
+    let tmp = TempDir::new("spans_filter_only").unwrap();
+    fs::write(tmp.path().join("alpha.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("beta.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let hits = spans_by_name(&mut cache, "ext:txt", SearchOptions::default());
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|(_, spans)| spans.is_empty()));
+
+    let hits = spans_by_name(&mut cache, "alpha ext:txt", SearchOptions::default());
+    assert_eq!(hits, vec![("alpha.txt".to_string(), vec![0..5])]);
+}
+
+#[test]
+fn search_with_spans_matches_search_results() {
+    let tmp = TempDir::new("spans_parity").unwrap();
+    fs::create_dir(tmp.path().join("notes")).unwrap();
+    fs::write(tmp.path().join("notes/todo.md"), b"x").unwrap();
+    fs::write(tmp.path().join("todo.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let plain = cache.search("todo | notes").unwrap();
+    let spanned: Vec<_> = cache
+        .search_with_spans(
+            "todo | notes",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|(index, _)| index)
+        .collect();
+    assert_eq!(plain, spanned);
+}
+
+#[test]
+fn search_with_spans_cancelled_returns_none() {
+    let tmp = TempDir::new("spans_cancelled").unwrap();
+    fs::write(tmp.path().join("alpha.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let token = CancellationToken::new(4100);
+    let _ = CancellationToken::new(4101);
+    let result = cache
+        .search_with_spans("alpha", SearchOptions::default(), token)
+        .unwrap();
+    assert!(result.is_none());
+}
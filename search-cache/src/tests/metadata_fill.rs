@@ -0,0 +1,71 @@
+use super::prelude::*;
+
+fn cache_with_files(name: &str, count: usize) -> (TempDir, SearchCache) {
+    let tmp = TempDir::new(name).unwrap();
+    for i in 0..count {
+        fs::write(tmp.path().join(format!("file_{i:02}.bin")), vec![0u8; i]).unwrap();
+    }
+    let cache = SearchCache::walk_fs(tmp.path());
+    (tmp, cache)
+}
+
+#[test]
+fn cancelled_fill_leaves_later_batches_untouched() {
+    let (_tmp, mut cache) = cache_with_files("metadata_fill_cancel", 10);
+    let files = cache.search("ext:bin").unwrap();
+    assert_eq!(files.len(), 10);
+    assert!(
+        files
+            .iter()
+            .all(|&index| cache.file_nodes[index].metadata.is_none())
+    );
+
+    // Let the first batch through, then report cancellation.
+    let mut batches = 0;
+    let outcome = cache.fill_missing_metadata_in_batches(&files, true, 4, || {
+        batches += 1;
+        (batches == 1).then_some(())
+    });
+    assert!(outcome.is_none());
+    assert_eq!(batches, 2);
+
+    let filled: Vec<bool> = files
+        .iter()
+        .map(|&index| cache.file_nodes[index].metadata.is_some())
+        .collect();
+    assert_eq!(filled.iter().filter(|&&filled| filled).count(), 4);
+    assert!(filled[..4].iter().all(|&filled| filled), "{filled:?}");
+}
+
+#[test]
+fn fill_only_reads_nodes_without_metadata() {
+    let (_tmp, mut cache) = cache_with_files("metadata_fill_skip", 3);
+    let files = cache.search("ext:bin").unwrap();
+    cache.ensure_metadata(files[0]);
+
+    let mut batches = 0;
+    let outcome = cache.fill_missing_metadata_in_batches(&files, true, 1, || {
+        batches += 1;
+        Some(())
+    });
+    assert!(outcome.is_some());
+    assert_eq!(batches, 2, "only the two files without metadata are read");
+    assert!(
+        files
+            .iter()
+            .all(|&index| cache.file_nodes[index].metadata.is_some())
+    );
+}
+
+#[test]
+fn size_filter_still_matches_after_parallel_fill() {
+    let (_tmp, mut cache) = cache_with_files("metadata_fill_size", 10);
+    let hits = cache.search("size:>=5").unwrap();
+    let mut names: Vec<_> = hits
+        .iter()
+        .map(|&index| cache.file_nodes[index].name().to_string())
+        .collect();
+    names.sort();
+    let expected: Vec<_> = (5..10).map(|i| format!("file_{i:02}.bin")).collect();
+    assert_eq!(names, expected);
+}
@@ -12,12 +12,20 @@ mod prelude {
 mod support;
 
 mod cache_flow;
+mod consistency;
 mod date_edges;
 mod date_keywords;
 mod date_volume;
+mod dir_rollup;
+mod ext_index;
 mod integration_filters;
+mod match_spans;
+mod metadata_fill;
+mod name_index_pool;
 mod query_logic;
+mod set_ops;
 mod size_filters;
+mod top_k;
 mod traversal;
 mod type_filters;
 mod wildcard_star;
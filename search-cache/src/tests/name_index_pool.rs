@@ -0,0 +1,62 @@
+use super::prelude::*;
+use crate::cache::NAME_POOL;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let tmp = TempDir::new("name_index_pool").unwrap();
+    fs::create_dir(tmp.path().join("sub")).unwrap();
+    for name in ["a.txt", "b.txt", "sub/a.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let cache = SearchCache::walk_fs(tmp.path());
+    (tmp, cache)
+}
+
+#[test]
+fn index_keys_are_the_pooled_names() {
+    let (_tmp, cache) = build_cache();
+    let names = cache.name_index.names_with_extension("txt").unwrap();
+    assert_eq!(
+        names.iter().copied().collect::<Vec<_>>(),
+        ["a.txt", "b.txt"]
+    );
+    for &name in names {
+        // The key is the pool's copy, shared with every node carrying the name.
+        assert_eq!(name.as_ptr(), NAME_POOL.push(name).as_ptr());
+        for &index in cache.name_index.get(name).unwrap().iter() {
+            assert_eq!(cache.file_nodes[index].name().as_ptr(), name.as_ptr());
+        }
+    }
+    assert_eq!(cache.name_index.get("a.txt").unwrap().len(), 2);
+
+    // Only the persisted form owns its own copy of each name.
+    let persistent = cache.name_index.as_persistent();
+    let (owned, _) = persistent.get_key_value("a.txt").unwrap();
+    assert_ne!(owned.as_ptr(), NAME_POOL.push("a.txt").as_ptr());
+}
+
+#[test]
+fn removing_the_last_index_drops_the_name() {
+    let (_tmp, mut cache) = build_cache();
+    let len = cache.name_index.len();
+    let indices: Vec<_> = cache
+        .name_index
+        .get("a.txt")
+        .unwrap()
+        .iter()
+        .copied()
+        .collect();
+    let (last, rest) = indices.split_last().unwrap();
+
+    for &index in rest {
+        assert!(cache.name_index.remove_index("a.txt", index));
+    }
+    assert_eq!(cache.name_index.get("a.txt").unwrap().len(), 1);
+    assert_eq!(cache.name_index.len(), len);
+
+    assert!(cache.name_index.remove_index("a.txt", *last));
+    assert!(cache.name_index.get("a.txt").is_none());
+    assert_eq!(cache.name_index.len(), len - 1);
+    let names = cache.name_index.names_with_extension("txt").unwrap();
+    assert_eq!(names.iter().copied().collect::<Vec<_>>(), ["b.txt"]);
+    assert!(!cache.name_index.remove_index("a.txt", *last));
+}
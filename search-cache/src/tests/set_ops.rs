@@ -0,0 +1,73 @@
+use super::{prelude::*, support::list_file_names};
+use crate::{
+    SlabIndex,
+    query::{difference_in_place, intersect_in_place},
+};
+
+fn ids(raw: &[u32]) -> Vec<SlabIndex> {
+    raw.iter().map(|&i| SlabIndex::new(i as usize)).collect()
+}
+
+#[test]
+fn four_operand_and_matches_expected_files() {
+    let tmp = TempDir::new("set_ops_and").unwrap();
+    let root = tmp.path();
+    for dir in ["proj/src", "proj/docs", "other/src"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::write(root.join("proj/src/main.rs"), vec![b'x'; 64]).unwrap();
+    fs::write(root.join("proj/src/main_window.rs"), vec![b'x'; 64]).unwrap();
+    fs::write(root.join("proj/src/main_test.rs"), b"x").unwrap();
+    fs::write(root.join("proj/src/lib.rs"), vec![b'x'; 64]).unwrap();
+    fs::write(root.join("proj/docs/main.md"), vec![b'x'; 64]).unwrap();
+    fs::write(root.join("other/src/main.rs"), vec![b'x'; 64]).unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+
+    let query = format!(
+        "infolder:{} ext:rs size:>10 src/main !window",
+        root.join("proj").display()
+    );
+    let hits = cache.search(&query).unwrap();
+    assert_eq!(list_file_names(&cache, &hits), vec!["main.rs"]);
+    assert_eq!(
+        cache.node_path(hits[0]).unwrap(),
+        root.join("proj/src/main.rs")
+    );
+}
+
+#[test]
+fn intersect_keeps_left_order_for_unsorted_operands() {
+    let mut values = ids(&[9, 2, 7, 4, 2, 11]);
+    intersect_in_place(&mut values, ids(&[4, 11, 2, 5]), CancellationToken::noop()).unwrap();
+    assert_eq!(values, ids(&[2, 4, 2, 11]));
+}
+
+#[test]
+fn intersect_merges_sorted_operands() {
+    let mut values = ids(&[1, 3, 3, 5, 8, 13]);
+    intersect_in_place(&mut values, ids(&[13, 3, 4, 8]), CancellationToken::noop()).unwrap();
+    assert_eq!(values, ids(&[3, 3, 8, 13]));
+
+    let mut values = ids(&[1, 2, 3]);
+    intersect_in_place(&mut values, Vec::new(), CancellationToken::noop()).unwrap();
+    assert!(values.is_empty());
+}
+
+#[test]
+fn difference_preserves_order_on_both_paths() {
+    let mut unsorted = ids(&[6, 1, 9, 4]);
+    difference_in_place(&mut unsorted, ids(&[9, 6]), CancellationToken::noop()).unwrap();
+    assert_eq!(unsorted, ids(&[1, 4]));
+
+    let mut sorted = ids(&[1, 4, 6, 9]);
+    difference_in_place(&mut sorted, ids(&[9, 6]), CancellationToken::noop()).unwrap();
+    assert_eq!(sorted, ids(&[1, 4]));
+}
+
+#[test]
+fn cancelled_intersection_returns_none() {
+    let token = CancellationToken::new(40);
+    let _ = CancellationToken::new(41);
+    let mut values = ids(&[1, 2, 3]);
+    assert!(intersect_in_place(&mut values, ids(&[2]), token).is_none());
+}
@@ -0,0 +1,156 @@
+use super::{
+    prelude::*,
+    support::{node_name, set_file_times},
+};
+use crate::{SearchOptions, SlabIndex, TopKKey};
+
+/// Creates the named files with pinned timestamps next to an `unrelated.md` decoy.
+fn build_cache(names: &[(&str, i64)]) -> (TempDir, SearchCache) {
+    let tmp = TempDir::new("top_k").unwrap();
+    for (name, _) in names {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    fs::write(tmp.path().join("unrelated.md"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    for &(name, mtime) in names {
+        let index = index_of(&cache, name);
+        set_file_times(&mut cache, index, mtime, mtime);
+    }
+    (tmp, cache)
+}
+
+fn index_of(cache: &SearchCache, name: &str) -> SlabIndex {
+    cache
+        .search_empty(CancellationToken::noop())
+        .unwrap()
+        .into_iter()
+        .find(|&index| cache.file_nodes[index].name() == name)
+        .unwrap()
+}
+
+fn top_k(cache: &mut SearchCache, query: &str, k: usize, key: TopKKey) -> Vec<String> {
+    let hits = cache
+        .search_top_k(
+            query,
+            k,
+            key,
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .unwrap();
+    hits.into_iter().map(|i| node_name(cache, i)).collect()
+}
+
+#[test]
+fn mtime_desc_returns_exactly_the_k_newest_in_order() {
+    let (_tmp, mut cache) = build_cache(&[
+        ("log_3.txt", 3_000),
+        ("log_1.txt", 1_000),
+        ("log_6.txt", 6_000),
+        ("log_2.txt", 2_000),
+        ("log_5.txt", 5_000),
+        ("log_4.txt", 4_000),
+    ]);
+    assert_eq!(
+        top_k(&mut cache, "ext:txt", 3, TopKKey::MtimeDesc),
+        vec!["log_6.txt", "log_5.txt", "log_4.txt"]
+    );
+    assert_eq!(
+        top_k(&mut cache, "ext:txt", 2, TopKKey::MtimeAsc),
+        vec!["log_1.txt", "log_2.txt"]
+    );
+}
+
+#[test]
+fn k_larger_than_match_count_returns_all_sorted() {
+    let (_tmp, mut cache) = build_cache(&[
+        ("log_2.txt", 2_000),
+        ("log_9.txt", 9_000),
+        ("log_4.txt", 4_000),
+    ]);
+    assert_eq!(
+        top_k(&mut cache, "log", 50, TopKKey::MtimeDesc),
+        vec!["log_9.txt", "log_4.txt", "log_2.txt"]
+    );
+    assert!(top_k(&mut cache, "log", 0, TopKKey::MtimeDesc).is_empty());
+}
+
+#[test]
+fn ties_keep_slab_order() {
+    let (_tmp, mut cache) = build_cache(&[
+        ("c.txt", 5_000),
+        ("a.txt", 5_000),
+        ("b.txt", 5_000),
+        ("old.txt", 1_000),
+    ]);
+    assert_eq!(
+        top_k(&mut cache, "ext:txt", 2, TopKKey::MtimeDesc),
+        vec!["a.txt", "b.txt"]
+    );
+}
+
+#[test]
+fn ties_across_folders_keep_slab_order() {
+    let tmp = TempDir::new("top_k_folders").unwrap();
+    for dir in ["b_dir", "a_dir"] {
+        fs::create_dir(tmp.path().join(dir)).unwrap();
+    }
+    for name in ["b_dir/a.txt", "a_dir/z.txt", "m.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let mut tied: Vec<SlabIndex> = ["a.txt", "z.txt", "m.txt"]
+        .into_iter()
+        .map(|name| index_of(&cache, name))
+        .collect();
+    for &index in &tied {
+        set_file_times(&mut cache, index, 5_000, 5_000);
+    }
+    tied.sort_unstable();
+    let hits = cache
+        .search_top_k(
+            "ext:txt",
+            3,
+            TopKKey::MtimeDesc,
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(hits, tied);
+}
+
+#[test]
+fn top_k_fetches_missing_metadata_lazily() {
+    let tmp = TempDir::new("top_k_lazy").unwrap();
+    fs::write(tmp.path().join("small.bin"), vec![0u8; 10]).unwrap();
+    fs::write(tmp.path().join("large.bin"), vec![0u8; 1_000]).unwrap();
+    fs::write(tmp.path().join("medium.bin"), vec![0u8; 100]).unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let large = index_of(&cache, "large.bin");
+    assert!(cache.file_nodes[large].metadata.is_none());
+
+    assert_eq!(
+        top_k(&mut cache, "ext:bin", 2, TopKKey::SizeDesc),
+        vec!["large.bin", "medium.bin"]
+    );
+    assert!(cache.file_nodes[large].metadata.is_some());
+}
+
+#[test]
+fn cancelled_top_k_returns_none() {
+    let (_tmp, mut cache) = build_cache(&[("log_1.txt", 1_000)]);
+    let token = CancellationToken::new(50);
+    let _ = CancellationToken::new(51);
+    let result = cache
+        .search_top_k(
+            "log",
+            5,
+            TopKKey::MtimeDesc,
+            SearchOptions::default(),
+            token,
+        )
+        .unwrap();
+    assert!(result.is_none());
+}
@@ -23,7 +23,7 @@ fn test_all_subnodes_returns_all_descendants() {
     // Find src directory index
     let src_path = tmp.path().join("src");
     let src_idx = cache
-        .node_index_for_path(&src_path)
+        .index_of_path(&src_path)
         .expect("src directory should exist");
 
     // Get all subnodes
@@ -53,7 +53,7 @@ fn test_all_subnodes_empty_directory() {
 
     let empty_path = tmp.path().join("empty");
     let empty_idx = cache
-        .node_index_for_path(&empty_path)
+        .index_of_path(&empty_path)
         .expect("empty directory should exist");
 
     let subnodes = cache
@@ -76,7 +76,7 @@ fn test_all_subnodes_deep_nesting() {
     // Get subnodes from 'a' directory
     let a_path = tmp.path().join("a");
     let a_idx = cache
-        .node_index_for_path(&a_path)
+        .index_of_path(&a_path)
         .expect("a directory should exist");
 
     let subnodes = cache
@@ -120,3 +120,88 @@ fn test_all_subnodes_cancellation() {
     let result = cache.all_subnodes(root_idx, token);
     assert!(result.is_none(), "Should return None when cancelled");
 }
+
+#[test]
+fn ancestors_follow_parent_links_from_root() {
+    let tmp = TempDir::new("ancestors").unwrap();
+    fs::create_dir_all(tmp.path().join("src/utils")).unwrap();
+    fs::write(tmp.path().join("src/utils/helper.rs"), b"x").unwrap();
+    let cache = SearchCache::walk_fs(tmp.path());
+
+    let helper = cache
+        .index_of_path(&tmp.path().join("src/utils/helper.rs"))
+        .unwrap();
+    let crumbs = cache.ancestors(helper);
+
+    let names: Vec<&str> = crumbs.iter().map(|(_, name)| *name).collect();
+    let utils_path = tmp.path().join("src/utils");
+    let expected: Vec<&str> = utils_path
+        .iter()
+        .map(|segment| segment.to_str().unwrap())
+        .collect();
+    assert_eq!(expected[0], "/");
+    assert_eq!(names, expected);
+
+    // Each crumb is the parent of the next, ending at the node's own parent.
+    let root = crumbs[0].0;
+    assert!(cache.file_nodes[root].parent().is_none());
+    for pair in crumbs.windows(2) {
+        assert_eq!(cache.file_nodes[pair[1].0].parent(), Some(pair[0].0));
+    }
+    let utils = cache.index_of_path(&utils_path).unwrap();
+    assert_eq!(crumbs.last().unwrap().0, utils);
+
+    assert!(cache.ancestors(root).is_empty());
+}
+
+/// `depth` nested folders, each holding `files_per_dir` files.
+fn build_deep_tree(tmp: &TempDir, depth: usize, files_per_dir: usize) -> SearchCache {
+    let mut dir = tmp.path().to_path_buf();
+    for level in 0..depth {
+        dir.push(format!("level_{level}"));
+        fs::create_dir(&dir).unwrap();
+        for file in 0..files_per_dir {
+            fs::write(dir.join(format!("file_{file}.txt")), b"x").unwrap();
+        }
+    }
+    SearchCache::walk_fs(tmp.path())
+}
+
+#[test]
+fn node_paths_matches_individual_node_path() {
+    let tmp = TempDir::new("node_paths").unwrap();
+    let cache = build_deep_tree(&tmp, 6, 4);
+    let mut indices = cache.search_empty(CancellationToken::noop()).unwrap();
+    // Folders after their own files, repeats, and an index outside the slab.
+    indices.reverse();
+    indices.extend_from_within(..3);
+    indices.push(crate::SlabIndex::new(1 << 24));
+
+    let batch = cache.node_paths(&indices);
+    let single: Vec<_> = indices.iter().map(|&i| cache.node_path(i)).collect();
+    assert_eq!(batch, single);
+    assert!(batch.last().unwrap().is_none());
+}
+
+#[test]
+#[ignore = "timing benchmark, run manually"]
+fn node_paths_vs_node_path_on_deep_tree() {
+    let tmp = TempDir::new("node_paths_bench").unwrap();
+    let cache = build_deep_tree(&tmp, 40, 25);
+    let indices = cache.search_empty(CancellationToken::noop()).unwrap();
+    let page = &indices[..indices.len().min(500)];
+
+    let start = std::time::Instant::now();
+    for _ in 0..200 {
+        std::hint::black_box(page.iter().map(|&i| cache.node_path(i)).collect::<Vec<_>>());
+    }
+    let single = start.elapsed();
+
+    let start = std::time::Instant::now();
+    for _ in 0..200 {
+        std::hint::black_box(cache.node_paths(page));
+    }
+    let batch = start.elapsed();
+
+    println!("500-result page x200: node_path {single:?}, node_paths {batch:?}");
+}
@@ -1593,3 +1593,38 @@ fn test_type_spreadsheet_all_variants() {
     let results = cache.search("type:spreadsheet").unwrap();
     assert_eq!(results.len(), 5);
 }
+
+#[test]
+fn test_type_picture_minus_negated_ext() {
+    let tmp = TempDir::new("type_picture_not_gif").unwrap();
+    fs::create_dir(tmp.path().join("nested")).unwrap();
+    fs::write(tmp.path().join("photo.jpg"), b"x").unwrap();
+    fs::write(tmp.path().join("graphic.png"), b"x").unwrap();
+    fs::write(tmp.path().join("animation.gif"), b"x").unwrap();
+    fs::write(tmp.path().join("nested/loop.GIF"), b"x").unwrap();
+    fs::write(tmp.path().join("notes.txt"), b"x").unwrap();
+
+    let mut cache = SearchCache::walk_fs(tmp.path());
+
+    let names = |cache: &mut SearchCache, query: &str| {
+        let mut names: Vec<String> = cache
+            .search(query)
+            .unwrap()
+            .into_iter()
+            .map(|index| cache.file_nodes[index].name().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    // The negation only removes gifs from the picture set; it doesn't admit
+    // the non-picture files a bare `!ext:gif` would match.
+    let expected = ["graphic.png", "photo.jpg"];
+    assert_eq!(names(&mut cache, "type:picture !ext:gif"), expected);
+    assert_eq!(names(&mut cache, "!ext:gif type:picture"), expected);
+    assert_eq!(
+        names(&mut cache, "type:picture !ext:gif;png"),
+        ["photo.jpg"]
+    );
+    assert!(names(&mut cache, "!ext:gif").contains(&"notes.txt".to_string()));
+}
@@ -0,0 +1,61 @@
+//! Probes whether a volume matches file names case-insensitively, so search
+//! defaults can follow the file system.
+
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    sync::{
+        LazyLock, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Probe results by device id. Only successful probes are kept, so a volume
+/// that was read-only once is probed again next time.
+static PROBED: LazyLock<Mutex<HashMap<u64, bool>>> = LazyLock::new(Default::default);
+
+/// Whether the volume holding `path` treats `A` and `a` as the same name, or
+/// `None` if that couldn't be found out. Probing creates and removes a file
+/// in `path` (or in its parent, for a file), so it needs write access there.
+pub(crate) fn is_case_insensitive(path: &Path) -> Option<bool> {
+    let metadata = path.metadata().ok()?;
+    let dev = metadata.dev();
+    if let Some(&known) = PROBED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&dev)
+    {
+        return Some(known);
+    }
+    let dir = if metadata.is_dir() {
+        path
+    } else {
+        path.parent()?
+    };
+    let insensitive = probe(dir)?;
+    PROBED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(dev, insensitive);
+    Some(insensitive)
+}
+
+fn probe(dir: &Path) -> Option<bool> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let name = format!(
+        ".Cardinal-Case-Probe-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let probe = dir.join(&name);
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .ok()?;
+    let insensitive = dir.join(name.to_lowercase()).symlink_metadata().is_ok();
+    let _ = std::fs::remove_file(&probe);
+    Some(insensitive)
+}
@@ -0,0 +1,101 @@
+//! Append-only log of the changes applied to a cache since its last flush, so
+//! a crash costs a replay instead of a rewalk.
+//!
+//! Each record is a little-endian `u32` length followed by that many bytes of
+//! postcard-encoded [`WalRecord`]. A record cut short by a crash ends the log.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum WalRecord {
+    /// The subtree at this path was replaced by a fresh walk.
+    Insert(PathBuf),
+    /// The path vanished and was removed with its subtree.
+    Remove(PathBuf),
+    /// Every event up to this id has been applied.
+    EventId(u64),
+    /// Every root was walked afresh, superseding all records before it.
+    Rescan,
+}
+
+#[derive(Debug)]
+pub(crate) struct Wal {
+    path: PathBuf,
+    file: File,
+}
+
+impl Wal {
+    /// Opens `path` for appending, creating it if needed.
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open WAL {path:?}"))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    /// Writes `record` with a single `write_all`, so it reaches the OS before
+    /// the change is reported applied. It isn't synced: the log survives the
+    /// app crashing, not the machine.
+    pub(crate) fn append(&mut self, record: &WalRecord) -> Result<()> {
+        let payload = postcard::to_stdvec(record).context("Failed to encode WAL record")?;
+        let len = u32::try_from(payload.len()).context("WAL record too large")?;
+        let mut frame = Vec::with_capacity(size_of::<u32>() + payload.len());
+        frame.extend_from_slice(&len.to_le_bytes());
+        frame.extend_from_slice(&payload);
+        self.file
+            .write_all(&frame)
+            .with_context(|| format!("Failed to append to WAL {:?}", self.path))
+    }
+
+    /// Drops every record, once a flush has made them redundant.
+    pub(crate) fn truncate(&mut self) -> Result<()> {
+        self.file
+            .set_len(0)
+            .with_context(|| format!("Failed to truncate WAL {:?}", self.path))
+    }
+}
+
+/// Every complete record in the log at `path`, oldest first. A missing file
+/// is an empty log; a torn or undecodable tail is logged and skipped.
+pub(crate) fn read_wal(path: &Path) -> Result<Vec<WalRecord>> {
+    let mut bytes = Vec::new();
+    match File::open(path) {
+        Ok(mut file) => file
+            .read_to_end(&mut bytes)
+            .with_context(|| format!("Failed to read WAL {path:?}"))?,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("Failed to open WAL {path:?}")),
+    };
+    let mut records = Vec::new();
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let record = rest
+            .split_first_chunk::<{ size_of::<u32>() }>()
+            .and_then(|(len, tail)| tail.split_at_checked(u32::from_le_bytes(*len) as usize))
+            .and_then(|(payload, tail)| {
+                rest = tail;
+                postcard::from_bytes(payload).ok()
+            });
+        let Some(record) = record else {
+            warn!(
+                "Ignoring torn WAL tail in {path:?} after {} records",
+                records.len()
+            );
+            break;
+        };
+        records.push(record);
+    }
+    Ok(records)
+}
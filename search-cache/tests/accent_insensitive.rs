@@ -0,0 +1,105 @@
+use search_cache::{EmptyBehavior, SearchCache, SearchOptions, ThreadBudget};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+const ACCENTS: SearchOptions = SearchOptions {
+    case_insensitive: true,
+    unicode_case_fold: false,
+    accent_insensitive: true,
+    match_full_path: false,
+    space_is_literal: false,
+    sniff_content: false,
+    collect_diagnostics: false,
+    thread_budget: ThreadBudget::Available,
+    min_query_len: 1,
+    empty_query: EmptyBehavior::MatchNone,
+};
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("accent_insensitive").unwrap();
+    // The second name is already decomposed: `e` followed by U+0301.
+    for name in [
+        "Résumé.pdf",
+        "cafe\u{301}-menu.txt",
+        "resume-draft.txt",
+        "notes.txt",
+    ] {
+        fs::write(temp_dir.path().join(name), b"x").unwrap();
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+fn names(cache: &mut SearchCache, query: &str, options: SearchOptions) -> Vec<String> {
+    let indices = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .expect("search should succeed")
+        .nodes
+        .expect("noop token should not cancel");
+    let mut names: Vec<String> = cache
+        .expand_file_nodes(&indices)
+        .into_iter()
+        .map(|node| {
+            node.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn resume_matches_accented_names_only_when_enabled() {
+    let (_tmp, mut cache) = build_cache();
+    let plain = SearchOptions {
+        accent_insensitive: false,
+        ..ACCENTS
+    };
+    assert_eq!(names(&mut cache, "resume", plain), ["resume-draft.txt"]);
+    assert_eq!(
+        names(&mut cache, "resume", ACCENTS),
+        ["Résumé.pdf", "resume-draft.txt"]
+    );
+    // Accented needles fold too, and decomposed names lose their marks.
+    assert_eq!(
+        names(&mut cache, "RÉSUMÉ", ACCENTS),
+        ["Résumé.pdf", "resume-draft.txt"]
+    );
+    assert_eq!(
+        names(&mut cache, "cafe-", ACCENTS),
+        ["cafe\u{301}-menu.txt"]
+    );
+    assert!(names(&mut cache, "cafe-", plain).is_empty());
+}
+
+#[test]
+fn accent_folding_does_not_imply_case_folding() {
+    let (_tmp, mut cache) = build_cache();
+    let case_sensitive = SearchOptions {
+        case_insensitive: false,
+        ..ACCENTS
+    };
+    assert_eq!(names(&mut cache, "Resume", case_sensitive), ["Résumé.pdf"]);
+    assert_eq!(
+        names(&mut cache, "resume", case_sensitive),
+        ["resume-draft.txt"]
+    );
+}
+
+#[test]
+fn accent_folding_applies_to_name_filter_and_wildcards() {
+    let (_tmp, mut cache) = build_cache();
+    assert_eq!(
+        names(&mut cache, "name:resume.pdf", ACCENTS),
+        ["Résumé.pdf"]
+    );
+    assert_eq!(names(&mut cache, "r?sum?.*", ACCENTS), ["Résumé.pdf"]);
+    assert_eq!(
+        names(&mut cache, "résumé*", ACCENTS),
+        ["Résumé.pdf", "resume-draft.txt"]
+    );
+}
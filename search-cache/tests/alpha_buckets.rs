@@ -0,0 +1,97 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeMap, fs};
+use tempdir::TempDir;
+
+fn build_fixture() -> TempDir {
+    let tmp = TempDir::new("alpha_buckets").unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("Archive/old")).unwrap();
+    for file in [
+        "apple.txt",
+        "Avocado.md",
+        "banana.txt",
+        "Archive/alpha.log",
+        "Archive/old/zeta",
+        "écru.txt",
+        "1st.txt",
+    ] {
+        fs::write(root.join(file), b"").unwrap();
+    }
+    tmp
+}
+
+#[test]
+fn buckets_count_nodes_by_folded_first_letter() {
+    let tmp = build_fixture();
+    let cache = SearchCache::walk_fs(tmp.path());
+    let root = cache.index_of_path(tmp.path()).unwrap();
+
+    assert_eq!(
+        cache.alpha_buckets(Some(root)),
+        BTreeMap::from([
+            ('1', 1),
+            ('a', 4), // apple.txt, Avocado.md, Archive, alpha.log
+            ('b', 1),
+            ('o', 1),
+            ('z', 1),
+            ('é', 1),
+        ])
+    );
+    let archive = cache.index_of_path(&tmp.path().join("Archive")).unwrap();
+    assert_eq!(
+        cache.alpha_buckets(Some(archive)),
+        BTreeMap::from([('a', 1), ('o', 1), ('z', 1)])
+    );
+
+    // The whole index also holds the root's ancestor folders.
+    let all = cache.alpha_buckets(None);
+    assert!(all[&'a'] >= 4);
+    assert_eq!(all[&'é'], 1);
+}
+
+#[test]
+fn startswith_matches_either_case() {
+    let tmp = build_fixture();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let mut names = |query: &str| {
+        let nodes = cache
+            .search_with_options(
+                &format!("infolder:{} {query}", tmp.path().display()),
+                SearchOptions::default(),
+                CancellationToken::noop(),
+            )
+            .unwrap()
+            .nodes
+            .unwrap();
+        let mut names: Vec<String> = cache
+            .node_paths(&nodes)
+            .into_iter()
+            .flatten()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        names("startswith:a"),
+        ["Archive", "Avocado.md", "alpha.log", "apple.txt"]
+    );
+    assert_eq!(
+        names("startswith:A file:"),
+        ["Avocado.md", "alpha.log", "apple.txt"]
+    );
+    assert_eq!(names("startswith:AP"), ["apple.txt"]);
+    assert_eq!(names("startswith:É"), ["écru.txt"]);
+    assert!(names("startswith:q").is_empty());
+    assert!(
+        cache
+            .search_with_options(
+                "startswith:",
+                SearchOptions::default(),
+                CancellationToken::noop()
+            )
+            .is_err()
+    );
+}
@@ -0,0 +1,73 @@
+use search_cache::{CacheDiff, SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{fs, path::Path};
+use tempdir::TempDir;
+
+/// Walks `root` and loads the metadata of every file, the way a `size:`
+/// search would.
+fn walk_with_metadata(root: &Path) -> SearchCache {
+    let mut cache = SearchCache::walk_fs(root);
+    cache
+        .search_with_options(
+            "file: size:>=0",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap();
+    cache
+}
+
+#[test]
+fn diff_reports_added_removed_and_modified_files() {
+    let tmp = TempDir::new("cache_diff").unwrap();
+    let root = tmp.path();
+    fs::create_dir(root.join("docs")).unwrap();
+    fs::write(root.join("docs/kept.txt"), b"same").unwrap();
+    fs::write(root.join("docs/gone.txt"), b"old").unwrap();
+    fs::write(root.join("docs/edited.txt"), b"short").unwrap();
+    let before = walk_with_metadata(root);
+
+    fs::remove_file(root.join("docs/gone.txt")).unwrap();
+    fs::write(root.join("docs/new-file.txt"), b"").unwrap();
+    fs::write(root.join("docs/edited.txt"), b"much longer now").unwrap();
+    let after = walk_with_metadata(root);
+
+    assert_eq!(
+        before.diff(&after),
+        CacheDiff {
+            added: vec![root.join("docs/new-file.txt")],
+            removed: vec![root.join("docs/gone.txt")],
+            modified: vec![root.join("docs/edited.txt")],
+        }
+    );
+    let reversed = after.diff(&before);
+    assert_eq!(reversed.added, vec![root.join("docs/gone.txt")]);
+    assert_eq!(reversed.removed, vec![root.join("docs/new-file.txt")]);
+    assert!(after.diff(&walk_with_metadata(root)).is_empty());
+}
+
+#[test]
+fn diff_lists_whole_folders_and_skips_unloaded_metadata() {
+    let tmp = TempDir::new("cache_diff").unwrap();
+    let root = tmp.path();
+    fs::write(root.join("notes.txt"), b"short").unwrap();
+    let before = SearchCache::walk_fs(root);
+
+    fs::create_dir_all(root.join("new-dir/sub")).unwrap();
+    fs::write(root.join("new-dir/sub/leaf.txt"), b"").unwrap();
+    fs::write(root.join("notes.txt"), b"much longer now").unwrap();
+    let after = SearchCache::walk_fs(root);
+
+    // Neither walk loaded metadata, so the edit goes unnoticed.
+    assert_eq!(
+        before.diff(&after),
+        CacheDiff {
+            added: vec![
+                root.join("new-dir"),
+                root.join("new-dir/sub"),
+                root.join("new-dir/sub/leaf.txt"),
+            ],
+            ..CacheDiff::default()
+        }
+    );
+}
@@ -0,0 +1,95 @@
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{fs, path::PathBuf};
+use tempdir::TempDir;
+
+fn renamed(path: PathBuf, kind: EventFlag, id: u64) -> FsEvent {
+    FsEvent {
+        path,
+        flag: EventFlag::ItemRenamed | kind,
+        id,
+    }
+}
+
+fn search(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..SearchOptions::default()
+    };
+    let nodes = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+#[test]
+fn case_only_rename_updates_the_node_in_place() {
+    let tmp = TempDir::new("case_only_rename").unwrap();
+    let root = tmp.path();
+    fs::write(root.join("Photo.JPG"), b"pixels").unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+    let index = cache.index_of_path(&root.join("Photo.JPG")).unwrap();
+
+    fs::rename(root.join("Photo.JPG"), root.join("Photo.jpg")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            renamed(root.join("Photo.JPG"), EventFlag::ItemIsFile, 1),
+            renamed(root.join("Photo.jpg"), EventFlag::ItemIsFile, 2),
+        ])
+        .unwrap();
+
+    assert_eq!(cache.index_of_path(&root.join("Photo.jpg")), Some(index));
+    assert_eq!(cache.index_of_path(&root.join("Photo.JPG")), None);
+    assert_eq!(search(&mut cache, "photo"), [root.join("Photo.jpg")]);
+}
+
+#[test]
+fn case_only_rename_of_a_folder_keeps_its_contents() {
+    let tmp = TempDir::new("case_only_rename").unwrap();
+    let root = tmp.path();
+    fs::create_dir(root.join("Album")).unwrap();
+    fs::write(root.join("Album/cover.png"), b"").unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+    let cover = cache.index_of_path(&root.join("Album/cover.png")).unwrap();
+
+    fs::rename(root.join("Album"), root.join("album")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            renamed(root.join("album"), EventFlag::ItemIsDir, 1),
+            renamed(root.join("Album"), EventFlag::ItemIsDir, 2),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        cache.index_of_path(&root.join("album/cover.png")),
+        Some(cover)
+    );
+    assert_eq!(search(&mut cache, "album"), [root.join("album")]);
+    assert_eq!(search(&mut cache, "cover"), [root.join("album/cover.png")]);
+}
+
+#[test]
+fn distinct_spellings_both_on_disk_are_left_alone() {
+    let tmp = TempDir::new("case_only_rename").unwrap();
+    let root = tmp.path();
+    fs::write(root.join("notes.txt"), b"").unwrap();
+    if fs::metadata(root.join("NOTES.txt")).is_ok() {
+        // One file under both spellings: nothing to tell apart here.
+        return;
+    }
+    let mut cache = SearchCache::walk_fs(root);
+    fs::write(root.join("NOTES.txt"), b"").unwrap();
+    cache
+        .handle_fs_events(vec![
+            renamed(root.join("notes.txt"), EventFlag::ItemIsFile, 1),
+            renamed(root.join("NOTES.txt"), EventFlag::ItemIsFile, 2),
+        ])
+        .unwrap();
+
+    let mut found = search(&mut cache, "notes");
+    found.sort();
+    assert_eq!(found, [root.join("NOTES.txt"), root.join("notes.txt")]);
+}
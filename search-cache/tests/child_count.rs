@@ -0,0 +1,32 @@
+use search_cache::SearchCache;
+use std::os::unix::fs::symlink;
+use tempdir::TempDir;
+
+#[test]
+fn child_count_reports_immediate_entries_of_directories() {
+    let tmp = TempDir::new("child_count").unwrap();
+    let root = tmp.path();
+    for file in ["a.txt", "docs/b.md", "docs/c.md", "docs/deep/d.rs"] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    std::fs::create_dir(root.join("empty")).unwrap();
+    symlink(root.join("docs"), root.join("link")).unwrap();
+    let cache = SearchCache::walk_fs(root);
+    let index = |path: &str| cache.index_of_path(&root.join(path)).unwrap();
+
+    // a.txt, docs, empty and link; only what's directly inside counts.
+    assert_eq!(cache.child_count(index("")), Some(4));
+    assert_eq!(cache.child_count(index("docs")), Some(3));
+    assert_eq!(cache.child_count(index("docs/deep")), Some(1));
+    assert_eq!(cache.child_count(index("empty")), Some(0));
+    assert_eq!(cache.child_count(index("a.txt")), None);
+    // Symlinks aren't followed, so even one to a folder has no children.
+    assert_eq!(cache.child_count(index("link")), None);
+
+    assert_eq!(
+        cache.child_counts(&[index("docs"), index("docs/b.md"), index("empty")]),
+        [Some(3), None, Some(0)]
+    );
+}
@@ -1,6 +1,9 @@
 use search_cache::{SearchCache, SearchOptions, SlabIndex};
 use search_cancel::CancellationToken;
-use std::fs;
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
 use tempdir::TempDir;
 
 // Public constant from the implementation
@@ -25,6 +28,7 @@ fn content_filter_rejects_empty_needle() {
         r#"content:"""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     );
@@ -52,6 +56,7 @@ fn content_filter_single_byte_exact_match() {
         "content:a",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -64,6 +69,7 @@ fn content_filter_single_byte_exact_match() {
         "content:A",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -88,6 +94,7 @@ fn content_filter_single_byte_case_insensitive() {
         "content:a",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -100,6 +107,7 @@ fn content_filter_single_byte_case_insensitive() {
         "content:A",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -123,6 +131,7 @@ fn content_filter_needle_spans_exact_buffer_boundary() {
         "content:BOUNDARY",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -149,6 +158,7 @@ fn content_filter_needle_spans_three_chunks() {
         "content:LONGNEEDLE",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -175,6 +185,7 @@ fn content_filter_needle_exceeds_buffer_size() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -198,6 +209,7 @@ fn content_filter_needle_equals_overlap_size() {
         "content:AB",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -217,6 +229,7 @@ fn content_filter_file_smaller_than_buffer() {
         "content:content",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -236,6 +249,7 @@ fn content_filter_empty_file_returns_no_match() {
         "content:anything",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -255,6 +269,7 @@ fn content_filter_needle_at_file_start() {
         "content:START",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -274,6 +289,7 @@ fn content_filter_needle_at_file_end() {
         "content:END",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -293,6 +309,7 @@ fn content_filter_multiple_occurrences_in_file() {
         "content:foo",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -315,6 +332,7 @@ fn content_filter_binary_with_null_bytes() {
         "content:TARGET",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -336,6 +354,7 @@ fn content_filter_utf8_multibyte_characters() {
         "content:世界",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -346,6 +365,7 @@ fn content_filter_utf8_multibyte_characters() {
         "content:🦀",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -369,6 +389,7 @@ fn content_filter_utf8_split_across_boundary() {
         "content:世界",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -394,6 +415,7 @@ fn content_filter_special_characters() {
         r#"content:"!@#$%""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -404,6 +426,7 @@ fn content_filter_special_characters() {
         r#"content:"&*()""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -424,6 +447,7 @@ fn content_filter_ignores_directories() {
         "content:content",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -451,6 +475,7 @@ fn content_filter_combined_with_extension() {
         "*.txt content:Bearer",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -463,6 +488,7 @@ fn content_filter_combined_with_extension() {
         "*.md content:Bearer",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -490,6 +516,7 @@ fn content_filter_combined_with_infolder() {
         &query,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -514,6 +541,7 @@ fn content_filter_combined_with_size() {
         "size:>1kb content:t",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -538,6 +566,7 @@ fn content_filter_with_not_operator() {
         "*.txt !content:secret",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -563,6 +592,7 @@ fn content_filter_with_or_operator() {
         "content:TODO | content:FIXME",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -594,6 +624,7 @@ fn content_filter_respects_cancellation() {
         "content:needle",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         token,
     );
@@ -605,6 +636,45 @@ fn content_filter_respects_cancellation() {
     ));
 }
 
+/// A deadline stops a long content scan and marks the outcome as timed out.
+#[test]
+fn content_filter_stops_at_the_deadline() {
+    let temp_dir = TempDir::new("content_deadline").unwrap();
+    let dir = temp_dir.path();
+
+    // Far more bytes than can be scanned within the deadline.
+    for i in 0..64 {
+        let content = vec![b'x'; CONTENT_BUFFER_BYTES * 16];
+        fs::write(dir.join(format!("large{i}.bin")), content).unwrap();
+    }
+
+    let mut cache = SearchCache::walk_fs(dir);
+    let token = CancellationToken::noop().with_deadline(Instant::now() + Duration::from_millis(1));
+    let outcome = cache
+        .search_with_options("content:needle", SearchOptions::default(), token)
+        .unwrap();
+    assert!(outcome.nodes.is_none());
+    assert!(outcome.timed_out);
+
+    // Superseded searches are cancelled too, but didn't time out.
+    let token = CancellationToken::new(2000);
+    let _ = CancellationToken::new(2001);
+    let outcome = cache
+        .search_with_options("content:needle", SearchOptions::default(), token)
+        .unwrap();
+    assert!(outcome.nodes.is_none());
+    assert!(!outcome.timed_out);
+
+    let outcome = cache
+        .search_with_options(
+            "large1",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap();
+    assert!(!outcome.timed_out);
+}
+
 /// Test unreadable file (permission denied scenario simulation)
 #[test]
 #[cfg(unix)]
@@ -628,6 +698,7 @@ fn content_filter_handles_unreadable_file() {
         "content:secret",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -658,6 +729,7 @@ fn content_filter_regex_special_chars_treated_literally() {
         "content:.*",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -667,6 +739,7 @@ fn content_filter_regex_special_chars_treated_literally() {
         "content:[test]+",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -676,6 +749,7 @@ fn content_filter_regex_special_chars_treated_literally() {
         "content:(group)?",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -699,6 +773,7 @@ fn content_filter_handles_long_lines() {
         "content:NEEDLE",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -720,6 +795,7 @@ fn content_filter_whitespace_in_needle() {
         r#"content:"word three""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -730,6 +806,7 @@ fn content_filter_whitespace_in_needle() {
         r#"content:"three   spaced""#,
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -752,6 +829,7 @@ fn content_filter_case_insensitive_mixed_case() {
             &format!(r#"content:"{needle}""#),
             SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -775,6 +853,7 @@ fn content_filter_file_exact_buffer_size() {
         "content:TARGET",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -795,6 +874,7 @@ fn content_filter_no_false_positive_at_eof() {
         "content:TARGET",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -814,6 +894,7 @@ fn content_filter_repeated_pattern_in_needle() {
         "content:aaaaaa",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -823,6 +904,7 @@ fn content_filter_repeated_pattern_in_needle() {
         "content:BBBBBB",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -846,6 +928,7 @@ fn content_filter_all_ascii_printable() {
         "content:@ABCDEF",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -866,6 +949,7 @@ fn content_filter_high_bytes() {
         "content:AB",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
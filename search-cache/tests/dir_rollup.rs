@@ -0,0 +1,205 @@
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::{DirRollup, SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::Path};
+use tempdir::TempDir;
+
+/// `big` holds 360 bytes in 7 entries; the root adds `empty/` and a 5-byte
+/// `top.txt`.
+fn build_fixture() -> TempDir {
+    let tmp = TempDir::new("dir_rollup").unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("big/a")).unwrap();
+    fs::create_dir_all(root.join("big/b/c")).unwrap();
+    fs::create_dir(root.join("empty")).unwrap();
+    for (file, len) in [
+        ("big/a/x1", 100),
+        ("big/a/x2", 200),
+        ("big/b/c/y", 50),
+        ("big/f1", 10),
+        ("top.txt", 5),
+    ] {
+        fs::write(root.join(file), vec![0u8; len]).unwrap();
+    }
+    tmp
+}
+
+/// Matches under `root` relative to it (`""` for the root), and the number of
+/// metadata reads the search made.
+fn dirsize(cache: &mut SearchCache, root: &Path, query: &str) -> (BTreeSet<String>, usize) {
+    let options = SearchOptions {
+        collect_diagnostics: true,
+        ..SearchOptions::default()
+    };
+    let outcome = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap();
+    let paths = cache
+        .node_paths(&outcome.nodes.unwrap())
+        .into_iter()
+        .flatten()
+        .filter_map(|path| {
+            path.strip_prefix(root)
+                .ok()
+                .map(|path| path.to_string_lossy().into_owned())
+        })
+        .collect();
+    (paths, outcome.diagnostics.unwrap().metadata_reads)
+}
+
+fn set(paths: &[&str]) -> BTreeSet<String> {
+    paths.iter().map(|path| path.to_string()).collect()
+}
+
+#[test]
+fn rollup_sums_sizes_and_entries_bottom_up() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let mut cache = SearchCache::walk_fs(root);
+    let index = |cache: &SearchCache, path: &str| cache.index_of_path(&root.join(path)).unwrap();
+    assert_eq!(cache.dir_rollup(index(&cache, "big")), None);
+
+    let total = cache.spawn_size_rollup(CancellationToken::noop()).unwrap();
+    assert_eq!(total.size, 365);
+
+    let rollup = |cache: &SearchCache, path: &str| cache.dir_rollup(index(cache, path)).unwrap();
+    assert_eq!(
+        rollup(&cache, ""),
+        DirRollup {
+            size: 365,
+            entries: 10
+        }
+    );
+    assert_eq!(
+        rollup(&cache, "big"),
+        DirRollup {
+            size: 360,
+            entries: 7
+        }
+    );
+    assert_eq!(
+        rollup(&cache, "big/b"),
+        DirRollup {
+            size: 50,
+            entries: 2
+        }
+    );
+    assert_eq!(rollup(&cache, "empty"), DirRollup::default());
+    assert_eq!(cache.dir_rollup(index(&cache, "top.txt")), None);
+}
+
+#[test]
+fn dirsize_after_rollup_reads_no_metadata() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+
+    let mut cold = SearchCache::walk_fs(root);
+    let (matched, reads) = dirsize(&mut cold, root, "dirsize:>100");
+    assert_eq!(matched, set(&["", "big", "big/a"]));
+    assert!(reads > 0);
+    // The on-demand sums are cached too.
+    assert_eq!(dirsize(&mut cold, root, "dirsize:>100").1, 0);
+
+    let mut warm = SearchCache::walk_fs(root);
+    warm.spawn_size_rollup(CancellationToken::noop()).unwrap();
+    let (matched, reads) = dirsize(&mut warm, root, "dirsize:>100");
+    assert_eq!(matched, set(&["", "big", "big/a"]));
+    assert_eq!(reads, 0);
+    assert_eq!(
+        dirsize(&mut warm, root, "dirsize:50..60").0,
+        set(&["big/b", "big/b/c"])
+    );
+    assert_eq!(dirsize(&mut warm, root, "dirsize:empty").0, set(&["empty"]));
+    assert_eq!(
+        dirsize(&mut warm, root, "recursivecount:7").0,
+        set(&["big"])
+    );
+}
+
+#[test]
+fn rollup_in_slices_reaches_the_same_totals() {
+    let tmp = build_fixture();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let mut slices = 0;
+    let total = loop {
+        slices += 1;
+        // Each slice allows two checks before it gives up.
+        let mut checks = 0;
+        if let Some(total) = cache.spawn_size_rollup_while(|| {
+            checks += 1;
+            (checks <= 2).then_some(())
+        }) {
+            break total;
+        }
+    };
+    assert!(slices > 1);
+    assert_eq!(total.size, 365);
+    let root = cache.index_of_path(tmp.path()).unwrap();
+    assert_eq!(
+        cache.dir_rollup(root),
+        Some(DirRollup {
+            size: 365,
+            entries: 10
+        })
+    );
+}
+
+#[test]
+fn file_system_events_drop_rollups_above_the_change() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let mut cache = SearchCache::walk_fs(root);
+    cache.spawn_size_rollup(CancellationToken::noop()).unwrap();
+
+    fs::write(root.join("big/a/x3"), vec![0u8; 1000]).unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: root.join("big/a/x3"),
+            id: cache.last_event_id().unwrap_or_default() + 1,
+            flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+        }])
+        .unwrap();
+    let rollup = |cache: &SearchCache, path: &str| {
+        cache.dir_rollup(cache.index_of_path(&root.join(path)).unwrap())
+    };
+    let big = cache.index_of_path(&root.join("big")).unwrap();
+    assert_eq!(cache.dir_rollup(big), None);
+    assert_eq!(rollup(&cache, "big/a"), None);
+    assert_eq!(rollup(&cache, ""), None);
+    // Folders off the changed path keep theirs.
+    assert_eq!(rollup(&cache, "big/b").unwrap().size, 50);
+    assert_eq!(rollup(&cache, "empty").unwrap().entries, 0);
+
+    assert_eq!(
+        dirsize(&mut cache, root, "dirsize:>1kb").0,
+        set(&["", "big", "big/a"])
+    );
+    assert_eq!(cache.dir_rollup(big).unwrap().size, 1360);
+
+    fs::remove_dir_all(root.join("big/b/c")).unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: root.join("big/b/c"),
+            id: cache.last_event_id().unwrap_or_default() + 1,
+            flag: EventFlag::ItemRemoved | EventFlag::ItemIsDir,
+        }])
+        .unwrap();
+    assert_eq!(rollup(&cache, "big/b"), None);
+    assert_eq!(rollup(&cache, "big/a").unwrap().size, 1300);
+    cache.spawn_size_rollup(CancellationToken::noop()).unwrap();
+    assert_eq!(cache.dir_rollup(big).unwrap().size, 1310);
+}
+
+#[test]
+fn dirsize_rejects_what_isnt_a_size() {
+    let tmp = TempDir::new("dir_rollup").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    for query in ["dirsize:huge-ish", "dirsize:10..1", "dirsize:"] {
+        assert!(
+            cache
+                .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+                .is_err(),
+            "{query}"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("dupe_filter").unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "a/report-q1.txt",
+        "b/report-q1.txt",
+        "b/Report-Q1.txt",
+        "c/unique-notes.md",
+        "a/assets-dir/logo.png",
+        "b/assets-dir/icon.png",
+    ] {
+        let full = root.join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn search(cache: &mut SearchCache, query: &str, options: SearchOptions) -> BTreeSet<PathBuf> {
+    let nodes = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+fn set(root: &std::path::Path, paths: &[&str]) -> BTreeSet<PathBuf> {
+    paths.iter().map(|path| root.join(path)).collect()
+}
+
+#[test]
+fn dupe_keeps_names_shared_among_the_candidates() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        search(&mut cache, "file: dupe:", SearchOptions::default()),
+        set(root, &["a/report-q1.txt", "b/report-q1.txt"])
+    );
+    // Preceding terms decide the candidates: folders only match folders.
+    assert_eq!(
+        search(&mut cache, "folder: dupe:", SearchOptions::default()),
+        set(root, &["a/assets-dir", "b/assets-dir"])
+    );
+    // A namesake outside the candidates doesn't count.
+    let in_a = format!("in:{} file: dupe:", root.join("a").display());
+    assert!(search(&mut cache, &in_a, SearchOptions::default()).is_empty());
+    // An argument narrows the candidates.
+    assert_eq!(
+        search(&mut cache, "dupe:assets", SearchOptions::default()),
+        set(root, &["a/assets-dir", "b/assets-dir"])
+    );
+}
+
+#[test]
+fn dupe_compares_names_with_the_search_options() {
+    let (tmp, mut cache) = build_cache();
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        search(&mut cache, "file: dupe:", options),
+        set(
+            tmp.path(),
+            &["a/report-q1.txt", "b/Report-Q1.txt", "b/report-q1.txt"]
+        )
+    );
+}
@@ -86,7 +86,9 @@ fn test_cancellation_with_stop_flag() {
 
     let stop = Box::leak(Box::new(AtomicBool::new(false)));
     let walk_data = fswalk::WalkData::new(&root_path, &[], false, Some(stop));
-    let mut cache = SearchCache::walk_fs_with_walk_data(&walk_data, Some(stop)).unwrap();
+    let mut cache = SearchCache::walk_fs_with_walk_data(&walk_data, Some(stop))
+        .unwrap()
+        .unwrap();
 
     // Set stop flag during search, then create new token to cancel previous
     stop.store(true, Ordering::SeqCst);
@@ -270,6 +272,7 @@ fn test_case_sensitivity() {
             "File",
             SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         )
@@ -288,6 +291,7 @@ fn test_case_sensitivity() {
             "file",
             SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         )
@@ -0,0 +1,50 @@
+use search_cache::{EmptyBehavior, SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+fn hits(cache: &mut SearchCache, query: &str, empty_query: EmptyBehavior) -> usize {
+    let options = SearchOptions {
+        empty_query,
+        ..SearchOptions::default()
+    };
+    cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap()
+        .len()
+}
+
+#[test]
+fn empty_queries_match_nothing_unless_asked_for_everything() {
+    let tmp = TempDir::new("empty_query").unwrap();
+    fs::create_dir(tmp.path().join("docs")).unwrap();
+    fs::write(tmp.path().join("docs/a.txt"), b"").unwrap();
+    fs::write(tmp.path().join("b.md"), b"").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let total = cache.get_total_files();
+
+    assert_eq!(
+        SearchOptions::default().empty_query,
+        EmptyBehavior::MatchNone
+    );
+    for query in ["", "   ", "\t", "()", "\"\""] {
+        assert_eq!(
+            hits(&mut cache, query, EmptyBehavior::MatchNone),
+            0,
+            "{query:?}"
+        );
+        assert_eq!(
+            hits(&mut cache, query, EmptyBehavior::MatchAll),
+            total,
+            "{query:?}"
+        );
+    }
+    // Only a query that is empty as a whole is affected.
+    assert_eq!(hits(&mut cache, "b.md", EmptyBehavior::MatchNone), 1);
+    assert_eq!(
+        hits(&mut cache, "!b.md", EmptyBehavior::MatchNone),
+        total - 1
+    );
+}
@@ -0,0 +1,93 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use tempdir::TempDir;
+
+fn empty_cache() -> SearchCache {
+    let tmp = TempDir::new("flag_filter").unwrap();
+    SearchCache::walk_fs(tmp.path())
+}
+
+#[test]
+fn unknown_flag_is_rejected() {
+    let cache = empty_cache();
+    let err = cache.validate_query("flag:sparkly").unwrap_err();
+    assert!(err.message.contains("Unknown file flag"), "{err}");
+    assert_eq!(
+        cache.validate_query("flag:").unwrap_err().message,
+        "flag: requires a flag name"
+    );
+    assert!(cache.validate_query("flag:Locked flag:hidden").is_ok());
+}
+
+#[cfg(not(target_os = "macos"))]
+#[test]
+fn no_node_carries_flags_off_macos() {
+    let tmp = TempDir::new("flag_filter_other").unwrap();
+    std::fs::File::create(tmp.path().join("plain.txt")).unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let options = SearchOptions {
+        collect_diagnostics: true,
+        ..SearchOptions::default()
+    };
+    let outcome = cache
+        .search_with_options("flag:locked", options, CancellationToken::noop())
+        .unwrap();
+    assert!(outcome.nodes.unwrap().is_empty());
+    assert_eq!(outcome.diagnostics.unwrap().nodes_scanned, 0);
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::*;
+    use std::{
+        collections::BTreeSet,
+        ffi::CString,
+        os::unix::ffi::OsStrExt,
+        path::{Path, PathBuf},
+    };
+
+    fn chflags(path: &Path, flags: libc::c_uint) {
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let status = unsafe { libc::chflags(path.as_ptr(), flags) };
+        assert_eq!(
+            status,
+            0,
+            "chflags failed: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+
+    fn search(cache: &mut SearchCache, query: &str) -> BTreeSet<PathBuf> {
+        let nodes = cache
+            .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+            .unwrap()
+            .nodes
+            .unwrap();
+        cache.node_paths(&nodes).into_iter().flatten().collect()
+    }
+
+    #[test]
+    fn locked_and_hidden_follow_bsd_flags() {
+        let tmp = TempDir::new("flag_filter").unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let locked = root.join("locked.txt");
+        let hidden = root.join("hidden.txt");
+        for name in ["locked.txt", "hidden.txt", "plain.txt", ".dotfile"] {
+            std::fs::File::create(root.join(name)).unwrap();
+        }
+        chflags(&locked, libc::UF_IMMUTABLE);
+        chflags(&hidden, libc::UF_HIDDEN);
+        let mut cache = SearchCache::walk_fs(&root);
+
+        let locked_hits = search(&mut cache, "flag:locked");
+        let hidden_hits = search(&mut cache, "flag:hidden");
+        let narrowed = search(&mut cache, "plain flag:locked");
+        // Unlock first so the temp dir can be removed.
+        chflags(&locked, 0);
+
+        assert_eq!(locked_hits, BTreeSet::from([locked]));
+        // A dot name alone doesn't make a file flag-hidden.
+        assert_eq!(hidden_hits, BTreeSet::from([hidden]));
+        assert!(narrowed.is_empty());
+    }
+}
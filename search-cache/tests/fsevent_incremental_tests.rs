@@ -1,7 +1,7 @@
 //! Tests for FSEvent handling and incremental cache updates
 //! Covers: add, remove, rename operations, rescan triggers, event batching
 
-use cardinal_sdk::{EventFlag, FsEvent};
+use cardinal_sdk::{EventFlag, FsEvent, current_event_id};
 use search_cache::SearchCache;
 use search_cancel::CancellationToken;
 use std::path::PathBuf;
@@ -341,7 +341,9 @@ fn test_event_id_tracking() {
     let initial_files = ["test.txt"];
     let (mut cache, root) = build_initial_cache(&initial_files);
 
-    let initial_event_id = cache.last_event_id();
+    // A fresh walk hasn't applied any event yet.
+    assert_eq!(cache.last_event_id(), None);
+    let initial_event_id = current_event_id();
 
     // Send event with higher ID
     let new_file = root.join("new.txt");
@@ -355,11 +357,7 @@ fn test_event_id_tracking() {
 
     cache.handle_fs_events(vec![event]).unwrap();
 
-    let new_event_id = cache.last_event_id();
-    assert!(
-        new_event_id > initial_event_id,
-        "Event ID should be updated"
-    );
+    let new_event_id = cache.last_event_id().expect("Event ID should be updated");
     assert_eq!(
         new_event_id,
         initial_event_id + 100,
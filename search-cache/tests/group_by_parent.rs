@@ -0,0 +1,50 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::collections::BTreeMap;
+use tempdir::TempDir;
+
+#[test]
+fn group_by_parent_collects_hits_under_their_folders() {
+    let tmp = TempDir::new("group_by_parent").unwrap();
+    let root = tmp.path();
+    for file in [
+        "top.rs",
+        "notes.txt",
+        "src/lib.rs",
+        "src/main.rs",
+        "src/readme.md",
+        "src/nested/mod.rs",
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(root);
+    let hits = cache
+        .search_with_options(
+            "ext:rs",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .nodes
+        .unwrap();
+    let index = |path: &str| cache.index_of_path(&root.join(path)).unwrap();
+
+    let mut groups = cache.group_by_parent(&hits);
+    for children in groups.values_mut() {
+        children.sort_unstable();
+    }
+    let mut src = vec![index("src/lib.rs"), index("src/main.rs")];
+    src.sort_unstable();
+    // Non-matching siblings like notes.txt and readme.md stay out.
+    assert_eq!(
+        groups,
+        BTreeMap::from([
+            (index(""), vec![index("top.rs")]),
+            (index("src"), src),
+            (index("src/nested"), vec![index("src/nested/mod.rs")]),
+        ])
+    );
+    assert!(cache.group_by_parent(&[]).is_empty());
+}
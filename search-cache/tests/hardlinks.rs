@@ -0,0 +1,95 @@
+#![cfg(unix)]
+
+use cardinal_sdk::{EventFlag, FsEvent, current_event_id};
+use search_cache::{SearchCache, SearchOptions, SlabIndex};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+fn find(cache: &mut SearchCache, query: &str) -> SlabIndex {
+    let nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    assert_eq!(nodes.len(), 1, "{query}");
+    nodes[0]
+}
+
+#[test]
+fn search_hardlinks_returns_every_link_to_the_inode() {
+    let tmp = TempDir::new("hardlinks").unwrap();
+    let root = tmp.path();
+    fs::create_dir(root.join("sub")).unwrap();
+    fs::write(root.join("original.txt"), b"shared").unwrap();
+    fs::hard_link(root.join("original.txt"), root.join("sub/linked.txt")).unwrap();
+    // Same size, so only the inode tells it apart.
+    fs::write(root.join("lookalike.txt"), b"shared").unwrap();
+
+    let mut cache = SearchCache::walk_fs(root);
+    let original = find(&mut cache, "original.txt");
+    let linked = find(&mut cache, "linked.txt");
+    let lookalike = find(&mut cache, "lookalike.txt");
+
+    let mut expected = vec![original, linked];
+    expected.sort_unstable();
+    let noop = CancellationToken::noop;
+    assert_eq!(
+        cache.search_hardlinks(original, noop()),
+        Some(expected.clone())
+    );
+    assert_eq!(cache.search_hardlinks(linked, noop()), Some(expected));
+    assert_eq!(
+        cache.search_hardlinks(lookalike, noop()),
+        Some(vec![lookalike])
+    );
+}
+
+#[test]
+fn search_hardlinks_reads_ids_once_and_forgets_them_on_changes() {
+    let tmp = TempDir::new("hardlinks_ids").unwrap();
+    let root = tmp.path();
+    fs::write(root.join("original.txt"), b"shared").unwrap();
+    fs::hard_link(root.join("original.txt"), root.join("linked.txt")).unwrap();
+    fs::write(root.join("lookalike.txt"), b"shared").unwrap();
+
+    let mut cache = SearchCache::walk_fs(root);
+    let original = find(&mut cache, "original.txt");
+    let linked = find(&mut cache, "linked.txt");
+    let mut expected = vec![original, linked];
+    expected.sort_unstable();
+    let search =
+        |cache: &mut SearchCache| cache.search_hardlinks(original, CancellationToken::noop());
+    assert_eq!(search(&mut cache), Some(expected.clone()));
+
+    // The lookalike turning into a link goes unnoticed until an event for it
+    // drops the id read above.
+    fs::remove_file(root.join("lookalike.txt")).unwrap();
+    fs::hard_link(root.join("original.txt"), root.join("lookalike.txt")).unwrap();
+    assert_eq!(search(&mut cache), Some(expected));
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: root.join("lookalike.txt"),
+            flag: EventFlag::ItemCreated,
+            id: current_event_id(),
+        }])
+        .unwrap();
+    let lookalike = find(&mut cache, "lookalike.txt");
+    let mut expected = vec![original, linked, lookalike];
+    expected.sort_unstable();
+    assert_eq!(search(&mut cache), Some(expected));
+}
+
+#[test]
+fn search_hardlinks_returns_none_when_cancelled() {
+    let tmp = TempDir::new("hardlinks_cancel").unwrap();
+    let root = tmp.path();
+    fs::write(root.join("original.txt"), b"shared").unwrap();
+    fs::hard_link(root.join("original.txt"), root.join("linked.txt")).unwrap();
+
+    let mut cache = SearchCache::walk_fs(root);
+    let original = find(&mut cache, "original.txt");
+    let token = CancellationToken::new(1);
+    let _ = CancellationToken::new(2);
+    assert_eq!(cache.search_hardlinks(original, token), None);
+}
@@ -0,0 +1,88 @@
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::{SearchCache, SlabIndex};
+use std::{fs, path::Path};
+use tempdir::TempDir;
+
+fn create(cache: &mut SearchCache, root: &Path, name: &str, id: u64) {
+    let path = root.join(name);
+    fs::File::create(&path).unwrap();
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path,
+            flag: EventFlag::ItemCreated,
+            id,
+        }])
+        .unwrap();
+}
+
+fn index_of(cache: &SearchCache, root: &Path, name: &str) -> SlabIndex {
+    cache.index_of_path(&root.join(name)).unwrap()
+}
+
+/// `names` reordered from the most recently indexed down.
+fn recently_indexed(cache: &mut SearchCache, root: &Path, names: &[&str]) -> Vec<String> {
+    let indices: Vec<SlabIndex> = names
+        .iter()
+        .map(|name| index_of(cache, root, name))
+        .collect();
+    let mut nodes = cache.expand_file_nodes(&indices);
+    nodes.sort_by_key(|node| std::cmp::Reverse(node.index_seq));
+    nodes
+        .into_iter()
+        .map(|node| {
+            node.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn index_seq_follows_insertion_order() {
+    let tmp = TempDir::new("index_seq").unwrap();
+    let root = tmp.path();
+    fs::File::create(root.join("walked.txt")).unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+
+    create(&mut cache, root, "first.txt", 1);
+    create(&mut cache, root, "second.txt", 2);
+    create(&mut cache, root, "third.txt", 3);
+    let names = ["second.txt", "walked.txt", "third.txt", "first.txt"];
+    assert_eq!(
+        recently_indexed(&mut cache, root, &names),
+        ["third.txt", "second.txt", "first.txt", "walked.txt"]
+    );
+
+    // Recreating a file counts as learning about it anew, whatever its mtime.
+    fs::remove_file(root.join("first.txt")).unwrap();
+    create(&mut cache, root, "first.txt", 4);
+    assert_eq!(
+        recently_indexed(&mut cache, root, &names),
+        ["first.txt", "third.txt", "second.txt", "walked.txt"]
+    );
+}
+
+#[test]
+fn index_seq_survives_a_reload_and_keeps_growing() {
+    let tmp = TempDir::new("index_seq").unwrap();
+    let root = tmp.path();
+    let mut cache = SearchCache::walk_fs(root);
+    create(&mut cache, root, "before.txt", 1);
+    let before = cache.expand_file_nodes(&[index_of(&cache, root, "before.txt")])[0].index_seq;
+
+    let db = TempDir::new("index_seq_db").unwrap();
+    let db_path = db.path().join("cache.db");
+    cache.flush_snapshot_to_file(&db_path).unwrap();
+    let mut loaded =
+        SearchCache::try_read_persistent_cache(root, &db_path, &Vec::new(), None).unwrap();
+    let index = index_of(&loaded, root, "before.txt");
+    assert_eq!(loaded.expand_file_nodes(&[index])[0].index_seq, before);
+
+    create(&mut loaded, root, "after.txt", 2);
+    assert_eq!(
+        recently_indexed(&mut loaded, root, &["before.txt", "after.txt"]),
+        ["after.txt", "before.txt"]
+    );
+}
@@ -0,0 +1,135 @@
+use search_cache::{IndexStats, RootInfo, SearchCache, WalkData};
+use std::{collections::BTreeMap, os::unix::fs::symlink};
+use tempdir::TempDir;
+
+fn build_fixture() -> TempDir {
+    let temp_dir = TempDir::new("index_stats").unwrap();
+    let root = temp_dir.path();
+    for file in ["a.txt", "docs/a.txt", "docs/b.md", "docs/deep/c.rs"] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    std::fs::create_dir(root.join("empty")).unwrap();
+    symlink(root.join("a.txt"), root.join("link")).unwrap();
+    temp_dir
+}
+
+#[test]
+fn stats_count_each_node_type() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let cache = SearchCache::walk_fs_with_walk_data(&WalkData::new(root, &[], true, None), None)
+        .unwrap()
+        .unwrap();
+    // The root's ancestor chain is indexed as directories too, each with its
+    // own name ("/" included).
+    let chain = root.ancestors().count();
+
+    let stats = cache.stats();
+    assert_eq!(
+        stats,
+        IndexStats {
+            files: 4,
+            directories: chain + 3,
+            symlinks: 1,
+            // `a.txt` appears twice but is one name.
+            unique_names: chain + 3 + 4,
+            name_pool_bytes: stats.name_pool_bytes,
+        }
+    );
+    assert_eq!(
+        stats.files + stats.directories + stats.symlinks,
+        cache.get_total_files()
+    );
+    assert!(stats.name_pool_bytes >= "a.txt".len() + "deep".len());
+}
+
+#[test]
+fn symlinks_without_metadata_count_as_files() {
+    let tmp = build_fixture();
+    let stats = SearchCache::walk_fs(tmp.path()).stats();
+    assert_eq!(stats.symlinks, 0);
+    assert_eq!(stats.files, 5);
+}
+
+#[test]
+fn extension_histogram_counts_files_per_extension() {
+    let tmp = TempDir::new("extension_histogram").unwrap();
+    let root = tmp.path();
+    for file in [
+        "a.txt",
+        "B.TXT",
+        "Makefile",
+        ".gitignore",
+        "src/main.rs",
+        "src/lib.rs",
+        "src/notes.txt",
+        "src/nested/mod.rs",
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    // Folders don't count, whatever their name.
+    std::fs::create_dir(root.join("photos.jpg")).unwrap();
+    let cache = SearchCache::walk_fs(root);
+    let histogram = |entries: &[(&str, usize)]| -> BTreeMap<String, usize> {
+        entries
+            .iter()
+            .map(|(extension, files)| (extension.to_string(), *files))
+            .collect()
+    };
+
+    assert_eq!(
+        cache.extension_histogram(None),
+        histogram(&[("rs", 3), ("txt", 3)])
+    );
+    let src = cache.index_of_path(&root.join("src")).unwrap();
+    assert_eq!(
+        cache.extension_histogram(Some(src)),
+        histogram(&[("rs", 3), ("txt", 1)])
+    );
+    let nested = cache
+        .index_of_path(&root.join("src/nested/mod.rs"))
+        .unwrap();
+    assert_eq!(
+        cache.extension_histogram(Some(nested)),
+        histogram(&[("rs", 1)])
+    );
+}
+
+#[test]
+fn roots_count_the_entries_below_each_root() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let fixture = RootInfo {
+        path: root.to_path_buf(),
+        total_files: 5,
+        total_dirs: 3,
+    };
+    assert_eq!(SearchCache::walk_fs(root).roots(), vec![fixture.clone()]);
+
+    let other = TempDir::new("index_stats_other").unwrap();
+    std::fs::create_dir(other.path().join("photos")).unwrap();
+    std::fs::File::create(other.path().join("photos/beach.jpg")).unwrap();
+    // `docs` lies inside the fixture, so its entries count for both roots.
+    let docs = root.join("docs");
+    let cache = SearchCache::walk_fs_roots(&[root, other.path(), &docs]).unwrap();
+    assert_eq!(
+        cache.roots(),
+        vec![
+            fixture,
+            RootInfo {
+                path: other.path().to_path_buf(),
+                total_files: 1,
+                total_dirs: 1,
+            },
+            RootInfo {
+                path: docs,
+                total_files: 3,
+                total_dirs: 1,
+            },
+        ]
+    );
+}
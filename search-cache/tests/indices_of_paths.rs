@@ -0,0 +1,48 @@
+use search_cache::SearchCache;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn indices_of_paths_matches_one_by_one_lookups() {
+    let tmp = TempDir::new("indices_of_paths").unwrap();
+    let root = tmp.path();
+    for file in [
+        "a.txt",
+        "docs/b.md",
+        "docs/c.md",
+        "docs/deep/d.rs",
+        "other/e.txt",
+    ] {
+        let full = root.join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+
+    let paths = vec![
+        root.join("docs/deep/d.rs"),
+        root.join("docs/b.md"),
+        root.join("docs/missing.md"),
+        root.join("missing/deeper/x.txt"),
+        root.join("docs"),
+        root.join("docs/c.md"),
+        root.join("a.txt"),
+        root.join("docs/b.md"),
+        root.to_path_buf(),
+        "/".into(),
+        "relative/e.txt".into(),
+    ];
+    let expected: Vec<_> = paths.iter().map(|path| cache.index_of_path(path)).collect();
+    let resolved = cache.indices_of_paths(&paths);
+
+    assert_eq!(resolved, expected);
+    assert!(resolved[0].is_some() && resolved[1] == resolved[7]);
+    assert_eq!(resolved[2..4], [None, None]);
+    assert_eq!(resolved[10], None);
+    for (path, index) in paths.iter().zip(&resolved) {
+        if let Some(index) = index {
+            assert_eq!(cache.node_path(*index).as_ref(), Some(path));
+        }
+    }
+    assert!(cache.indices_of_paths(&[]).is_empty());
+}
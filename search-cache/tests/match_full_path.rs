@@ -0,0 +1,115 @@
+use search_cache::{EmptyBehavior, SearchCache, SearchOptions, ThreadBudget};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tempdir::TempDir;
+
+const NAMES: SearchOptions = SearchOptions {
+    case_insensitive: false,
+    unicode_case_fold: false,
+    accent_insensitive: false,
+    match_full_path: false,
+    space_is_literal: false,
+    sniff_content: false,
+    collect_diagnostics: false,
+    thread_budget: ThreadBudget::Available,
+    min_query_len: 1,
+    empty_query: EmptyBehavior::MatchNone,
+};
+const FULL_PATH: SearchOptions = SearchOptions {
+    match_full_path: true,
+    ..NAMES
+};
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("match_full_path").unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "demo-home/Projects/cardinal.md",
+        "demo-home/Projects/src/main.rs",
+        "demo-home/notes.md",
+        "other/Projects-old/readme.md",
+    ] {
+        let full = root.join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn search(cache: &mut SearchCache, query: &str, options: SearchOptions) -> BTreeSet<PathBuf> {
+    let nodes = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+fn set(root: &std::path::Path, paths: &[&str]) -> BTreeSet<PathBuf> {
+    paths.iter().map(|path| root.join(path)).collect()
+}
+
+#[test]
+fn path_fragments_reach_descendants_only_in_full_path_mode() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    // Segments match name by name, so only the folder itself qualifies.
+    assert_eq!(
+        search(&mut cache, "demo-home/Proj", NAMES),
+        set(root, &["demo-home/Projects"])
+    );
+    assert_eq!(
+        search(&mut cache, "demo-home/Proj", FULL_PATH),
+        set(
+            root,
+            &[
+                "demo-home/Projects",
+                "demo-home/Projects/cardinal.md",
+                "demo-home/Projects/src",
+                "demo-home/Projects/src/main.rs",
+            ]
+        )
+    );
+    // Filters still apply to the nodes themselves.
+    assert!(search(&mut cache, "ext:md demo-home/Proj", NAMES).is_empty());
+    assert_eq!(
+        search(&mut cache, "ext:md demo-home/Proj", FULL_PATH),
+        set(root, &["demo-home/Projects/cardinal.md"])
+    );
+    assert_eq!(
+        search(&mut cache, "ext:md !demo-home/", FULL_PATH),
+        set(root, &["other/Projects-old/readme.md"])
+    );
+}
+
+#[test]
+fn full_path_words_follow_case_and_wildcards() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert!(search(&mut cache, "DEMO-HOME/projects/src", FULL_PATH).is_empty());
+    let insensitive = SearchOptions {
+        case_insensitive: true,
+        ..FULL_PATH
+    };
+    assert_eq!(
+        search(&mut cache, "DEMO-HOME/projects/src", insensitive),
+        set(
+            root,
+            &["demo-home/Projects/src", "demo-home/Projects/src/main.rs"]
+        )
+    );
+    // Wildcards cover the whole path, with `*` crossing `/`.
+    assert_eq!(
+        search(&mut cache, "*/Projects*/*.md", FULL_PATH),
+        set(
+            root,
+            &[
+                "demo-home/Projects/cardinal.md",
+                "other/Projects-old/readme.md"
+            ]
+        )
+    );
+}
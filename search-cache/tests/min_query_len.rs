@@ -0,0 +1,85 @@
+use search_cache::{EmptyBehavior, SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("min_query_len").unwrap();
+    for name in ["-", "-x.txt", "b-b.md"] {
+        fs::write(temp_dir.path().join(name), b"x").unwrap();
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+fn min_len(min_query_len: usize) -> SearchOptions {
+    SearchOptions {
+        min_query_len,
+        ..SearchOptions::default()
+    }
+}
+
+#[test]
+fn short_query_is_gated_below_the_minimum() {
+    let (_tmp, mut cache) = build_cache();
+
+    let outcome = cache
+        .search_with_options("-", min_len(2), CancellationToken::noop())
+        .unwrap();
+    assert!(outcome.below_min_length);
+    assert_eq!(outcome.nodes, Some(Vec::new()));
+    assert!(outcome.highlights.is_empty());
+    // Surrounding whitespace doesn't count, characters rather than bytes do.
+    for query in ["  -  ", "é"] {
+        let outcome = cache
+            .search_with_options(query, min_len(2), CancellationToken::noop())
+            .unwrap();
+        assert!(outcome.below_min_length, "{query:?}");
+    }
+    assert_eq!(
+        cache
+            .search_with_spans("-", min_len(2), CancellationToken::noop())
+            .unwrap(),
+        Some(Vec::new())
+    );
+    // A gated query never scans, so not even an invalid one fails.
+    let outcome = cache
+        .search_with_options("(", min_len(2), CancellationToken::noop())
+        .unwrap();
+    assert!(outcome.below_min_length);
+}
+
+#[test]
+fn short_query_runs_at_the_default_minimum() {
+    let (_tmp, mut cache) = build_cache();
+    assert_eq!(SearchOptions::default().min_query_len, 1);
+
+    for options in [SearchOptions::default(), min_len(1)] {
+        let outcome = cache
+            .search_with_options("-", options, CancellationToken::noop())
+            .unwrap();
+        assert!(!outcome.below_min_length);
+        assert_eq!(outcome.nodes.unwrap().len(), 3);
+    }
+    let outcome = cache
+        .search_with_options("-x", min_len(2), CancellationToken::noop())
+        .unwrap();
+    assert!(!outcome.below_min_length);
+    assert_eq!(outcome.nodes.unwrap().len(), 1);
+}
+
+#[test]
+fn empty_query_is_never_gated() {
+    let (_tmp, mut cache) = build_cache();
+    let total = cache.get_total_files();
+
+    let options = SearchOptions {
+        empty_query: EmptyBehavior::MatchAll,
+        ..min_len(3)
+    };
+    let outcome = cache
+        .search_with_options("", options, CancellationToken::noop())
+        .unwrap();
+    assert!(!outcome.below_min_length);
+    assert_eq!(outcome.nodes.unwrap().len(), total);
+}
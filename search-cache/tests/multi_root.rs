@@ -0,0 +1,128 @@
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+fn create_files(root: &Path, files: &[&str]) {
+    for file in files {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+}
+
+fn build_roots() -> (TempDir, TempDir) {
+    let first = TempDir::new("multi_root_a").unwrap();
+    let second = TempDir::new("multi_root_b").unwrap();
+    create_files(first.path(), &["docs/report.txt", "shared.txt"]);
+    create_files(second.path(), &["photos/report.jpg", "shared.txt"]);
+    (first, second)
+}
+
+fn query_paths(cache: &mut SearchCache, query: String) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query, CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn sorted(mut paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths.sort();
+    paths
+}
+
+#[test]
+fn search_spans_every_root_with_absolute_paths() {
+    let (first, second) = build_roots();
+    let (a, b) = (first.path(), second.path());
+    let mut cache = SearchCache::walk_fs_roots(&[a, b]).unwrap();
+
+    assert_eq!(
+        query_paths(&mut cache, "report".to_string()),
+        sorted(vec![a.join("docs/report.txt"), b.join("photos/report.jpg")])
+    );
+    assert_eq!(
+        query_paths(&mut cache, "shared.txt".to_string()),
+        sorted(vec![a.join("shared.txt"), b.join("shared.txt")])
+    );
+
+    let index = cache.index_of_path(&b.join("photos")).unwrap();
+    assert_eq!(cache.node_path(index).unwrap(), b.join("photos"));
+}
+
+#[test]
+fn folder_filters_scope_to_one_root() {
+    let (first, second) = build_roots();
+    let (a, b) = (first.path(), second.path());
+    let mut cache = SearchCache::walk_fs_roots(&[a, b]).unwrap();
+
+    assert_eq!(
+        query_paths(&mut cache, format!("infolder:{} report", b.display())),
+        vec![b.join("photos/report.jpg")]
+    );
+    assert_eq!(
+        query_paths(&mut cache, format!("parent:{}", a.display())),
+        sorted(vec![a.join("docs"), a.join("shared.txt")])
+    );
+}
+
+#[test]
+fn overlapping_roots_share_nodes() {
+    let (first, _second) = build_roots();
+    let a = first.path();
+    let single = SearchCache::walk_fs(a);
+    let mut cache = SearchCache::walk_fs_roots(&[a, &a.join("docs")]).unwrap();
+
+    assert_eq!(cache.get_total_files(), single.get_total_files());
+    assert_eq!(
+        query_paths(&mut cache, "report".to_string()),
+        vec![a.join("docs/report.txt")]
+    );
+}
+
+#[test]
+fn persisted_roots_must_match_on_load() {
+    let (first, second) = build_roots();
+    let (a, b) = (first.path(), second.path());
+    let cache_dir = TempDir::new("multi_root_cache").unwrap();
+    let cache_path = cache_dir.path().join("cache.zstd");
+    let cache = SearchCache::walk_fs_roots(&[a, b]).unwrap();
+    let total = cache.get_total_files();
+    cache.flush_to_file(&cache_path).unwrap();
+
+    let roots = [a.to_path_buf(), b.to_path_buf()];
+    let loaded =
+        SearchCache::try_read_persistent_cache_with_roots(&roots, &cache_path, &Vec::new(), None)
+            .unwrap();
+    assert_eq!(loaded.get_total_files(), total);
+
+    let err =
+        SearchCache::try_read_persistent_cache(a, &cache_path, &Vec::new(), None).unwrap_err();
+    assert!(err.to_string().contains("Inconsistent root paths"), "{err}");
+}
+
+#[test]
+fn empty_or_relative_roots_are_rejected() {
+    let (first, _second) = build_roots();
+    let err = SearchCache::walk_fs_roots(&[]).unwrap_err();
+    assert!(err.to_string().contains("requires a root"), "{err}");
+
+    let err = SearchCache::walk_fs_roots(&[first.path(), Path::new("relative")]).unwrap_err();
+    assert!(err.to_string().contains("must be absolute"), "{err}");
+}
+
+#[test]
+fn watch_paths_list_every_root() {
+    let (first, second) = build_roots();
+    let (a, b) = (first.path(), second.path());
+    let cache = SearchCache::walk_fs_roots(&[a, b]).unwrap();
+    assert_eq!(
+        cache.watch_paths(),
+        [a, b].map(|root| root.to_string_lossy().into_owned())
+    );
+}
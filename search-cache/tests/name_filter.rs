@@ -0,0 +1,115 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("name_filter").unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "report*.txt",
+        "report-2024.txt",
+        "old-report-2024.txt",
+        "report-2024.txt.bak",
+        "my report.txt",
+        "archive/report*.txt",
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn query_paths(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query.to_string(), CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn quoted_name_matches_wildcard_characters_literally() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query_paths(&mut cache, "name:\"report*.txt\""),
+        vec![root.join("archive/report*.txt"), root.join("report*.txt")]
+    );
+    assert_eq!(
+        query_paths(&mut cache, "name:\"my report.txt\""),
+        vec![root.join("my report.txt")]
+    );
+}
+
+#[test]
+fn unquoted_name_matches_the_whole_name_with_wildcards() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    // Anchored at both ends, unlike the bare `report*.txt` token.
+    assert_eq!(
+        query_paths(&mut cache, "name:report*.txt"),
+        vec![
+            root.join("archive/report*.txt"),
+            root.join("report*.txt"),
+            root.join("report-2024.txt"),
+        ]
+    );
+    assert!(query_paths(&mut cache, "name:report").is_empty());
+}
+
+#[test]
+fn name_filter_composes_and_respects_case_options() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    let query = format!(
+        "infolder:{} name:\"report*.txt\"",
+        root.join("archive").display()
+    );
+    assert_eq!(
+        query_paths(&mut cache, &query),
+        vec![root.join("archive/report*.txt")]
+    );
+
+    assert!(query_paths(&mut cache, "name:\"REPORT*.TXT\"").is_empty());
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..SearchOptions::default()
+    };
+    let hits = cache
+        .search_with_options("name:\"REPORT*.TXT\"", options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    assert_eq!(hits.len(), 2);
+
+    assert!(
+        cache
+            .query_files("name:".to_string(), CancellationToken::noop())
+            .is_err()
+    );
+}
+
+#[test]
+fn name_filter_spans_cover_the_whole_name() {
+    let (_tmp, mut cache) = build_cache();
+    let hits = cache
+        .search_with_spans(
+            "name:\"my report.txt\"",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].1, vec![0.."my report.txt".len()]);
+}
@@ -0,0 +1,59 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs};
+use tempdir::TempDir;
+
+fn names(cache: &mut SearchCache, query: &str) -> BTreeSet<String> {
+    let nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache
+        .node_paths(&nodes)
+        .into_iter()
+        .flatten()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn namelen_counts_characters_not_bytes() {
+    let tmp = TempDir::new("name_length").unwrap();
+    let long = format!("{}.txt", "x".repeat(116));
+    // 5, 9 (11 bytes), 7 (13 bytes) and 120 characters.
+    for name in ["a.txt", "résumé.md", "日本語.txt", long.as_str()] {
+        fs::write(tmp.path().join(name), b"").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let set = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+    assert_eq!(names(&mut cache, "file: namelen:>100"), set(&[&long]));
+    assert_eq!(names(&mut cache, "file: namelen:<7"), set(&["a.txt"]));
+    assert_eq!(
+        names(&mut cache, "file: namelen:<=7"),
+        set(&["a.txt", "日本語.txt"])
+    );
+    assert_eq!(names(&mut cache, "file: namelen:9"), set(&["résumé.md"]));
+    assert_eq!(
+        names(&mut cache, "file: namelen:6..9"),
+        set(&["résumé.md", "日本語.txt"])
+    );
+    assert_eq!(names(&mut cache, "file: namelen:..5"), set(&["a.txt"]));
+    // An 11-byte name isn't 11 long.
+    assert!(names(&mut cache, "file: namelen:11").is_empty());
+}
+
+#[test]
+fn namelen_rejects_what_isnt_a_count() {
+    let tmp = TempDir::new("name_length").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    for query in ["namelen:>1kb", "namelen:long", "namelen:9..3", "namelen:"] {
+        assert!(
+            cache
+                .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+                .is_err(),
+            "{query}"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("noext_filter").unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "README",
+        "Makefile",
+        ".gitignore",
+        "main.rs",
+        ".hidden.txt",
+        "trailing.",
+        "src/lib.rs",
+        "src/LICENSE",
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn query_paths(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query.to_string(), CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn noext_matches_files_without_an_extension() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    // Folders are left out like with ext:, and a trailing dot adds no extension.
+    assert_eq!(
+        query_paths(&mut cache, "noext:"),
+        vec![
+            root.join(".gitignore"),
+            root.join("Makefile"),
+            root.join("README"),
+            root.join("src/LICENSE"),
+            root.join("trailing."),
+        ]
+    );
+}
+
+#[test]
+fn leading_dot_is_not_an_extension() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert!(query_paths(&mut cache, "ext:gitignore").is_empty());
+    assert_eq!(
+        query_paths(&mut cache, "ext:txt"),
+        vec![root.join(".hidden.txt")]
+    );
+}
+
+#[test]
+fn noext_composes_with_other_terms() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query_paths(&mut cache, "noext:Make"),
+        vec![root.join("Makefile")]
+    );
+    assert_eq!(
+        query_paths(
+            &mut cache,
+            &format!("infolder:{} noext:", root.join("src").display())
+        ),
+        vec![root.join("src/LICENSE")]
+    );
+    assert_eq!(
+        query_paths(&mut cache, "!noext: file:"),
+        vec![
+            root.join(".hidden.txt"),
+            root.join("main.rs"),
+            root.join("src/lib.rs"),
+        ]
+    );
+}
@@ -0,0 +1,57 @@
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf};
+use tempdir::TempDir;
+
+fn query_paths(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query.to_string(), CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn invalid_utf8_names_are_indexed_with_their_real_paths() {
+    let tmp = TempDir::new("non_utf8_names").unwrap();
+    let root = tmp.path();
+    // Latin-1 "café", which is not valid UTF-8.
+    let dir = root.join(OsStr::from_bytes(b"caf\xE9"));
+    let file = dir.join(OsStr::from_bytes(b"menu\xFF.txt"));
+    std::fs::create_dir(&dir).unwrap();
+    std::fs::File::create(&file).unwrap();
+    std::fs::File::create(root.join("menu.txt")).unwrap();
+
+    let mut cache = SearchCache::walk_fs(root);
+    // The valid parts of a name still match, and results point at the real file.
+    assert_eq!(query_paths(&mut cache, "caf"), vec![dir.clone()]);
+    let hits = query_paths(&mut cache, "ext:txt");
+    assert_eq!(hits, vec![file.clone(), root.join("menu.txt")]);
+    assert!(hits[0].exists());
+
+    let index = cache.index_of_path(&file).expect("indexed by its bytes");
+    assert_eq!(cache.node_path(index).unwrap(), file);
+    assert_eq!(cache.node_paths(&[index]), vec![Some(file.clone())]);
+}
+
+#[test]
+fn invalid_utf8_names_survive_a_cache_round_trip() {
+    let tmp = TempDir::new("non_utf8_names_persist").unwrap();
+    let root = tmp.path();
+    let file = root.join(OsStr::from_bytes(b"\xC3report.log"));
+    std::fs::File::create(&file).unwrap();
+
+    let cache_dir = TempDir::new("non_utf8_names_cache").unwrap();
+    let cache_path = cache_dir.path().join("cache.zstd");
+    SearchCache::walk_fs(root)
+        .flush_to_file(&cache_path)
+        .unwrap();
+    let mut cache =
+        SearchCache::try_read_persistent_cache(root, &cache_path, &Vec::new(), None).unwrap();
+
+    assert_eq!(query_paths(&mut cache, "report"), vec![file]);
+}
@@ -369,3 +369,61 @@ fn test_infolder_with_negation() {
         assert_ne!(path.extension().and_then(|s| s.to_str()), Some("rs"));
     }
 }
+
+#[test]
+fn test_indir_excludes_grandchildren_that_infolder_includes() {
+    let (mut cache, root) = build_nested_cache();
+    let src_path = root.join("src");
+
+    let indir: Vec<PathBuf> = cache
+        .query_files(
+            format!("indir:{} ext:rs", src_path.display()),
+            CancellationToken::noop(),
+        )
+        .expect("Query should succeed")
+        .expect("Should return results")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    let infolder: Vec<PathBuf> = cache
+        .query_files(
+            format!("infolder:{} ext:rs", src_path.display()),
+            CancellationToken::noop(),
+        )
+        .expect("Query should succeed")
+        .expect("Should return results")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+
+    let helper = src_path.join("utils/helper.rs");
+    assert_eq!(indir.len(), 2, "indir:src ext:rs should skip utils/*.rs");
+    assert!(indir.iter().all(|path| path.parent() == Some(&*src_path)));
+    assert!(!indir.contains(&helper));
+    assert_eq!(infolder.len(), 4);
+    assert!(infolder.contains(&helper));
+}
+
+#[test]
+fn test_indir_matches_parent_without_base() {
+    let (mut cache, root) = build_nested_cache();
+    let src_path = root.join("src");
+
+    let indir = cache
+        .query_files(
+            format!("indir:{}", src_path.display()),
+            CancellationToken::noop(),
+        )
+        .expect("Query should succeed")
+        .expect("Should return results");
+    let parent = cache
+        .query_files(
+            format!("parent:{}", src_path.display()),
+            CancellationToken::noop(),
+        )
+        .expect("Query should succeed")
+        .expect("Should return results");
+
+    assert_eq!(indir.len(), 3);
+    assert_eq!(indir.len(), parent.len());
+}
@@ -0,0 +1,124 @@
+use search_cache::{IndexingCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{path::PathBuf, sync::Arc, thread};
+use tempdir::TempDir;
+
+fn build_tree() -> TempDir {
+    let temp_dir = TempDir::new("partial_search").unwrap();
+    for file in [
+        "a/report.txt",
+        "b/report.md",
+        "b/deep/report.rs",
+        "c/report.csv",
+    ] {
+        let full = temp_dir.path().join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    temp_dir
+}
+
+fn partial_paths(cache: &IndexingCache, query: &str) -> (Vec<PathBuf>, bool) {
+    let partial = cache
+        .search_partial(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap();
+    let nodes = partial
+        .outcome
+        .nodes
+        .expect("search should not be cancelled");
+    let mut paths: Vec<PathBuf> = cache.with_cache(|cache| {
+        nodes
+            .into_iter()
+            .map(|index| cache.node_path(index).unwrap())
+            .collect()
+    });
+    paths.sort();
+    (paths, partial.complete)
+}
+
+#[test]
+fn half_built_cache_returns_incomplete_results() {
+    let tmp = build_tree();
+    let root = tmp.path();
+    let cache = IndexingCache::new(root, Vec::new(), None);
+
+    assert_eq!(partial_paths(&cache, "report"), (vec![], false));
+
+    assert_eq!(cache.index_next(), Some(true));
+    assert_eq!(
+        partial_paths(&cache, "report"),
+        (vec![root.join("a/report.txt")], false)
+    );
+
+    assert_eq!(cache.index_next(), Some(true));
+    assert_eq!(
+        partial_paths(&cache, "report"),
+        (
+            vec![
+                root.join("a/report.txt"),
+                root.join("b/deep/report.rs"),
+                root.join("b/report.md"),
+            ],
+            false
+        )
+    );
+
+    assert_eq!(cache.index_next(), Some(false));
+    let (paths, complete) = partial_paths(&cache, "report");
+    assert!(complete);
+    assert_eq!(paths.len(), 4);
+    assert_eq!(cache.index_next(), Some(false));
+}
+
+#[test]
+fn finished_cache_matches_a_full_walk() {
+    let tmp = build_tree();
+    let root = tmp.path();
+    let cache = IndexingCache::new(root, vec![root.join("b")], None);
+    cache.index_all().unwrap();
+    assert!(cache.is_complete());
+
+    let (paths, _) = partial_paths(&cache, "report");
+    assert_eq!(
+        paths,
+        vec![root.join("a/report.txt"), root.join("c/report.csv")]
+    );
+
+    let mut cache = cache.into_cache();
+    let hits = cache
+        .query_files("ext:csv".to_string(), CancellationToken::noop())
+        .unwrap()
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].path, root.join("c/report.csv"));
+}
+
+#[test]
+fn searches_run_while_another_thread_indexes() {
+    let tmp = TempDir::new("partial_search_concurrent").unwrap();
+    for dir in 0..20 {
+        for file in 0..20 {
+            let full = tmp.path().join(format!("dir_{dir:02}/file_{file:02}.txt"));
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::File::create(full).unwrap();
+        }
+    }
+    let cache = Arc::new(IndexingCache::new(tmp.path(), Vec::new(), None));
+    let indexer = {
+        let cache = Arc::clone(&cache);
+        thread::spawn(move || cache.index_all())
+    };
+
+    let mut last = 0;
+    loop {
+        let (paths, complete) = partial_paths(&cache, "file_");
+        // Entries are only ever added, so results never shrink.
+        assert!(paths.len() >= last);
+        last = paths.len();
+        if complete {
+            break;
+        }
+    }
+    assert_eq!(last, 400);
+    indexer.join().unwrap().unwrap();
+}
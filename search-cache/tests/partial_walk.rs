@@ -0,0 +1,108 @@
+use search_cache::{SearchCache, ThreadBudget, WalkData};
+use search_cancel::CancellationToken;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tempdir::TempDir;
+
+fn build_fixture(dirs: usize, files: usize) -> TempDir {
+    let temp_dir = TempDir::new("partial_walk").unwrap();
+    for dir in 0..dirs {
+        let dir = temp_dir.path().join(format!("dir_{dir:03}"));
+        std::fs::create_dir(&dir).unwrap();
+        for file in 0..files {
+            std::fs::File::create(dir.join(format!("file_{file:03}.txt"))).unwrap();
+        }
+    }
+    temp_dir
+}
+
+fn all_paths(cache: &SearchCache) -> BTreeSet<PathBuf> {
+    let nodes = cache.search_empty(CancellationToken::noop()).unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+fn partial_walk_data<'w>(root: &'w Path, cancel: &'w AtomicBool) -> WalkData<'w> {
+    WalkData::new(root, &[], false, Some(cancel))
+        .keep_partial(true)
+        .thread_budget(ThreadBudget::threads(1))
+}
+
+#[test]
+fn cancelling_mid_walk_yields_a_usable_subset() {
+    static CANCEL: AtomicBool = AtomicBool::new(false);
+    let tmp = build_fixture(100, 100);
+    let root = tmp.path();
+    let full = all_paths(&SearchCache::walk_fs(root));
+
+    let walk_data = partial_walk_data(root, &CANCEL);
+    let partial = std::thread::scope(|s| {
+        s.spawn(|| {
+            while walk_data.num_dirs.load(Ordering::Relaxed) < 10 {
+                std::hint::spin_loop();
+            }
+            CANCEL.store(true, Ordering::Relaxed);
+        });
+        SearchCache::walk_fs_cancellable(&walk_data, Some(&CANCEL)).unwrap()
+    });
+    assert!(!partial.complete);
+    let mut cache = partial.cache;
+    assert!(!cache.is_complete());
+    let walked = all_paths(&cache);
+    assert!(walked.len() < full.len(), "{} nodes", walked.len());
+    assert!(walked.is_subset(&full));
+    // The partial cache answers queries over what it has.
+    let hits: BTreeSet<PathBuf> = cache
+        .query_files("file_050.txt".to_string(), CancellationToken::noop())
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    assert!(!hits.is_empty());
+    assert!(hits.iter().all(|hit| walked.contains(hit)));
+
+    // Still cancelled: nothing is lost, and the walk can be finished later.
+    assert!(cache.finish_walk().is_none());
+    assert!(!cache.is_complete());
+    CANCEL.store(false, Ordering::Relaxed);
+    cache.finish_walk().unwrap();
+    assert!(cache.is_complete());
+    assert_eq!(all_paths(&cache), full);
+}
+
+#[test]
+fn partial_cache_survives_a_flush_and_finishes_after_reload() {
+    static CANCEL: AtomicBool = AtomicBool::new(true);
+    let tmp = build_fixture(3, 3);
+    let root = tmp.path();
+    let full = all_paths(&SearchCache::walk_fs(root));
+
+    let walk_data = partial_walk_data(root, &CANCEL);
+    let partial = SearchCache::walk_fs_cancellable(&walk_data, Some(&CANCEL)).unwrap();
+    assert!(!partial.complete);
+    // Cancelled before the root was listed: only the root and its ancestors.
+    assert_eq!(
+        all_paths(&partial.cache),
+        root.ancestors().map(Path::to_path_buf).collect()
+    );
+
+    let db = TempDir::new("partial_walk_db").unwrap();
+    let db_path = db.path().join("cache.db");
+    partial.cache.flush_to_file(&db_path).unwrap();
+    CANCEL.store(false, Ordering::Relaxed);
+    let mut cache =
+        SearchCache::try_read_persistent_cache(root, &db_path, &Vec::new(), Some(&CANCEL)).unwrap();
+    assert!(!cache.is_complete());
+    cache.finish_walk().unwrap();
+    assert!(cache.is_complete());
+    assert_eq!(all_paths(&cache), full);
+
+    // An uncancelled walk is complete from the start.
+    let walk_data = partial_walk_data(root, &CANCEL);
+    let walked = SearchCache::walk_fs_cancellable(&walk_data, Some(&CANCEL)).unwrap();
+    assert!(walked.complete);
+    assert_eq!(all_paths(&walked.cache), full);
+}
@@ -0,0 +1,120 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("path_filter").unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "app/main.log",
+        "app/node_modules/left-pad/install.log",
+        "app/node_modules/left-pad/index.js",
+        "app/src/node_modules/deep/nested/build.log",
+        "app/tmp/run.log",
+        "lib/node_modules_backup.log",
+        "lib/util.js",
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn query_paths(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query.to_string(), CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn negated_path_glob_drops_nodes_anywhere_under_matching_folders() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    // `*` crosses `/`, so nesting depth doesn't matter; the text only has to
+    // appear somewhere in the path, which also catches the backup file.
+    assert_eq!(
+        query_paths(&mut cache, "ext:log !path:*node_modules*"),
+        vec![root.join("app/main.log"), root.join("app/tmp/run.log")]
+    );
+    // Slashes around the name limit it to whole folders.
+    assert_eq!(
+        query_paths(&mut cache, "ext:log !path:*/node_modules/*"),
+        vec![
+            root.join("app/main.log"),
+            root.join("app/tmp/run.log"),
+            root.join("lib/node_modules_backup.log"),
+        ]
+    );
+    // Directories inside go too; the folders themselves have no `/` after
+    // the name and stay.
+    let kept = query_paths(&mut cache, "!path:*/node_modules/*");
+    assert!(kept.contains(&root.join("app/node_modules")));
+    assert!(kept.contains(&root.join("app/src/node_modules")));
+    for inside in [
+        "app/node_modules/left-pad",
+        "app/node_modules/left-pad/index.js",
+        "app/src/node_modules/deep/nested",
+    ] {
+        assert!(!kept.contains(&root.join(inside)), "{inside}");
+    }
+}
+
+#[test]
+fn path_glob_matches_the_whole_path() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query_paths(&mut cache, "path:*/app/*.log"),
+        vec![
+            root.join("app/main.log"),
+            root.join("app/node_modules/left-pad/install.log"),
+            root.join("app/src/node_modules/deep/nested/build.log"),
+            root.join("app/tmp/run.log"),
+        ]
+    );
+    // Anchored at both ends: a bare name doesn't match a full path.
+    assert!(query_paths(&mut cache, "path:main.log").is_empty());
+    let exact = root.join("lib/util.js");
+    assert_eq!(
+        query_paths(&mut cache, &format!("path:{}", exact.display())),
+        vec![exact]
+    );
+    assert_eq!(
+        query_paths(&mut cache, "path:*/LIB/?til.js"),
+        Vec::<PathBuf>::new()
+    );
+}
+
+#[test]
+fn path_glob_follows_case_sensitivity_and_rejects_empty_globs() {
+    let (tmp, mut cache) = build_cache();
+    let options = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    let hits = cache
+        .search_with_options("path:*/LIB/?til.js", options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    assert_eq!(
+        cache.node_paths(&hits),
+        vec![Some(tmp.path().join("lib/util.js"))]
+    );
+
+    let err = cache
+        .search_with_options(r#"path:"""#, options, CancellationToken::noop())
+        .unwrap_err();
+    assert!(err.to_string().contains("path: requires a glob"), "{err}");
+}
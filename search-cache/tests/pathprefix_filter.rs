@@ -0,0 +1,119 @@
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("pathprefix_filter").unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "a/Documents/report.txt",
+        "a/Docs/old.txt",
+        "a/Downloads/report.txt",
+        "a/notes/Documents.txt",
+        "b/Documents/report.txt",
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn query_paths(cache: &mut SearchCache, query: String) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query, CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn prefix(path: &Path) -> String {
+    format!("pathprefix:{}", path.display())
+}
+
+#[test]
+fn pathprefix_matches_partial_segments() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query_paths(&mut cache, prefix(&root.join("a/Doc"))),
+        vec![
+            root.join("a/Docs"),
+            root.join("a/Docs/old.txt"),
+            root.join("a/Documents"),
+            root.join("a/Documents/report.txt"),
+        ]
+    );
+    // A trailing slash leaves out the folder itself.
+    assert_eq!(
+        query_paths(
+            &mut cache,
+            format!("{}/", prefix(&root.join("a/Documents")))
+        ),
+        vec![root.join("a/Documents/report.txt")]
+    );
+}
+
+#[test]
+fn pathprefix_differs_from_infolder() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    // infolder: needs a real folder and only walks its subtree.
+    let err = cache
+        .query_files(
+            format!("infolder:{}", root.join("a/Doc").display()),
+            CancellationToken::noop(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("not found"), "{err}");
+    assert_eq!(
+        query_paths(
+            &mut cache,
+            format!("infolder:{} report", root.join("a").display())
+        ),
+        vec![
+            root.join("a/Documents/report.txt"),
+            root.join("a/Downloads/report.txt"),
+        ]
+    );
+    assert_eq!(
+        query_paths(&mut cache, format!("{} report", prefix(&root.join("a/Do")))),
+        vec![
+            root.join("a/Documents/report.txt"),
+            root.join("a/Downloads/report.txt"),
+        ]
+    );
+    assert_eq!(
+        query_paths(
+            &mut cache,
+            format!("{} report", prefix(&root.join("a/Doc")))
+        ),
+        vec![root.join("a/Documents/report.txt")]
+    );
+}
+
+#[test]
+fn pathprefix_requires_a_non_empty_prefix() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert!(query_paths(&mut cache, prefix(&root.join("c"))).is_empty());
+    assert!(
+        cache
+            .query_files("pathprefix:".to_string(), CancellationToken::noop())
+            .is_err()
+    );
+    assert!(
+        cache
+            .query_files("pathprefix:\"\"".to_string(), CancellationToken::noop())
+            .is_err()
+    );
+}
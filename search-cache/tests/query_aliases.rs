@@ -0,0 +1,114 @@
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("query_aliases").unwrap();
+    let root = temp_dir.path();
+    for (file, size) in [
+        ("big.txt", 4096),
+        ("small.txt", 10),
+        ("big.md", 4096),
+        ("notes/huge.txt", 8192),
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::write(full, vec![b'x'; size]).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn query_paths(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query.to_string(), CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn alias_expands_to_its_fragment() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    cache.register_alias("bigtxt", "ext:txt size:>1kb").unwrap();
+
+    let expected = query_paths(&mut cache, "ext:txt size:>1kb");
+    assert_eq!(
+        expected,
+        vec![root.join("big.txt"), root.join("notes/huge.txt")]
+    );
+    assert_eq!(query_paths(&mut cache, "bigtxt:"), expected);
+    assert_eq!(query_paths(&mut cache, "@bigtxt"), expected);
+    assert_eq!(query_paths(&mut cache, "BigTxt:"), expected);
+}
+
+#[test]
+fn alias_composes_with_the_rest_of_the_query() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    cache.register_alias("big", "size:>1kb").unwrap();
+    // Aliases may build on each other, and expand as one operand.
+    cache.register_alias("bigtxt", "@big ext:txt").unwrap();
+
+    assert_eq!(
+        query_paths(&mut cache, "@bigtxt !huge"),
+        vec![root.join("big.txt")]
+    );
+    assert_eq!(
+        query_paths(&mut cache, "!@big ext:txt"),
+        vec![root.join("small.txt")]
+    );
+    assert_eq!(
+        query_paths(&mut cache, "small | big:"),
+        vec![
+            root.join("big.md"),
+            root.join("big.txt"),
+            root.join("notes/huge.txt"),
+            root.join("small.txt"),
+        ]
+    );
+
+    // Unknown `@words` are still plain name searches.
+    assert!(query_paths(&mut cache, "@nothing").is_empty());
+    // Removing `big` leaves `@big` inside `bigtxt` as a plain word.
+    assert!(cache.remove_alias("BIG"));
+    assert!(query_paths(&mut cache, "@bigtxt").is_empty());
+}
+
+#[test]
+fn self_referential_aliases_are_rejected() {
+    let (_tmp, mut cache) = build_cache();
+
+    let err = cache.register_alias("loop", "ext:txt @loop").unwrap_err();
+    assert!(err.to_string().contains("cycle"), "{err}");
+    assert!(!cache.remove_alias("loop"));
+
+    cache.register_alias("a", "b: ext:txt").unwrap();
+    let err = cache.register_alias("b", "ext:md | a:").unwrap_err();
+    assert_eq!(err.to_string(), "Alias cycle: b -> a -> b");
+    cache.register_alias("b", "ext:md").unwrap();
+    assert!(query_paths(&mut cache, "a:").is_empty());
+}
+
+#[test]
+fn alias_names_are_validated() {
+    let (_tmp, mut cache) = build_cache();
+
+    let err = cache.register_alias("size", "ext:txt").unwrap_err();
+    assert!(err.to_string().contains("built-in"), "{err}");
+    for name in ["", "two words", "@at", "a|b"] {
+        assert!(cache.register_alias(name, "ext:txt").is_err(), "{name:?}");
+    }
+    assert!(cache.register_alias("bad", "(ext:txt").is_err());
+    assert!(cache.register_alias("typo", "size:").is_ok());
+    let err = cache
+        .query_files("typo:big".to_string(), CancellationToken::noop())
+        .unwrap_err();
+    assert!(err.to_string().contains("no argument"), "{err}");
+}
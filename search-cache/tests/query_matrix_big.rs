@@ -275,6 +275,7 @@ fn case_insensitive_option_matrix() {
     let mut cache = build_cache();
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let insensitive = cache
         .search_with_options("readme.md", opts, CancellationToken::noop())
@@ -284,6 +285,7 @@ fn case_insensitive_option_matrix() {
         .len();
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let sensitive = cache
         .search_with_options("readme.md", opts, CancellationToken::noop())
@@ -297,10 +299,12 @@ fn case_insensitive_option_matrix() {
     let cache_arc = Arc::new(RwLock::new(cache));
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let iter_insensitive = collect_iterator_indices(&cache_arc, "readme.md", opts, 10).len();
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let iter_sensitive = collect_iterator_indices(&cache_arc, "readme.md", opts, 10).len();
     assert!(iter_insensitive >= iter_sensitive, "Iterator: case insensitive should match >= case sensitive");
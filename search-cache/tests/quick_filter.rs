@@ -0,0 +1,100 @@
+use search_cache::{QuickFilter, SearchCache, SearchOptions, SlabIndex};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tempdir::TempDir;
+
+const MB: u64 = 1024 * 1024;
+
+fn paths(cache: &SearchCache, nodes: Option<Vec<SlabIndex>>) -> BTreeSet<PathBuf> {
+    cache
+        .node_paths(&nodes.expect("search should not be cancelled"))
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+// The only test in this binary: it points `HOME` at the fixture so
+// `~/Downloads` resolves inside it, and nothing else may read the
+// environment meanwhile.
+#[test]
+fn quick_filters_match_their_documented_queries() {
+    let tmp = TempDir::new("quick_filter").unwrap();
+    let root = tmp.path();
+    // SAFETY: no other thread is running yet.
+    unsafe { std::env::set_var("HOME", root) };
+    for (file, size) in [
+        ("Downloads/setup.dmg", 0),
+        ("Downloads/old/readme.md", 0),
+        ("other/setup.dmg", 0),
+        ("media/movie.mp4", 150 * MB),
+        ("media/photo.jpg", 120 * MB),
+        ("media/song.mp3", 1024),
+        ("media/backup.bin", 200 * MB),
+        ("docs/plan.md", 0),
+        ("docs/draft.txt", 0),
+        ("other/plan.md", 0),
+    ] {
+        let full = root.join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        // Sparse, so the big ones cost no disk.
+        fs::File::create(full).unwrap().set_len(size).unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(root);
+
+    let documented = [
+        (
+            QuickFilter::RecentDownloads,
+            "infolder:~/Downloads dc:pastweek",
+            vec![
+                "Downloads/old",
+                "Downloads/old/readme.md",
+                "Downloads/setup.dmg",
+            ],
+        ),
+        (
+            QuickFilter::LargeMedia,
+            "file: size:>100mb <audio:|video:|type:picture>",
+            vec!["media/movie.mp4", "media/photo.jpg"],
+        ),
+        (
+            QuickFilter::TodaysWork,
+            "doc: dm:today",
+            vec![
+                "Downloads/old/readme.md",
+                "docs/draft.txt",
+                "docs/plan.md",
+                "other/plan.md",
+            ],
+        ),
+        (
+            QuickFilter::DuplicateNames,
+            "file: dupe:",
+            vec![
+                "Downloads/setup.dmg",
+                "docs/plan.md",
+                "other/plan.md",
+                "other/setup.dmg",
+            ],
+        ),
+    ];
+    assert_eq!(
+        documented.clone().map(|(filter, ..)| filter),
+        QuickFilter::ALL,
+        "every quick filter is covered"
+    );
+    for (filter, query, expected) in documented {
+        assert_eq!(filter.query(), query);
+        let quick = cache
+            .quick_filter(filter, SearchOptions::default(), CancellationToken::noop())
+            .unwrap()
+            .nodes;
+        let quick = paths(&cache, quick);
+        let spelled = cache
+            .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+            .unwrap()
+            .nodes;
+        assert_eq!(quick, paths(&cache, spelled), "{filter:?}");
+        let expected: BTreeSet<_> = expected.into_iter().map(|path| root.join(path)).collect();
+        assert_eq!(quick, expected, "{filter:?}");
+    }
+}
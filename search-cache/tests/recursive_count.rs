@@ -0,0 +1,121 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::Path};
+use tempdir::TempDir;
+
+/// Matches under `root`, relative to it; the root itself is `""`. The root's
+/// ancestors are indexed folders too but are left out.
+fn relative(cache: &mut SearchCache, root: &Path, query: &str) -> BTreeSet<String> {
+    let nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache
+        .node_paths(&nodes)
+        .into_iter()
+        .flatten()
+        .filter_map(|path| {
+            path.strip_prefix(root)
+                .ok()
+                .map(|path| path.to_string_lossy().into_owned())
+        })
+        .collect()
+}
+
+/// `big` holds 7 entries: `a`, `a/x1`, `a/x2`, `b`, `b/c`, `b/c/y` and `f1`.
+fn build_fixture() -> TempDir {
+    let tmp = TempDir::new("recursive_count").unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("big/a")).unwrap();
+    fs::create_dir_all(root.join("big/b/c")).unwrap();
+    fs::create_dir(root.join("empty")).unwrap();
+    for file in ["big/a/x1", "big/a/x2", "big/b/c/y", "big/f1", "top.txt"] {
+        fs::write(root.join(file), b"").unwrap();
+    }
+    tmp
+}
+
+#[test]
+fn recursivecount_counts_every_descendant_of_a_folder() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let mut cache = SearchCache::walk_fs(root);
+    let set = |paths: &[&str]| paths.iter().map(|path| path.to_string()).collect();
+
+    // The root holds `big` and its 7 entries, `empty` and `top.txt`.
+    assert_eq!(relative(&mut cache, root, "recursivecount:10"), set(&[""]));
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:7"),
+        set(&["big"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:2"),
+        set(&["big/a", "big/b"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:1"),
+        set(&["big/b/c"])
+    );
+    // Files hold nothing but never match, even for 0.
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:0"),
+        set(&["empty"])
+    );
+}
+
+#[test]
+fn recursivecount_comparisons_and_ranges() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let mut cache = SearchCache::walk_fs(root);
+    let set = |paths: &[&str]| paths.iter().map(|path| path.to_string()).collect();
+
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:>2"),
+        set(&["", "big"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:>=2"),
+        set(&["", "big", "big/a", "big/b"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:<2"),
+        set(&["big/b/c", "empty"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:<=2"),
+        set(&["big/a", "big/b", "big/b/c", "empty"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:!=2"),
+        set(&["", "big", "big/b/c", "empty"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "recursivecount:1..7"),
+        set(&["big", "big/a", "big/b", "big/b/c"])
+    );
+    assert_eq!(
+        relative(&mut cache, root, "big recursivecount:>0"),
+        set(&["big"])
+    );
+}
+
+#[test]
+fn recursivecount_rejects_what_isnt_a_count() {
+    let tmp = TempDir::new("recursive_count").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    for query in [
+        "recursivecount:>1k",
+        "recursivecount:many",
+        "recursivecount:9..3",
+        "recursivecount:",
+    ] {
+        assert!(
+            cache
+                .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+                .is_err(),
+            "{query}"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tempdir::TempDir;
+
+const MARKER_SECS: u64 = 1_700_000_000;
+
+fn touch(path: &Path, secs: u64) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let file = fs::File::create(path).unwrap();
+    file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap();
+}
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("reference_mtime").unwrap();
+    let root = temp_dir.path();
+    touch(&root.join("build-stamp"), MARKER_SECS);
+    touch(&root.join("src/old-a.rs"), MARKER_SECS - 3600);
+    touch(&root.join("src/old-b.rs"), MARKER_SECS - 1);
+    touch(&root.join("src/new-a.rs"), MARKER_SECS + 1);
+    touch(&root.join("src/nested/new-b.rs"), MARKER_SECS + 3600);
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn search(cache: &mut SearchCache, query: &str) -> BTreeSet<PathBuf> {
+    let nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+fn set(root: &Path, paths: &[&str]) -> BTreeSet<PathBuf> {
+    paths.iter().map(|path| root.join(path)).collect()
+}
+
+#[test]
+fn newerthan_and_olderthan_compare_with_the_reference_mtime() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    let marker = root.join("build-stamp");
+
+    // The marker itself ties and matches neither.
+    assert_eq!(
+        search(&mut cache, &format!("file: newerthan:{}", marker.display())),
+        set(root, &["src/new-a.rs", "src/nested/new-b.rs"])
+    );
+    assert_eq!(
+        search(&mut cache, &format!("file: olderthan:{}", marker.display())),
+        set(root, &["src/old-a.rs", "src/old-b.rs"])
+    );
+    // The reference doesn't need to be indexed.
+    let outside = TempDir::new("reference_mtime_outside").unwrap();
+    let stamp = outside.path().join("stamp");
+    touch(&stamp, MARKER_SECS - 60);
+    assert_eq!(
+        search(&mut cache, &format!("new- newerthan:{}", stamp.display())),
+        set(root, &["src/new-a.rs", "src/nested/new-b.rs"])
+    );
+    assert_eq!(
+        search(&mut cache, &format!("old- newerthan:{}", stamp.display())),
+        set(root, &["src/old-b.rs"])
+    );
+}
+
+#[test]
+fn reference_must_exist() {
+    let (tmp, mut cache) = build_cache();
+    let missing = tmp.path().join("no-such-stamp");
+    let err = cache
+        .search_with_options(
+            &format!("newerthan:{}", missing.display()),
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().starts_with("newerthan: can't read"),
+        "{err}"
+    );
+    let err = cache
+        .search_with_options(
+            "olderthan:",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap_err();
+    assert_eq!(err.to_string(), "olderthan: requires a file path");
+}
@@ -0,0 +1,85 @@
+use search_cache::{SearchCache, SearchOptions, SlabIndex};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("result_ordering").unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("sub")).unwrap();
+    for name in [
+        "report.txt",
+        "report.md",
+        "notes.txt",
+        "other.rs",
+        "sub/report.txt",
+        "sub/draft.md",
+    ] {
+        fs::write(root.join(name), name.as_bytes()).unwrap();
+    }
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn search(cache: &mut SearchCache, query: &str) -> Vec<SlabIndex> {
+    cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap()
+}
+
+#[test]
+fn or_with_overlapping_operands_returns_each_node_once() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    let nodes = search(&mut cache, "report|ext:txt|report.txt");
+    let paths: Vec<PathBuf> = cache.node_paths(&nodes).into_iter().flatten().collect();
+    let unique: BTreeSet<PathBuf> = paths.iter().cloned().collect();
+    assert_eq!(paths.len(), unique.len(), "{paths:?}");
+    assert_eq!(
+        unique,
+        ["report.txt", "report.md", "notes.txt", "sub/report.txt"]
+            .into_iter()
+            .map(|name| root.join(name))
+            .collect()
+    );
+
+    // Operand order has no say in the result order.
+    assert_eq!(search(&mut cache, "ext:txt|report.txt|report"), nodes);
+    assert_eq!(search(&mut cache, "<report.txt|ext:txt>|report"), nodes);
+}
+
+#[test]
+fn results_are_sorted_whichever_filters_ran() {
+    let (_tmp, mut cache) = build_cache();
+    for query in [
+        "report",
+        "*.txt",
+        "ext:md",
+        "type:file",
+        "size:>0",
+        "!other",
+        "dm:today|ext:md",
+        "ext:md|report.txt|notes",
+        "file: (rs|md|txt)",
+    ] {
+        let nodes = search(&mut cache, query);
+        assert!(!nodes.is_empty(), "{query:?}");
+        assert!(
+            nodes.windows(2).all(|pair| pair[0] < pair[1]),
+            "{query:?} gave {nodes:?}"
+        );
+    }
+
+    let spans = cache
+        .search_with_spans(
+            "ext:md|report",
+            SearchOptions::default(),
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .unwrap();
+    assert!(spans.windows(2).all(|pair| pair[0].0 < pair[1].0));
+}
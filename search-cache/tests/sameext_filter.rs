@@ -0,0 +1,107 @@
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("sameext_filter").unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "a.jpg",
+        "b.JPG",
+        "c.png",
+        "notes.txt",
+        "trips/d.jpg",
+        "trips/e.jpeg",
+        "trips/README",
+    ] {
+        let full = root.join(file);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::File::create(full).unwrap();
+    }
+    std::fs::create_dir(root.join("album.jpg")).unwrap();
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn query_paths(cache: &mut SearchCache, query: String) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query, CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn sameext(path: &Path) -> String {
+    format!("sameext:{}", path.display())
+}
+
+#[test]
+fn sameext_matches_files_sharing_the_reference_extension() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    // Case-insensitive like ext:, files only (album.jpg is a folder).
+    assert_eq!(
+        query_paths(&mut cache, sameext(&root.join("a.jpg"))),
+        vec![
+            root.join("a.jpg"),
+            root.join("b.JPG"),
+            root.join("trips/d.jpg")
+        ]
+    );
+}
+
+#[test]
+fn sameext_composes_with_a_base() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    let query = format!(
+        "{} infolder:{}",
+        sameext(&root.join("a.jpg")),
+        root.join("trips").display()
+    );
+    assert_eq!(
+        query_paths(&mut cache, query),
+        vec![root.join("trips/d.jpg")]
+    );
+
+    let query = format!("!b {}", sameext(&root.join("a.jpg")));
+    assert_eq!(
+        query_paths(&mut cache, query),
+        vec![root.join("a.jpg"), root.join("trips/d.jpg")]
+    );
+}
+
+#[test]
+fn sameext_rejects_missing_or_extensionless_references() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    let err = cache
+        .query_files(
+            sameext(&root.join("missing.jpg")),
+            CancellationToken::noop(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("not found"), "{err}");
+
+    let err = cache
+        .query_files(
+            sameext(&root.join("trips/README")),
+            CancellationToken::noop(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("no extension"), "{err}");
+
+    assert!(
+        cache
+            .query_files("sameext:".to_string(), CancellationToken::noop())
+            .is_err()
+    );
+}
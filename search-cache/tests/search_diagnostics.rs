@@ -0,0 +1,77 @@
+use search_cache::{EmptyBehavior, SearchCache, SearchOptions, ThreadBudget};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("search_diagnostics").unwrap();
+    for name in ["alpha.txt", "beta.txt", "gamma.md"] {
+        fs::write(temp_dir.path().join(name), b"x").unwrap();
+    }
+    fs::create_dir(temp_dir.path().join("nested")).unwrap();
+    fs::write(temp_dir.path().join("nested/delta.txt"), b"x").unwrap();
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+const DIAGNOSTICS: SearchOptions = SearchOptions {
+    case_insensitive: false,
+    unicode_case_fold: false,
+    accent_insensitive: false,
+    match_full_path: false,
+    space_is_literal: false,
+    sniff_content: false,
+    collect_diagnostics: true,
+    thread_budget: ThreadBudget::Available,
+    min_query_len: 1,
+    empty_query: EmptyBehavior::MatchNone,
+};
+
+#[test]
+fn diagnostics_absent_unless_requested() {
+    let (_tmp, mut cache) = build_cache();
+    let outcome = cache
+        .search_with_options("alpha", SearchOptions::default(), CancellationToken::noop())
+        .unwrap();
+    assert!(outcome.diagnostics.is_none());
+}
+
+#[test]
+fn diagnostics_count_filter_scans_and_metadata_reads() {
+    let (_tmp, mut cache) = build_cache();
+    let total = cache.get_total_files();
+
+    let outcome = cache
+        .search_with_options("dm:>=1970-01-02", DIAGNOSTICS, CancellationToken::noop())
+        .unwrap();
+    let diagnostics = outcome.diagnostics.expect("diagnostics requested");
+    assert!(outcome.nodes.is_some());
+    assert_eq!(diagnostics.nodes_scanned, total);
+    // walk_fs skips file metadata, so each of the four files is stat'ed once.
+    assert_eq!(diagnostics.metadata_reads, 4);
+    assert!(!diagnostics.used_mdfind);
+    assert!(diagnostics.elapsed.as_secs() < 5);
+
+    // Metadata is cached on the nodes now and counters reset per search.
+    let diagnostics = cache
+        .search_with_options("dm:>=1970-01-02", DIAGNOSTICS, CancellationToken::noop())
+        .unwrap()
+        .diagnostics
+        .unwrap();
+    assert_eq!(diagnostics.nodes_scanned, total);
+    assert_eq!(diagnostics.metadata_reads, 0);
+}
+
+#[test]
+fn diagnostics_count_name_and_base_filter_scans() {
+    let (_tmp, mut cache) = build_cache();
+    let outcome = cache
+        .search_with_options("txt ext:txt", DIAGNOSTICS, CancellationToken::noop())
+        .unwrap();
+    let hits = outcome.nodes.unwrap();
+    let diagnostics = outcome.diagnostics.unwrap();
+    assert_eq!(hits.len(), 3);
+    // The name scan walks the shared pool, then ext: only tests the three name hits.
+    assert!(diagnostics.nodes_scanned >= 3 + 3);
+    assert_eq!(diagnostics.metadata_reads, 0);
+}
@@ -51,6 +51,7 @@ fn and_space_multi_segments_basic() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Space acts as AND; require both alpha and beta.
     let indices =
@@ -90,6 +91,7 @@ fn or_operator_multi_segments() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("alpha | gamma", opts, CancellationToken::noop()));
@@ -120,6 +122,7 @@ fn not_operator_excludes_segment() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("alpha !beta", opts, CancellationToken::noop()));
@@ -151,6 +154,7 @@ fn mixed_and_or_precedence() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Current precedence groups left-to-right; validate minimal presence of alpha_beta and any gamma-containing.
     let indices = guard_indices(cache.search_with_options(
@@ -197,6 +201,7 @@ fn multi_segments_with_wildcards() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Use space-AND with a trailing wildcard on second term to reflect implementation behavior observed.
     let indices =
@@ -233,6 +238,7 @@ fn multi_segments_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("alpha beta", opts, CancellationToken::noop()));
@@ -278,6 +284,7 @@ fn regex_plus_plain_segment() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // regex selects numeric alpha, then AND beta plain segment
     let indices = guard_indices(cache.search_with_options(
@@ -303,6 +310,7 @@ fn filter_and_terms_multi_segments() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // ext:txt intersects with alpha and beta
     let indices = guard_indices(cache.search_with_options(
@@ -336,6 +344,7 @@ fn not_with_filter_multi_segments() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // alpha AND beta AND NOT (ext:md) => .txt + .rs
     let indices = guard_indices(cache.search_with_options(
@@ -371,6 +380,7 @@ fn chained_not_and_or_segments() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // (alpha AND gamma) OR (delta AND NOT beta)
     let indices = guard_indices(cache.search_with_options(
@@ -693,6 +703,7 @@ fn partial_file_name_wildcard_extensions() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("readme*.md", opts, CancellationToken::noop()));
@@ -733,6 +744,7 @@ fn partial_file_name_leading_wildcard() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("*readme.md", opts, CancellationToken::noop()));
@@ -806,6 +818,7 @@ fn case_insensitive_partial_segment_variants() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo*bar/baz", opts, CancellationToken::noop()));
@@ -840,6 +853,7 @@ fn partial_unicode_segment_wildcard() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("café*/docs", opts, CancellationToken::noop()));
@@ -30,6 +30,7 @@ fn leading_slash_anchors_to_root_segment() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/foo/bar/baz.txt",
@@ -57,6 +58,7 @@ fn trailing_slash_requires_exact_last_segment() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("docs/guide/", opts, CancellationToken::noop()));
@@ -98,6 +100,7 @@ fn case_insensitive_segments_match_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("/foo/bar/baz/", opts, CancellationToken::noop()));
@@ -119,6 +122,7 @@ fn mixed_prefix_suffix_segments_for_files() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo/report.txt", opts, CancellationToken::noop()));
@@ -159,6 +163,7 @@ fn trailing_slash_deep_exact_directory() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("a/b/c/d/", opts, CancellationToken::noop()));
@@ -217,6 +222,7 @@ fn mixed_case_segments_case_sensitive_behavior() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo/bar/baz/", opts, CancellationToken::noop()));
@@ -245,6 +251,7 @@ fn mixed_case_segments_case_insensitive_behavior() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("/foo/bar/baz/", opts, CancellationToken::noop()));
@@ -274,6 +281,7 @@ fn wildcard_last_segment_multiple_extensions() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "docs/guide/readme.*",
@@ -309,6 +317,7 @@ fn wildcard_last_segment_multiple_extensions_case_insensitive() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "docs/guide/readme*.md",
@@ -583,6 +592,7 @@ fn globstar_case_sensitive_vs_insensitive_variants() {
 
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let insensitive =
         guard_indices(cache.search_with_options("aa/**/file.txt", opts, CancellationToken::noop()));
@@ -807,6 +817,7 @@ fn unicode_path_segments_case_insensitive() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/café/文件/notes.txt",
@@ -833,6 +844,7 @@ fn unicode_path_segments_case_sensitive() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "café/文件/notes.txt",
@@ -1014,6 +1026,7 @@ fn case_sensitive_exact_segment_casing() {
     // Case sensitive: only exact lower-case path should be returned for lower-case query.
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("src/lib/core/", opts, CancellationToken::noop()));
@@ -1043,6 +1056,7 @@ fn case_insensitive_directory_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("/src/lib/core/", opts, CancellationToken::noop()));
@@ -1070,6 +1084,7 @@ fn mixed_wildcard_case_sensitive_file_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "app/config/readme.*",
@@ -1106,6 +1121,7 @@ fn mixed_wildcard_case_insensitive_file_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/app/config/readme.*",
@@ -1134,6 +1150,7 @@ fn case_sensitive_file_exact_match_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "guide/ReadMe.md",
@@ -1159,6 +1176,7 @@ fn case_insensitive_file_exact_match_variants() {
     let mut cache = SearchCache::walk_fs(root);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "guide/readme.md",
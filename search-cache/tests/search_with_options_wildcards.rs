@@ -50,6 +50,7 @@ fn single_segment_wildcard_complex_pattern_case_sensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo*alpha*.txt", opts, CancellationToken::noop()));
@@ -80,6 +81,7 @@ fn single_segment_wildcard_complex_pattern_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("foo*bar*.txt", opts, CancellationToken::noop()));
@@ -115,6 +117,7 @@ fn leading_wildcard_matches_suffix() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("*beta.txt", opts, CancellationToken::noop()));
@@ -145,6 +148,7 @@ fn trailing_wildcard_matches_prefix() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("alpha*", opts, CancellationToken::noop()));
@@ -176,6 +180,7 @@ fn question_mark_single_character() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
         guard_indices(cache.search_with_options("file?.txt", opts, CancellationToken::noop()));
@@ -207,6 +212,7 @@ fn star_only_matches_all_files() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options("*", opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
@@ -249,6 +255,7 @@ fn multi_segment_wildcard_intersection_case_sensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Both segments must match: alpha* AND *beta*.txt (beta can appear later)
     let indices = guard_indices(cache.search_with_options(
@@ -291,6 +298,7 @@ fn multi_segment_wildcard_intersection_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "alpha* *beta*.txt",
@@ -331,6 +339,7 @@ fn complex_mixed_wildcards_and_question_mark() {
     let mut cache = SearchCache::walk_fs(dir);
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Pattern: a*b?c*.txt => a then any, b then any single char, c then any, .txt
     let indices =
@@ -0,0 +1,88 @@
+//! Its own test binary, so the counting allocator only sees this benchmark.
+
+use search_cache::{SlabIndex, intersect_in_place};
+use search_cancel::CancellationToken;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashSet,
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+/// Counts allocations so the benchmark below can compare set strategies.
+/// Forwards everything to the system allocator, which is the default anyway.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn ids(raw: &[u32]) -> Vec<SlabIndex> {
+    raw.iter().map(|&i| SlabIndex::new(i as usize)).collect()
+}
+
+/// The HashSet-based intersection `intersect_in_place` replaced.
+fn hashset_intersect(values: &mut Vec<SlabIndex>, rhs: &[SlabIndex]) {
+    let rhs_set: HashSet<SlabIndex> = rhs.iter().copied().collect();
+    let mut filtered = Vec::with_capacity(values.len().min(rhs.len()));
+    for index in values.iter().copied() {
+        if rhs_set.contains(&index) {
+            filtered.push(index);
+        }
+    }
+    *values = filtered;
+}
+
+#[test]
+#[ignore = "timing benchmark, run manually"]
+fn intersection_allocations_vs_hashset() {
+    const LEN: u32 = 200_000;
+    // Scrambled order, like name-ordered results over a walked tree.
+    let scrambled = |step: u32| -> Vec<SlabIndex> {
+        ids(&(0..LEN)
+            .map(|i| i.wrapping_mul(2_654_435_761) % LEN)
+            .filter(|i| i % step == 0)
+            .collect::<Vec<_>>())
+    };
+    let operands: Vec<Vec<SlabIndex>> = [2, 3, 5].into_iter().map(scrambled).collect();
+
+    // Build inputs up front so only the intersections themselves are counted.
+    let mut legacy = scrambled(1);
+    let mut current = legacy.clone();
+    let owned = operands.clone();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for rhs in &operands {
+        hashset_intersect(&mut legacy, rhs);
+    }
+    let legacy_elapsed = start.elapsed();
+    let legacy_allocs = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for rhs in owned {
+        intersect_in_place(&mut current, rhs, CancellationToken::noop()).unwrap();
+    }
+    let elapsed = start.elapsed();
+    let allocs = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    assert_eq!(black_box(current), legacy);
+    println!(
+        "hashset: {legacy_allocs} allocations in {legacy_elapsed:?}; \
+         sorted in place: {allocs} allocations in {elapsed:?}"
+    );
+}
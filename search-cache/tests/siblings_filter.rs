@@ -0,0 +1,74 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tempdir::TempDir;
+
+fn search(cache: &mut SearchCache, query: &str) -> BTreeSet<PathBuf> {
+    let nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+#[test]
+fn siblings_share_the_reference_parent() {
+    let tmp = TempDir::new("siblings_filter").unwrap();
+    let root = tmp.path();
+    for file in [
+        "reports/q1.pdf",
+        "reports/q2.pdf",
+        "reports/q3.pdf",
+        "reports/notes.txt",
+        "reports/archive/q0.pdf",
+        "other/q4.pdf",
+    ] {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::File::create(path).unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(root);
+    let paths = |names: &[&str]| -> BTreeSet<PathBuf> {
+        names.iter().map(|name| root.join(name)).collect()
+    };
+    let reference = root.join("reports/q2.pdf");
+
+    assert_eq!(
+        search(&mut cache, &format!("siblings:{}", reference.display())),
+        paths(&[
+            "reports/q1.pdf",
+            "reports/q3.pdf",
+            "reports/notes.txt",
+            "reports/archive",
+        ])
+    );
+    // Combined with other terms it narrows them like a scope filter.
+    assert_eq!(
+        search(
+            &mut cache,
+            &format!("ext:pdf siblings:{}", reference.display())
+        ),
+        paths(&["reports/q1.pdf", "reports/q3.pdf"])
+    );
+    // A folder works as the reference too.
+    assert_eq!(
+        search(
+            &mut cache,
+            &format!("siblings:{}", root.join("reports").display())
+        ),
+        paths(&["other"])
+    );
+}
+
+#[test]
+fn siblings_of_missing_path_is_an_error() {
+    let tmp = TempDir::new("siblings_missing").unwrap();
+    fs::File::create(tmp.path().join("present.txt")).unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let query = format!("siblings:{}", tmp.path().join("absent.txt").display());
+    let err = cache
+        .search_with_options(&query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap_err();
+    assert!(err.to_string().contains("not found"), "{err}");
+}
@@ -0,0 +1,91 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tempdir::TempDir;
+
+const JPEG_HEADER: &[u8] = b"\xFF\xD8\xFF\xE0\x00\x10JFIF\x00\x01\x01\x00\x00\x01";
+
+fn sniffing() -> SearchOptions {
+    SearchOptions {
+        sniff_content: true,
+        ..SearchOptions::default()
+    }
+}
+
+fn search(cache: &mut SearchCache, query: &str, options: SearchOptions) -> BTreeSet<PathBuf> {
+    let nodes = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+#[test]
+fn jpeg_without_extension_matches_only_when_sniffing() {
+    let tmp = TempDir::new("sniff_content").unwrap();
+    let root = tmp.path();
+    fs::write(root.join("photo"), JPEG_HEADER).unwrap();
+    fs::write(root.join("renamed.txt"), JPEG_HEADER).unwrap();
+    fs::write(root.join("real.jpg"), b"not actually a jpeg").unwrap();
+    fs::write(root.join("notes"), b"plain text, no signature").unwrap();
+    fs::File::create(root.join("empty")).unwrap();
+    fs::create_dir(root.join("folder")).unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+    let paths = |names: &[&str]| -> BTreeSet<PathBuf> {
+        names.iter().map(|name| root.join(name)).collect()
+    };
+
+    assert_eq!(
+        search(&mut cache, "type:picture", SearchOptions::default()),
+        paths(&["real.jpg"])
+    );
+    assert_eq!(
+        search(&mut cache, "type:picture", sniffing()),
+        paths(&["photo", "real.jpg", "renamed.txt"])
+    );
+    // Groups without a signature check are unaffected.
+    assert_eq!(
+        search(&mut cache, "type:doc", sniffing()),
+        paths(&["renamed.txt"])
+    );
+}
+
+#[test]
+fn sniffing_recognizes_other_groups() {
+    let tmp = TempDir::new("sniff_content_groups").unwrap();
+    let root = tmp.path();
+    fs::write(root.join("paper"), b"%PDF-1.7\n%\xE2\xE3\xCF\xD3").unwrap();
+    fs::write(root.join("song"), b"ID3\x04\x00\x00\x00\x00\x00\x00").unwrap();
+    fs::write(
+        root.join("clip"),
+        b"\x00\x00\x00\x18ftypmp42\x00\x00\x00\x00",
+    )
+    .unwrap();
+    fs::write(root.join("bundle"), b"\x1F\x8B\x08\x00\x00\x00\x00\x00").unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+    let paths = |names: &[&str]| -> BTreeSet<PathBuf> {
+        names.iter().map(|name| root.join(name)).collect()
+    };
+
+    assert_eq!(
+        search(&mut cache, "type:pdf", sniffing()),
+        paths(&["paper"])
+    );
+    assert_eq!(
+        search(&mut cache, "type:audio", sniffing()),
+        paths(&["song"])
+    );
+    assert_eq!(
+        search(&mut cache, "type:video", sniffing()),
+        paths(&["clip"])
+    );
+    assert_eq!(
+        search(&mut cache, "type:archive", sniffing()),
+        paths(&["bundle"])
+    );
+    assert!(search(&mut cache, "type:picture", sniffing()).is_empty());
+    // Macros share the groups, and their name part still narrows the result.
+    assert_eq!(search(&mut cache, "audio:so", sniffing()), paths(&["song"]));
+    assert!(search(&mut cache, "audio:so", SearchOptions::default()).is_empty());
+}
@@ -0,0 +1,51 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tempdir::TempDir;
+
+fn literal() -> SearchOptions {
+    SearchOptions {
+        space_is_literal: true,
+        ..SearchOptions::default()
+    }
+}
+
+fn search(cache: &mut SearchCache, query: &str, options: SearchOptions) -> BTreeSet<PathBuf> {
+    let nodes = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+#[test]
+fn spaces_join_words_only_in_literal_mode() {
+    let tmp = TempDir::new("space_is_literal").unwrap();
+    let root = tmp.path();
+    for file in ["my report.txt", "report for my team.txt", "my-notes.md"] {
+        fs::File::create(root.join(file)).unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(root);
+    let paths = |names: &[&str]| -> BTreeSet<PathBuf> {
+        names.iter().map(|name| root.join(name)).collect()
+    };
+
+    assert_eq!(
+        search(&mut cache, "my report", SearchOptions::default()),
+        paths(&["my report.txt", "report for my team.txt"])
+    );
+    assert_eq!(
+        search(&mut cache, "my report", literal()),
+        paths(&["my report.txt"])
+    );
+    // Filters and operators still split the phrase.
+    assert_eq!(
+        search(&mut cache, "my ext:md", literal()),
+        paths(&["my-notes.md"])
+    );
+    assert_eq!(
+        search(&mut cache, "team | my report", literal()),
+        paths(&["my report.txt", "report for my team.txt"])
+    );
+}
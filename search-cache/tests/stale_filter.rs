@@ -0,0 +1,121 @@
+#![cfg(unix)]
+
+use cardinal_sdk::{EventFlag, FsEvent, current_event_id};
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{
+    collections::BTreeSet,
+    fs::{self, File, FileTimes},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tempdir::TempDir;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn set_times(path: &Path, accessed_days_ago: u32, modified_days_ago: u32) {
+    let now = SystemTime::now();
+    let times = FileTimes::new()
+        .set_accessed(now - DAY * accessed_days_ago)
+        .set_modified(now - DAY * modified_days_ago);
+    File::open(path).unwrap().set_times(times).unwrap();
+}
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("stale_filter").unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("archive")).unwrap();
+    for name in ["archive/old.txt", "opened.txt", "edited.txt", "recent.txt"] {
+        fs::write(root.join(name), b"x").unwrap();
+    }
+    set_times(&root.join("archive/old.txt"), 800, 800);
+    set_times(&root.join("opened.txt"), 0, 800);
+    set_times(&root.join("edited.txt"), 800, 0);
+    set_times(&root.join("recent.txt"), 10, 10);
+    // Folders never count, however old.
+    set_times(&root.join("archive"), 800, 800);
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn search(cache: &mut SearchCache, query: &str) -> BTreeSet<PathBuf> {
+    let nodes = cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+fn set(root: &Path, paths: &[&str]) -> BTreeSet<PathBuf> {
+    paths.iter().map(|path| root.join(path)).collect()
+}
+
+#[test]
+fn stale_needs_both_atime_and_mtime_to_be_old() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        search(&mut cache, "stale:1y"),
+        set(root, &["archive/old.txt"])
+    );
+    assert_eq!(
+        search(&mut cache, "stale:365d"),
+        set(root, &["archive/old.txt"])
+    );
+    assert_eq!(
+        search(&mut cache, "stale:6mo"),
+        set(root, &["archive/old.txt"])
+    );
+    assert_eq!(
+        search(&mut cache, "stale:1w"),
+        set(root, &["archive/old.txt", "recent.txt"])
+    );
+    assert!(search(&mut cache, "stale:3years").is_empty());
+    assert_eq!(
+        search(&mut cache, "recent stale:1w"),
+        set(root, &["recent.txt"])
+    );
+}
+
+#[test]
+fn stale_keeps_access_times_until_an_event_drops_them() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+    assert_eq!(
+        search(&mut cache, "stale:1y"),
+        set(root, &["archive/old.txt"])
+    );
+
+    // Opening a file sends no event, so the atime read above still answers.
+    set_times(&root.join("archive/old.txt"), 0, 800);
+    assert_eq!(
+        search(&mut cache, "stale:1y"),
+        set(root, &["archive/old.txt"])
+    );
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: root.join("archive/old.txt"),
+            flag: EventFlag::ItemModified,
+            id: current_event_id(),
+        }])
+        .unwrap();
+    assert!(search(&mut cache, "stale:1y").is_empty());
+}
+
+#[test]
+fn stale_rejects_unknown_durations() {
+    let (_tmp, mut cache) = build_cache();
+    for (query, message) in [
+        ("stale:", "stale: requires a duration"),
+        ("stale:6m", "unknown duration unit"),
+        ("stale:year", "expects a duration"),
+        ("stale:99999999999y", "out of range"),
+    ] {
+        let err = cache
+            .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+            .unwrap_err();
+        assert!(err.to_string().contains(message), "{query}: {err}");
+    }
+}
@@ -0,0 +1,29 @@
+use search_cache::SearchCache;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn suggestions_rank_names_by_how_many_nodes_carry_them() {
+    let tmp = TempDir::new("suggest").unwrap();
+    let root = tmp.path();
+    for dir in ["a", "b", "c"] {
+        fs::create_dir(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("report.txt"), b"x").unwrap();
+    }
+    fs::write(root.join("a/repo.md"), b"x").unwrap();
+    fs::write(root.join("b/repo.md"), b"x").unwrap();
+    fs::write(root.join("replay.log"), b"x").unwrap();
+    fs::write(root.join("Report.pdf"), b"x").unwrap();
+    fs::write(root.join("unrelated.rep"), b"x").unwrap();
+    let cache = SearchCache::walk_fs(root);
+
+    assert_eq!(
+        cache.suggest("rep", 10),
+        ["report.txt", "repo.md", "replay.log"]
+    );
+    assert_eq!(cache.suggest("rep", 2), ["report.txt", "repo.md"]);
+    assert_eq!(cache.suggest("report", 10), ["report.txt"]);
+    assert_eq!(cache.suggest("Rep", 10), ["Report.pdf"]);
+    assert!(cache.suggest("rep", 0).is_empty());
+    assert!(cache.suggest("zzz", 10).is_empty());
+}
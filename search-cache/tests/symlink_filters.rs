@@ -0,0 +1,97 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::{os::unix::fs::symlink, path::PathBuf};
+use tempdir::TempDir;
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("symlink_filters").unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir(root.join("docs")).unwrap();
+    std::fs::File::create(root.join("docs/target.txt")).unwrap();
+    symlink(root.join("docs/target.txt"), root.join("valid_link.txt")).unwrap();
+    symlink(root.join("docs/gone.txt"), root.join("dangling_link.txt")).unwrap();
+    symlink(root.join("docs"), root.join("docs_link")).unwrap();
+    let cache = SearchCache::walk_fs(root);
+    (temp_dir, cache)
+}
+
+fn query_paths(cache: &mut SearchCache, query: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query.to_string(), CancellationToken::noop())
+        .expect("query should succeed")
+        .expect("query should not be cancelled")
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn type_symlink_matches_valid_and_dangling_links() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query_paths(&mut cache, "type:symlink"),
+        vec![
+            root.join("dangling_link.txt"),
+            root.join("docs_link"),
+            root.join("valid_link.txt"),
+        ]
+    );
+    assert_eq!(
+        query_paths(&mut cache, "type:symlink .txt !dangling"),
+        vec![root.join("valid_link.txt")]
+    );
+}
+
+#[test]
+fn broken_matches_only_dangling_links() {
+    let (tmp, mut cache) = build_cache();
+    let root = tmp.path();
+
+    assert_eq!(
+        query_paths(&mut cache, "broken:"),
+        vec![root.join("dangling_link.txt")]
+    );
+    assert!(query_paths(&mut cache, "broken:valid").is_empty());
+
+    // Removing a link's target breaks it without rescanning.
+    std::fs::remove_file(root.join("docs/target.txt")).unwrap();
+    assert_eq!(
+        query_paths(&mut cache, "broken:link.txt"),
+        vec![root.join("dangling_link.txt"), root.join("valid_link.txt")]
+    );
+}
+
+#[test]
+fn cancelled_broken_filter_returns_none() {
+    let (_tmp, mut cache) = build_cache();
+    let token = CancellationToken::new(60);
+    let _ = CancellationToken::new(61);
+    let result = cache.query_files("broken:".to_string(), token).unwrap();
+    assert!(result.is_none());
+}
+
+#[test]
+fn type_symlink_stats_only_nodes_walked_as_files() {
+    let (_tmp, mut cache) = build_cache();
+    let options = SearchOptions {
+        collect_diagnostics: true,
+        ..SearchOptions::default()
+    };
+    let outcome = cache
+        .search_with_options("type:symlink", options, CancellationToken::noop())
+        .unwrap();
+    assert_eq!(outcome.nodes.unwrap().len(), 3);
+    // The walk leaves `target.txt` and the three links without metadata;
+    // `docs` and the walk root's ancestors are directories and aren't stat'ed.
+    assert_eq!(outcome.diagnostics.unwrap().metadata_reads, 4);
+
+    // Everything is in place now, so a repeat stats nothing.
+    let outcome = cache
+        .search_with_options("type:symlink", options, CancellationToken::noop())
+        .unwrap();
+    assert_eq!(outcome.diagnostics.unwrap().metadata_reads, 0);
+}
@@ -66,6 +66,7 @@ fn tag_filter_matches_case_insensitive() {
         "tag:project",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -92,6 +93,7 @@ fn tag_filter_matches_substring() {
         "tag:Alpha",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -140,6 +142,7 @@ fn tag_filter_case_sensitive_exact_match() {
         "tag:Project",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -170,6 +173,7 @@ fn tag_filter_case_insensitive_matches_both() {
         "tag:project",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -781,15 +785,24 @@ fn tag_filter_single_character_tag() {
 #[ignore = "This test is slow and should be run manually when needed"]
 fn tag_mdfind_speed() {
     let mut cache = SearchCache::walk_fs(Path::new("/"));
-    let now = std::time::Instant::now();
-    guard_indices(cache.search_with_options(
-        "tag:A",
-        SearchOptions::default(),
-        CancellationToken::noop(),
-    ));
-    let elapsed = now.elapsed();
-    println!("mdfind search took: {elapsed:?}");
-    assert!(elapsed.as_secs() < 1, "Search using mdfind took too long");
+    let diagnostics = cache
+        .search_with_options(
+            "tag:A",
+            SearchOptions {
+                collect_diagnostics: true,
+                ..Default::default()
+            },
+            CancellationToken::noop(),
+        )
+        .expect("search should succeed")
+        .diagnostics
+        .expect("diagnostics requested");
+    println!("mdfind search took: {:?}", diagnostics.elapsed);
+    assert!(diagnostics.used_mdfind, "large base should use mdfind");
+    assert!(
+        diagnostics.elapsed.as_secs() < 1,
+        "Search using mdfind took too long"
+    );
 }
 
 #[test]
@@ -830,6 +843,7 @@ fn tag_filter_case_sensitive_substring() {
         "tag:Alpha",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -870,6 +884,7 @@ fn tag_filter_mixed_case_in_query() {
         "tag:PrOjEcT",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -998,6 +1013,7 @@ fn tag_filter_list_case_insensitive_duplicates() {
         "tag:Project;project;PROJECT",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1221,6 +1237,7 @@ fn tag_filter_partial_match_at_word_boundary() {
         "tag:work",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1266,6 +1283,7 @@ fn tag_filter_list_case_sensitive_no_match() {
         "tag:Project;Important",
         SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1287,6 +1305,7 @@ fn tag_filter_list_case_insensitive_match() {
         "tag:Project;Important",
         SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -1954,3 +1973,68 @@ fn tag_filter_list_with_100_items() {
     ));
     assert_eq!(indices.len(), 100);
 }
+
+#[test]
+fn tag_filter_unicode_case_fold_matches_accented_tags() {
+    let temp_dir = TempDir::new("tag_unicode_fold").unwrap();
+    let dir = temp_dir.path();
+
+    let file = dir.join("menu.txt");
+    fs::write(&file, b"dummy").unwrap();
+    write_tags(&file, &["Café", "Straße"]);
+
+    let mut cache = SearchCache::walk_fs(dir);
+    let ascii = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    let unicode = SearchOptions {
+        case_insensitive: true,
+        unicode_case_fold: true,
+        ..Default::default()
+    };
+    for query in ["tag:CAFÉ", "tag:STRASSE"] {
+        let ascii_hits =
+            guard_indices(cache.search_with_options(query, ascii, CancellationToken::noop()));
+        assert!(
+            ascii_hits.is_empty(),
+            "{query} should not fold without unicode"
+        );
+        let unicode_hits =
+            guard_indices(cache.search_with_options(query, unicode, CancellationToken::noop()));
+        assert_eq!(unicode_hits.len(), 1, "{query} should fold with unicode");
+    }
+}
+
+#[test]
+fn tag_filter_accent_insensitive_matches_only_when_enabled() {
+    let temp_dir = TempDir::new("tag_accent_fold").unwrap();
+    let dir = temp_dir.path();
+
+    let file = dir.join("cv.pdf");
+    fs::write(&file, b"dummy").unwrap();
+    write_tags(&file, &["Résumé"]);
+
+    let mut cache = SearchCache::walk_fs(dir);
+    let plain = SearchOptions {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    let accents = SearchOptions {
+        accent_insensitive: true,
+        ..plain
+    };
+    let hits = |cache: &mut SearchCache, query: &str, options: SearchOptions| {
+        guard_indices(cache.search_with_options(query, options, CancellationToken::noop())).len()
+    };
+    assert_eq!(hits(&mut cache, "tag:resume", plain), 0);
+    assert_eq!(hits(&mut cache, "tag:resume", accents), 1);
+    assert_eq!(hits(&mut cache, "tag:RESUME", accents), 1);
+    // Folding accents leaves case alone.
+    let case_sensitive = SearchOptions {
+        case_insensitive: false,
+        ..accents
+    };
+    assert_eq!(hits(&mut cache, "tag:resume", case_sensitive), 0);
+    assert_eq!(hits(&mut cache, "tag:Resume", case_sensitive), 1);
+}
@@ -0,0 +1,71 @@
+use search_cache::{SearchCache, SearchOptions, ThreadBudget, WalkData};
+use search_cancel::CancellationToken;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+fn build_tree() -> TempDir {
+    let temp_dir = TempDir::new("thread_budget").unwrap();
+    for dir in 0..6 {
+        for file in 0..20 {
+            let path = temp_dir.path().join(format!("dir_{dir}/note_{file}.txt"));
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let body = if file % 3 == 0 { "needle here" } else { "hay" };
+            std::fs::write(path, body).unwrap();
+        }
+    }
+    temp_dir
+}
+
+fn search_paths(cache: &mut SearchCache, query: &str, budget: ThreadBudget) -> Vec<PathBuf> {
+    let options = SearchOptions {
+        thread_budget: budget,
+        ..SearchOptions::default()
+    };
+    let nodes = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    let mut paths: Vec<PathBuf> = nodes
+        .into_iter()
+        .map(|index| cache.node_path(index).unwrap())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn serial_budget_matches_the_parallel_results() {
+    let tmp = build_tree();
+    let serial = ThreadBudget::threads(1);
+    let parallel_cache = SearchCache::walk_fs(tmp.path());
+    let ignore = Vec::new();
+    let walk_data = WalkData::new(tmp.path(), &ignore, false, None).thread_budget(serial);
+    let mut serial_cache = SearchCache::walk_fs_with_walk_data(&walk_data, None)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        serial_cache.get_total_files(),
+        parallel_cache.get_total_files()
+    );
+
+    let mut parallel_cache = parallel_cache;
+    for query in ["note_1", "content:needle", "note_1 content:needle"] {
+        let parallel = search_paths(&mut parallel_cache, query, ThreadBudget::Available);
+        assert!(!parallel.is_empty(), "{query}");
+        assert_eq!(
+            search_paths(&mut serial_cache, query, serial),
+            parallel,
+            "{query}"
+        );
+        assert_eq!(
+            search_paths(&mut parallel_cache, query, serial),
+            parallel,
+            "{query}"
+        );
+    }
+    assert_eq!(
+        search_paths(&mut serial_cache, "content:needle", serial).len(),
+        42
+    );
+}
@@ -0,0 +1,98 @@
+use search_cache::{EmptyBehavior, SearchCache, SearchOptions, ThreadBudget};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+const ASCII: SearchOptions = SearchOptions {
+    case_insensitive: true,
+    unicode_case_fold: false,
+    accent_insensitive: false,
+    match_full_path: false,
+    space_is_literal: false,
+    sniff_content: false,
+    collect_diagnostics: false,
+    thread_budget: ThreadBudget::Available,
+    min_query_len: 1,
+    empty_query: EmptyBehavior::MatchNone,
+};
+const UNICODE: SearchOptions = SearchOptions {
+    case_insensitive: true,
+    unicode_case_fold: true,
+    accent_insensitive: false,
+    match_full_path: false,
+    space_is_literal: false,
+    sniff_content: false,
+    collect_diagnostics: false,
+    thread_budget: ThreadBudget::Available,
+    min_query_len: 1,
+    empty_query: EmptyBehavior::MatchNone,
+};
+
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("unicode_case_fold").unwrap();
+    for name in ["Hauptstraße.txt", "İstanbul.md", "notes.txt"] {
+        fs::write(temp_dir.path().join(name), b"x").unwrap();
+    }
+    fs::create_dir(temp_dir.path().join("ŞEHİR")).unwrap();
+    fs::write(temp_dir.path().join("ŞEHİR/Straße.log"), b"x").unwrap();
+    let cache = SearchCache::walk_fs(temp_dir.path());
+    (temp_dir, cache)
+}
+
+fn names(cache: &mut SearchCache, query: &str, options: SearchOptions) -> Vec<String> {
+    let indices = cache
+        .search_with_options(query, options, CancellationToken::noop())
+        .expect("search should succeed")
+        .nodes
+        .expect("noop token should not cancel");
+    let mut names: Vec<String> = cache
+        .expand_file_nodes(&indices)
+        .into_iter()
+        .map(|node| {
+            node.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn sharp_s_matches_double_s_only_with_unicode_fold() {
+    let (_tmp, mut cache) = build_cache();
+    assert!(names(&mut cache, "STRASSE", ASCII).is_empty());
+    assert_eq!(
+        names(&mut cache, "STRASSE", UNICODE),
+        ["Hauptstraße.txt", "Straße.log"]
+    );
+}
+
+#[test]
+fn dotted_capital_i_matches_ascii_i_only_with_unicode_fold() {
+    let (_tmp, mut cache) = build_cache();
+    assert!(names(&mut cache, "istanbul", ASCII).is_empty());
+    assert_eq!(names(&mut cache, "istanbul", UNICODE), ["İstanbul.md"]);
+}
+
+#[test]
+fn unicode_fold_applies_to_path_segments_and_wildcards() {
+    let (_tmp, mut cache) = build_cache();
+    assert!(names(&mut cache, "şehir/strasse", ASCII).is_empty());
+    assert_eq!(names(&mut cache, "şehir/strasse", UNICODE), ["Straße.log"]);
+    assert_eq!(names(&mut cache, "STRASSE.l*", UNICODE), ["Straße.log"]);
+}
+
+#[test]
+fn unicode_fold_is_ignored_for_case_sensitive_search() {
+    let (_tmp, mut cache) = build_cache();
+    let options = SearchOptions {
+        case_insensitive: false,
+        unicode_case_fold: true,
+        ..Default::default()
+    };
+    assert!(names(&mut cache, "STRASSE", options).is_empty());
+    assert_eq!(names(&mut cache, "straße", options), ["Hauptstraße.txt"]);
+}
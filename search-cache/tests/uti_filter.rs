@@ -0,0 +1,112 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+fn search_error(cache: &mut SearchCache, query: &str) -> String {
+    cache
+        .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn uti_filter_rejects_arguments_that_are_not_content_types() {
+    let temp_dir = TempDir::new("uti_filter_invalid").unwrap();
+    fs::write(temp_dir.path().join("file.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(temp_dir.path());
+
+    assert!(search_error(&mut cache, "uti:").contains("uti: requires a content type"));
+    assert!(search_error(&mut cache, r#"uti:"""#).contains("uti: requires a content type"));
+    assert!(
+        search_error(&mut cache, "uti:\"public.image' || true\"").contains("not a content type")
+    );
+    assert!(search_error(&mut cache, "uti:>public.image").contains("does not support"));
+}
+
+#[cfg(not(target_os = "macos"))]
+#[test]
+fn uti_filter_needs_spotlight() {
+    let temp_dir = TempDir::new("uti_filter_unsupported").unwrap();
+    fs::write(temp_dir.path().join("file.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(temp_dir.path());
+
+    assert!(search_error(&mut cache, "uti:public.image").contains("only available on macOS"));
+}
+
+#[cfg(target_os = "macos")]
+#[test]
+fn uti_filter_matches_images_the_extension_map_misses() {
+    use std::{collections::BTreeSet, path::PathBuf};
+
+    let temp_dir = TempDir::new("uti_filter_image").unwrap();
+    let root = temp_dir.path();
+    // `.jpe` is a JPEG extension Launch Services knows but `type:picture` doesn't.
+    let photo = root.join("photo.jpe");
+    fs::write(
+        &photo,
+        [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0x00,
+        ],
+    )
+    .unwrap();
+    fs::write(root.join("notes.txt"), b"not an image").unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+
+    let mut search = |query: &str| -> BTreeSet<PathBuf> {
+        let nodes = cache
+            .search_with_options(query, SearchOptions::default(), CancellationToken::noop())
+            .unwrap()
+            .nodes
+            .unwrap();
+        cache.node_paths(&nodes).into_iter().flatten().collect()
+    };
+    assert!(search("type:picture").is_empty());
+    assert_eq!(search("uti:public.image"), BTreeSet::from([photo.clone()]));
+    assert_eq!(
+        search("uti:PUBLIC.JPEG;public.movie"),
+        BTreeSet::from([photo])
+    );
+    assert_eq!(
+        search("uti:public.plain-text"),
+        BTreeSet::from([root.join("notes.txt")])
+    );
+}
+
+#[cfg(target_os = "macos")]
+#[test]
+fn uti_filter_keeps_content_types_until_an_event_drops_them() {
+    use cardinal_sdk::{EventFlag, FsEvent, current_event_id};
+
+    let temp_dir = TempDir::new("uti_filter_cached").unwrap();
+    let root = temp_dir.path();
+    let notes = root.join("notes.txt");
+    fs::write(&notes, b"plain text").unwrap();
+    let mut cache = SearchCache::walk_fs(root);
+
+    let search = |cache: &mut SearchCache| -> usize {
+        cache
+            .search_with_options(
+                "uti:public.plain-text",
+                SearchOptions::default(),
+                CancellationToken::noop(),
+            )
+            .unwrap()
+            .nodes
+            .unwrap()
+            .len()
+    };
+    assert_eq!(search(&mut cache), 1);
+
+    // Without an event the tree read above still answers.
+    fs::remove_file(&notes).unwrap();
+    assert_eq!(search(&mut cache), 1);
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: notes,
+            flag: EventFlag::ItemRemoved,
+            id: current_event_id(),
+        }])
+        .unwrap();
+    assert_eq!(search(&mut cache), 0);
+}
@@ -0,0 +1,163 @@
+use cardinal_syntax::FilterKind;
+use search_cache::{PlanExpr, PlanSegment, SearchCache};
+use tempdir::TempDir;
+
+fn empty_cache() -> SearchCache {
+    let tmp = TempDir::new("validate_query").unwrap();
+    SearchCache::walk_fs(tmp.path())
+}
+
+#[test]
+fn valid_query_plan_lists_filters_and_structure() {
+    let cache = empty_cache();
+    let plan = cache
+        .validate_query("ext:rs;toml src/ma* !size:>1mb | dm:today")
+        .expect("query should validate");
+
+    let kinds: Vec<_> = plan.filters().iter().map(|f| f.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![FilterKind::Ext, FilterKind::Size, FilterKind::DateModified]
+    );
+
+    // `|` binds tighter than the implicit AND, as in Everything.
+    let PlanExpr::And(parts) = &plan.expr else {
+        panic!("expected top-level AND, got {:?}", plan.expr);
+    };
+    assert_eq!(parts.len(), 3);
+    assert!(matches!(&parts[0], PlanExpr::Filter(f) if f.kind == FilterKind::Ext));
+    assert_eq!(
+        parts[1],
+        PlanExpr::Name(vec![
+            PlanSegment::Suffix("src".into()),
+            PlanSegment::Prefix("ma*".into()),
+        ])
+    );
+    let PlanExpr::Or(branches) = &parts[2] else {
+        panic!("expected OR group, got {:?}", parts[2]);
+    };
+    assert!(
+        matches!(&branches[0], PlanExpr::Not(inner) if matches!(&**inner, PlanExpr::Filter(f) if f.kind == FilterKind::Size))
+    );
+    assert!(matches!(&branches[1], PlanExpr::Filter(f) if f.kind == FilterKind::DateModified));
+}
+
+#[test]
+fn blank_query_is_an_empty_plan() {
+    let cache = empty_cache();
+    let plan = cache
+        .validate_query("   ")
+        .expect("blank query should validate");
+    assert_eq!(plan.expr, PlanExpr::Empty);
+    assert!(plan.filters().is_empty());
+}
+
+#[test]
+fn syntax_error_points_at_offending_position() {
+    let cache = empty_cache();
+    let line = "report > draft";
+    let err = cache.validate_query(line).unwrap_err();
+    assert!(err.message.contains("closing delimiter"), "{err}");
+    assert_eq!(&line[err.span.clone()], ">");
+
+    let line = "report \"unterminated";
+    let err = cache.validate_query(line).unwrap_err();
+    assert!(err.message.contains("closing quote"), "{err}");
+    assert_eq!(err.span, line.len()..line.len());
+
+    let err = cache.validate_query("<foo bar").unwrap_err();
+    assert!(err.message.contains("expected '>'"), "{err}");
+    assert_eq!(err.span, 8..8);
+}
+
+#[test]
+fn invalid_filter_argument_points_at_argument() {
+    let cache = empty_cache();
+    let line = "report size:huge-ish dm:today";
+    let err = cache.validate_query(line).unwrap_err();
+    assert_eq!(&line[err.span.clone()], "huge-ish");
+
+    let line = "dm:today dc:someday";
+    let err = cache.validate_query(line).unwrap_err();
+    assert_eq!(&line[err.span.clone()], "someday");
+
+    let line = "type:spreadsheetz";
+    let err = cache.validate_query(line).unwrap_err();
+    assert!(err.message.contains("Unknown type category"), "{err}");
+    assert_eq!(&line[err.span.clone()], "spreadsheetz");
+}
+
+#[test]
+fn missing_argument_and_bad_regex_are_rejected() {
+    let cache = empty_cache();
+    let err = cache.validate_query("size:").unwrap_err();
+    assert_eq!(err.message, "size: requires a value");
+
+    let line = "notes regex:[unclosed";
+    let err = cache.validate_query(line).unwrap_err();
+    assert!(err.message.starts_with("Invalid regex pattern"), "{err}");
+    assert_eq!(&line[err.span.clone()], "[unclosed");
+}
+
+#[test]
+fn collect_errors_reports_every_invalid_term() {
+    let cache = empty_cache();
+    let line = "report type:spreadsheetz size:10mb..1mb dm:today";
+    let errors = cache.parse_query_collect_errors(line).unwrap_err();
+    let [category, range] = &errors[..] else {
+        panic!("expected two errors, got {errors:?}");
+    };
+    assert!(
+        category.message.contains("Unknown type category"),
+        "{category}"
+    );
+    assert_eq!(&line[category.span.clone()], "spreadsheetz");
+    assert!(range.message.contains("less than or equal"), "{range}");
+    assert_eq!(&line[range.span.clone()], "10mb..1mb");
+
+    // The single-error form reports the first of them.
+    assert_eq!(&cache.validate_query(line).unwrap_err(), category);
+}
+
+#[test]
+fn collect_errors_matches_validate_query_otherwise() {
+    let cache = empty_cache();
+    let line = "ext:rs src | dm:today";
+    assert_eq!(
+        cache.parse_query_collect_errors(line),
+        cache.validate_query(line).map_err(|err| vec![err])
+    );
+
+    let errors = cache
+        .parse_query_collect_errors("report > size:huge")
+        .unwrap_err();
+    let [syntax] = &errors[..] else {
+        panic!("a syntax error should be reported alone, got {errors:?}");
+    };
+    assert!(syntax.message.contains("closing delimiter"), "{syntax}");
+}
+
+#[test]
+fn aliases_are_expanded_before_validating() {
+    let mut cache = empty_cache();
+    cache
+        .register_alias("bigpdf", "ext:pdf size:>10mb")
+        .unwrap();
+    let plan = cache.validate_query("report bigpdf:").unwrap();
+    let kinds: Vec<_> = plan.filters().iter().map(|f| f.kind.clone()).collect();
+    assert_eq!(kinds, vec![FilterKind::Ext, FilterKind::Size]);
+    assert_eq!(
+        cache.validate_query("@bigpdf").unwrap(),
+        cache.validate_query("ext:pdf size:>10mb").unwrap()
+    );
+
+    let err = cache.validate_query("bigpdf:draft").unwrap_err();
+    assert!(err.message.contains("takes no argument"), "{err}");
+    assert!(
+        empty_cache()
+            .validate_query("bigpdf:")
+            .unwrap_err()
+            .message
+            .contains("bigpdf")
+    );
+}
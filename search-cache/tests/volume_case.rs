@@ -0,0 +1,36 @@
+use search_cache::SearchOptions;
+use std::fs;
+use tempdir::TempDir;
+
+#[test]
+fn for_volume_follows_the_file_system() {
+    let tmp = TempDir::new("volume_case").unwrap();
+    let dir = tmp.path();
+    fs::File::create(dir.join("Upper")).unwrap();
+    let insensitive = dir.join("upper").exists();
+    fs::remove_file(dir.join("Upper")).unwrap();
+
+    let options = SearchOptions::for_volume(dir);
+    assert_eq!(options.case_insensitive, insensitive);
+    // The probe cleans up after itself, and a file probes its parent's volume.
+    assert_eq!(fs::read_dir(dir).unwrap().count(), 0);
+    fs::write(dir.join("notes.txt"), b"").unwrap();
+    assert_eq!(
+        SearchOptions::for_volume(&dir.join("notes.txt")).case_insensitive,
+        insensitive
+    );
+    // Nothing else departs from the defaults.
+    let defaults = SearchOptions::default();
+    assert_eq!(options.min_query_len, defaults.min_query_len);
+    assert!(!options.match_full_path && !options.accent_insensitive);
+}
+
+#[test]
+fn for_volume_falls_back_to_the_platform_default() {
+    let tmp = TempDir::new("volume_case").unwrap();
+    let missing = tmp.path().join("no-such-dir");
+    assert_eq!(
+        SearchOptions::for_volume(&missing).case_insensitive,
+        cfg!(target_os = "macos")
+    );
+}
@@ -0,0 +1,174 @@
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tempdir::TempDir;
+
+fn build_fixture() -> TempDir {
+    let temp_dir = TempDir::new("wal").unwrap();
+    for file in ["keep.txt", "gone.txt", "dir/old.txt", "dir/nested/deep.txt"] {
+        let full = temp_dir.path().join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+    temp_dir
+}
+
+fn all_paths(cache: &SearchCache) -> BTreeSet<PathBuf> {
+    let nodes = cache.search_empty(CancellationToken::noop()).unwrap();
+    cache.node_paths(&nodes).into_iter().flatten().collect()
+}
+
+fn event(path: PathBuf, flag: EventFlag, id: u64) -> FsEvent {
+    FsEvent { path, flag, id }
+}
+
+/// Changes the tree under `root` and feeds `cache` the matching events.
+fn apply_changes(cache: &mut SearchCache, root: &Path) {
+    fs::File::create(root.join("new.txt")).unwrap();
+    fs::remove_file(root.join("gone.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            event(root.join("new.txt"), EventFlag::ItemCreated, 10),
+            event(root.join("gone.txt"), EventFlag::ItemRemoved, 11),
+        ])
+        .unwrap();
+    fs::create_dir_all(root.join("added/inner")).unwrap();
+    fs::File::create(root.join("added/inner/file.md")).unwrap();
+    fs::remove_dir_all(root.join("dir/nested")).unwrap();
+    cache
+        .handle_fs_events(vec![
+            event(root.join("added"), EventFlag::ItemCreated, 12),
+            event(root.join("dir/nested"), EventFlag::ItemRemoved, 13),
+        ])
+        .unwrap();
+}
+
+#[test]
+fn replaying_the_wal_recovers_changes_lost_in_a_crash() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let db = TempDir::new("wal_db").unwrap();
+    let db_path = db.path().join("cache.db");
+    let wal_path = db.path().join("cache.wal");
+
+    let mut cache = SearchCache::walk_fs(root);
+    cache.flush_snapshot_to_file(&db_path).unwrap();
+    cache.enable_wal(&wal_path).unwrap();
+    apply_changes(&mut cache, root);
+    let expected = all_paths(&cache);
+    assert_eq!(expected, all_paths(&SearchCache::walk_fs(root)));
+    // Crash: the changes never make it into a flush.
+    drop(cache);
+
+    let mut stale =
+        SearchCache::try_read_persistent_cache(root, &db_path, &Vec::new(), None).unwrap();
+    assert_ne!(all_paths(&stale), expected);
+    assert_eq!(stale.replay_wal(&wal_path).unwrap(), 6);
+    assert_eq!(all_paths(&stale), expected);
+    assert_eq!(stale.last_event_id(), Some(13));
+    let hits = stale
+        .query_files("file.md".to_string(), CancellationToken::noop())
+        .unwrap()
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+}
+
+#[test]
+fn flushing_empties_the_wal() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let db = TempDir::new("wal_db").unwrap();
+    let db_path = db.path().join("cache.db");
+    let wal_path = db.path().join("cache.wal");
+
+    // Nothing logged yet replays nothing, not even a missing file.
+    let mut cache = SearchCache::walk_fs(root);
+    assert_eq!(cache.replay_wal(&wal_path).unwrap(), 0);
+    cache.enable_wal(&wal_path).unwrap();
+    apply_changes(&mut cache, root);
+    assert!(fs::metadata(&wal_path).unwrap().len() > 0);
+
+    cache.flush_snapshot_to_file(&db_path).unwrap();
+    assert_eq!(fs::metadata(&wal_path).unwrap().len(), 0);
+    fs::File::create(root.join("later.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("later.txt"),
+            EventFlag::ItemCreated,
+            20,
+        )])
+        .unwrap();
+    // Replaying what was logged since the flush is harmless on the live cache.
+    assert_eq!(cache.replay_wal(&wal_path).unwrap(), 2);
+    cache.flush_to_file(&db_path).unwrap();
+    assert_eq!(fs::metadata(&wal_path).unwrap().len(), 0);
+
+    let loaded = SearchCache::try_read_persistent_cache(root, &db_path, &Vec::new(), None).unwrap();
+    assert_eq!(all_paths(&loaded), all_paths(&SearchCache::walk_fs(root)));
+}
+
+#[test]
+fn torn_wal_tail_is_ignored() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let db = TempDir::new("wal_db").unwrap();
+    let db_path = db.path().join("cache.db");
+    let wal_path = db.path().join("cache.wal");
+
+    let mut cache = SearchCache::walk_fs(root);
+    cache.flush_snapshot_to_file(&db_path).unwrap();
+    cache.enable_wal(&wal_path).unwrap();
+    apply_changes(&mut cache, root);
+    let expected = all_paths(&cache);
+    drop(cache);
+    // A record whose write was cut short says it is longer than what follows.
+    let mut wal = fs::OpenOptions::new().append(true).open(&wal_path).unwrap();
+    wal.write_all(&64u32.to_le_bytes()).unwrap();
+    wal.write_all(b"partial").unwrap();
+
+    let mut stale =
+        SearchCache::try_read_persistent_cache(root, &db_path, &Vec::new(), None).unwrap();
+    assert_eq!(stale.replay_wal(&wal_path).unwrap(), 6);
+    assert_eq!(all_paths(&stale), expected);
+}
+
+#[test]
+fn replaying_a_logged_rescan_walks_again() {
+    let tmp = build_fixture();
+    let root = tmp.path();
+    let db = TempDir::new("wal_db").unwrap();
+    let db_path = db.path().join("cache.db");
+    let wal_path = db.path().join("cache.wal");
+
+    let mut cache = SearchCache::walk_fs(root);
+    cache.flush_snapshot_to_file(&db_path).unwrap();
+    cache.enable_wal(&wal_path).unwrap();
+    apply_changes(&mut cache, root);
+    // Events were dropped, so only the rescan sees this one.
+    fs::File::create(root.join("unseen.txt")).unwrap();
+    cache.rescan().unwrap();
+    fs::File::create(root.join("later.txt")).unwrap();
+    cache
+        .handle_fs_events(vec![event(
+            root.join("later.txt"),
+            EventFlag::ItemCreated,
+            u64::MAX,
+        )])
+        .unwrap();
+    let expected = all_paths(&cache);
+    assert!(expected.contains(&root.join("unseen.txt")));
+    drop(cache);
+
+    let mut stale =
+        SearchCache::try_read_persistent_cache(root, &db_path, &Vec::new(), None).unwrap();
+    // The rescan restarted the log: it and the two records after it.
+    assert_eq!(stale.replay_wal(&wal_path).unwrap(), 3);
+    assert_eq!(all_paths(&stale), expected);
+    assert_eq!(stale.last_event_id(), Some(u64::MAX));
+}
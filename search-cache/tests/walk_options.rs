@@ -0,0 +1,143 @@
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::{SearchCache, ThreadBudget, WalkData};
+use search_cancel::CancellationToken;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use tempdir::TempDir;
+
+fn create_files(root: &Path, files: &[&str]) {
+    for file in files {
+        let full = root.join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::File::create(full).unwrap();
+    }
+}
+
+/// Paths below `root`, relative to it.
+fn relative_paths(cache: &SearchCache, root: &Path) -> BTreeSet<PathBuf> {
+    let nodes = cache.search_empty(CancellationToken::noop()).unwrap();
+    cache
+        .node_paths(&nodes)
+        .into_iter()
+        .flatten()
+        .filter_map(|path| Some(path.strip_prefix(root).ok()?.to_path_buf()))
+        .filter(|path| !path.as_os_str().is_empty())
+        .collect()
+}
+
+fn created(cache: &mut SearchCache, paths: &[PathBuf]) {
+    let events = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| FsEvent {
+            path: path.clone(),
+            flag: EventFlag::ItemCreated,
+            id: i as u64 + 1,
+        })
+        .collect();
+    cache.handle_fs_events(events).unwrap();
+}
+
+fn expected(paths: &[&str]) -> BTreeSet<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn only_extensions_apply_to_events_and_rescans() {
+    let tmp = TempDir::new("walk_options_extensions").unwrap();
+    let root = tmp.path();
+    create_files(root, &["main.rs", "notes.txt"]);
+    let walk_data = WalkData::new(root, &[], false, None).only_extensions(&["rs"]);
+    let mut cache = SearchCache::walk_fs_with_walk_data(&walk_data, None)
+        .unwrap()
+        .unwrap();
+    assert_eq!(cache.walk_options(), walk_data.options());
+    assert_eq!(relative_paths(&cache, root), expected(&["main.rs"]));
+
+    create_files(root, &["lib.rs", "todo.md", "src/mod.rs", "src/readme.txt"]);
+    created(
+        &mut cache,
+        &[root.join("lib.rs"), root.join("todo.md"), root.join("src")],
+    );
+    let walked = expected(&["lib.rs", "main.rs", "src", "src/mod.rs"]);
+    assert_eq!(relative_paths(&cache, root), walked);
+
+    cache.rescan().unwrap();
+    assert_eq!(relative_paths(&cache, root), walked);
+    assert_eq!(cache.walk_options(), walk_data.options());
+}
+
+#[test]
+fn skip_hidden_and_thread_budget_apply_to_events_and_rescans() {
+    let tmp = TempDir::new("walk_options_hidden").unwrap();
+    let root = tmp.path();
+    create_files(root, &["visible.txt", ".env"]);
+    let walk_data = WalkData::new(root, &[], false, None)
+        .skip_hidden(true)
+        .thread_budget(ThreadBudget::threads(1));
+    let mut cache = SearchCache::walk_fs_with_walk_data(&walk_data, None)
+        .unwrap()
+        .unwrap();
+    assert_eq!(relative_paths(&cache, root), expected(&["visible.txt"]));
+
+    create_files(
+        root,
+        &[".DS_Store", ".git/HEAD", "src/.cache/blob", "src/lib.rs"],
+    );
+    created(
+        &mut cache,
+        &[
+            root.join(".DS_Store"),
+            root.join(".git/HEAD"),
+            root.join(".git"),
+            root.join("src"),
+        ],
+    );
+    let walked = expected(&["src", "src/lib.rs", "visible.txt"]);
+    assert_eq!(relative_paths(&cache, root), walked);
+
+    let (mut phantom1, mut phantom2) = (PathBuf::new(), Vec::new());
+    assert_eq!(
+        cache.walk_data(&mut phantom1, &mut phantom2).options(),
+        walk_data.options()
+    );
+    cache.rescan().unwrap();
+    assert_eq!(relative_paths(&cache, root), walked);
+}
+
+#[test]
+fn walk_filters_survive_a_flush_and_reload() {
+    let tmp = TempDir::new("walk_options_reload").unwrap();
+    let root = tmp.path();
+    let db = TempDir::new("walk_options_reload_db").unwrap();
+    let cache_path = db.path().join("cache.db");
+    create_files(root, &["main.rs", "notes.txt", ".env"]);
+    let walk_data = WalkData::new(root, &[], false, None)
+        .only_extensions(&["rs"])
+        .skip_hidden(true);
+    let cache = SearchCache::walk_fs_with_walk_data(&walk_data, None)
+        .unwrap()
+        .unwrap();
+    cache.flush_to_file(&cache_path).unwrap();
+
+    let mut cache =
+        SearchCache::try_read_persistent_cache(root, &cache_path, &Vec::new(), None).unwrap();
+    assert_eq!(cache.walk_options(), walk_data.options());
+    create_files(root, &["todo.txt", "lib.rs", ".git/HEAD.rs"]);
+    created(
+        &mut cache,
+        &[
+            root.join("todo.txt"),
+            root.join("lib.rs"),
+            root.join(".git"),
+        ],
+    );
+    let walked = expected(&["lib.rs", "main.rs"]);
+    assert_eq!(relative_paths(&cache, root), walked);
+
+    cache.rescan().unwrap();
+    assert_eq!(relative_paths(&cache, root), walked);
+}
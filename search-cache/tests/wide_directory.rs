@@ -0,0 +1,83 @@
+use cardinal_sdk::{EventFlag, FsEvent};
+use search_cache::SearchCache;
+use std::{fs, time::Instant};
+use tempdir::TempDir;
+
+const WIDE: usize = 10_000;
+
+fn event(path: std::path::PathBuf, flag: EventFlag, id: u64) -> FsEvent {
+    FsEvent { path, flag, id }
+}
+
+#[test]
+fn paths_in_a_wide_directory_resolve_quickly() {
+    let tmp = TempDir::new("wide_directory").unwrap();
+    let wide = tmp.path().join("wide");
+    fs::create_dir(&wide).unwrap();
+    let files: Vec<_> = (0..WIDE)
+        .map(|i| wide.join(format!("file-{i:05}.txt")))
+        .collect();
+    for file in &files {
+        fs::File::create(file).unwrap();
+    }
+    let cache = SearchCache::walk_fs(tmp.path());
+
+    let started = Instant::now();
+    for _ in 0..10 {
+        for file in &files {
+            let index = cache.index_of_path(file).unwrap();
+            assert_eq!(cache.node_path(index).as_ref(), Some(file));
+        }
+    }
+    // Scanning 10k siblings per lookup takes over a minute in a debug build;
+    // the name map takes well under a second.
+    let elapsed = started.elapsed();
+    assert!(elapsed.as_secs() < 10, "resolving took {elapsed:?}");
+    assert!(cache.index_of_path(&wide.join("file-99999.txt")).is_none());
+}
+
+#[test]
+fn wide_directory_lookups_follow_inserts_and_removals() {
+    let tmp = TempDir::new("wide_directory").unwrap();
+    let wide = tmp.path().join("wide");
+    fs::create_dir(&wide).unwrap();
+    for i in 0..WIDE {
+        fs::File::create(wide.join(format!("file-{i:05}.txt"))).unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path());
+    let gone = wide.join("file-00042.txt");
+    assert!(cache.index_of_path(&gone).is_some());
+
+    let added = wide.join("added.txt");
+    fs::File::create(&added).unwrap();
+    fs::remove_file(&gone).unwrap();
+    cache
+        .handle_fs_events(vec![
+            event(added.clone(), EventFlag::ItemCreated, 1),
+            event(gone.clone(), EventFlag::ItemRemoved, 2),
+        ])
+        .unwrap();
+    let index = cache.index_of_path(&added).unwrap();
+    assert_eq!(cache.node_path(index), Some(added.clone()));
+    assert!(cache.index_of_path(&gone).is_none());
+
+    // The removed file's slab slot goes to a new node elsewhere; the
+    // directory's lookups must not hand it out under the old name.
+    let other = tmp.path().join("other.txt");
+    fs::File::create(&other).unwrap();
+    cache
+        .handle_fs_events(vec![event(other.clone(), EventFlag::ItemCreated, 3)])
+        .unwrap();
+    assert!(cache.index_of_path(&gone).is_none());
+    let index = cache.index_of_path(&other).unwrap();
+    assert_eq!(cache.node_path(index), Some(other));
+
+    // Rescanning a child replaces it with a node at a fresh index.
+    let rescanned = wide.join("file-00007.txt");
+    fs::write(&rescanned, b"changed").unwrap();
+    cache
+        .handle_fs_events(vec![event(rescanned.clone(), EventFlag::ItemModified, 4)])
+        .unwrap();
+    let index = cache.index_of_path(&rescanned).unwrap();
+    assert_eq!(cache.node_path(index), Some(rescanned));
+}
@@ -1,4 +1,7 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
 
 /// How often long-running loops should check whether execution was cancelled.
 pub const CANCEL_CHECK_INTERVAL: usize = 0x10000;
@@ -8,16 +11,19 @@ pub static ACTIVE_SEARCH_VERSION: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Clone, Copy, Debug)]
 pub struct CancellationToken {
-    active_version: &'static AtomicU64,
+    /// `None` marks the noop sentinel, which never observes any atomic.
+    active_version: Option<&'static AtomicU64>,
     version: u64,
+    /// Set by [`Self::with_deadline`]; past it the token reads as cancelled.
+    deadline: Option<Instant>,
 }
 
 impl CancellationToken {
     pub fn noop() -> Self {
-        static NOOP: AtomicU64 = AtomicU64::new(0);
         Self {
             version: 0,
-            active_version: &NOOP,
+            active_version: None,
+            deadline: None,
         }
     }
 
@@ -25,12 +31,48 @@ impl CancellationToken {
         ACTIVE_SEARCH_VERSION.store(version, Ordering::SeqCst);
         Self {
             version,
-            active_version: &ACTIVE_SEARCH_VERSION,
+            active_version: Some(&ACTIVE_SEARCH_VERSION),
+            deadline: None,
         }
     }
 
+    /// The same token, but also cancelled once `deadline` passes, so a slow
+    /// search gives up even if no newer one replaces it.
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// The same token without its deadline, for background work that should
+    /// only stop once a newer search arrives.
+    pub fn without_deadline(self) -> Self {
+        Self {
+            deadline: None,
+            ..self
+        }
+    }
+
+    pub fn is_noop(&self) -> bool {
+        self.active_version.is_none() && self.deadline.is_none()
+    }
+
+    /// Whether the deadline, if any, has passed. Tells a timed out search
+    /// apart from one superseded by a newer version.
+    pub fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
     pub fn is_cancelled(&self) -> Option<()> {
-        if self.version != self.active_version.load(Ordering::Relaxed) {
+        if self.timed_out() {
+            return None;
+        }
+        let Some(active_version) = self.active_version else {
+            return Some(());
+        };
+        if self.version != active_version.load(Ordering::Relaxed) {
             None
         } else {
             Some(())
@@ -59,6 +101,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dropping_a_passed_deadline_uncancels() {
+        let token = CancellationToken::noop().with_deadline(Instant::now());
+        assert!(token.is_cancelled().is_none());
+        assert!(token.without_deadline().is_cancelled().is_some());
+    }
+
     #[test]
     fn cancelled_after_version_change() {
         let token_v1 = CancellationToken::new(1);
@@ -0,0 +1,26 @@
+use search_cancel::CancellationToken;
+use std::time::{Duration, Instant};
+
+#[test]
+fn deadline_cancels_once_it_passes() {
+    let token = CancellationToken::noop().with_deadline(Instant::now() + Duration::from_millis(50));
+    assert!(!token.is_noop());
+    assert!(token.is_cancelled().is_some());
+    assert!(!token.timed_out());
+
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(token.is_cancelled().is_none());
+    assert!(token.is_cancelled_sparse(0).is_none());
+    assert!(token.timed_out());
+}
+
+#[test]
+fn an_expired_deadline_is_not_a_version_change() {
+    let token = CancellationToken::noop();
+    assert!(!token.timed_out());
+    let expired = token.with_deadline(Instant::now());
+    assert!(expired.is_cancelled().is_none());
+    assert!(expired.timed_out());
+    // The original copy keeps running.
+    assert!(token.is_cancelled().is_some());
+}
@@ -0,0 +1,23 @@
+use search_cancel::{ACTIVE_SEARCH_VERSION, CancellationToken};
+use std::sync::atomic::Ordering;
+
+// Lives in its own test binary because it writes the shared active version.
+#[test]
+fn noop_token_ignores_active_version_writes() {
+    let token = CancellationToken::noop();
+    let copied = token;
+    assert!(token.is_noop());
+
+    let _active = CancellationToken::new(7);
+    ACTIVE_SEARCH_VERSION.store(u64::MAX, Ordering::SeqCst);
+    assert!(token.is_cancelled().is_some());
+    assert!(copied.is_cancelled().is_some());
+    assert!(CancellationToken::noop().is_cancelled().is_some());
+
+    // Version 0 is what the noop token carries; it must not alias the live atomic.
+    let live = CancellationToken::new(0);
+    let _newer = CancellationToken::new(1);
+    assert!(live.is_cancelled().is_none());
+    assert!(token.is_cancelled().is_some());
+    assert!(!live.is_noop());
+}